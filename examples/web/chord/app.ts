@@ -66,6 +66,7 @@ type GuitarFingeringInfo = {
   omitted_pitch_spaces: number[];
   covered_pitch_classes: number[];
   omitted_pitch_classes: number[];
+  svg: string;
 };
 
 type ChordAnalysis = {
@@ -95,35 +96,6 @@ type ChordAnalysis = {
   abc_notation: string;
 };
 
-type VexChordOptions = Record<string, string | number | boolean>;
-type VexChordDefinition = {
-  chord: Array<[number, number | "x"]>;
-  position: number;
-  bars: VexBarre[];
-  barres: VexBarre[];
-  tuning: string[];
-};
-type VexBarre = {
-  fromString: number;
-  toString: number;
-  fret: number;
-};
-type VexChordBox = {
-  draw: (chord: VexChordDefinition) => void;
-};
-type VexChordBoxConstructor = new (
-  selector: string,
-  options: VexChordOptions,
-) => VexChordBox;
-type VexRenderer = {
-  draw?: (
-    selector: string,
-    chord: VexChordDefinition,
-    options: VexChordOptions,
-  ) => void;
-  ChordBox?: VexChordBoxConstructor;
-};
-
 type AbcRenderer = (
   target: string,
   abc: string,
@@ -144,9 +116,6 @@ type AnalyzeOptions = {
 
 declare global {
   interface Window {
-    vexchords?: unknown;
-    VexChords?: unknown;
-    Vexchords?: unknown;
     ABCJS?: { renderAbc?: AbcRenderer };
     abcjs?: { renderAbc?: AbcRenderer };
     webkitAudioContext?: typeof AudioContext;
@@ -201,7 +170,6 @@ const soundTuningParam = "soundTuning";
 const guitarTuningParam = "guitarTuning";
 const defaultSoundTuningId = "EqualTemperament";
 const defaultGuitarTuning = "E2 A2 D3 G3 B3 E4";
-const vexChordsUrl = "https://cdn.jsdelivr.net/npm/vexchords@1.2.0/dist/vexchords.dev.js";
 const historyStorageKey = "music21-rs.chordInspector.history";
 const maxHistoryItems = 24;
 const randomNoteLimitMin = 1;
@@ -210,8 +178,6 @@ const randomNoteDefaultMin = 3;
 const randomNoteDefaultMax = 6;
 let shareResetTimer: number | null = null;
 let currentAnalysis: ChordAnalysis | null = null;
-let guitarRenderToken = 0;
-let vexChordsPromise: Promise<VexRenderer | null> | null = null;
 let audioContext: AudioContext | null = null;
 let activeChordNodes: OscillatorNode[] = [];
 let nextChordPlaybackMode: PlaybackMode = "arpeggio";
@@ -436,13 +402,6 @@ function displayPitchName(value: unknown): string {
   return `${match[1]}${match[2].replaceAll("-", "b")}${match[3] ?? ""}`;
 }
 
-function cssVar(name: string, fallback: string): string {
-  return (
-    getComputedStyle(document.documentElement).getPropertyValue(name).trim() ||
-    fallback
-  );
-}
-
 function renderKeyboard(pitchData: PitchInfo[]): void {
   const active = new Set(pitchData.map((pitch) => pitch.midi));
   keyboard.replaceChildren();
@@ -478,8 +437,7 @@ function renderKeyboard(pitchData: PitchInfo[]): void {
   }
 }
 
-async function renderGuitarFingering(fingering: GuitarFingeringInfo | null): Promise<void> {
-  const renderToken = ++guitarRenderToken;
+function renderGuitarFingering(fingering: GuitarFingeringInfo | null): void {
   guitarFingering.replaceChildren();
   if (!fingering) {
     const empty = document.createElement("div");
@@ -492,20 +450,9 @@ async function renderGuitarFingering(fingering: GuitarFingeringInfo | null): Pro
   const chart = document.createElement("div");
   chart.id = "guitar-fingering-chart";
   chart.className = "guitar-chart";
+  chart.innerHTML = fingering.svg;
   guitarFingering.appendChild(chart);
 
-  const vex = await loadVexChordsRenderer();
-  if (renderToken !== guitarRenderToken) return;
-  if (vex) {
-    try {
-      drawVexChord(vex, `#${chart.id}`, fingering);
-    } catch (err) {
-      renderGuitarUnavailable(chart, err);
-    }
-  } else {
-    renderGuitarUnavailable(chart);
-  }
-
   const covered = document.createElement("div");
   covered.className = "guitar-note-list";
   for (const pitchClass of fingering.covered_pitch_classes || []) {
@@ -532,186 +479,6 @@ async function renderGuitarFingering(fingering: GuitarFingeringInfo | null): Pro
   }
 }
 
-function renderGuitarUnavailable(node: HTMLElement, reason: unknown = null): void {
-  if (reason) console.error("VexChords render failed", reason);
-  node.replaceChildren();
-  const empty = document.createElement("div");
-  empty.className = "guitar-empty";
-  empty.textContent = reason
-    ? `Guitar chart failed: ${reason instanceof Error ? reason.message : String(reason)}`
-    : "VexChords unavailable";
-  node.appendChild(empty);
-}
-
-function vexChordsRenderer(): unknown {
-  return window.vexchords || window.VexChords || window.Vexchords || null;
-}
-
-function loadVexChordsRenderer(): Promise<VexRenderer | null> {
-  const globalRenderer = normalizeVexChordsRenderer(vexChordsRenderer());
-  if (globalRenderer) return Promise.resolve(globalRenderer);
-  vexChordsPromise ||= loadScript(vexChordsUrl)
-    .then(() => normalizeVexChordsRenderer(vexChordsRenderer()))
-    .catch((err) => {
-      console.error("VexChords load failed", err);
-      return null;
-    });
-  return vexChordsPromise;
-}
-
-function loadScript(src: string): Promise<void> {
-  return new Promise((resolve, reject) => {
-    const existing = document.querySelector(`script[src="${src}"]`);
-    if (existing) {
-      existing.addEventListener("load", () => resolve(), { once: true });
-      existing.addEventListener("error", () => reject(new Error(`Failed to load ${src}`)), {
-        once: true,
-      });
-      return;
-    }
-
-    const script = document.createElement("script");
-    script.src = src;
-    script.async = true;
-    script.addEventListener("load", () => resolve(), { once: true });
-    script.addEventListener("error", () => reject(new Error(`Failed to load ${src}`)), {
-      once: true,
-    });
-    document.head.appendChild(script);
-  });
-}
-
-function normalizeVexChordsRenderer(module: unknown): VexRenderer | null {
-  const moduleRecord = module as Record<string, unknown> | null | undefined;
-  const defaultRecord = moduleRecord?.default as Record<string, unknown> | null | undefined;
-  for (const candidate of [
-    module,
-    moduleRecord?.default,
-    moduleRecord?.vexchords,
-    defaultRecord?.default,
-  ]) {
-    if (!candidate) continue;
-    const candidateRecord = candidate as Record<string, unknown>;
-    if (typeof candidateRecord.draw === "function") {
-      return {
-        draw: candidateRecord.draw.bind(candidate) as VexRenderer["draw"],
-      };
-    }
-    if (typeof candidateRecord.ChordBox === "function") {
-      return { ChordBox: candidateRecord.ChordBox as VexChordBoxConstructor };
-    }
-    if (typeof candidate === "function") {
-      return { ChordBox: candidate as VexChordBoxConstructor };
-    }
-  }
-  return null;
-}
-
-function drawVexChord(
-  vex: VexRenderer,
-  selector: string,
-  fingering: GuitarFingeringInfo,
-): void {
-  const chord = vexChordForFingering(fingering);
-  const options = vexChordOptions(fingering);
-  if (typeof vex.draw === "function") {
-    vex.draw(selector, chord, options);
-    return;
-  }
-  if (typeof vex.ChordBox === "function") {
-    const chordBox = new vex.ChordBox(selector, options);
-    chordBox.draw(chord);
-    return;
-  }
-  throw new Error("VexChords renderer has no draw API");
-}
-
-function vexChordOptions(fingering: GuitarFingeringInfo): VexChordOptions {
-  const ink = cssVar("--ink", "#151515");
-  const panel = cssVar("--panel", "#ffffff");
-  const accent = cssVar("--accent", "#0f766e");
-  const muted = cssVar("--muted", "#61646b");
-  return {
-    width: 190,
-    height: 220,
-    numStrings: Math.max(1, (fingering.strings || []).length),
-    numFrets: 5,
-    showTuning: true,
-    defaultColor: ink,
-    bgColor: panel,
-    strokeColor: accent,
-    textColor: ink,
-    stringColor: muted,
-    fretColor: muted,
-    labelColor: muted,
-  };
-}
-
-function vexChordForFingering(fingering: GuitarFingeringInfo): VexChordDefinition {
-  const position = fingering.base_fret > 1 ? fingering.base_fret : 0;
-  const barres = vexBarresForFingering(fingering, position);
-  return {
-    chord: (fingering.strings || []).map((string) => [
-      string.string_number,
-      vexFretValue(string.fret, position),
-    ]),
-    position,
-    bars: barres,
-    barres,
-    tuning: (fingering.strings || []).map((string) => vexTuningLabel(string.string_name)),
-  };
-}
-
-function vexTuningLabel(value: string | null | undefined): string {
-  return String(value ?? "").replace(/-?\d+$/, "");
-}
-
-function vexFretValue(fret: number | null | undefined, position: number): number | "x" {
-  if (fret === null || fret === undefined) return "x";
-  if (fret === 0 || position <= 1) return fret;
-  return fret - position + 1;
-}
-
-function vexBarresForFingering(
-  fingering: GuitarFingeringInfo,
-  position: number,
-): VexBarre[] {
-  const groups = new Map<string, number[]>();
-  for (const string of fingering.strings || []) {
-    const fretValue = string.fret;
-    const finger = string.finger;
-    if (
-      fretValue === null ||
-      finger === null ||
-      !Number.isFinite(fretValue) ||
-      fretValue <= 0 ||
-      !Number.isFinite(finger)
-    ) {
-      continue;
-    }
-    const fret = vexFretValue(fretValue, position);
-    const key = `${finger}:${fret}`;
-    const strings = groups.get(key) || [];
-    strings.push(string.string_number);
-    groups.set(key, strings);
-  }
-
-  const barres: VexBarre[] = [];
-  for (const [key, strings] of groups) {
-    if (strings.length < 2) continue;
-    const sorted = strings.sort((left, right) => right - left);
-    const isContiguous = sorted.every((string, index) => index === 0 || sorted[index - 1] - string === 1);
-    if (!isContiguous) continue;
-    const [, fret] = key.split(":");
-    barres.push({
-      fromString: sorted[0],
-      toString: sorted[sorted.length - 1],
-      fret: Number(fret),
-    });
-  }
-  return barres;
-}
-
 function displayPitchClassName(pitchClass: number): string {
   return pcNames[((pitchClass % 12) + 12) % 12];
 }
@@ -833,18 +600,13 @@ function render(data: ChordAnalysis): void {
   renderFacts(data);
   renderChips(pitchedNames, data.pitched_common_names);
   renderResolutions(data);
-  void renderGuitarFingering(data.guitar_fingering);
+  renderGuitarFingering(data.guitar_fingering);
   renderPitches(data);
   renderKeyboard(data.pitches);
   renderNotation(data);
   renderPolyrhythmLink(data);
 }
 
-document.addEventListener("music21-theme-change", () => {
-  if (!currentAnalysis) return;
-  void renderGuitarFingering(currentAnalysis.guitar_fingering);
-});
-
 function renderPolyrhythmLink(data: ChordAnalysis): void {
   const rhythm = data.polyrhythm_input || "1";
   const url = new URL(
@@ -1,9 +1,9 @@
 //! WebAssembly bindings for the browser examples.
 
 use music21_rs::{
-    ALL_TUNING_SYSTEMS, Chord, ChordResolutionSuggestion, Error, GuitarTuning, Key, KnownChordType,
-    Pitch, Polyrhythm, Result, ScalaArchive, ScalaScale, TuningSystem, abc_chord, abc_duration,
-    pitch_class_name,
+    ALL_TUNING_SYSTEMS, Chord, ChordResolutionSuggestion, DiagramOptions, Error, GuitarTuning, Key,
+    KnownChordType, Pitch, Polyrhythm, Result, ScalaArchive, ScalaScale, TuningSystem, abc_chord,
+    abc_duration, pitch_class_name,
 };
 use serde::Serialize;
 use std::{collections::BTreeSet, fmt};
@@ -89,6 +89,7 @@ struct GuitarFingeringInfo {
     omitted_pitch_spaces: Vec<i32>,
     covered_pitch_classes: Vec<u8>,
     omitted_pitch_classes: Vec<u8>,
+    svg: String,
 }
 
 #[derive(Serialize)]
@@ -378,7 +379,8 @@ fn analyze_chord_inner(
         key_estimate,
         roman_numeral_context,
         roman_numeral_estimate,
-        guitar_fingering: guitar_fingering.map(guitar_fingering_info),
+        guitar_fingering: guitar_fingering
+            .map(|fingering| guitar_fingering_info(fingering, root_pitch_class(&chord))),
         polyrhythm_input: chord.polyrhythm_ratio_string(),
         resolution_chords,
         pitches,
@@ -934,7 +936,15 @@ fn display_key_context(key: &Key) -> String {
     format!("{} {}", display_pitch_name(&key.tonic().name()), key.mode())
 }
 
-fn guitar_fingering_info(fingering: music21_rs::GuitarFingering) -> GuitarFingeringInfo {
+fn guitar_fingering_info(
+    fingering: music21_rs::GuitarFingering,
+    root_pitch_class: Option<u8>,
+) -> GuitarFingeringInfo {
+    let mut diagram_options = DiagramOptions::default();
+    if let Some(pitch_class) = root_pitch_class {
+        diagram_options = diagram_options.with_root(pitch_class);
+    }
+    let svg = fingering.to_svg(&diagram_options);
     GuitarFingeringInfo {
         strings: fingering
             .strings
@@ -959,9 +969,17 @@ fn guitar_fingering_info(fingering: music21_rs::GuitarFingering) -> GuitarFinger
         omitted_pitch_spaces: fingering.omitted_pitch_spaces,
         covered_pitch_classes: fingering.covered_pitch_classes,
         omitted_pitch_classes: fingering.omitted_pitch_classes,
+        svg,
     }
 }
 
+fn root_pitch_class(chord: &Chord) -> Option<u8> {
+    let name = chord.root_pitch_name()?;
+    let (step, accidentals) = name.split_at(1);
+    let pitch = Pitch::from_name(format!("{step}{}", accidentals.replace('b', "-"))).ok()?;
+    Some((pitch.ps().round() as i32).rem_euclid(12) as u8)
+}
+
 fn resolution_chord_info(suggestion: ChordResolutionSuggestion) -> ResolutionChordInfo {
     let chord = suggestion.chord;
     ResolutionChordInfo {
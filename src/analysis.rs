@@ -1,20 +1,239 @@
+//! Key finding after music21's `analysis.discrete` family.
+//!
+//! A pitch-class histogram is correlated against a major and a minor weight
+//! profile rotated to every tonic. The profile is selectable, the histogram
+//! can weigh pitches by how long they sound, and modal keys can join major
+//! and minor as candidates. [`analyze_key_windows`] repeats the estimate over
+//! a sliding window to follow modulations through a [`Stream`].
+
+use std::{fmt, str::FromStr};
+
 use crate::{
     chord::Chord,
-    defaults::{FloatType, IntegerType},
+    defaults::FloatType,
     error::{Error, Result},
     key::Key,
     pitch::Pitch,
+    stream::Stream,
 };
 
-const MAJOR_PROFILE: [FloatType; 12] = [
-    6.35, 2.23, 3.48, 2.33, 4.38, 4.09, 2.52, 5.19, 2.39, 3.66, 2.29, 2.88,
-];
-const MINOR_PROFILE: [FloatType; 12] = [
-    6.33, 2.68, 3.52, 5.38, 2.60, 3.53, 2.54, 4.75, 3.98, 2.69, 3.34, 3.17,
-];
 const TONICS: [&str; 12] = [
     "C", "C#", "D", "E-", "E", "F", "F#", "G", "A-", "A", "B-", "B",
 ];
+const ENHARMONIC_TONICS: [&str; 12] = [
+    "B#", "D-", "C##", "D#", "F-", "E#", "G-", "F##", "G#", "B--", "A#", "C-",
+];
+const MAJOR_DEGREES: [usize; 7] = [0, 2, 4, 5, 7, 9, 11];
+const MINOR_DEGREES: [usize; 7] = [0, 2, 3, 5, 7, 8, 10];
+/// Church modes other than major and minor, with their scale degrees and
+/// whether they borrow the major or the minor profile's degree weights.
+const MODES: [(&str, [usize; 7], bool); 5] = [
+    ("dorian", [0, 2, 3, 5, 7, 9, 10], false),
+    ("phrygian", [0, 1, 3, 5, 7, 8, 10], false),
+    ("lydian", [0, 2, 4, 6, 7, 9, 11], true),
+    ("mixolydian", [0, 2, 4, 5, 7, 9, 10], true),
+    ("locrian", [0, 1, 3, 5, 6, 8, 10], false),
+];
+const OFFSET_EPSILON: FloatType = 1e-9;
+
+/// A pitch-class weight profile for key finding.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum KeyProfile {
+    /// Krumhansl and Kessler's probe-tone ratings (1982), the weights of the
+    /// classic Krumhansl–Schmuckler algorithm.
+    #[default]
+    KrumhanslKessler,
+    /// Temperley's corpus weights from the Kostka–Payne harmony textbook
+    /// excerpts (2007).
+    TemperleyKostkaPayne,
+    /// Aarden's weights from the Essen folksong collection (2003).
+    AardenEssen,
+    /// Bellman and Budge's weights from chord usage in common-practice
+    /// repertoire (2005).
+    BellmanBudge,
+    /// Sapp's simple weights: tonic and dominant 2, other scale degrees 1.
+    Simple,
+}
+
+impl KeyProfile {
+    /// Every profile, in declaration order.
+    pub const ALL: [KeyProfile; 5] = [
+        KeyProfile::KrumhanslKessler,
+        KeyProfile::TemperleyKostkaPayne,
+        KeyProfile::AardenEssen,
+        KeyProfile::BellmanBudge,
+        KeyProfile::Simple,
+    ];
+
+    /// Returns the profile's name, as accepted by [`KeyProfile::from_str`].
+    pub fn name(self) -> &'static str {
+        match self {
+            KeyProfile::KrumhanslKessler => "krumhansl-kessler",
+            KeyProfile::TemperleyKostkaPayne => "temperley-kostka-payne",
+            KeyProfile::AardenEssen => "aarden-essen",
+            KeyProfile::BellmanBudge => "bellman-budge",
+            KeyProfile::Simple => "simple",
+        }
+    }
+
+    /// Returns the major-key weights, indexed by semitones above the tonic.
+    pub fn major_weights(self) -> [FloatType; 12] {
+        match self {
+            KeyProfile::KrumhanslKessler => [
+                6.35, 2.23, 3.48, 2.33, 4.38, 4.09, 2.52, 5.19, 2.39, 3.66, 2.29, 2.88,
+            ],
+            KeyProfile::TemperleyKostkaPayne => [
+                0.748, 0.060, 0.488, 0.082, 0.670, 0.460, 0.096, 0.715, 0.104, 0.366, 0.057, 0.400,
+            ],
+            KeyProfile::AardenEssen => [
+                17.7661, 0.145624, 14.9265, 0.160186, 19.8049, 11.3587, 0.291248, 22.062, 0.145624,
+                8.15494, 0.232998, 4.95122,
+            ],
+            KeyProfile::BellmanBudge => [
+                16.80, 0.86, 12.95, 1.41, 13.49, 11.93, 1.25, 20.28, 1.80, 8.04, 0.62, 10.57,
+            ],
+            KeyProfile::Simple => [2.0, 0.0, 1.0, 0.0, 1.0, 1.0, 0.0, 2.0, 0.0, 1.0, 0.0, 1.0],
+        }
+    }
+
+    /// Returns the minor-key weights, indexed by semitones above the tonic.
+    pub fn minor_weights(self) -> [FloatType; 12] {
+        match self {
+            KeyProfile::KrumhanslKessler => [
+                6.33, 2.68, 3.52, 5.38, 2.60, 3.53, 2.54, 4.75, 3.98, 2.69, 3.34, 3.17,
+            ],
+            KeyProfile::TemperleyKostkaPayne => [
+                0.712, 0.084, 0.474, 0.618, 0.049, 0.460, 0.105, 0.747, 0.404, 0.067, 0.133, 0.330,
+            ],
+            KeyProfile::AardenEssen => [
+                18.2648, 0.737619, 14.0499, 16.8599, 0.702494, 14.4362, 0.702494, 18.6161, 4.56621,
+                1.93186, 7.37619, 1.75623,
+            ],
+            KeyProfile::BellmanBudge => [
+                18.16, 0.69, 12.99, 13.34, 1.07, 11.15, 1.38, 21.07, 7.49, 1.53, 0.92, 10.21,
+            ],
+            KeyProfile::Simple => [2.0, 0.0, 1.0, 1.0, 0.0, 1.0, 0.0, 2.0, 1.0, 0.0, 0.5, 0.5],
+        }
+    }
+
+    /// Returns the weights for `mode`, indexed by semitones above the tonic.
+    ///
+    /// Major and minor (and their aliases ionian and aeolian) use the
+    /// profile's own weights. The other church modes have no published
+    /// profiles; they place the major or minor degree weights, matching the
+    /// mode's third, on the mode's scale degrees, and give the five remaining
+    /// pitch classes the mean chromatic weight.
+    pub fn mode_weights(self, mode: &str) -> Result<[FloatType; 12]> {
+        let mode = mode.to_lowercase();
+        match mode.as_str() {
+            "major" | "ionian" => return Ok(self.major_weights()),
+            "minor" | "aeolian" => return Ok(self.minor_weights()),
+            _ => {}
+        }
+        let (_, degrees, major) = MODES
+            .iter()
+            .find(|(name, _, _)| *name == mode)
+            .ok_or_else(|| Error::Analysis(format!("no key profile for mode {mode:?}")))?;
+        let (parent, parent_degrees) = if *major {
+            (self.major_weights(), MAJOR_DEGREES)
+        } else {
+            (self.minor_weights(), MINOR_DEGREES)
+        };
+        let chromatic = (0..12)
+            .filter(|pc| !parent_degrees.contains(pc))
+            .map(|pc| parent[pc])
+            .sum::<FloatType>()
+            / 5.0;
+        let mut weights = [chromatic; 12];
+        for (degree, parent_degree) in degrees.iter().zip(parent_degrees) {
+            weights[*degree] = parent[parent_degree];
+        }
+        Ok(weights)
+    }
+}
+
+impl fmt::Display for KeyProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for KeyProfile {
+    type Err = Error;
+
+    fn from_str(name: &str) -> Result<Self> {
+        let normalized = name.trim().to_lowercase().replace(['_', ' ', '–'], "-");
+        match normalized.as_str() {
+            "krumhansl-kessler" | "krumhansl" | "kk" | "krumhansl-schmuckler" => {
+                Ok(KeyProfile::KrumhanslKessler)
+            }
+            "temperley-kostka-payne" | "temperley" | "tkp" => Ok(KeyProfile::TemperleyKostkaPayne),
+            "aarden-essen" | "aarden" => Ok(KeyProfile::AardenEssen),
+            "bellman-budge" | "bellman" => Ok(KeyProfile::BellmanBudge),
+            "simple" | "sapp" => Ok(KeyProfile::Simple),
+            _ => Err(Error::Analysis(format!("unknown key profile {name:?}"))),
+        }
+    }
+}
+
+/// Options for key finding from a [`Stream`] or histogram.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KeyFindingOptions {
+    profile: KeyProfile,
+    modal: bool,
+    durations: bool,
+}
+
+impl Default for KeyFindingOptions {
+    fn default() -> Self {
+        Self {
+            profile: KeyProfile::default(),
+            modal: false,
+            durations: true,
+        }
+    }
+}
+
+impl KeyFindingOptions {
+    /// Returns the options with a different weight profile. Krumhansl–Kessler
+    /// by default.
+    pub fn with_profile(mut self, profile: KeyProfile) -> Self {
+        self.profile = profile;
+        self
+    }
+
+    /// Returns the options with dorian, phrygian, lydian, mixolydian and
+    /// locrian keys added, or not, to the major and minor candidates. Off by
+    /// default.
+    pub fn with_modal_keys(mut self, modal: bool) -> Self {
+        self.modal = modal;
+        self
+    }
+
+    /// Returns the options weighing each pitch by its length in quarters, or
+    /// counting every sounding pitch once. Weighted by default.
+    pub fn with_duration_weighting(mut self, durations: bool) -> Self {
+        self.durations = durations;
+        self
+    }
+
+    /// Returns the weight profile.
+    pub fn profile(&self) -> KeyProfile {
+        self.profile
+    }
+
+    /// Returns whether modal keys are candidates.
+    pub fn includes_modal_keys(&self) -> bool {
+        self.modal
+    }
+
+    /// Returns whether pitches are weighted by duration.
+    pub fn weights_by_duration(&self) -> bool {
+        self.durations
+    }
+}
 
 /// A ranked key estimate.
 #[derive(Clone, Debug)]
@@ -35,6 +254,42 @@ impl KeyEstimate {
     }
 }
 
+/// The best key for one window of a [`Stream`].
+#[derive(Clone, Debug)]
+pub struct WindowedKey {
+    start: FloatType,
+    end: FloatType,
+    estimate: KeyEstimate,
+    confidence: FloatType,
+}
+
+impl WindowedKey {
+    /// Returns the window's start offset in quarters.
+    pub fn start(&self) -> FloatType {
+        self.start
+    }
+
+    /// Returns the window's end offset in quarters.
+    pub fn end(&self) -> FloatType {
+        self.end
+    }
+
+    /// Returns the best key estimate for the window.
+    pub fn estimate(&self) -> &KeyEstimate {
+        &self.estimate
+    }
+
+    /// Returns the best key for the window.
+    pub fn key(&self) -> &Key {
+        &self.estimate.key
+    }
+
+    /// Returns the window's [`tonal_certainty`], from 0 to 1.
+    pub fn confidence(&self) -> FloatType {
+        self.confidence
+    }
+}
+
 /// Estimates likely keys from pitches using Krumhansl-Schmuckler profiles.
 pub fn estimate_key_from_pitches(pitches: &[Pitch]) -> Result<Vec<KeyEstimate>> {
     if pitches.is_empty() {
@@ -45,11 +300,10 @@ pub fn estimate_key_from_pitches(pitches: &[Pitch]) -> Result<Vec<KeyEstimate>>
 
     let mut histogram = [0.0; 12];
     for pitch in pitches {
-        let pc = (pitch.ps().round() as IntegerType).rem_euclid(12) as usize;
-        histogram[pc] += 1.0;
+        histogram[usize::from(pitch.pitch_class_number())] += 1.0;
     }
 
-    estimate_key_from_histogram(&histogram)
+    estimate_key_from_histogram(&histogram, &KeyFindingOptions::default())
 }
 
 /// Estimates likely keys from chords using Krumhansl-Schmuckler profiles.
@@ -58,12 +312,66 @@ pub fn estimate_key_from_chords(chords: &[Chord]) -> Result<Vec<KeyEstimate>> {
     estimate_key_from_pitches(&pitches)
 }
 
-fn estimate_key_from_histogram(histogram: &[FloatType; 12]) -> Result<Vec<KeyEstimate>> {
+/// Estimates likely keys from everything sounding in `stream`.
+///
+/// The histogram is built by [`pitch_class_histogram`] and ranked by
+/// [`estimate_key_from_histogram`].
+pub fn estimate_key_from_stream(
+    stream: &Stream,
+    options: &KeyFindingOptions,
+) -> Result<Vec<KeyEstimate>> {
+    let histogram = pitch_class_histogram(stream, options);
+    if histogram.iter().all(|weight| *weight == 0.0) {
+        return Err(Error::Analysis(
+            "key estimation needs at least one sounding pitch".to_string(),
+        ));
+    }
+    estimate_key_from_histogram(&histogram, options)
+}
+
+/// Returns the pitch-class histogram of `stream`, indexed from C.
+///
+/// Each note and chord tone adds its length in quarters, or 1 when
+/// [`KeyFindingOptions::weights_by_duration`] is off. Rests add nothing.
+pub fn pitch_class_histogram(stream: &Stream, options: &KeyFindingOptions) -> [FloatType; 12] {
+    histogram_between(
+        stream,
+        FloatType::NEG_INFINITY,
+        FloatType::INFINITY,
+        options,
+    )
+}
+
+/// Ranks every candidate key by how well its profile correlates with
+/// `histogram`, best first.
+///
+/// Candidates are the 24 major and minor keys, plus the five other church
+/// modes on every tonic when [`KeyFindingOptions::includes_modal_keys`] is
+/// set. Modal tonics take whichever enharmonic spelling needs fewer sharps
+/// or flats.
+pub fn estimate_key_from_histogram(
+    histogram: &[FloatType; 12],
+    options: &KeyFindingOptions,
+) -> Result<Vec<KeyEstimate>> {
+    let profile = options.profile;
+    let mut modes = vec![
+        ("major", profile.major_weights()),
+        ("minor", profile.minor_weights()),
+    ];
+    if options.modal {
+        for (mode, _, _) in MODES {
+            modes.push((mode, profile.mode_weights(mode)?));
+        }
+    }
+
     let mut estimates = Vec::new();
     for (tonic_pc, tonic) in TONICS.iter().enumerate() {
-        for (mode, profile) in [("major", MAJOR_PROFILE), ("minor", MINOR_PROFILE)] {
-            let key = Key::from_tonic_mode(tonic, mode)?;
-            let rotated = rotate_profile(&profile, tonic_pc);
+        for (mode, weights) in &modes {
+            let key = match *mode {
+                "major" | "minor" => Key::from_tonic_mode(tonic, *mode)?,
+                _ => modal_key(tonic_pc, mode)?,
+            };
+            let rotated = rotate_profile(weights, tonic_pc);
             estimates.push(KeyEstimate {
                 key,
                 score: correlation(histogram, &rotated),
@@ -80,6 +388,113 @@ fn estimate_key_from_histogram(histogram: &[FloatType; 12]) -> Result<Vec<KeyEst
     Ok(estimates)
 }
 
+/// Returns how clearly the best of `estimates` stands out, from 0 to 1.
+///
+/// After music21's `tonalCertainty`: the mean of the leader's correlation
+/// and its lead over the runner-up. Estimates are expected best first, as
+/// the estimation functions return them.
+pub fn tonal_certainty(estimates: &[KeyEstimate]) -> FloatType {
+    let Some(best) = estimates.first() else {
+        return 0.0;
+    };
+    let lead = estimates
+        .get(1)
+        .map_or(best.score, |runner_up| best.score - runner_up.score);
+    ((best.score.max(0.0) + lead) / 2.0).clamp(0.0, 1.0)
+}
+
+/// Estimates the key of each window of `stream`.
+///
+/// Windows are `window_length` quarters long and start every `step`
+/// quarters from offset 0 until the window reaching the end of the stream.
+/// Notes count only for the part of them inside a window when weighted by
+/// duration. Windows in which nothing sounds are skipped.
+pub fn analyze_key_windows(
+    stream: &Stream,
+    window_length: FloatType,
+    step: FloatType,
+    options: &KeyFindingOptions,
+) -> Result<Vec<WindowedKey>> {
+    if !(window_length > 0.0 && step > 0.0) {
+        return Err(Error::Analysis(format!(
+            "key windows need a positive length and step, not {window_length} and {step}"
+        )));
+    }
+    let end_offset = stream.end_offset();
+    let mut windows = Vec::new();
+    let mut start: FloatType = 0.0;
+    loop {
+        let end = start + window_length;
+        let histogram = histogram_between(stream, start, end, options);
+        if histogram.iter().any(|weight| *weight > 0.0) {
+            let mut estimates = estimate_key_from_histogram(&histogram, options)?;
+            let confidence = tonal_certainty(&estimates);
+            windows.push(WindowedKey {
+                start,
+                end,
+                estimate: estimates.swap_remove(0),
+                confidence,
+            });
+        }
+        if end >= end_offset - OFFSET_EPSILON {
+            break;
+        }
+        start += step;
+    }
+    Ok(windows)
+}
+
+impl Stream {
+    /// Estimates likely keys for the whole stream.
+    ///
+    /// See [`estimate_key_from_stream`].
+    pub fn estimate_key(&self, options: &KeyFindingOptions) -> Result<Vec<KeyEstimate>> {
+        estimate_key_from_stream(self, options)
+    }
+
+    /// Estimates the key of each window of the stream.
+    ///
+    /// See [`analyze_key_windows`].
+    pub fn key_windows(
+        &self,
+        window_length: FloatType,
+        step: FloatType,
+        options: &KeyFindingOptions,
+    ) -> Result<Vec<WindowedKey>> {
+        analyze_key_windows(self, window_length, step, options)
+    }
+}
+
+fn histogram_between(
+    stream: &Stream,
+    start: FloatType,
+    end: FloatType,
+    options: &KeyFindingOptions,
+) -> [FloatType; 12] {
+    let mut histogram = [0.0; 12];
+    for event in stream.events() {
+        let onset = event.offset();
+        let release = onset + event.element().quarter_length();
+        let sounding = release.min(end) - onset.max(start);
+        if sounding <= OFFSET_EPSILON {
+            continue;
+        }
+        let weight = if options.durations { sounding } else { 1.0 };
+        for pitch in event.element().pitches() {
+            histogram[usize::from(pitch.pitch_class_number())] += weight;
+        }
+    }
+    histogram
+}
+
+fn modal_key(tonic_pc: usize, mode: &str) -> Result<Key> {
+    [TONICS[tonic_pc], ENHARMONIC_TONICS[tonic_pc]]
+        .into_iter()
+        .filter_map(|tonic| Key::from_tonic_mode(tonic, mode).ok())
+        .min_by_key(|key| key.sharps().abs())
+        .ok_or_else(|| Error::Analysis(format!("cannot spell a {mode} key on {tonic_pc}")))
+}
+
 fn rotate_profile(profile: &[FloatType; 12], tonic_pc: usize) -> [FloatType; 12] {
     let mut rotated = [0.0; 12];
     for pc in 0..12 {
@@ -114,6 +529,18 @@ fn correlation(left: &[FloatType; 12], right: &[FloatType; 12]) -> FloatType {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{duration::Duration, note::Note, stream::test_stream};
+
+    fn note(name: &str, quarter_length: FloatType) -> Note {
+        Note::from_name(name)
+            .unwrap()
+            .with_duration(Duration::new(quarter_length).unwrap())
+    }
+
+    fn best(estimates: &[KeyEstimate]) -> (String, String) {
+        let key = estimates[0].key();
+        (key.tonic().name(), key.mode().to_string())
+    }
 
     #[test]
     fn estimates_c_major_from_tonic_triad_material() {
@@ -133,4 +560,119 @@ mod tests {
         let estimates = estimate_key_from_chords(&chords).unwrap();
         assert!(!estimates.is_empty());
     }
+
+    #[test]
+    fn every_profile_finds_a_minor_and_names_round_trip() {
+        let stream = test_stream(&[
+            ("A3", 2.0),
+            ("B3", 0.5),
+            ("C4", 1.0),
+            ("D4", 0.5),
+            ("E4", 2.0),
+            ("G#4", 1.0),
+            ("A4", 3.0),
+            ("E4", 1.0),
+            ("C4", 1.0),
+            ("A3", 2.0),
+        ]);
+        for profile in KeyProfile::ALL {
+            let options = KeyFindingOptions::default().with_profile(profile);
+            let estimates = stream.estimate_key(&options).unwrap();
+            assert_eq!(best(&estimates), ("A".into(), "minor".into()), "{profile}");
+            assert_eq!(estimates.len(), 24);
+            assert_eq!(profile.name().parse::<KeyProfile>().unwrap(), profile);
+        }
+        assert_eq!(
+            "TKP".parse::<KeyProfile>().unwrap(),
+            KeyProfile::TemperleyKostkaPayne
+        );
+        assert!("bach".parse::<KeyProfile>().is_err());
+    }
+
+    #[test]
+    fn durations_weigh_the_histogram() {
+        // Long Gs and short Cs: weighted, G dominates; counted, C and G tie.
+        let stream = test_stream(&[("G4", 3.0), ("C4", 0.5), ("G4", 3.0), ("C4", 0.5)]);
+        let weighted = pitch_class_histogram(&stream, &KeyFindingOptions::default());
+        assert_eq!((weighted[7], weighted[0]), (6.0, 1.0));
+        let counted = pitch_class_histogram(
+            &stream,
+            &KeyFindingOptions::default().with_duration_weighting(false),
+        );
+        assert_eq!((counted[7], counted[0]), (2.0, 2.0));
+        assert!(estimate_key_from_stream(&Stream::new(), &KeyFindingOptions::default()).is_err());
+    }
+
+    #[test]
+    fn modal_keys_join_the_candidates() {
+        let profile = KeyProfile::Simple;
+        let dorian = profile.mode_weights("Dorian").unwrap();
+        assert_eq!((dorian[0], dorian[9], dorian[8]), (2.0, 1.0, 0.1));
+        assert!(profile.mode_weights("bebop").is_err());
+
+        // D dorian: a tune circling D and A with B natural and C natural.
+        let stream = test_stream(&[
+            ("D4", 2.0),
+            ("E4", 0.5),
+            ("F4", 0.5),
+            ("G4", 1.0),
+            ("A4", 2.0),
+            ("B4", 1.0),
+            ("C5", 0.5),
+            ("A4", 0.5),
+            ("D5", 2.0),
+            ("A4", 1.0),
+            ("F4", 1.0),
+            ("D4", 3.0),
+        ]);
+        let options = KeyFindingOptions::default()
+            .with_profile(profile)
+            .with_modal_keys(true);
+        let estimates = stream.estimate_key(&options).unwrap();
+        assert_eq!(estimates.len(), 84);
+        assert_eq!(best(&estimates), ("D".into(), "dorian".into()));
+    }
+
+    #[test]
+    fn windows_follow_a_modulation_with_confidence() {
+        let mut stream = test_stream(&[
+            ("C4", 1.0),
+            ("E4", 1.0),
+            ("G4", 1.0),
+            ("F4", 0.5),
+            ("D4", 0.5),
+            ("B3", 1.0),
+            ("D4", 1.0),
+            ("C4", 2.0),
+        ]);
+        for (name, quarter_length) in [
+            ("G4", 1.0),
+            ("B4", 1.0),
+            ("D5", 1.0),
+            ("C5", 0.5),
+            ("A4", 0.5),
+            ("F#4", 1.0),
+            ("A4", 1.0),
+            ("G4", 2.0),
+        ] {
+            stream.push(note(name, quarter_length));
+        }
+        let windows = stream
+            .key_windows(8.0, 4.0, &KeyFindingOptions::default())
+            .unwrap();
+        let spans = windows
+            .iter()
+            .map(|window| (window.start(), window.end()))
+            .collect::<Vec<_>>();
+        assert_eq!(spans, vec![(0.0, 8.0), (4.0, 12.0), (8.0, 16.0)]);
+        assert_eq!(windows[0].key().tonic().name(), "C");
+        assert_eq!(windows[2].key().tonic().name(), "G");
+        assert!(windows[0].confidence() > windows[1].confidence());
+        assert!(
+            windows
+                .iter()
+                .all(|window| (0.0..=1.0).contains(&window.confidence()))
+        );
+        assert!(analyze_key_windows(&stream, 0.0, 1.0, &KeyFindingOptions::default()).is_err());
+    }
 }
@@ -6,8 +6,21 @@ use crate::{
 use std::collections::BTreeSet;
 
 const STANDARD_TUNING: [&str; 6] = ["E2", "A2", "D3", "G3", "B3", "E4"];
+const UKULELE_TUNING: [&str; 4] = ["G4", "C4", "E4", "A4"];
+const BANJO_TUNING: [&str; 5] = ["G4", "D3", "G3", "B3", "D4"];
+/// The fret where a five-string banjo's short fifth string starts.
+const BANJO_FIFTH_STRING_FRET: u8 = 5;
+const BASS_TUNING: [&str; 4] = ["E1", "A1", "D2", "G2"];
 const MAX_FRET: u8 = 12;
 const MAX_FRET_SPAN: u8 = 4;
+/// The highest fret a guitar shape may start at and still count as open.
+const OPEN_POSITION_FRET: u8 = 2;
+/// The ukulele's frets are close enough that a shape starting at the third
+/// fret, such as its `0 0 0 3` C, is still played in open position.
+const UKULELE_OPEN_POSITION_FRET: u8 = 3;
+const DEFAULT_SHAPE_LIMIT: usize = 10;
+/// Difficulty added per fret the hand moves between consecutive shapes.
+const MOVEMENT_WEIGHT: usize = 20;
 
 /// Open-string pitch data for a guitar tuning.
 ///
@@ -22,20 +35,57 @@ pub struct GuitarTuningString {
     pub pitch_space: IntegerType,
     /// Open-string pitch class.
     pub pitch_class: u8,
+    /// The fret a short string starts at, such as a banjo's fifth string at
+    /// the fifth fret, or `0` for a full-length string.
+    ///
+    /// A short string sounds its open pitch unstopped and can only be stopped
+    /// above this fret, counting frets as on the full-length strings.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub first_fret: u8,
+}
+
+impl GuitarTuningString {
+    /// Returns the pitch space the string sounds at `fret`, or `None` for a
+    /// fret below a short string's start.
+    pub fn fret_pitch_space(&self, fret: u8) -> Option<IntegerType> {
+        match fret {
+            0 => Some(self.pitch_space),
+            fret if fret <= self.first_fret => None,
+            fret => Some(self.pitch_space + IntegerType::from(fret - self.first_fret)),
+        }
+    }
+
+    /// Returns the fret that sounds `pitch_space` on this string, if any.
+    pub fn fret_for_pitch_space(&self, pitch_space: IntegerType) -> Option<u8> {
+        match pitch_space - self.pitch_space {
+            0 => Some(0),
+            above if above > 0 => u8::try_from(above + IntegerType::from(self.first_fret)).ok(),
+            _ => None,
+        }
+    }
 }
 
-/// Guitar tuning used for fingering generation.
+/// Tuning of a fretted instrument used for fingering generation.
 ///
-/// Strings are ordered from low to high, for example standard six-string guitar
-/// tuning is `["E2", "A2", "D3", "G3", "B3", "E4"]`.
+/// Strings are ordered as strung, from the bass side of the neck to the treble
+/// side, for example standard six-string guitar tuning is
+/// `["E2", "A2", "D3", "G3", "B3", "E4"]`. Re-entrant tunings list their strings
+/// in the same physical order, so a ukulele's high G comes first.
 #[derive(Clone, Debug, Eq, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GuitarTuning {
     strings: Vec<GuitarTuningString>,
+    #[cfg_attr(feature = "serde", serde(default = "default_open_position"))]
+    open_position: u8,
+}
+
+#[cfg(feature = "serde")]
+fn default_open_position() -> u8 {
+    OPEN_POSITION_FRET
 }
 
 impl GuitarTuning {
-    /// Builds a tuning from low-to-high open-string pitch names.
+    /// Builds a tuning from open-string pitch names, bass side first.
     pub fn new<I, S>(strings: I) -> crate::Result<Self>
     where
         I: IntoIterator<Item = S>,
@@ -49,7 +99,8 @@ impl GuitarTuning {
                 Ok(GuitarTuningString {
                     name: pitch.name_with_octave(),
                     pitch_space,
-                    pitch_class: pitch_class(&pitch),
+                    pitch_class: pitch.pitch_class_number(),
+                    first_fret: 0,
                 })
             })
             .collect::<crate::Result<Vec<_>>>()?;
@@ -65,7 +116,10 @@ impl GuitarTuning {
             ));
         }
 
-        Ok(Self { strings })
+        Ok(Self {
+            strings,
+            open_position: OPEN_POSITION_FRET,
+        })
     }
 
     /// Returns standard six-string guitar tuning.
@@ -73,11 +127,47 @@ impl GuitarTuning {
         Self::new(STANDARD_TUNING).expect("standard guitar tuning should be valid")
     }
 
-    /// Returns the tuning strings from low to high.
+    /// Returns standard re-entrant ukulele tuning, G4-C4-E4-A4.
+    pub fn ukulele() -> Self {
+        Self::new(UKULELE_TUNING)
+            .expect("ukulele tuning should be valid")
+            .with_open_position(UKULELE_OPEN_POSITION_FRET)
+    }
+
+    /// Returns open-G five-string banjo tuning, G4-D3-G3-B3-D4.
+    ///
+    /// The short fifth string starts at the fifth fret, so it is played open
+    /// or stopped from the sixth fret up.
+    pub fn banjo() -> Self {
+        let mut banjo = Self::new(BANJO_TUNING).expect("banjo tuning should be valid");
+        banjo.strings[0].first_fret = BANJO_FIFTH_STRING_FRET;
+        banjo
+    }
+
+    /// Returns standard four-string bass tuning, E1-A1-D2-G2.
+    pub fn bass() -> Self {
+        Self::new(BASS_TUNING).expect("bass tuning should be valid")
+    }
+
+    /// Returns the tuning with shapes starting at or below `fret` counted as
+    /// open position, where open strings make a shape easier. The default is
+    /// 2, or 3 for [`Self::ukulele`], whose frets are closer together.
+    pub fn with_open_position(mut self, fret: u8) -> Self {
+        self.open_position = fret;
+        self
+    }
+
+    /// Returns the tuning strings, bass side first.
     pub fn strings(&self) -> &[GuitarTuningString] {
         &self.strings
     }
 
+    /// Returns the highest fret a shape may start at and still count as open
+    /// position.
+    pub fn open_position(&self) -> u8 {
+        self.open_position
+    }
+
     fn len(&self) -> usize {
         self.strings.len()
     }
@@ -89,6 +179,80 @@ impl Default for GuitarTuning {
     }
 }
 
+/// Neck and hand limits for fingering generation.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FingeringOptions {
+    fret_count: u8,
+    capo: u8,
+    max_stretch: u8,
+    limit: usize,
+}
+
+impl Default for FingeringOptions {
+    fn default() -> Self {
+        Self {
+            fret_count: MAX_FRET,
+            capo: 0,
+            max_stretch: MAX_FRET_SPAN,
+            limit: DEFAULT_SHAPE_LIMIT,
+        }
+    }
+}
+
+impl FingeringOptions {
+    /// Returns the options searching frets up to `fret_count`. The default
+    /// is 12.
+    pub fn with_fret_count(mut self, fret_count: u8) -> Self {
+        self.fret_count = fret_count;
+        self
+    }
+
+    /// Returns the options with a capo at `capo`.
+    ///
+    /// Frets in the resulting fingerings count from the capo, and the
+    /// open-string fields describe each string as the capo stops it.
+    pub fn with_capo(mut self, capo: u8) -> Self {
+        self.capo = capo;
+        self
+    }
+
+    /// Returns the options allowing at most `max_stretch` frets between the
+    /// lowest and highest fretted notes. The default is 4; below the fifth
+    /// fret, where frets are wider, one fret less is allowed.
+    pub fn with_max_stretch(mut self, max_stretch: u8) -> Self {
+        self.max_stretch = max_stretch;
+        self
+    }
+
+    /// Returns the options keeping the `limit` easiest shapes. The default is
+    /// 10.
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// Returns the highest fret searched.
+    pub fn fret_count(&self) -> u8 {
+        self.fret_count
+    }
+
+    /// Returns the capo fret, `0` for none.
+    pub fn capo(&self) -> u8 {
+        self.capo
+    }
+
+    /// Returns the widest allowed stretch in frets.
+    pub fn max_stretch(&self) -> u8 {
+        self.max_stretch
+    }
+
+    /// Returns how many shapes are kept.
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+}
+
 /// One string in a suggested guitar fingering.
 #[derive(Clone, Debug, Eq, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -131,6 +295,12 @@ pub struct GuitarFingering {
     pub covered_pitch_classes: Vec<u8>,
     /// Chord pitch classes not present in the fingering.
     pub omitted_pitch_classes: Vec<u8>,
+    /// Difficulty score; lower is easier.
+    ///
+    /// Missing chord tones, muted strings, wide stretches and high positions
+    /// all add to it.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub difficulty: usize,
 }
 
 #[derive(Clone, Debug)]
@@ -146,6 +316,38 @@ struct Candidate {
     score: usize,
 }
 
+/// The easiest shapes found so far, easiest first.
+#[derive(Clone, Debug)]
+struct Ranked {
+    limit: usize,
+    candidates: Vec<Candidate>,
+}
+
+impl Ranked {
+    /// The score a new shape must beat to be kept.
+    fn bound(&self) -> usize {
+        if self.candidates.len() < self.limit {
+            usize::MAX
+        } else {
+            self.candidates
+                .last()
+                .map_or(usize::MAX, |worst| worst.score)
+        }
+    }
+
+    fn insert(&mut self, candidate: Candidate) {
+        if candidate.score >= self.bound() {
+            return;
+        }
+        // Equal scores keep the order they were found in.
+        let index = self
+            .candidates
+            .partition_point(|kept| kept.score <= candidate.score);
+        self.candidates.insert(index, candidate);
+        self.candidates.truncate(self.limit);
+    }
+}
+
 #[derive(Clone, Debug)]
 struct Barre {
     fret: u8,
@@ -169,6 +371,221 @@ pub(crate) fn suggested_guitar_fingering_with_tuning(
     chord: &Chord,
     tuning: &GuitarTuning,
 ) -> Option<GuitarFingering> {
+    ranked_guitar_fingerings(chord, tuning, &FingeringOptions::default().with_limit(1))
+        .into_iter()
+        .next()
+}
+
+pub(crate) fn ranked_guitar_fingerings(
+    chord: &Chord,
+    tuning: &GuitarTuning,
+    fingering_options: &FingeringOptions,
+) -> Vec<GuitarFingering> {
+    ranked_candidates(chord, tuning, fingering_options)
+        .map(|ranked| {
+            ranked
+                .candidates
+                .into_iter()
+                .map(|candidate| candidate.fingering)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Picks one shape per chord, trading each shape's difficulty against how far
+/// the hand moves from the previous one.
+///
+/// Each chord's [`FingeringOptions::limit`] easiest shapes are considered.
+/// The hand is placed with one finger per fret, and every fret it must shift
+/// between shapes costs a little more than a fret of extra stretch. Returns
+/// `None` when some chord has no playable shape.
+pub fn optimize_fingering_sequence(
+    chords: &[Chord],
+    tuning: &GuitarTuning,
+    options: &FingeringOptions,
+) -> Option<Vec<GuitarFingering>> {
+    let candidates = chords
+        .iter()
+        .map(|chord| {
+            let shapes = ranked_guitar_fingerings(chord, tuning, options);
+            (!shapes.is_empty()).then_some(shapes)
+        })
+        .collect::<Option<Vec<_>>>()?;
+    Some(cheapest_fingering_path(candidates))
+}
+
+/// The path search behind [`optimize_fingering_sequence`], over shapes the
+/// caller has already found: picks one shape from each non-empty list,
+/// trading difficulty against hand movement.
+pub(crate) fn cheapest_fingering_path(
+    candidates: Vec<Vec<GuitarFingering>>,
+) -> Vec<GuitarFingering> {
+    let Some(first) = candidates.first() else {
+        return Vec::new();
+    };
+
+    // Cheapest total cost ending on each shape, with the shape before it.
+    let mut costs = vec![
+        first
+            .iter()
+            .map(|shape| (shape.difficulty, 0))
+            .collect::<Vec<_>>(),
+    ];
+    for (index, shapes) in candidates.iter().enumerate().skip(1) {
+        let previous = &candidates[index - 1];
+        let previous_costs = &costs[index - 1];
+        let step = shapes
+            .iter()
+            .map(|shape| {
+                previous
+                    .iter()
+                    .zip(previous_costs)
+                    .enumerate()
+                    .map(|(from, (before, (cost, _)))| {
+                        let movement = usize::from(hand_movement(before, shape));
+                        (cost + movement * MOVEMENT_WEIGHT + shape.difficulty, from)
+                    })
+                    .min()
+                    .expect("every chord has at least one shape")
+            })
+            .collect();
+        costs.push(step);
+    }
+
+    let last = costs.last().expect("at least one chord");
+    let (mut choice, _) = last
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, (cost, _))| *cost)
+        .expect("every chord has at least one shape");
+    let mut path = vec![0; candidates.len()];
+    for index in (0..candidates.len()).rev() {
+        path[index] = choice;
+        choice = costs[index][choice].1;
+    }
+    path.into_iter()
+        .zip(candidates)
+        .map(|(choice, mut shapes)| shapes.swap_remove(choice))
+        .collect()
+}
+
+/// How far the index finger must move between two shapes.
+fn hand_movement(from: &GuitarFingering, to: &GuitarFingering) -> u8 {
+    let frets = |shape: &GuitarFingering| {
+        shape
+            .strings
+            .iter()
+            .filter_map(|string| string.fret)
+            .collect::<Vec<_>>()
+    };
+    window_distance(hand_window(&frets(from)), hand_window(&frets(to)))
+}
+
+/// The index-finger positions from which a shape can be held: anywhere from
+/// its highest fret less three up to its lowest fretted note.
+fn hand_window(frets: &[u8]) -> (u8, u8) {
+    let fretted = frets.iter().copied().filter(|fret| *fret > 0);
+    match (fretted.clone().min(), fretted.max()) {
+        (Some(low), Some(high)) => (high.saturating_sub(3).max(1), low),
+        // Open strings leave the hand wherever it is.
+        _ => (1, u8::MAX),
+    }
+}
+
+/// How far the hand must move between two [`hand_window`]s.
+fn window_distance((from_low, from_high): (u8, u8), (to_low, to_high): (u8, u8)) -> u8 {
+    to_low
+        .saturating_sub(from_high)
+        .max(from_low.saturating_sub(to_high))
+}
+
+/// Scores one exact placement, a fret above the capo (or `None`) per string,
+/// as [`Chord::guitar_fingerings`] scores its shapes. Returns `None` when the
+/// hand cannot hold it.
+pub(crate) fn fingering_from_frets(
+    frets: &[Option<u8>],
+    tuning: &GuitarTuning,
+    fingering_options: &FingeringOptions,
+) -> Option<GuitarFingering> {
+    let tuning = capo_tuning(tuning, fingering_options.capo)?;
+    let choices = frets
+        .iter()
+        .zip(tuning.strings())
+        .map(|(fret, string)| {
+            let pitch_space = match fret {
+                Some(fret) => Some(string.fret_pitch_space(*fret)?),
+                None => None,
+            };
+            Some(StringChoice {
+                fret: *fret,
+                pitch_space,
+                pitch_class: pitch_space.map(|pitch_space| pitch_space.rem_euclid(12) as u8),
+            })
+        })
+        .collect::<Option<Vec<_>>>()?;
+    let pitch_spaces = choices
+        .iter()
+        .filter_map(|choice| choice.pitch_space)
+        .collect::<BTreeSet<_>>();
+    let pitch_classes = choices
+        .iter()
+        .filter_map(|choice| choice.pitch_class)
+        .collect::<BTreeSet<_>>();
+    score_candidate(
+        &choices,
+        &pitch_spaces,
+        &pitch_classes,
+        None,
+        &tuning,
+        fingering_options.max_stretch,
+        usize::MAX,
+    )
+    .map(|candidate| candidate.fingering)
+}
+
+/// A capo shortens every string, so the instrument is searched as if it were
+/// tuned up to the capo. A short string the capo does not reach keeps its
+/// open pitch and starts that much nearer the capo.
+pub(crate) fn capo_tuning(tuning: &GuitarTuning, capo: u8) -> Option<GuitarTuning> {
+    if capo == 0 {
+        return Some(tuning.clone());
+    }
+    let strings = tuning
+        .strings()
+        .iter()
+        .map(|string| {
+            if capo < string.first_fret {
+                return Ok(GuitarTuningString {
+                    first_fret: string.first_fret - capo,
+                    ..string.clone()
+                });
+            }
+            let semitones = capo - string.first_fret;
+            let interval = crate::interval::Interval::from_semitones(IntegerType::from(semitones))?;
+            let stopped = interval.transpose_pitch(&Pitch::from_name(&string.name)?)?;
+            Ok(GuitarTuningString {
+                name: stopped.name_with_octave(),
+                pitch_space: string.pitch_space + IntegerType::from(semitones),
+                pitch_class: stopped.pitch_class_number(),
+                first_fret: 0,
+            })
+        })
+        .collect::<crate::Result<Vec<_>>>()
+        .ok()?;
+    Some(GuitarTuning {
+        strings,
+        open_position: tuning.open_position,
+    })
+}
+
+fn ranked_candidates(
+    chord: &Chord,
+    tuning: &GuitarTuning,
+    fingering_options: &FingeringOptions,
+) -> Option<Ranked> {
+    if fingering_options.capo >= fingering_options.fret_count || fingering_options.limit == 0 {
+        return None;
+    }
     if chord.pitches().iter().any(|pitch| {
         let ps = pitch.ps();
         (ps - ps.round()).abs() > FloatType::EPSILON
@@ -190,15 +607,26 @@ pub(crate) fn suggested_guitar_fingering_with_tuning(
     let root_pitch_class = chord
         .root_pitch_name()
         .and_then(|name| Pitch::from_name(name).ok())
-        .map(|pitch| pitch_class(&pitch));
+        .map(|pitch| pitch.pitch_class_number());
 
+    let capo = fingering_options.capo;
+    let tuning = capo_tuning(tuning, capo)?;
     let options = tuning
         .strings()
         .iter()
-        .map(|string| string_choices(string.pitch_space, &target_pitch_classes))
+        .map(|string| {
+            string_choices(
+                string,
+                &target_pitch_classes,
+                fingering_options.fret_count - capo,
+            )
+        })
         .collect::<Vec<_>>();
 
-    let mut best: Option<Candidate> = None;
+    let mut ranked = Ranked {
+        limit: fingering_options.limit,
+        candidates: Vec::new(),
+    };
     let mut current = Vec::with_capacity(tuning.len());
     collect_candidates(
         &options,
@@ -207,11 +635,12 @@ pub(crate) fn suggested_guitar_fingering_with_tuning(
         &target_pitch_spaces,
         &target_pitch_classes,
         root_pitch_class,
-        tuning,
-        &mut best,
+        &tuning,
+        fingering_options.max_stretch,
+        &mut ranked,
     );
 
-    best.map(|candidate| candidate.fingering)
+    Some(ranked)
 }
 
 /// Frets on one string that sound a chord tone, plus the option of muting it.
@@ -225,8 +654,9 @@ pub(crate) fn suggested_guitar_fingering_with_tuning(
 /// the frets it uses, rather than sweeping a window across the neck: the
 /// windows overlap heavily, so sweeping found every shape five times over.
 fn string_choices(
-    open_pitch_space: IntegerType,
+    string: &GuitarTuningString,
     target_pitch_classes: &BTreeSet<u8>,
+    fret_count: u8,
 ) -> Vec<StringChoice> {
     let mut choices = vec![StringChoice {
         fret: None,
@@ -234,8 +664,10 @@ fn string_choices(
         pitch_class: None,
     }];
 
-    for fret in 0..=MAX_FRET {
-        let pitch_space = open_pitch_space + IntegerType::from(fret);
+    for fret in 0..=fret_count {
+        let Some(pitch_space) = string.fret_pitch_space(fret) else {
+            continue;
+        };
         let pitch_class = pitch_space.rem_euclid(12) as u8;
         if target_pitch_classes.contains(&pitch_class) {
             choices.push(StringChoice {
@@ -257,22 +689,20 @@ fn collect_candidates(
     target_pitch_classes: &BTreeSet<u8>,
     root_pitch_class: Option<u8>,
     tuning: &GuitarTuning,
-    best: &mut Option<Candidate>,
+    max_stretch: u8,
+    ranked: &mut Ranked,
 ) {
     if string_index == options.len() {
-        let bound = best.as_ref().map_or(usize::MAX, |best| best.score);
         if let Some(candidate) = score_candidate(
             current,
             target_pitch_spaces,
             target_pitch_classes,
             root_pitch_class,
             tuning,
-            bound,
-        ) && best
-            .as_ref()
-            .is_none_or(|current| candidate.score < current.score)
-        {
-            *best = Some(candidate);
+            max_stretch,
+            ranked.bound(),
+        ) {
+            ranked.insert(candidate);
         }
         return;
     }
@@ -288,7 +718,7 @@ fn collect_candidates(
                 low = low.min(played);
                 high = high.max(played);
             }
-            if high - low > MAX_FRET_SPAN {
+            if high - low > max_stretch {
                 continue;
             }
         }
@@ -302,7 +732,8 @@ fn collect_candidates(
             target_pitch_classes,
             root_pitch_class,
             tuning,
-            best,
+            max_stretch,
+            ranked,
         );
         current.pop();
     }
@@ -332,6 +763,7 @@ fn score_candidate(
     target_pitch_classes: &BTreeSet<u8>,
     root_pitch_class: Option<u8>,
     tuning: &GuitarTuning,
+    max_stretch: u8,
     bound: usize,
 ) -> Option<Candidate> {
     let sounding_indices = choices
@@ -376,9 +808,13 @@ fn score_candidate(
         .filter(|choice| choice.fret.is_none())
         .count();
     let internal_mutes = internal_muted_string_count(choices, &sounding_indices);
-    let bass_pitch_class = sounding_indices
-        .first()
-        .and_then(|index| choices[*index].pitch_class);
+    // The lowest-sounding string, which in re-entrant tunings need not be
+    // the first one.
+    let bass_pitch_class = choices
+        .iter()
+        .filter_map(|choice| choice.pitch_space.zip(choice.pitch_class))
+        .min_by_key(|(pitch_space, _)| *pitch_space)
+        .map(|(_, pitch_class)| pitch_class);
     let root_is_missing =
         root_pitch_class.is_some_and(|root| !covered_pitch_classes.contains(&root));
     let bass_is_not_root =
@@ -406,15 +842,15 @@ fn score_candidate(
         + usize::from(root_is_missing) * 400
         + internal_mutes * 120
         + muted_count * 30;
-    // The final score can still fall below this floor, because open strings are
-    // rewarded by subtraction, so allow for the largest bonus a six-string shape
-    // could earn. Pruning tighter than this would drop voicings that win.
-    const MAX_OPEN_BONUS: usize = 6 * 12;
-    if cheap_floor.saturating_sub(MAX_OPEN_BONUS) >= bound {
+    // Open strings are rewarded by subtracting from an allowance of the
+    // largest bonus the instrument's strings could earn, so the final score
+    // never falls below this floor and never saturates at zero.
+    let open_allowance = choices.len() * 12;
+    if cheap_floor >= bound {
         return None;
     }
 
-    let finger_assignment = finger_assignment(choices)?;
+    let finger_assignment = finger_assignment(choices, max_stretch)?;
 
     let unreachable = unreachable_same_fret_pairs(
         &choices
@@ -446,10 +882,11 @@ fn score_candidate(
     // the nut: an open string mixed into a shape held at the seventh fret is a
     // stretch across the neck, not a convenience, so the reward tapers off and
     // then becomes a penalty.
-    let score = if base_fret <= 2 {
-        penalties.saturating_sub(open_strings * 12)
+    // How near counts as near depends on the instrument's fret spacing.
+    let score = if base_fret <= tuning.open_position {
+        penalties + open_allowance - open_strings * 12
     } else {
-        penalties + open_strings * 20
+        penalties + open_allowance + open_strings * 20
     };
 
     let strings = choices
@@ -479,12 +916,13 @@ fn score_candidate(
             omitted_pitch_spaces,
             covered_pitch_classes: covered_pitch_classes.into_iter().collect(),
             omitted_pitch_classes,
+            difficulty: score,
         },
         score,
     })
 }
 
-fn finger_assignment(choices: &[StringChoice]) -> Option<Vec<Option<u8>>> {
+fn finger_assignment(choices: &[StringChoice], max_stretch: u8) -> Option<Vec<Option<u8>>> {
     let fretted_positions = choices
         .iter()
         .enumerate()
@@ -500,7 +938,7 @@ fn finger_assignment(choices: &[StringChoice]) -> Option<Vec<Option<u8>>> {
         return Some(vec![None; choices.len()]);
     }
 
-    if !fret_span_is_reachable(&fretted_positions) {
+    if !fret_span_is_reachable(&fretted_positions, max_stretch) {
         return None;
     }
 
@@ -525,7 +963,7 @@ fn finger_assignment(choices: &[StringChoice]) -> Option<Vec<Option<u8>>> {
     assignment_from_groups(groups, choices.len(), &fretted_positions)
 }
 
-fn fret_span_is_reachable(fretted_positions: &[(usize, u8)]) -> bool {
+fn fret_span_is_reachable(fretted_positions: &[(usize, u8)], max_stretch: u8) -> bool {
     let Some(lowest) = fretted_positions.iter().map(|(_, fret)| *fret).min() else {
         return true;
     };
@@ -536,7 +974,11 @@ fn fret_span_is_reachable(fretted_positions: &[(usize, u8)]) -> bool {
         .unwrap_or(lowest);
     let span = highest - lowest;
 
-    span <= if lowest >= 5 { 4 } else { 3 }
+    span <= if lowest >= 5 {
+        max_stretch
+    } else {
+        max_stretch.saturating_sub(1)
+    }
 }
 
 fn assignment_from_groups(
@@ -694,10 +1136,6 @@ fn internal_muted_string_count(choices: &[StringChoice], sounding_indices: &[usi
         .count()
 }
 
-fn pitch_class(pitch: &Pitch) -> u8 {
-    (pitch.ps().round() as IntegerType).rem_euclid(12) as u8
-}
-
 fn pitch_space(pitch: &Pitch) -> crate::Result<IntegerType> {
     let pitch_space = pitch.ps();
     let rounded = pitch_space.round();
@@ -725,31 +1163,90 @@ mod tests {
             .collect()
     }
 
+    #[test]
+    fn the_banjo_fifth_string_starts_at_the_fifth_fret() {
+        let banjo = GuitarTuning::banjo();
+        let fifth = &banjo.strings()[0];
+        assert_eq!(fifth.fret_pitch_space(0), Some(67));
+        assert_eq!(fifth.fret_pitch_space(3), None);
+        assert_eq!(fifth.fret_pitch_space(7), Some(69));
+        assert_eq!(fifth.fret_for_pitch_space(69), Some(7));
+
+        for notes in ["A C# E", "D F# A", "C E G", "E G# B"] {
+            for shape in Chord::new(notes)
+                .unwrap()
+                .guitar_fingerings(&banjo, &FingeringOptions::default())
+            {
+                assert!(
+                    shape.strings[0]
+                        .fret
+                        .is_none_or(|fret| fret == 0 || fret > 5),
+                    "{notes}: fifth string at fret {:?}",
+                    shape.strings[0].fret
+                );
+            }
+        }
+
+        // A capo below the fifth fret leaves the short string open.
+        let capoed = capo_tuning(&banjo, 2).unwrap();
+        assert_eq!(capoed.strings()[0].pitch_space, 67);
+        assert_eq!(capoed.strings()[0].first_fret, 3);
+        assert_eq!(capoed.strings()[1].pitch_space, 52);
+    }
+
+    #[test]
+    fn open_strings_are_rewarded_up_to_the_third_fret() {
+        let difficulty = |frets: &[Option<u8>]| {
+            fingering_from_frets(
+                frets,
+                &GuitarTuning::ukulele(),
+                &FingeringOptions::default(),
+            )
+            .unwrap()
+            .difficulty
+        };
+        let open = difficulty(&[Some(0), Some(0), Some(0), Some(3)]);
+        let closed = difficulty(&[Some(5), Some(4), Some(3), Some(3)]);
+        assert!(
+            open < closed,
+            "0 0 0 3 ({open}) should beat 5 4 3 3 ({closed})"
+        );
+    }
+
+    #[test]
+    fn guitar_open_position_stays_at_the_second_fret() {
+        assert_eq!(GuitarTuning::standard().open_position(), OPEN_POSITION_FRET);
+        assert_eq!(
+            GuitarTuning::ukulele().open_position(),
+            UKULELE_OPEN_POSITION_FRET
+        );
+        let frets = [None, Some(3), Some(5), Some(3), Some(5), Some(0)];
+        let difficulty = |tuning: &GuitarTuning| {
+            fingering_from_frets(&frets, tuning, &FingeringOptions::default())
+                .unwrap()
+                .difficulty
+        };
+        let guitar = GuitarTuning::standard();
+        assert!(difficulty(&guitar) > difficulty(&guitar.clone().with_open_position(3)));
+    }
+
     #[test]
     fn finger_assignment_rejects_more_than_four_independent_fingers() {
         assert!(
-            finger_assignment(&choices(&[
-                Some(1),
-                Some(2),
-                Some(3),
-                Some(4),
-                Some(5),
-                None
-            ]))
+            finger_assignment(
+                &choices(&[Some(1), Some(2), Some(3), Some(4), Some(5), None]),
+                MAX_FRET_SPAN
+            )
             .is_none()
         );
     }
 
     #[test]
     fn finger_assignment_accepts_barre_shapes() {
-        let assignment = finger_assignment(&choices(&[
-            Some(1),
-            Some(3),
-            Some(3),
-            Some(2),
-            Some(1),
-            Some(1),
-        ]))
+        let assignment = finger_assignment(
+            &choices(&[Some(1), Some(3), Some(3), Some(2), Some(1), Some(1)]),
+            MAX_FRET_SPAN,
+        )
         .unwrap();
 
         assert_eq!(
@@ -761,29 +1258,31 @@ mod tests {
     #[test]
     fn finger_assignment_rejects_low_position_five_fret_stretches() {
         assert!(
-            finger_assignment(&choices(&[Some(1), Some(2), Some(3), Some(5), None, None]))
-                .is_none()
+            finger_assignment(
+                &choices(&[Some(1), Some(2), Some(3), Some(5), None, None]),
+                MAX_FRET_SPAN
+            )
+            .is_none()
         );
     }
 
     #[test]
     fn finger_assignment_allows_higher_position_extended_reaches() {
-        let assignment =
-            finger_assignment(&choices(&[Some(5), Some(7), Some(8), Some(9), None, None])).unwrap();
+        let assignment = finger_assignment(
+            &choices(&[Some(5), Some(7), Some(8), Some(9), None, None]),
+            MAX_FRET_SPAN,
+        )
+        .unwrap();
 
         assert_eq!(assignment.iter().flatten().count(), 4);
     }
 
     #[test]
     fn finger_assignment_does_not_barre_over_open_string() {
-        let assignment = finger_assignment(&choices(&[
-            Some(1),
-            Some(2),
-            Some(0),
-            Some(3),
-            Some(1),
-            Some(1),
-        ]))
+        let assignment = finger_assignment(
+            &choices(&[Some(1), Some(2), Some(0), Some(3), Some(1), Some(1)]),
+            MAX_FRET_SPAN,
+        )
         .unwrap();
 
         assert!(assignment.iter().flatten().collect::<BTreeSet<_>>().len() <= 4);
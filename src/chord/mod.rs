@@ -12,8 +12,12 @@ use crate::key::keysignature::KeySignature;
 use crate::note::generalnote::GeneralNoteTrait;
 use crate::note::{IntoNote, Note};
 use crate::pitch::{Pitch, PitchClass, PitchClassSpecifier};
+use crate::pitchclassset::PitchClassSet;
 
-pub use guitar::{GuitarFingering, GuitarStringFingering, GuitarTuning, GuitarTuningString};
+pub use guitar::{
+    FingeringOptions, GuitarFingering, GuitarStringFingering, GuitarTuning, GuitarTuningString,
+    optimize_fingering_sequence,
+};
 
 use num::integer::{gcd, lcm};
 use std::fmt::{Display, Formatter};
@@ -27,7 +31,7 @@ use std::str::FromStr;
 /// pitch names, slices of pitches or notes, MIDI pitch numbers, vectors, and
 /// `None` for an empty chord.
 pub struct Chord {
-    _notes: Vec<Note>,
+    pub(crate) _notes: Vec<Note>,
     duration: Option<Duration>,
     #[cfg_attr(feature = "serde", serde(skip))]
     from_integer_pitches: bool,
@@ -263,6 +267,21 @@ impl Chord {
         guitar::suggested_guitar_fingering_with_tuning(self, tuning)
     }
 
+    /// Returns the easiest shapes for the supplied tuning, up to the limit,
+    /// in order of difficulty.
+    ///
+    /// The limit is [`FingeringOptions::limit`]. Each shape comes with its
+    /// [`GuitarFingering::difficulty`]. The first shape is the one
+    /// [`Self::guitar_fingering_with_tuning`] suggests when the options are the
+    /// defaults.
+    pub fn guitar_fingerings(
+        &self,
+        tuning: &GuitarTuning,
+        options: &FingeringOptions,
+    ) -> Vec<GuitarFingering> {
+        guitar::ranked_guitar_fingerings(self, tuning, options)
+    }
+
     fn pitched_name_for_common_name(&self, name_str: &str) -> String {
         if name_str == "empty chord" {
             return name_str.to_string();
@@ -501,13 +520,13 @@ impl Chord {
         let Some(p0) = self._notes.first().map(|n| &n._pitch) else {
             return "empty chord".to_string();
         };
-        let p0_pitch_class = Self::pitch_class(p0);
+        let p0_pitch_class = p0.pitch_class_number();
 
         let Some(p1) = self
             ._notes
             .iter()
             .skip(1)
-            .find(|n| Self::pitch_class(&n._pitch) != p0_pitch_class)
+            .find(|n| n._pitch.pitch_class_number() != p0_pitch_class)
             .map(|n| &n._pitch)
         else {
             return "unknown chord".to_string();
@@ -573,7 +592,7 @@ impl Chord {
 
         let root_pc = self
             .find_root_pitch()
-            .map(Self::pitch_class)
+            .map(Pitch::pitch_class_number)
             .filter(|root_pc| pitch_classes.contains(root_pc))
             .unwrap_or(pitch_classes[0]);
         let mut offsets = pitch_classes
@@ -648,6 +667,11 @@ impl Chord {
         self.bass_pitch().map(Self::display_pitch_name)
     }
 
+    /// Returns the chord's pitch classes as a [`PitchClassSet`].
+    pub fn pitch_class_set(&self) -> PitchClassSet {
+        PitchClassSet::new(self.ordered_pitch_classes())
+    }
+
     /// Returns the Forte class, such as `"3-11B"`, when available.
     ///
     /// Returns `None` when the chord's pitch-class set has no Forte-table
@@ -711,7 +735,7 @@ impl Chord {
                     .partial_cmp(&b._pitch.ps())
                     .unwrap_or(std::cmp::Ordering::Equal)
             })
-            .map(|n| (n._pitch.ps().round() as IntegerType).rem_euclid(12) as u8)?;
+            .map(|n| n._pitch.pitch_class_number())?;
 
         let interval = ((bass_pc as IntegerType - root_pc as IntegerType).rem_euclid(12)) as u8;
         match interval {
@@ -864,7 +888,7 @@ impl Chord {
             return Ok(suggestions);
         }
 
-        if let Some(root_pc) = self.find_root_pitch().map(Self::pitch_class) {
+        if let Some(root_pc) = self.find_root_pitch().map(Pitch::pitch_class_number) {
             if self.is_dominant_function_sonority() {
                 let tonic = Self::pitch_class_name((root_pc + 5) % 12);
                 for mode in ["major", "minor"] {
@@ -938,7 +962,7 @@ impl Chord {
         let mut pcs = self
             ._notes
             .iter()
-            .map(|note| (note._pitch.ps().round() as IntegerType).rem_euclid(12) as u8)
+            .map(|note| note._pitch.pitch_class_number())
             .collect::<Vec<_>>();
         pcs.sort_unstable();
         pcs.dedup();
@@ -964,7 +988,7 @@ impl Chord {
         let Some(root_pitch) = self.find_root_pitch() else {
             return Ok(None);
         };
-        let target_pc = (Self::pitch_class(root_pitch) + semitones) % 12;
+        let target_pc = (root_pitch.pitch_class_number() + semitones) % 12;
         Self::triad_for_key_pitch_class(key, target_pc)?
             .map(|chord| self.place_resolution_near_source(chord))
             .transpose()
@@ -973,7 +997,7 @@ impl Chord {
     fn triad_for_key_pitch_class(key: &Key, target_pc: u8) -> Result<Option<Self>> {
         for degree in 1..=7 {
             let degree_pitch = key.pitch_from_degree(degree)?;
-            if Self::pitch_class(&degree_pitch) == target_pc {
+            if degree_pitch.pitch_class_number() == target_pc {
                 return Ok(Some(key.triad_from_degree(degree)?));
             }
         }
@@ -1130,11 +1154,11 @@ impl Chord {
         let Some(root_pitch) = self.find_root_pitch() else {
             return false;
         };
-        let root_pc = Self::pitch_class(root_pitch);
+        let root_pc = root_pitch.pitch_class_number();
         let chord_pcs = self.pitch_class_set();
         intervals
             .iter()
-            .all(|interval| chord_pcs.contains(&((root_pc + interval) % 12)))
+            .all(|interval| chord_pcs.contains((root_pc + interval) % 12))
     }
 
     fn is_contextual_augmented_sixth(&self, key: &Key) -> Result<bool> {
@@ -1143,11 +1167,11 @@ impl Chord {
             return Ok(false);
         }
 
-        let tonic_pc = Self::pitch_class(&key.pitch_from_degree(1)?);
-        let second_pc = Self::pitch_class(&key.pitch_from_degree(2)?);
-        let third_pc = Self::pitch_class(&key.pitch_from_degree(3)?);
-        let fourth_pc = Self::pitch_class(&key.pitch_from_degree(4)?);
-        let sixth_pc = Self::pitch_class(&key.pitch_from_degree(6)?);
+        let tonic_pc = key.pitch_from_degree(1)?.pitch_class_number();
+        let second_pc = key.pitch_from_degree(2)?.pitch_class_number();
+        let third_pc = key.pitch_from_degree(3)?.pitch_class_number();
+        let fourth_pc = key.pitch_from_degree(4)?.pitch_class_number();
+        let sixth_pc = key.pitch_from_degree(6)?.pitch_class_number();
 
         let raised_fourth_pc = (fourth_pc + 1) % 12;
         let lowered_sixth_pc = if (sixth_pc + 12 - tonic_pc) % 12 == 9 {
@@ -1156,7 +1180,7 @@ impl Chord {
             sixth_pc
         };
 
-        if !chord_pcs.contains(&lowered_sixth_pc) || !chord_pcs.contains(&raised_fourth_pc) {
+        if !chord_pcs.contains(lowered_sixth_pc) || !chord_pcs.contains(raised_fourth_pc) {
             return Ok(false);
         }
 
@@ -1183,10 +1207,11 @@ impl Chord {
             raised_second_pc,
         ];
 
-        Ok(chord_pcs.contains(&tonic_pc)
+        Ok(chord_pcs.contains(tonic_pc)
             && chord_pcs
+                .pitch_classes()
                 .iter()
-                .all(|pc| allowed_pcs.iter().any(|allowed| allowed == pc)))
+                .all(|pc| allowed_pcs.contains(pc)))
     }
 
     fn common_names_with_primary(&self) -> Vec<String> {
@@ -1197,11 +1222,7 @@ impl Chord {
         names
     }
 
-    fn pitch_class_set(&self) -> std::collections::BTreeSet<u8> {
-        self.ordered_pitch_classes().into_iter().collect()
-    }
-
-    fn find_root_pitch(&self) -> Option<&Pitch> {
+    pub(crate) fn find_root_pitch(&self) -> Option<&Pitch> {
         let mut non_duplicating_notes: Vec<&Note> = Vec::new();
         let mut seen_steps = std::collections::HashSet::new();
         for note in &self._notes {
@@ -1320,10 +1341,6 @@ impl Chord {
         best_pc
     }
 
-    fn pitch_class(pitch: &Pitch) -> u8 {
-        (pitch.ps().round() as IntegerType).rem_euclid(12) as u8
-    }
-
     fn pitch_class_name(pc: u8) -> &'static str {
         CANDIDATE_TONICS[pc as usize % 12]
     }
@@ -1577,7 +1594,10 @@ impl IntoNotes for &[IntegerType] {
 
 #[cfg(test)]
 mod tests {
-    use crate::{Duration, GuitarTuning, Key, Pitch, chord::Chord};
+    use crate::{
+        Duration, FingeringOptions, GuitarFingering, GuitarTuning, Key, Pitch, chord::Chord,
+        optimize_fingering_sequence,
+    };
 
     #[test]
     fn set_duration_applies_to_non_empty_chords() {
@@ -1771,6 +1791,103 @@ mod tests {
         }
     }
 
+    fn frets(fingering: &GuitarFingering) -> String {
+        fingering
+            .strings
+            .iter()
+            .map(|string| string.fret.map_or("x".to_string(), |fret| fret.to_string()))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    #[test]
+    fn guitar_fingerings_are_ranked_by_difficulty() {
+        let chord = Chord::new("C E G").unwrap();
+        let options = FingeringOptions::default();
+        let shapes = chord.guitar_fingerings(&GuitarTuning::standard(), &options);
+        assert_eq!(shapes.len(), options.limit());
+        assert_eq!(shapes[0], chord.guitar_fingering().unwrap());
+        assert!(
+            shapes
+                .windows(2)
+                .all(|pair| pair[0].difficulty <= pair[1].difficulty)
+        );
+
+        let low = chord.guitar_fingerings(
+            &GuitarTuning::standard(),
+            &options.with_fret_count(5).with_max_stretch(2),
+        );
+        assert!(low.iter().all(|shape| {
+            shape.fret_span <= 2
+                && shape
+                    .strings
+                    .iter()
+                    .all(|string| string.fret.is_none_or(|fret| fret <= 5))
+        }));
+    }
+
+    #[test]
+    fn guitar_fingerings_cover_other_fretted_instruments() {
+        let shape = |tuning: &GuitarTuning, notes: &str| {
+            frets(
+                &Chord::new(notes)
+                    .unwrap()
+                    .guitar_fingerings(tuning, &FingeringOptions::default())[0],
+            )
+        };
+        let ukulele = GuitarTuning::ukulele();
+        assert_eq!(shape(&ukulele, "C E G"), "0 0 0 3");
+        assert_eq!(shape(&ukulele, "A C E"), "2 0 0 0");
+        assert_eq!(shape(&ukulele, "F A C"), "2 0 1 0");
+        assert_eq!(shape(&GuitarTuning::banjo(), "G B D"), "0 0 0 0 0");
+        assert_eq!(shape(&GuitarTuning::bass(), "G B D"), "3 2 0 0");
+
+        // The re-entrant G string is not the bass of a ukulele C chord.
+        let c = &Chord::new("C E G")
+            .unwrap()
+            .guitar_fingerings(&ukulele, &FingeringOptions::default())[0];
+        assert_eq!(c.strings[0].pitch_space, Some(67));
+        assert_eq!(c.covered_pitch_spaces[0], 60);
+    }
+
+    #[test]
+    fn guitar_fingerings_count_frets_from_the_capo() {
+        let options = FingeringOptions::default().with_capo(3);
+        let shapes = Chord::new("C E G")
+            .unwrap()
+            .guitar_fingerings(&GuitarTuning::standard(), &options);
+        assert_eq!(frets(&shapes[0]), "x 0 2 2 2 0");
+        assert_eq!(shapes[0].strings[0].string_name, "G2");
+        assert!(
+            Chord::new("C E G")
+                .unwrap()
+                .guitar_fingerings(&GuitarTuning::standard(), &options.with_capo(12))
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn fingering_sequences_minimize_hand_movement() {
+        let tuning = GuitarTuning::standard();
+        let options = FingeringOptions::default();
+        let chords = ["C E G", "B D F#", "C E G"].map(|notes| Chord::new(notes).unwrap());
+        // On its own B minor is easiest as a seventh-fret barre; between two
+        // open C chords the second-fret shape saves the shift.
+        assert_eq!(
+            frets(&chords[1].guitar_fingerings(&tuning, &options)[0]),
+            "7 5 4 4 7 7"
+        );
+        let sequence = optimize_fingering_sequence(&chords, &tuning, &options).unwrap();
+        assert_eq!(
+            sequence.iter().map(frets).collect::<Vec<_>>(),
+            ["x 3 2 0 1 0", "x 2 4 4 3 x", "x 3 2 0 1 0"]
+        );
+        assert_eq!(
+            optimize_fingering_sequence(&[], &tuning, &options),
+            Some(Vec::new())
+        );
+    }
+
     #[test]
     fn guitar_tuning_rejects_empty_tunings() {
         assert!(GuitarTuning::new(Vec::<&str>::new()).is_err());
@@ -361,6 +361,35 @@ pub(crate) fn known_chord_table_entries() -> Vec<KnownChordTableEntry> {
     entries.into_iter().map(|(_, entry)| entry).collect()
 }
 
+/// A Forte-table set class and its prime form.
+#[derive(Debug, Clone)]
+pub(crate) struct ForteTableEntry {
+    pub(crate) cardinality: u8,
+    pub(crate) index: u8,
+    pub(crate) prime_form: Vec<u8>,
+}
+
+/// Every Forte set class from cardinality 1 through 12, in Forte order.
+pub(crate) fn forte_table_entries() -> Vec<ForteTableEntry> {
+    FORTE
+        .iter()
+        .enumerate()
+        .flat_map(|(cardinality, entries)| {
+            entries
+                .iter()
+                .enumerate()
+                .filter_map(move |(index, entry)| {
+                    let entry = entry.as_ref()?;
+                    Some(ForteTableEntry {
+                        cardinality: cardinality as u8,
+                        index: index as u8,
+                        prime_form: bool_vec_to_pitch_classes(&entry.pitch_classes()),
+                    })
+                })
+        })
+        .collect()
+}
+
 fn find_cardinality_member(card: u8, index: u8, inversion: Sign) -> Option<&'static Pcivicv> {
     CARDINALITY_TO_CHORD_MEMBERS
         .get(card as usize)?
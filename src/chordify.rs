@@ -0,0 +1,343 @@
+//! Vertical slicing of a [`Stream`] into chords, after music21's `chordify`.
+//!
+//! The stream is cut at every onset and release, and each slice becomes one
+//! [`Chord`] holding everything sounding in it. Overlapping parts merged into
+//! a single stream, for example, become a homophonic chord stream that
+//! chord-by-chord tools such as Roman numeral analysis can read.
+
+use crate::{
+    chord::Chord,
+    defaults::FloatType,
+    duration::Duration,
+    error::Result,
+    note::Note,
+    rest::Rest,
+    stream::{Stream, StreamElement, StreamEvent},
+    tie::Tie,
+};
+
+const OFFSET_EPSILON: FloatType = 1e-9;
+
+/// Options for [`chordify`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChordifyOptions {
+    ties: bool,
+    rests: bool,
+    duplicates: bool,
+}
+
+impl Default for ChordifyOptions {
+    fn default() -> Self {
+        Self {
+            ties: true,
+            rests: true,
+            duplicates: false,
+        }
+    }
+}
+
+impl ChordifyOptions {
+    /// Returns the options with ties added, or not, to notes that sound
+    /// across a slice boundary. Ties are added by default.
+    ///
+    /// Without them, every slice is a fresh attack and ties already in the
+    /// input are dropped.
+    pub fn with_ties(mut self, ties: bool) -> Self {
+        self.ties = ties;
+        self
+    }
+
+    /// Returns the options keeping, or dropping, silent slices. Kept by
+    /// default; consecutive silent slices become a single rest.
+    pub fn with_rests(mut self, rests: bool) -> Self {
+        self.rests = rests;
+        self
+    }
+
+    /// Returns the options keeping, or merging, pitches sounding twice in a
+    /// slice with the same spelling and octave. Merged by default, like
+    /// music21's `removeRedundantPitches`.
+    pub fn with_duplicate_pitches(mut self, duplicates: bool) -> Self {
+        self.duplicates = duplicates;
+        self
+    }
+
+    /// Returns whether ties are added across slice boundaries.
+    pub fn adds_ties(&self) -> bool {
+        self.ties
+    }
+
+    /// Returns whether silent slices become rests.
+    pub fn keeps_rests(&self) -> bool {
+        self.rests
+    }
+
+    /// Returns whether duplicate pitches are kept.
+    pub fn keeps_duplicate_pitches(&self) -> bool {
+        self.duplicates
+    }
+}
+
+/// Slices `stream` at every onset and release into a stream of chords.
+///
+/// Each chord holds every pitch sounding during its slice, lowest first, and
+/// lasts until the next onset or release. A note that sounds across several
+/// slices appears in each, tied from one to the next when
+/// [`ChordifyOptions::adds_ties`] is set; a tie already on an input note
+/// carries over to the first or last slice it sounds in. Zero-length events
+/// are ignored.
+pub fn chordify(stream: &Stream, options: &ChordifyOptions) -> Result<Stream> {
+    let spans = stream
+        .events()
+        .iter()
+        .filter(|event| event.element().quarter_length() > OFFSET_EPSILON)
+        .map(|event| {
+            let start = event.offset();
+            (
+                start,
+                start + event.element().quarter_length(),
+                event.element(),
+            )
+        })
+        .collect::<Vec<_>>();
+    let mut times = spans
+        .iter()
+        .flat_map(|(start, end, _)| [*start, *end])
+        .collect::<Vec<_>>();
+    times.sort_by(FloatType::total_cmp);
+    times.dedup_by(|later, earlier| *later - *earlier < OFFSET_EPSILON);
+
+    let mut events = Vec::new();
+    let mut silence: Option<(FloatType, FloatType)> = None;
+    for window in times.windows(2) {
+        let (slice_start, slice_end) = (window[0], window[1]);
+        let mut notes: Vec<Note> = Vec::new();
+        for (start, end, element) in &spans {
+            if *start > slice_start + OFFSET_EPSILON || *end < slice_end - OFFSET_EPSILON {
+                continue;
+            }
+            let element_notes = match element {
+                StreamElement::Note(note) => std::slice::from_ref(note),
+                StreamElement::Chord(chord) => chord.notes(),
+                StreamElement::Rest(_) => &[],
+            };
+            for note in element_notes {
+                let duplicate = notes
+                    .iter()
+                    .any(|kept| kept.pitch_name_with_octave() == note.pitch_name_with_octave());
+                if duplicate && !options.duplicates {
+                    continue;
+                }
+                let input_tie = note.tie();
+                let from_previous = *start < slice_start - OFFSET_EPSILON
+                    || input_tie.is_some_and(Tie::holds_from_previous);
+                let into_next = *end > slice_end + OFFSET_EPSILON
+                    || input_tie.is_some_and(Tie::holds_into_next);
+                let mut note = note.clone();
+                note.set_tie(Tie::from_holds(from_previous, into_next).filter(|_| options.ties));
+                notes.push(note);
+            }
+        }
+
+        if notes.is_empty() {
+            if options.rests {
+                silence = Some((silence.map_or(slice_start, |(start, _)| start), slice_end));
+            }
+            continue;
+        }
+        if let Some((start, end)) = silence.take() {
+            events.push(StreamEvent::new(
+                start,
+                Rest::from_quarter_length(end - start)?,
+            ));
+        }
+        notes.sort_by(|left, right| left.pitch().ps().total_cmp(&right.pitch().ps()));
+        let chord = Chord::new(notes)?.with_duration(Duration::new(slice_end - slice_start)?);
+        events.push(StreamEvent::new(slice_start, chord));
+    }
+    if let Some((start, end)) = silence {
+        events.push(StreamEvent::new(
+            start,
+            Rest::from_quarter_length(end - start)?,
+        ));
+    }
+    Ok(Stream::from_events(events))
+}
+
+impl Stream {
+    /// Slices the stream into a stream of chords.
+    ///
+    /// See [`chordify`].
+    pub fn chordify(&self, options: &ChordifyOptions) -> Result<Stream> {
+        chordify(self, options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Key, analysis::estimate_key_from_chords, roman::analyze_chord};
+
+    fn note(name: &str, quarter_length: FloatType) -> Note {
+        Note::from_name(name)
+            .unwrap()
+            .with_duration(Duration::new(quarter_length).unwrap())
+    }
+
+    /// Soprano E5 (2) D5 (2) over bass C3 (1) G2 (3), with an inner C4
+    /// entering late.
+    fn two_voices() -> Stream {
+        let mut stream = Stream::new();
+        stream.insert(0.0, note("E5", 2.0));
+        stream.insert(2.0, note("D5", 2.0));
+        stream.insert(0.0, note("C3", 1.0));
+        stream.insert(1.0, note("G2", 3.0));
+        stream.insert(1.5, note("C4", 1.0));
+        stream
+    }
+
+    /// Offset, length, and each note's name and tie.
+    type Slice = (FloatType, FloatType, Vec<(String, Option<Tie>)>);
+
+    fn slices(stream: &Stream) -> Vec<Slice> {
+        stream
+            .events()
+            .iter()
+            .map(|event| {
+                let notes = match event.element() {
+                    StreamElement::Chord(chord) => chord
+                        .notes()
+                        .iter()
+                        .map(|note| (note.pitch_name_with_octave(), note.tie()))
+                        .collect(),
+                    StreamElement::Note(_) => panic!("chordify returns chords"),
+                    StreamElement::Rest(_) => Vec::new(),
+                };
+                (event.offset(), event.element().quarter_length(), notes)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn slices_at_every_onset_and_release_with_ties() {
+        let chords = two_voices().chordify(&ChordifyOptions::default()).unwrap();
+        let held = |name: &str, tie| (name.to_string(), Some(tie));
+        let fresh = |name: &str| (name.to_string(), None);
+        assert_eq!(
+            slices(&chords),
+            vec![
+                (0.0, 1.0, vec![fresh("C3"), held("E5", Tie::Start)]),
+                (
+                    1.0,
+                    0.5,
+                    vec![held("G2", Tie::Start), held("E5", Tie::Continue)]
+                ),
+                (
+                    1.5,
+                    0.5,
+                    vec![
+                        held("G2", Tie::Continue),
+                        held("C4", Tie::Start),
+                        held("E5", Tie::Stop)
+                    ]
+                ),
+                (
+                    2.0,
+                    0.5,
+                    vec![
+                        held("G2", Tie::Continue),
+                        held("C4", Tie::Stop),
+                        held("D5", Tie::Start)
+                    ]
+                ),
+                (2.5, 1.5, vec![held("G2", Tie::Stop), held("D5", Tie::Stop)]),
+            ]
+        );
+
+        let untied = two_voices()
+            .chordify(&ChordifyOptions::default().with_ties(false))
+            .unwrap();
+        assert!(
+            slices(&untied)
+                .iter()
+                .flat_map(|(_, _, notes)| notes)
+                .all(|(_, tie)| tie.is_none())
+        );
+    }
+
+    #[test]
+    fn keeps_or_drops_rests_and_duplicates() {
+        let mut stream = Stream::new();
+        stream.insert(0.0, Rest::from_quarter_length(1.0).unwrap());
+        stream.insert(1.0, Chord::new("C4 E4").unwrap());
+        stream.insert(1.0, note("C4", 1.0));
+        stream.insert(2.0, Rest::from_quarter_length(0.5).unwrap());
+        stream.insert(2.5, Rest::from_quarter_length(0.5).unwrap());
+        stream.insert(3.0, note("G4", 1.0).with_tie(Tie::Start));
+
+        let chords = chordify(&stream, &ChordifyOptions::default()).unwrap();
+        let summary = slices(&chords)
+            .into_iter()
+            .map(|(offset, length, notes)| (offset, length, notes.len()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            vec![(0.0, 1.0, 0), (1.0, 1.0, 2), (2.0, 1.0, 0), (3.0, 1.0, 1)]
+        );
+        assert_eq!(slices(&chords)[3].2[0].1, Some(Tie::Start));
+
+        let options = ChordifyOptions::default()
+            .with_rests(false)
+            .with_duplicate_pitches(true);
+        let chords = chordify(&stream, &options).unwrap();
+        let summary = slices(&chords)
+            .into_iter()
+            .map(|(offset, _, notes)| (offset, notes.len()))
+            .collect::<Vec<_>>();
+        assert_eq!(summary, vec![(1.0, 3), (3.0, 1)]);
+    }
+
+    #[test]
+    fn chordified_voices_feed_chord_analysis() {
+        let mut stream = Stream::new();
+        for (offset, bass, upper) in [
+            (0.0, "C3", ["E4", "G4", "C5"]),
+            (1.0, "F2", ["F4", "A4", "C5"]),
+            (2.0, "G2", ["D4", "G4", "B4"]),
+            (3.0, "C3", ["E4", "G4", "C5"]),
+        ] {
+            stream.insert(offset, note(bass, 1.0));
+            for pitch in upper {
+                stream.insert(offset, note(pitch, 1.0));
+            }
+        }
+        let chords = stream
+            .chordify(&ChordifyOptions::default())
+            .unwrap()
+            .events()
+            .iter()
+            .filter_map(|event| match event.element() {
+                StreamElement::Chord(chord) => Some(chord.clone()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        let key = Key::from_tonic_mode("C", "major").unwrap();
+        let figures = chords
+            .iter()
+            .map(|chord| {
+                analyze_chord(chord, key.clone())
+                    .unwrap()
+                    .unwrap()
+                    .figure()
+                    .to_string()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(figures, vec!["I", "IV", "V", "I"]);
+        assert_eq!(chords[1].forte_class().as_deref(), Some("3-11B"));
+        let estimate = estimate_key_from_chords(&chords).unwrap();
+        assert_eq!(estimate[0].key().tonic().name(), "C");
+        assert_eq!(estimate[0].key().mode(), "major");
+    }
+}
@@ -8,6 +8,7 @@ use crate::{
     pitch::Pitch,
 };
 use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
 
 /// Tertian quality parsed from a chord symbol.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -31,6 +32,39 @@ pub enum ChordQuality {
     Suspended4,
     /// Power-chord sonority containing a root and fifth.
     Power,
+    /// A single sustained root, music21's `pedal` kind.
+    Pedal,
+    /// music21's `Neapolitan` kind, spelled `1,-2,3,-5` above the root.
+    Neapolitan,
+    /// music21's `Italian` augmented-sixth kind, spelled `1,#4,-6`.
+    ItalianAugmentedSixth,
+    /// music21's `French` augmented-sixth kind, spelled `1,2,#4,-6`.
+    FrenchAugmentedSixth,
+    /// music21's `German` augmented-sixth kind, spelled `1,-3,#4,-6`.
+    GermanAugmentedSixth,
+    /// music21's `Tristan` kind, spelled `1,#4,#6,#9`.
+    Tristan,
+}
+
+/// Spelling conventions for writing a [`ChordSymbol`].
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ChordSymbolStyle {
+    /// Jazz lead-sheet text, such as `Cmaj7`, `Cm7b5`, `Bb7#9`, and `Cadd9`.
+    #[default]
+    Jazz,
+    /// Jazz lead-sheet symbols, such as `C△7`, `Cm△7`, `Cø7`, and `C°7`.
+    JazzSymbols,
+    /// music21's figures, such as `Cmaj7`, `CmM7`, `CM9`, and `B-dom7dim5`.
+    ///
+    /// Sonorities in music21's `CHORD_TYPES` table use its first abbreviation.
+    Music21,
+    /// Berklee conventions, such as `Cma7`, `Cmi7(b5)`, and `C7(b9,#11)`.
+    Berklee,
+    /// Nashville-chart suffixes, such as `Cm7`, `Cmaj7`, `C7sus`, and `C°`.
+    ///
+    /// [`crate::nashville`] writes the same suffixes after scale degrees.
+    Nashville,
 }
 
 /// A chord-symbol alteration such as `b5` or `#11`.
@@ -375,7 +409,64 @@ const MUSIC21_CHORD_TYPES: &[Music21ChordType] = &[
 ];
 
 impl ChordSymbol {
+    /// Builds a chord symbol from its parts.
+    ///
+    /// Extensions are the stacked degrees named by the figure (`7`, `9`, `11`,
+    /// `13`, or `6`); alterations raise or lower chord degrees, so a
+    /// minor-major seventh is a [`ChordQuality::Minor`] chord with its seventh
+    /// raised by one semitone. The figure is written in
+    /// [`ChordSymbolStyle::Jazz`].
+    pub fn new(
+        root: Pitch,
+        quality: ChordQuality,
+        extensions: impl IntoIterator<Item = u8>,
+        alterations: impl IntoIterator<Item = ChordAlteration>,
+        bass: Option<Pitch>,
+    ) -> Self {
+        let alterations = alterations.into_iter().collect::<Vec<_>>();
+        let mut extensions = extensions.into_iter().collect::<Vec<_>>();
+        for alteration in &alterations {
+            if alteration.degree > 5 && !extensions.contains(&alteration.degree) {
+                extensions.push(alteration.degree);
+            }
+        }
+        extensions.sort_unstable();
+        extensions.dedup();
+
+        let mut symbol = Self {
+            figure: String::new(),
+            root,
+            bass,
+            quality,
+            extensions,
+            alterations,
+            omissions: Vec::new(),
+            additions: Vec::new(),
+        };
+        symbol.normalize();
+        symbol.figure = symbol.render(ChordSymbolStyle::Jazz);
+        symbol
+    }
+
+    /// Returns the symbol with added tones, such as the `9` of `Cadd9`.
+    pub fn with_additions(mut self, additions: impl IntoIterator<Item = ChordAlteration>) -> Self {
+        self.additions = additions.into_iter().collect();
+        self.figure = self.render(ChordSymbolStyle::Jazz);
+        self
+    }
+
+    /// Returns the symbol with omitted chord degrees, such as the `3` of
+    /// `C7no3`.
+    pub fn with_omissions(mut self, omissions: impl IntoIterator<Item = u8>) -> Self {
+        self.omissions = omissions.into_iter().collect();
+        self.figure = self.render(ChordSymbolStyle::Jazz);
+        self
+    }
+
     /// Parses a chord symbol such as `"Cmaj7"`, `"F#m7b5"`, or `"Bb7#11"`.
+    ///
+    /// Every [`ChordSymbolStyle`] parses back to the same symbol, along with
+    /// every abbreviation in [`known_chord_symbol_types`].
     pub fn parse(figure: impl Into<String>) -> Result<Self> {
         let figure = figure.into();
         let trimmed = figure.trim();
@@ -399,33 +490,68 @@ impl ChordSymbol {
         let suffix_without_additions = strip_addition_groups(suffix);
         let mut additions = parse_additions(suffix);
         let mut omissions = parse_omissions(suffix);
-        for pitch_name in body_parts
+        for token in body_parts
             .additions
             .iter()
             .chain(bass_parts.iter().flat_map(|parts| parts.additions.iter()))
         {
-            if let Some(addition) = pitch_name_addition(&root, pitch_name) {
+            let addition = if is_degree_token(token) {
+                parse_addition_token(token)
+            } else {
+                pitch_name_addition(&root, token)
+            };
+            if let Some(addition) = addition {
                 additions.push(addition);
             }
         }
-        for pitch_name in body_parts
+        for token in body_parts
             .omissions
             .iter()
             .chain(bass_parts.iter().flat_map(|parts| parts.omissions.iter()))
         {
-            if let Some(omission) = pitch_name_degree(&root, pitch_name)
+            let omission = if is_degree_token(token) {
+                token.parse::<u8>().ok()
+            } else {
+                pitch_name_degree(&root, token)
+            };
+            if let Some(omission) = omission
                 && !omissions.contains(&omission)
             {
                 omissions.push(omission);
             }
         }
 
+        if let Some(quality) = special_quality(suffix_without_additions.trim()) {
+            return Ok(Self {
+                figure: trimmed.to_string(),
+                root,
+                bass,
+                quality,
+                extensions: Vec::new(),
+                alterations: Vec::new(),
+                omissions,
+                additions,
+            });
+        }
+
         let mut alterations = parse_alterations(&suffix_without_additions);
         add_implicit_music21_alterations(&suffix_without_additions, &mut alterations);
-        let extensions = parse_extensions(&suffix_without_additions, &alterations);
+        let mut extensions = parse_extensions(&suffix_without_additions, &alterations);
         let quality = parse_quality(&suffix_without_additions, &alterations);
+        if extensions.is_empty() && starts_with_major_seventh_sign(&suffix_without_additions) {
+            extensions.push(7);
+        }
+        if matches!(quality, ChordQuality::Minor | ChordQuality::Augmented)
+            && extensions
+                .iter()
+                .any(|degree| matches!(degree, 7 | 9 | 11 | 13))
+            && has_major_seventh_marker(&suffix_without_additions)
+            && !alterations.iter().any(|alteration| alteration.degree == 7)
+        {
+            alterations.push(ChordAlteration::new(7, 1));
+        }
 
-        Ok(Self {
+        let mut symbol = Self {
             figure: trimmed.to_string(),
             root,
             bass,
@@ -434,7 +560,9 @@ impl ChordSymbol {
             alterations,
             omissions,
             additions,
-        })
+        };
+        symbol.normalize();
+        Ok(symbol)
     }
 
     /// Returns the original chord-symbol figure.
@@ -477,47 +605,111 @@ impl ChordSymbol {
         &self.additions
     }
 
+    /// Returns a value that writes the symbol in a [`ChordSymbolStyle`].
+    ///
+    /// The [`fmt::Display`] implementation of [`ChordSymbol`] itself uses
+    /// [`ChordSymbolStyle::Jazz`].
+    pub fn display(&self, style: ChordSymbolStyle) -> ChordSymbolDisplay<'_> {
+        ChordSymbolDisplay {
+            symbol: self,
+            style,
+        }
+    }
+
+    /// Returns the symbol transposed by an interval.
+    ///
+    /// The root and slash bass are spelled by the interval, so `Bb7`
+    /// transposed up a major second is `C7`, and the figure is rewritten in
+    /// [`ChordSymbolStyle::Jazz`].
+    pub fn transpose(&self, interval: &Interval) -> Result<Self> {
+        let mut transposed = self.clone();
+        transposed.root = interval.transpose_pitch(&self.root)?;
+        transposed.bass = self
+            .bass
+            .as_ref()
+            .map(|bass| interval.transpose_pitch(bass))
+            .transpose()?;
+        transposed.figure = transposed.render(ChordSymbolStyle::Jazz);
+        Ok(transposed)
+    }
+
     /// Realizes the chord symbol as a [`Chord`].
     pub fn to_chord(&self) -> Result<Chord> {
+        let mut pitches = self
+            .chord_degrees()?
+            .into_iter()
+            .map(|(_, pitch)| pitch)
+            .collect::<Vec<_>>();
+
+        if let Some(bass) = &self.bass {
+            if let Some(index) = pitches.iter().position(|pitch| pitch.name() == bass.name()) {
+                let bass = pitches.remove(index);
+                pitches.insert(0, bass);
+            } else {
+                pitches.insert(0, bass.clone());
+            }
+        }
+
+        Chord::new(pitches.as_slice())
+    }
+
+    /// The chord's tones above the root, in degree order, each with the
+    /// degree it spells. The slash bass is not included.
+    pub(crate) fn chord_degrees(&self) -> Result<Vec<(u8, Pitch)>> {
+        let mut intervals = self.chord_intervals(true)?;
+        intervals.sort_unstable_by_key(|(degree, _)| *degree);
+        intervals.dedup();
+
+        intervals
+            .into_iter()
+            .map(|(degree, name)| {
+                Ok((
+                    degree,
+                    Interval::from_name(name)?.transpose_pitch(&self.root)?,
+                ))
+            })
+            .collect()
+    }
+
+    /// Chord degrees and the intervals that spell them above the root, with
+    /// or without the added and omitted tones.
+    fn chord_intervals(&self, with_modifiers: bool) -> Result<Vec<(u8, &'static str)>> {
         let mut intervals = self.base_intervals();
 
+        // music21 stacks every third below the highest named extension, so an
+        // eleventh carries its ninth and a thirteenth its ninth and eleventh.
+        let mut stacked = self
+            .extensions
+            .iter()
+            .copied()
+            .filter(|degree| !self.is_altered(*degree))
+            .collect::<Vec<_>>();
+        if stacked.contains(&13) {
+            stacked.extend([9, 11]);
+        } else if stacked.contains(&11) {
+            stacked.push(9);
+        }
         for extension in [6, 9, 11, 13] {
-            if self.extensions.contains(&extension)
-                && !self.alterations.iter().any(|alt| alt.degree == extension)
-            {
+            if stacked.contains(&extension) && !self.is_altered(extension) {
                 intervals.push((extension, default_extension_interval(extension)));
             }
         }
 
         for alteration in &self.alterations {
-            if alteration.degree == 5 {
+            if matches!(alteration.degree, 5 | 7) {
                 continue;
             }
             intervals.push(altered_interval(alteration)?);
         }
 
-        for addition in &self.additions {
-            intervals.push(added_interval(addition)?);
-        }
-
-        intervals.sort_unstable_by_key(|(degree, _)| *degree);
-        intervals.dedup();
-
-        let mut pitches = intervals
-            .into_iter()
-            .map(|(_, name)| Interval::from_name(name)?.transpose_pitch(&self.root))
-            .collect::<Result<Vec<_>>>()?;
-
-        if let Some(bass) = &self.bass {
-            if let Some(index) = pitches.iter().position(|pitch| pitch.name() == bass.name()) {
-                let bass = pitches.remove(index);
-                pitches.insert(0, bass);
-            } else {
-                pitches.insert(0, bass.clone());
+        if with_modifiers {
+            for addition in &self.additions {
+                intervals.push(added_interval(addition)?);
             }
+            intervals.retain(|(degree, _)| *degree == 1 || !self.omissions.contains(degree));
         }
 
-        Chord::new(pitches.as_slice())
+        Ok(intervals)
     }
 
     fn base_intervals(&self) -> Vec<(u8, &'static str)> {
@@ -532,48 +724,387 @@ impl ChordSymbol {
             });
 
         let fifth = altered_fifth.unwrap_or("P5");
-        let has_seventh = self
+        let seventh = self.has_seventh().then(|| self.seventh_interval());
+
+        let mut intervals = match self.quality {
+            ChordQuality::Major | ChordQuality::Minor | ChordQuality::Dominant => {
+                let third = if self.quality == ChordQuality::Minor {
+                    "m3"
+                } else {
+                    "M3"
+                };
+                vec![(1, "P1"), (3, third), (5, fifth)]
+            }
+            ChordQuality::Diminished | ChordQuality::HalfDiminished => {
+                vec![(1, "P1"), (3, "m3"), (5, "d5")]
+            }
+            ChordQuality::Augmented => vec![(1, "P1"), (3, "M3"), (5, "a5")],
+            ChordQuality::Suspended2 => vec![(1, "P1"), (2, "M2"), (5, fifth)],
+            ChordQuality::Suspended4 => vec![(1, "P1"), (4, "P4"), (5, fifth)],
+            ChordQuality::Power => vec![(1, "P1"), (5, fifth)],
+            ChordQuality::Pedal => vec![(1, "P1")],
+            ChordQuality::Neapolitan => vec![(1, "P1"), (2, "m2"), (3, "M3"), (5, "d5")],
+            ChordQuality::ItalianAugmentedSixth => vec![(1, "P1"), (4, "a4"), (6, "m6")],
+            ChordQuality::FrenchAugmentedSixth => {
+                vec![(1, "P1"), (2, "M2"), (4, "a4"), (6, "m6")]
+            }
+            ChordQuality::GermanAugmentedSixth => {
+                vec![(1, "P1"), (3, "m3"), (4, "a4"), (6, "m6")]
+            }
+            ChordQuality::Tristan => vec![(1, "P1"), (4, "a4"), (6, "a6"), (9, "a9")],
+        };
+        if let Some(seventh) = seventh {
+            intervals.push((7, seventh));
+        }
+        intervals
+    }
+
+    fn is_special(&self) -> bool {
+        matches!(
+            self.quality,
+            ChordQuality::Power
+                | ChordQuality::Pedal
+                | ChordQuality::Neapolitan
+                | ChordQuality::ItalianAugmentedSixth
+                | ChordQuality::FrenchAugmentedSixth
+                | ChordQuality::GermanAugmentedSixth
+                | ChordQuality::Tristan
+        )
+    }
+
+    fn has_seventh(&self) -> bool {
+        if self.is_special() {
+            return false;
+        }
+        matches!(
+            self.quality,
+            ChordQuality::Dominant | ChordQuality::HalfDiminished
+        ) || self
             .extensions
             .iter()
-            .any(|degree| matches!(degree, 7 | 9 | 11 | 13));
+            .any(|degree| matches!(degree, 7 | 9 | 11 | 13))
+    }
 
-        let intervals = match self.quality {
-            ChordQuality::Major => {
-                if has_seventh {
-                    vec![(1, "P1"), (3, "M3"), (5, fifth), (7, "M7")]
-                } else {
-                    vec![(1, "P1"), (3, "M3"), (5, fifth)]
-                }
+    fn is_altered(&self, degree: u8) -> bool {
+        self.alterations
+            .iter()
+            .any(|alteration| alteration.degree == degree)
+    }
+
+    fn seventh_shift(&self) -> IntegerType {
+        self.alterations
+            .iter()
+            .filter(|alteration| alteration.degree == 7)
+            .map(ChordAlteration::semitones)
+            .sum()
+    }
+
+    fn seventh_interval(&self) -> &'static str {
+        let natural = match self.quality {
+            ChordQuality::Major => 11,
+            ChordQuality::Diminished => 9,
+            _ => 10,
+        };
+        match natural + self.seventh_shift() {
+            ..=9 => "d7",
+            10 => "m7",
+            _ => "M7",
+        }
+    }
+
+    /// Folds alterations that the quality already implies back into it, so
+    /// `Cm7b5` and `Cø7` parse to the same symbol.
+    fn normalize(&mut self) {
+        if self.quality == ChordQuality::Minor
+            && self.has_seventh()
+            && self
+                .alterations
+                .iter()
+                .any(|alteration| alteration.degree == 5 && alteration.semitones == -1)
+        {
+            self.quality = ChordQuality::HalfDiminished;
+        }
+
+        let implied = match self.quality {
+            ChordQuality::Diminished | ChordQuality::HalfDiminished => Some((5, -1)),
+            ChordQuality::Augmented => Some((5, 1)),
+            _ => None,
+        };
+        // Only minor and augmented chords spell a raised seventh as an
+        // alteration; every other quality fixes its own seventh.
+        let alters_seventh = matches!(self.quality, ChordQuality::Minor | ChordQuality::Augmented);
+        self.alterations.retain(|alteration| {
+            Some((alteration.degree, alteration.semitones)) != implied
+                && (alters_seventh || alteration.degree != 7)
+        });
+        if self.is_special() {
+            self.extensions.clear();
+            self.alterations.clear();
+        }
+    }
+
+    fn render(&self, style: ChordSymbolStyle) -> String {
+        let mut text = styled_pitch_name(&self.root, style);
+        text.push_str(&self.styled_suffix(style));
+        if style != ChordSymbolStyle::Music21 {
+            text.push_str(&self.styled_modifiers(style));
+        }
+        if let Some(bass) = &self.bass {
+            text.push('/');
+            text.push_str(&styled_pitch_name(bass, style));
+        }
+        if style == ChordSymbolStyle::Music21 {
+            text.push_str(&self.styled_modifiers(style));
+        }
+        text
+    }
+
+    /// Everything written after the root in a style, without the slash bass.
+    pub(crate) fn suffix(&self, style: ChordSymbolStyle) -> String {
+        let mut suffix = self.styled_suffix(style);
+        suffix.push_str(&self.styled_modifiers(style));
+        suffix
+    }
+
+    /// The quality, extension and alteration text that follows the root.
+    fn styled_suffix(&self, style: ChordSymbolStyle) -> String {
+        if style == ChordSymbolStyle::Music21
+            && let Some(abbreviation) = self.music21_abbreviation()
+        {
+            return abbreviation.to_string();
+        }
+
+        let mut alterations = self
+            .alterations
+            .iter()
+            .filter(|alteration| alteration.degree != 7)
+            .map(|alteration| signed_degree(alteration.degree, alteration.semitones))
+            .collect::<Vec<_>>();
+        let mut suffix = self.styled_quality(style);
+        if self.quality == ChordQuality::HalfDiminished {
+            match style {
+                ChordSymbolStyle::Jazz => suffix.push_str("b5"),
+                ChordSymbolStyle::Berklee => alterations.insert(0, "b5".to_string()),
+                _ => {}
             }
+        }
+
+        if alterations.is_empty() {
+            return suffix;
+        }
+        // A bare alteration after the root would read as part of its name:
+        // `B` plus `b5` is a B-flat power chord.
+        if style == ChordSymbolStyle::Berklee || suffix.is_empty() {
+            suffix.push('(');
+            suffix.push_str(&alterations.join(","));
+            suffix.push(')');
+        } else {
+            suffix.push_str(&alterations.concat());
+        }
+        suffix
+    }
+
+    fn styled_quality(&self, style: ChordSymbolStyle) -> String {
+        use ChordSymbolStyle::{Berklee, Jazz, JazzSymbols, Music21, Nashville};
+
+        let number = self.has_seventh().then(|| {
+            self.extensions
+                .iter()
+                .copied()
+                .filter(|degree| matches!(degree, 7 | 9 | 11 | 13) && !self.is_altered(*degree))
+                .max()
+                .unwrap_or(7)
+        });
+        let sixth = self.extensions.contains(&6);
+        let raised = matches!(self.quality, ChordQuality::Minor | ChordQuality::Augmented)
+            && self.seventh_shift() > 0;
+
+        match self.quality {
+            ChordQuality::Major => match (number, style) {
+                (Some(7), Music21) => "maj7".to_string(),
+                (Some(n), Music21) => format!("M{n}"),
+                (Some(n), JazzSymbols) => format!("\u{25b3}{n}"),
+                (Some(n), Berklee) => format!("ma{n}"),
+                (Some(n), Jazz | Nashville) => format!("maj{n}"),
+                (None, _) if sixth => "6".to_string(),
+                (None, _) => String::new(),
+            },
             ChordQuality::Minor => {
-                if has_seventh {
-                    vec![(1, "P1"), (3, "m3"), (5, fifth), (7, "m7")]
-                } else {
-                    vec![(1, "P1"), (3, "m3"), (5, fifth)]
+                let prefix = if style == Berklee { "mi" } else { "m" };
+                match (number, raised, style) {
+                    (Some(n), true, Music21) => format!("{prefix}M{n}"),
+                    (Some(n), true, JazzSymbols) => format!("{prefix}\u{25b3}{n}"),
+                    (Some(n), true, Berklee) => format!("{prefix}(ma{n})"),
+                    (Some(n), true, Jazz | Nashville) => format!("{prefix}(maj{n})"),
+                    (Some(n), false, _) => format!("{prefix}{n}"),
+                    (None, _, _) if sixth => format!("{prefix}6"),
+                    (None, _, _) => prefix.to_string(),
                 }
             }
-            ChordQuality::Dominant => vec![(1, "P1"), (3, "M3"), (5, fifth), (7, "m7")],
+            ChordQuality::Dominant => number.unwrap_or(7).to_string(),
             ChordQuality::Diminished => {
-                if has_seventh {
-                    vec![(1, "P1"), (3, "m3"), (5, "d5"), (7, "d7")]
-                } else {
-                    vec![(1, "P1"), (3, "m3"), (5, "d5")]
+                let sign = match style {
+                    Jazz => "dim",
+                    JazzSymbols | Nashville => "\u{00b0}",
+                    Music21 | Berklee => "o",
+                };
+                match number {
+                    Some(n) => format!("{sign}{n}"),
+                    None if style == Music21 => "dim".to_string(),
+                    None => sign.to_string(),
                 }
             }
-            ChordQuality::Augmented => vec![(1, "P1"), (3, "M3"), (5, "a5")],
-            ChordQuality::HalfDiminished => vec![(1, "P1"), (3, "m3"), (5, "d5"), (7, "m7")],
-            ChordQuality::Suspended2 => vec![(1, "P1"), (2, "M2"), (5, fifth)],
-            ChordQuality::Suspended4 => vec![(1, "P1"), (4, "P4"), (5, fifth)],
-            ChordQuality::Power => vec![(1, "P1"), (5, fifth)],
-        };
+            ChordQuality::HalfDiminished => {
+                let n = number.unwrap_or(7);
+                match style {
+                    Jazz => format!("m{n}"),
+                    Berklee => format!("mi{n}"),
+                    JazzSymbols | Music21 | Nashville => format!("\u{00f8}{n}"),
+                }
+            }
+            ChordQuality::Augmented => match (number, raised, style) {
+                (Some(n), true, Music21) => format!("+M{n}"),
+                (Some(n), true, JazzSymbols) => format!("+\u{25b3}{n}"),
+                (Some(n), true, Berklee) => format!("+(ma{n})"),
+                (Some(n), true, Jazz | Nashville) => format!("+maj{n}"),
+                (Some(n), false, _) => format!("+{n}"),
+                (None, _, _) => "+".to_string(),
+            },
+            ChordQuality::Suspended2 | ChordQuality::Suspended4 => {
+                let sus = match (self.quality, style) {
+                    (ChordQuality::Suspended2, _) => "sus2",
+                    (_, Music21 | Nashville) => "sus",
+                    _ => "sus4",
+                };
+                match number {
+                    Some(n) => format!("{n}{sus}"),
+                    None => sus.to_string(),
+                }
+            }
+            ChordQuality::Power if style == Music21 => "power".to_string(),
+            ChordQuality::Power => "5".to_string(),
+            ChordQuality::Pedal => "pedal".to_string(),
+            ChordQuality::Neapolitan => "N6".to_string(),
+            ChordQuality::ItalianAugmentedSixth => "It+6".to_string(),
+            ChordQuality::FrenchAugmentedSixth => "Fr+6".to_string(),
+            ChordQuality::GermanAugmentedSixth => "Gr+6".to_string(),
+            ChordQuality::Tristan => "tristan".to_string(),
+        }
+    }
 
-        intervals
+    /// Omitted and added tones, such as `no3` and `add9`.
+    ///
+    /// Additions come last because the parser reads everything after `add`
+    /// as added tones.
+    fn styled_modifiers(&self, style: ChordSymbolStyle) -> String {
+        let mut modifiers = String::new();
+        let additions = self
+            .additions
+            .iter()
+            .map(|addition| signed_degree(addition.degree, addition.semitones))
+            .collect::<Vec<_>>();
+        let omissions = self.omissions.iter().map(u8::to_string).collect::<Vec<_>>();
+
+        if style == ChordSymbolStyle::Music21 {
+            if !additions.is_empty() {
+                modifiers.push_str("add");
+                modifiers.push_str(&additions.join(","));
+            }
+            if !omissions.is_empty() {
+                modifiers.push_str("omit");
+                modifiers.push_str(&omissions.join(","));
+            }
+        } else {
+            for omission in omissions {
+                modifiers.push_str("no");
+                modifiers.push_str(&omission);
+            }
+            if !additions.is_empty() {
+                modifiers.push_str("add");
+                modifiers.push_str(&additions.join(","));
+            }
+        }
+        modifiers
+    }
+
+    /// music21's abbreviation for this symbol's sonority, ignoring added and
+    /// omitted tones, when it is one of the `CHORD_TYPES`.
+    fn music21_abbreviation(&self) -> Option<&'static str> {
+        let intervals = self.chord_intervals(false).ok()?;
+        let mut degrees = intervals
             .into_iter()
-            .filter(|(degree, _)| !self.omissions.contains(degree))
-            .collect()
+            .map(|(degree, name)| {
+                let semitones = Interval::from_name(name).ok()?.semitones();
+                let natural = match degree {
+                    6 => 9,
+                    degree => base_semitone_for_degree(degree)?,
+                };
+                let mut shift = (semitones - natural).rem_euclid(12);
+                if shift > 6 {
+                    shift -= 12;
+                }
+                Some((degree, shift))
+            })
+            .collect::<Option<Vec<_>>>()?;
+        degrees.sort_unstable();
+        degrees.dedup();
+
+        let notation = degrees
+            .into_iter()
+            .map(|(degree, shift)| {
+                let accidental = if shift < 0 { "-" } else { "#" };
+                format!(
+                    "{}{degree}",
+                    accidental.repeat(shift.unsigned_abs() as usize)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        MUSIC21_CHORD_TYPES
+            .iter()
+            .find(|chord_type| chord_type.notation == notation)
+            .map(|chord_type| chord_type.abbreviation)
+    }
+}
+
+impl fmt::Display for ChordSymbol {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.display(ChordSymbolStyle::Jazz).fmt(formatter)
+    }
+}
+
+/// A [`ChordSymbol`] written in a [`ChordSymbolStyle`].
+///
+/// Returned by [`ChordSymbol::display`].
+#[derive(Clone, Copy, Debug)]
+pub struct ChordSymbolDisplay<'a> {
+    symbol: &'a ChordSymbol,
+    style: ChordSymbolStyle,
+}
+
+impl fmt::Display for ChordSymbolDisplay<'_> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(&self.symbol.render(self.style))
     }
 }
 
+fn styled_pitch_name(pitch: &Pitch, style: ChordSymbolStyle) -> String {
+    let name = pitch.name();
+    if style == ChordSymbolStyle::Music21 {
+        name
+    } else {
+        name.replace('-', "b")
+    }
+}
+
+fn signed_degree(degree: u8, semitones: IntegerType) -> String {
+    let accidental = if semitones < 0 { "b" } else { "#" };
+    format!(
+        "{}{degree}",
+        accidental.repeat(semitones.unsigned_abs() as usize)
+    )
+}
+
 /// Returns the music21 chord-symbol figure for a chord, when identified.
 ///
 /// This ports music21's `harmony.chordSymbolFigureFromChord` matching order and
@@ -609,7 +1140,7 @@ fn music21_chord_symbol_figure(chord: &Chord, explicit_root: Option<u8>) -> Opti
     let mut root_pitch = if let Some(root) = explicit_root {
         pitches
             .iter()
-            .find(|pitch| pitch_class(pitch) == root)
+            .find(|pitch| pitch.pitch_class_number() == root)
             .cloned()?
     } else {
         find_root_pitch(&pitches).cloned()?
@@ -625,7 +1156,7 @@ fn music21_chord_symbol_figure(chord: &Chord, explicit_root: Option<u8>) -> Opti
     let mut notation = matched.notation;
     let mut abbreviation = matched.abbreviation;
 
-    if pitch_class(bass_pitch) != pitch_class(&root_pitch)
+    if bass_pitch.pitch_class_number() != root_pitch.pitch_class_number()
         && matched.kind == "suspended-second"
         && matched.abbreviation == "sus2"
     {
@@ -635,7 +1166,7 @@ fn music21_chord_symbol_figure(chord: &Chord, explicit_root: Option<u8>) -> Opti
     }
 
     let mut figure = format!("{}{}", root_pitch.name(), abbreviation);
-    if pitch_class(bass_pitch) != pitch_class(&root_pitch) {
+    if bass_pitch.pitch_class_number() != root_pitch.pitch_class_number() {
         figure.push('/');
         figure.push_str(&bass_pitch.name());
     }
@@ -881,12 +1412,12 @@ fn analysis_value_for_degree(analysis: &Music21ChordAnalysis, degree: u8) -> Opt
 
 fn semitones_from_chord_step(pitches: &[Pitch], root_pitch: &Pitch, chord_step: u8) -> Option<u8> {
     let root_step = step_num(root_pitch);
-    let root_pc = pitch_class(root_pitch);
+    let root_pc = root_pitch.pitch_class_number();
 
     pitches.iter().find_map(|pitch| {
         let generic_interval = (step_num(pitch) - root_step).rem_euclid(7) + 1;
         if generic_interval == chord_step as IntegerType {
-            Some((pitch_class(pitch) + 12 - root_pc) % 12)
+            Some((pitch.pitch_class_number() + 12 - root_pc) % 12)
         } else {
             None
         }
@@ -915,7 +1446,7 @@ fn pitch_name_for_music21_degree(root_pitch: &Pitch, degree: u8, semitone: u8) -
     let root_index = LETTERS.iter().position(|letter| *letter == root_letter)?;
     let target_index = (root_index + (degree.saturating_sub(1) as usize % 7)) % 7;
     let desired_pc =
-        ((pitch_class(root_pitch) as IntegerType) + semitone as IntegerType).rem_euclid(12);
+        ((root_pitch.pitch_class_number() as IntegerType) + semitone as IntegerType).rem_euclid(12);
     let mut accidental = desired_pc - NATURAL_PCS[target_index];
     while accidental > 6 {
         accidental -= 12;
@@ -1006,10 +1537,6 @@ fn step_num(pitch: &Pitch) -> IntegerType {
     pitch.step().step_to_dnn_offset() - 1
 }
 
-fn pitch_class(pitch: &Pitch) -> u8 {
-    (pitch.ps().round() as IntegerType).rem_euclid(12) as u8
-}
-
 impl FromStr for ChordSymbol {
     type Err = Error;
 
@@ -1104,7 +1631,8 @@ fn music21_modifier_at(value: &str, idx: usize) -> Option<&'static str> {
 fn pitch_name_addition(root: &Pitch, pitch_name: &str) -> Option<ChordAlteration> {
     let pitch = Pitch::from_name(pitch_name).ok()?;
     let degree = pitch_name_degree(root, pitch_name)?;
-    let actual = ((pitch_class(&pitch) + 12 - pitch_class(root)) % 12) as IntegerType;
+    let actual =
+        ((pitch.pitch_class_number() + 12 - root.pitch_class_number()) % 12) as IntegerType;
     let base = base_semitone_for_degree(degree)?.rem_euclid(12);
     let mut semitones = actual - base;
     while semitones > 6 {
@@ -1151,6 +1679,9 @@ fn parse_quality(suffix: &str, alterations: &[ChordAlteration]) -> ChordQuality
     let has_flat_five = alterations
         .iter()
         .any(|alteration| alteration.degree == 5 && alteration.semitones == -1);
+    let has_seventh = parse_extensions(suffix, &[])
+        .iter()
+        .any(|degree| matches!(degree, 7 | 9 | 11 | 13));
 
     if suffix.starts_with('\u{00f8}') {
         ChordQuality::HalfDiminished
@@ -1158,15 +1689,18 @@ fn parse_quality(suffix: &str, alterations: &[ChordAlteration]) -> ChordQuality
         ChordQuality::Suspended2
     } else if lower.contains("sus") {
         ChordQuality::Suspended4
-    } else if lower.starts_with("maj") || suffix.starts_with('M') {
+    } else if lower.starts_with("ma")
+        || suffix.starts_with('M')
+        || starts_with_major_seventh_sign(suffix)
+    {
         ChordQuality::Major
     } else if lower.starts_with("min") || lower.starts_with('m') {
-        if has_flat_five && lower.contains('7') {
+        if has_flat_five && has_seventh {
             ChordQuality::HalfDiminished
         } else {
             ChordQuality::Minor
         }
-    } else if lower.starts_with("dim") || lower.starts_with('o') {
+    } else if lower.starts_with("dim") || lower.starts_with('o') || suffix.starts_with('\u{00b0}') {
         ChordQuality::Diminished
     } else if lower.starts_with("aug") || lower.starts_with('+') {
         ChordQuality::Augmented
@@ -1184,6 +1718,48 @@ fn parse_quality(suffix: &str, alterations: &[ChordAlteration]) -> ChordQuality
     }
 }
 
+/// Recognizes music21's non-tertian `CHORD_TYPES` abbreviations.
+fn special_quality(suffix: &str) -> Option<ChordQuality> {
+    match suffix {
+        "power" => Some(ChordQuality::Power),
+        "pedal" => Some(ChordQuality::Pedal),
+        "N6" => Some(ChordQuality::Neapolitan),
+        "It+6" | "It" => Some(ChordQuality::ItalianAugmentedSixth),
+        "Fr+6" | "Fr" => Some(ChordQuality::FrenchAugmentedSixth),
+        "Gr+6" | "Ger" | "Ger+6" => Some(ChordQuality::GermanAugmentedSixth),
+        "tristan" => Some(ChordQuality::Tristan),
+        _ => None,
+    }
+}
+
+fn starts_with_major_seventh_sign(suffix: &str) -> bool {
+    suffix.starts_with('\u{25b3}') || suffix.starts_with('\u{0394}')
+}
+
+/// Whether a minor or augmented figure raises its seventh, as in `mM7`,
+/// `m(maj7)`, `mi(ma7)`, `m△7`, or `+M7`.
+fn has_major_seventh_marker(suffix: &str) -> bool {
+    let rest = suffix
+        .char_indices()
+        .nth(1)
+        .map_or("", |(idx, _)| &suffix[idx..]);
+    rest.contains('M')
+        || rest.to_ascii_lowercase().contains("ma")
+        || rest.contains('\u{25b3}')
+        || rest.contains('\u{0394}')
+}
+
+/// Whether a modifier token names a chord degree, like `9` or `#11`, rather
+/// than a pitch, like `E-`.
+fn is_degree_token(token: &str) -> bool {
+    let mut chars = token.trim().chars();
+    match chars.next() {
+        Some(ch) if ch.is_ascii_digit() => true,
+        Some('#' | 'b' | '-') => chars.next().is_some_and(|ch| ch.is_ascii_digit()),
+        _ => false,
+    }
+}
+
 fn parse_extensions(suffix: &str, alterations: &[ChordAlteration]) -> Vec<u8> {
     let mut extensions = Vec::new();
     let bytes = suffix.as_bytes();
@@ -1194,6 +1770,8 @@ fn parse_extensions(suffix: &str, alterations: &[ChordAlteration]) -> Vec<u8> {
             && idx
                 .checked_sub(1)
                 .is_none_or(|prev| !matches!(bytes[prev] as char, '#' | 'b' | '-'))
+            && !suffix[..idx].ends_with("no")
+            && !suffix[..idx].ends_with("omit")
         {
             let start = idx;
             while idx < bytes.len() && bytes[idx].is_ascii_digit() {
@@ -1631,4 +2209,134 @@ mod tests {
             Some("CsusaddA,A-,D-,E,E-,F#omitF")
         );
     }
+
+    #[test]
+    fn builds_symbols_from_parts() {
+        let altered = ChordSymbol::new(
+            Pitch::from_name("B-").unwrap(),
+            ChordQuality::Dominant,
+            [7],
+            [ChordAlteration::new(9, -1), ChordAlteration::new(11, 1)],
+            Some(Pitch::from_name("D").unwrap()),
+        );
+        assert_eq!(altered.figure(), "Bb7b9#11/D");
+        assert_eq!(altered.extensions(), &[7, 9, 11]);
+        assert_eq!(
+            altered.to_chord().unwrap().pitch_classes(),
+            vec![2, 4, 5, 8, 10, 11]
+        );
+
+        let minor_major = ChordSymbol::new(
+            Pitch::from_name("C").unwrap(),
+            ChordQuality::Minor,
+            [7],
+            [ChordAlteration::new(7, 1)],
+            None,
+        );
+        assert_eq!(minor_major.to_string(), "Cm(maj7)");
+        assert_eq!(
+            minor_major.to_chord().unwrap().pitched_common_name(),
+            "C-minor-augmented tetrachord"
+        );
+
+        let added = ChordSymbol::new(
+            Pitch::from_name("G").unwrap(),
+            ChordQuality::Dominant,
+            [7],
+            [],
+            None,
+        )
+        .with_omissions([3])
+        .with_additions([ChordAlteration::new(4, 0)]);
+        assert_eq!(added.figure(), "G7no3add4");
+        assert_eq!(added.to_chord().unwrap().pitch_classes(), vec![0, 2, 5, 7]);
+    }
+
+    #[test]
+    fn renders_selectable_styles() {
+        let cases = [
+            ("Cmaj7", ["Cmaj7", "C\u{25b3}7", "Cmaj7", "Cma7", "Cmaj7"]),
+            (
+                "Bbm7b5",
+                [
+                    "Bbm7b5",
+                    "Bb\u{00f8}7",
+                    "B-\u{00f8}7",
+                    "Bbmi7(b5)",
+                    "Bb\u{00f8}7",
+                ],
+            ),
+            (
+                "CmM7",
+                ["Cm(maj7)", "Cm\u{25b3}7", "CmM7", "Cmi(ma7)", "Cm(maj7)"],
+            ),
+            ("Go7", ["Gdim7", "G\u{00b0}7", "Go7", "Go7", "G\u{00b0}7"]),
+            (
+                "D7b9#11/C",
+                [
+                    "D7b9#11/C",
+                    "D7b9#11/C",
+                    "D7b9#11/C",
+                    "D7(b9,#11)/C",
+                    "D7b9#11/C",
+                ],
+            ),
+            ("G7sus", ["G7sus4", "G7sus4", "G7sus", "G7sus4", "G7sus"]),
+            ("Eb", ["Eb", "Eb", "E-", "Eb", "Eb"]),
+            ("Cdom7dim5", ["C7b5", "C7b5", "Cdom7dim5", "C7(b5)", "C7b5"]),
+        ];
+        let styles = [
+            ChordSymbolStyle::Jazz,
+            ChordSymbolStyle::JazzSymbols,
+            ChordSymbolStyle::Music21,
+            ChordSymbolStyle::Berklee,
+            ChordSymbolStyle::Nashville,
+        ];
+
+        for (figure, expected) in cases {
+            let symbol = ChordSymbol::parse(figure).unwrap();
+            for (style, expected) in styles.into_iter().zip(expected) {
+                assert_eq!(symbol.display(style).to_string(), expected, "{figure}");
+            }
+        }
+    }
+
+    #[test]
+    fn every_known_chord_type_round_trips_in_every_style() {
+        let styles = [
+            ChordSymbolStyle::Jazz,
+            ChordSymbolStyle::JazzSymbols,
+            ChordSymbolStyle::Music21,
+            ChordSymbolStyle::Berklee,
+            ChordSymbolStyle::Nashville,
+        ];
+        let names = |symbol: &ChordSymbol| {
+            symbol
+                .to_chord()
+                .unwrap()
+                .pitches()
+                .iter()
+                .map(Pitch::name)
+                .collect::<Vec<_>>()
+        };
+
+        for chord_type in known_chord_symbol_types() {
+            let figure = format!("E-{}", chord_type.abbreviation);
+            let parsed = ChordSymbol::parse(figure.as_str()).unwrap();
+            assert_eq!(
+                parsed.display(ChordSymbolStyle::Music21).to_string(),
+                figure,
+                "{}",
+                chord_type.kind
+            );
+
+            for style in styles {
+                let rendered = parsed.display(style).to_string();
+                let reparsed = ChordSymbol::parse(rendered.as_str()).unwrap();
+                assert_eq!(reparsed.quality(), parsed.quality(), "{rendered}");
+                assert_eq!(names(&reparsed), names(&parsed), "{rendered}");
+                assert_eq!(reparsed.display(style).to_string(), rendered);
+            }
+        }
+    }
 }
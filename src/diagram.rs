@@ -0,0 +1,778 @@
+//! SVG chord, keyboard and pitch-class diagrams.
+//!
+//! Each renderer returns a self-contained `<svg>` document as a `String`, so
+//! the same diagram can be written to a file natively or inserted into a page
+//! from WebAssembly. Elements carry `diagram-*` classes, letting a stylesheet
+//! restyle inline SVG without re-rendering it.
+
+use std::fmt::Write;
+
+use crate::{
+    chord::{Chord, GuitarFingering},
+    defaults::{FloatType, IntegerType},
+    pitchclassset::PitchClassSet,
+};
+
+const STRING_SPACING: FloatType = 24.0;
+const FRET_SPACING: FloatType = 30.0;
+const WHITE_KEY_WIDTH: FloatType = 22.0;
+const WHITE_KEY_HEIGHT: FloatType = 90.0;
+const BLACK_KEY_WIDTH: FloatType = 13.0;
+const BLACK_KEY_HEIGHT: FloatType = 56.0;
+const CLOCK_SIZE: FloatType = 200.0;
+
+/// Colours, sizing and labelling shared by the diagram renderers.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DiagramOptions {
+    scale: FloatType,
+    ink: String,
+    background: String,
+    accent: String,
+    root_color: String,
+    muted: String,
+    fret_rows: u8,
+    labels: bool,
+    root: Option<u8>,
+}
+
+impl Default for DiagramOptions {
+    fn default() -> Self {
+        Self {
+            scale: 1.0,
+            ink: "#151515".to_string(),
+            background: "#ffffff".to_string(),
+            accent: "#0f766e".to_string(),
+            root_color: "#b45309".to_string(),
+            muted: "#61646b".to_string(),
+            fret_rows: 5,
+            labels: true,
+            root: None,
+        }
+    }
+}
+
+impl DiagramOptions {
+    /// Returns the options with the rendered `width` and `height` multiplied
+    /// by `scale`. The drawing itself is unchanged, as it uses a `viewBox`.
+    pub fn with_scale(mut self, scale: FloatType) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Returns the options with the given CSS colours for text and outlines,
+    /// the background, played notes, the root and secondary lines.
+    pub fn with_colors(
+        mut self,
+        ink: impl Into<String>,
+        background: impl Into<String>,
+        accent: impl Into<String>,
+        root: impl Into<String>,
+        muted: impl Into<String>,
+    ) -> Self {
+        self.ink = ink.into();
+        self.background = background.into();
+        self.accent = accent.into();
+        self.root_color = root.into();
+        self.muted = muted.into();
+        self
+    }
+
+    /// Returns the options drawing at least `rows` frets in fingering
+    /// diagrams. Wider shapes always get enough rows. The default is 5.
+    pub fn with_fret_rows(mut self, rows: u8) -> Self {
+        self.fret_rows = rows.max(1);
+        self
+    }
+
+    /// Returns the options with string, key and pitch-class labels shown or
+    /// hidden. They are shown by default.
+    pub fn with_labels(mut self, labels: bool) -> Self {
+        self.labels = labels;
+        self
+    }
+
+    /// Returns the options highlighting `pitch_class` as the root.
+    ///
+    /// [`Chord::keyboard_svg`] highlights the chord's own root when none is
+    /// set.
+    pub fn with_root(mut self, pitch_class: u8) -> Self {
+        self.root = Some(pitch_class % 12);
+        self
+    }
+
+    /// Returns the size multiplier.
+    pub fn scale(&self) -> FloatType {
+        self.scale
+    }
+
+    /// Returns the minimum number of fret rows.
+    pub fn fret_rows(&self) -> u8 {
+        self.fret_rows
+    }
+
+    /// Returns whether labels are drawn.
+    pub fn labels(&self) -> bool {
+        self.labels
+    }
+
+    /// Returns the highlighted root pitch class.
+    pub fn root(&self) -> Option<u8> {
+        self.root
+    }
+
+    fn note_color(&self, pitch_class: Option<u8>) -> &str {
+        if pitch_class.is_some() && pitch_class == self.root {
+            &self.root_color
+        } else {
+            &self.accent
+        }
+    }
+}
+
+/// Arrangement of the twelve pitch classes around a clock diagram.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ClockLayout {
+    /// Pitch classes in semitone order, 0 at the top.
+    #[default]
+    Chromatic,
+    /// Pitch classes in fifths, C, G, D and so on clockwise from the top.
+    Fifths,
+}
+
+impl ClockLayout {
+    /// Returns the clock position, 0 to 11 clockwise from the top, of
+    /// `pitch_class`.
+    pub fn position(self, pitch_class: u8) -> u8 {
+        match self {
+            Self::Chromatic => pitch_class % 12,
+            Self::Fifths => pitch_class % 12 * 7 % 12,
+        }
+    }
+}
+
+/// Draws a fretboard diagram of a guitar fingering.
+///
+/// Strings run bass to treble from left to right. Shapes that fit within the
+/// first [`DiagramOptions::fret_rows`] frets are drawn from the nut;
+/// others are drawn from their lowest fret, labelled `5fr` and so on. Muted
+/// strings get a cross above the nut and open strings a ring. Fretted notes
+/// show their finger number, and strings sharing a finger and fret become a
+/// barre. Notes matching [`DiagramOptions::root`] use the root colour.
+pub fn fingering_svg(fingering: &GuitarFingering, options: &DiagramOptions) -> String {
+    let strings = &fingering.strings;
+    let fretted = strings
+        .iter()
+        .filter_map(|string| string.fret.filter(|fret| *fret > 0))
+        .collect::<Vec<_>>();
+    let lowest = fretted.iter().copied().min().unwrap_or(1);
+    let highest = fretted.iter().copied().max().unwrap_or(1);
+    let first_fret = if highest <= options.fret_rows {
+        1
+    } else {
+        lowest
+    };
+    let rows = options.fret_rows.max(highest + 1 - first_fret);
+
+    let left = 30.0;
+    let top = 40.0;
+    let neck_width = STRING_SPACING * strings.len().saturating_sub(1) as FloatType;
+    let neck_height = FRET_SPACING * FloatType::from(rows);
+    let width = left * 2.0 + neck_width;
+    let height = top + neck_height + if options.labels { 30.0 } else { 12.0 };
+    let x = |index: usize| left + STRING_SPACING * index as FloatType;
+    let y = |fret: u8| top + FRET_SPACING * (FloatType::from(fret - first_fret) + 0.5);
+
+    let mut svg = open_svg(width, height, options);
+    for row in 0..=rows {
+        let fret_y = top + FRET_SPACING * FloatType::from(row);
+        line(
+            &mut svg,
+            "diagram-fret",
+            (left, fret_y),
+            (left + neck_width, fret_y),
+            &options.muted,
+            1.0,
+        );
+    }
+    for index in 0..strings.len() {
+        line(
+            &mut svg,
+            "diagram-string",
+            (x(index), top),
+            (x(index), top + neck_height),
+            &options.muted,
+            1.0,
+        );
+    }
+    if first_fret == 1 {
+        line(
+            &mut svg,
+            "diagram-nut",
+            (left, top),
+            (left + neck_width, top),
+            &options.ink,
+            5.0,
+        );
+    } else {
+        text(
+            &mut svg,
+            "diagram-position",
+            (left - 8.0, y(first_fret) + 4.0),
+            "end",
+            12.0,
+            &options.ink,
+            &format!("{first_fret}fr"),
+        );
+    }
+
+    let barres = barres(fingering);
+    for (fret, start, end, finger) in &barres {
+        let color = &options.accent;
+        let _ = writeln!(
+            svg,
+            r#"  <rect class="diagram-barre" x="{}" y="{}" width="{}" height="18" rx="9" fill="{color}"/>"#,
+            number(x(*start) - 9.0),
+            number(y(*fret) - 9.0),
+            number(x(*end) - x(*start) + 18.0),
+        );
+        text(
+            &mut svg,
+            "diagram-finger",
+            ((x(*start) + x(*end)) / 2.0, y(*fret) + 4.0),
+            "middle",
+            11.0,
+            &options.background,
+            &finger.to_string(),
+        );
+    }
+
+    for (index, string) in strings.iter().enumerate() {
+        let marker_y = top - 14.0;
+        match string.fret {
+            None => {
+                let (cx, size) = (x(index), 5.0);
+                for (from, to) in [
+                    ((cx - size, marker_y - size), (cx + size, marker_y + size)),
+                    ((cx - size, marker_y + size), (cx + size, marker_y - size)),
+                ] {
+                    line(&mut svg, "diagram-muted", from, to, &options.ink, 1.5);
+                }
+            }
+            Some(0) => {
+                let color = options.note_color(string.pitch_class);
+                let _ = writeln!(
+                    svg,
+                    r#"  <circle class="{}" cx="{}" cy="{}" r="6" fill="none" stroke="{color}" stroke-width="1.5"/>"#,
+                    note_class("diagram-open", string.pitch_class, options),
+                    number(x(index)),
+                    number(marker_y),
+                );
+            }
+            Some(fret) => {
+                let in_barre = barres.iter().any(|(barre_fret, start, end, finger)| {
+                    *barre_fret == fret
+                        && (*start..=*end).contains(&index)
+                        && string.finger == Some(*finger)
+                });
+                if in_barre && string.pitch_class != options.root {
+                    continue;
+                }
+                let color = options.note_color(string.pitch_class);
+                let _ = writeln!(
+                    svg,
+                    r#"  <circle class="{}" cx="{}" cy="{}" r="9" fill="{color}"/>"#,
+                    note_class("diagram-dot", string.pitch_class, options),
+                    number(x(index)),
+                    number(y(fret)),
+                );
+                if let Some(finger) = string.finger {
+                    text(
+                        &mut svg,
+                        "diagram-finger",
+                        (x(index), y(fret) + 4.0),
+                        "middle",
+                        11.0,
+                        &options.background,
+                        &finger.to_string(),
+                    );
+                }
+            }
+        }
+    }
+
+    if options.labels {
+        for (index, string) in strings.iter().enumerate() {
+            let name = string
+                .string_name
+                .trim_end_matches(|c: char| c.is_ascii_digit());
+            text(
+                &mut svg,
+                "diagram-label",
+                (x(index), top + neck_height + 20.0),
+                "middle",
+                12.0,
+                &options.muted,
+                &display_name(name),
+            );
+        }
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Draws a piano keyboard with `chord`'s pitches pressed.
+///
+/// The keyboard spans whole octaves from the C at or below the lowest pitch
+/// to the B at or above the highest, and at least two octaves. Pressed keys
+/// are labelled with the chord's spelling. Microtonal pitches press the
+/// nearest key.
+pub fn keyboard_svg(chord: &Chord, options: &DiagramOptions) -> String {
+    let pressed = chord
+        .pitches()
+        .into_iter()
+        .map(|pitch| (pitch.ps().round() as IntegerType, pitch))
+        .collect::<Vec<_>>();
+    let low = pressed.iter().map(|(key, _)| *key).min().unwrap_or(60);
+    let high = pressed.iter().map(|(key, _)| *key).max().unwrap_or(60);
+    let first = low.div_euclid(12) * 12;
+    let last = (high.div_euclid(12) * 12 + 11).max(first + 23);
+
+    let white_keys = (first..=last).filter(|key| !is_black(*key)).count();
+    let width = WHITE_KEY_WIDTH * white_keys as FloatType + 2.0;
+    let height = WHITE_KEY_HEIGHT + 2.0;
+    let mut svg = open_svg(width, height, options);
+    let mut white_index = 0;
+    let mut black_keys = Vec::new();
+    for key in first..=last {
+        let spelled = pressed
+            .iter()
+            .find(|(pressed, _)| *pressed == key)
+            .map(|(_, pitch)| pitch);
+        let pitch_class = key.rem_euclid(12) as u8;
+        if is_black(key) {
+            let key_x = 1.0 + WHITE_KEY_WIDTH * white_index as FloatType - BLACK_KEY_WIDTH / 2.0;
+            black_keys.push((key_x, pitch_class, spelled));
+            continue;
+        }
+        let key_x = 1.0 + WHITE_KEY_WIDTH * white_index as FloatType;
+        let fill = match spelled {
+            Some(_) => options.note_color(Some(pitch_class)),
+            None => &options.background,
+        };
+        let _ = writeln!(
+            svg,
+            r#"  <rect class="{}" x="{}" y="1" width="{}" height="{}" fill="{fill}" stroke="{}"/>"#,
+            key_class("diagram-white-key", spelled.is_some(), pitch_class, options),
+            number(key_x),
+            number(WHITE_KEY_WIDTH),
+            number(WHITE_KEY_HEIGHT),
+            options.ink,
+        );
+        if let Some(pitch) = spelled.filter(|_| options.labels) {
+            text(
+                &mut svg,
+                "diagram-label",
+                (key_x + WHITE_KEY_WIDTH / 2.0, WHITE_KEY_HEIGHT - 8.0),
+                "middle",
+                10.0,
+                &options.background,
+                &display_name(&pitch.name()),
+            );
+        }
+        white_index += 1;
+    }
+    for (key_x, pitch_class, spelled) in black_keys {
+        let fill = match spelled {
+            Some(_) => options.note_color(Some(pitch_class)),
+            None => &options.ink,
+        };
+        let _ = writeln!(
+            svg,
+            r#"  <rect class="{}" x="{}" y="1" width="{}" height="{}" fill="{fill}" stroke="{}"/>"#,
+            key_class("diagram-black-key", spelled.is_some(), pitch_class, options),
+            number(key_x),
+            number(BLACK_KEY_WIDTH),
+            number(BLACK_KEY_HEIGHT),
+            options.ink,
+        );
+        if let Some(pitch) = spelled.filter(|_| options.labels) {
+            text(
+                &mut svg,
+                "diagram-label",
+                (key_x + BLACK_KEY_WIDTH / 2.0, BLACK_KEY_HEIGHT - 8.0),
+                "middle",
+                8.0,
+                &options.background,
+                &display_name(&pitch.name()),
+            );
+        }
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Draws a pitch-class clock face with `set`'s members joined as a polygon.
+///
+/// With [`ClockLayout::Fifths`] the face is the circle of fifths. Labels are
+/// pitch-class integers.
+pub fn pitch_class_clock_svg(
+    set: PitchClassSet,
+    layout: ClockLayout,
+    options: &DiagramOptions,
+) -> String {
+    let center = CLOCK_SIZE / 2.0;
+    let radius = 72.0;
+    let point = |pitch_class: u8, radius: FloatType| {
+        let angle = FloatType::from(layout.position(pitch_class)) * std::f64::consts::TAU / 12.0;
+        (center + radius * angle.sin(), center - radius * angle.cos())
+    };
+
+    let mut svg = open_svg(CLOCK_SIZE, CLOCK_SIZE, options);
+    let _ = writeln!(
+        svg,
+        r#"  <circle class="diagram-clock" cx="{0}" cy="{0}" r="{1}" fill="none" stroke="{2}"/>"#,
+        number(center),
+        number(radius),
+        options.muted,
+    );
+    let mut members = set.pitch_classes();
+    members.sort_by_key(|pitch_class| layout.position(*pitch_class));
+    if members.len() > 1 {
+        let points = members
+            .iter()
+            .map(|pitch_class| {
+                let (x, y) = point(*pitch_class, radius);
+                format!("{},{}", number(x), number(y))
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+        let _ = writeln!(
+            svg,
+            r#"  <polygon class="diagram-polygon" points="{points}" fill="{0}" fill-opacity="0.2" stroke="{0}" stroke-width="2"/>"#,
+            options.accent,
+        );
+    }
+    for pitch_class in 0..12 {
+        let (x, y) = point(pitch_class, radius);
+        if set.contains(pitch_class) {
+            let _ = writeln!(
+                svg,
+                r#"  <circle class="{}" cx="{}" cy="{}" r="7" fill="{}"/>"#,
+                note_class("diagram-dot", Some(pitch_class), options),
+                number(x),
+                number(y),
+                options.note_color(Some(pitch_class)),
+            );
+        } else {
+            let _ = writeln!(
+                svg,
+                r#"  <circle class="diagram-point" cx="{}" cy="{}" r="3" fill="{}"/>"#,
+                number(x),
+                number(y),
+                options.muted,
+            );
+        }
+        if options.labels {
+            let (x, y) = point(pitch_class, radius + 18.0);
+            text(
+                &mut svg,
+                "diagram-label",
+                (x, y + 4.0),
+                "middle",
+                12.0,
+                &options.ink,
+                &pitch_class.to_string(),
+            );
+        }
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+impl GuitarFingering {
+    /// Draws the fingering as an SVG fretboard diagram.
+    ///
+    /// See [`fingering_svg`].
+    pub fn to_svg(&self, options: &DiagramOptions) -> String {
+        fingering_svg(self, options)
+    }
+}
+
+impl Chord {
+    /// Draws the chord on an SVG piano keyboard, highlighting its root unless
+    /// `options` names one.
+    ///
+    /// See [`keyboard_svg`].
+    pub fn keyboard_svg(&self, options: &DiagramOptions) -> String {
+        match (options.root, self.find_root_pitch()) {
+            (None, Some(root)) => {
+                keyboard_svg(self, &options.clone().with_root(root.pitch_class_number()))
+            }
+            _ => keyboard_svg(self, options),
+        }
+    }
+
+    /// Draws the chord's pitch classes on an SVG clock face, highlighting its
+    /// root unless `options` names one.
+    ///
+    /// See [`pitch_class_clock_svg`].
+    pub fn clock_svg(&self, layout: ClockLayout, options: &DiagramOptions) -> String {
+        let set = self.pitch_class_set();
+        match (options.root, self.find_root_pitch()) {
+            (None, Some(root)) => pitch_class_clock_svg(
+                set,
+                layout,
+                &options.clone().with_root(root.pitch_class_number()),
+            ),
+            _ => pitch_class_clock_svg(set, layout, options),
+        }
+    }
+}
+
+impl PitchClassSet {
+    /// Draws the set on an SVG clock face.
+    ///
+    /// See [`pitch_class_clock_svg`].
+    pub fn clock_svg(self, layout: ClockLayout, options: &DiagramOptions) -> String {
+        pitch_class_clock_svg(self, layout, options)
+    }
+}
+
+/// Barres as `(fret, first string, last string, finger)`: one finger
+/// stopping several strings at one fret, with every string between them
+/// fretted at or above it.
+fn barres(fingering: &GuitarFingering) -> Vec<(u8, usize, usize, u8)> {
+    let strings = &fingering.strings;
+    let mut barres = Vec::new();
+    for (index, string) in strings.iter().enumerate() {
+        let (Some(fret), Some(finger)) = (string.fret, string.finger) else {
+            continue;
+        };
+        if fret == 0 || barres.iter().any(|(f, _, _, g)| *f == fret && *g == finger) {
+            continue;
+        }
+        let end = strings
+            .iter()
+            .enumerate()
+            .rev()
+            .find(|(_, other)| other.fret == Some(fret) && other.finger == Some(finger))
+            .map_or(index, |(end, _)| end);
+        let clear = strings[index..=end]
+            .iter()
+            .all(|other| other.fret.is_some_and(|other_fret| other_fret >= fret));
+        if end > index && clear {
+            barres.push((fret, index, end, finger));
+        }
+    }
+    barres
+}
+
+fn open_svg(width: FloatType, height: FloatType, options: &DiagramOptions) -> String {
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {} {}" font-family="sans-serif">"#,
+        number(width * options.scale),
+        number(height * options.scale),
+        number(width),
+        number(height),
+    );
+    let _ = writeln!(
+        svg,
+        r#"  <rect class="diagram-background" width="{}" height="{}" fill="{}"/>"#,
+        number(width),
+        number(height),
+        options.background,
+    );
+    svg
+}
+
+fn line(
+    svg: &mut String,
+    class: &str,
+    (x1, y1): (FloatType, FloatType),
+    (x2, y2): (FloatType, FloatType),
+    color: &str,
+    width: FloatType,
+) {
+    let _ = writeln!(
+        svg,
+        r#"  <line class="{class}" x1="{}" y1="{}" x2="{}" y2="{}" stroke="{color}" stroke-width="{}"/>"#,
+        number(x1),
+        number(y1),
+        number(x2),
+        number(y2),
+        number(width),
+    );
+}
+
+fn text(
+    svg: &mut String,
+    class: &str,
+    (x, y): (FloatType, FloatType),
+    anchor: &str,
+    size: FloatType,
+    color: &str,
+    content: &str,
+) {
+    let _ = writeln!(
+        svg,
+        r#"  <text class="{class}" x="{}" y="{}" text-anchor="{anchor}" font-size="{}" fill="{color}">{}</text>"#,
+        number(x),
+        number(y),
+        number(size),
+        escape(content),
+    );
+}
+
+fn note_class(base: &str, pitch_class: Option<u8>, options: &DiagramOptions) -> String {
+    if pitch_class.is_some() && pitch_class == options.root {
+        format!("{base} diagram-root")
+    } else {
+        base.to_string()
+    }
+}
+
+fn key_class(base: &str, pressed: bool, pitch_class: u8, options: &DiagramOptions) -> String {
+    if pressed {
+        format!(
+            "{} diagram-pressed",
+            note_class(base, Some(pitch_class), options)
+        )
+    } else {
+        base.to_string()
+    }
+}
+
+fn is_black(key: IntegerType) -> bool {
+    matches!(key.rem_euclid(12), 1 | 3 | 6 | 8 | 10)
+}
+
+/// A pitch name with typographic accidentals, such as `B♭` for `B-`.
+fn display_name(name: &str) -> String {
+    if name.is_empty() {
+        return String::new();
+    }
+    let (step, accidentals) = name.split_at(1);
+    if accidentals.is_empty() || accidentals.chars().all(|c| c == '-' || c == '#') {
+        format!("{step}{}", accidentals.replace('-', "♭").replace('#', "♯"))
+    } else {
+        name.to_string()
+    }
+}
+
+/// A coordinate with at most two decimals and no trailing zeros.
+fn number(value: FloatType) -> String {
+    let text = format!("{value:.2}");
+    text.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chord::{FingeringOptions, GuitarTuning};
+
+    fn count(svg: &str, class: &str) -> usize {
+        svg.matches(&format!("class=\"{class}")).count()
+    }
+
+    fn shape(pitches: &str) -> GuitarFingering {
+        Chord::new(pitches)
+            .unwrap()
+            .guitar_fingerings(&GuitarTuning::standard(), &FingeringOptions::default())[0]
+            .clone()
+    }
+
+    #[test]
+    fn open_shape_has_nut_markers_and_labels() {
+        let c_major = shape("C3 E3 G3 C4 E4");
+        let svg = c_major.to_svg(&DiagramOptions::default().with_root(0));
+
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(svg.ends_with("</svg>\n"));
+        assert_eq!(count(&svg, "diagram-nut"), 1);
+        assert_eq!(count(&svg, "diagram-muted"), 2);
+        assert_eq!(count(&svg, "diagram-open"), 2);
+        assert_eq!(count(&svg, "diagram-dot"), 3);
+        assert_eq!(count(&svg, "diagram-fret"), 6);
+        assert_eq!(count(&svg, "diagram-string"), 6);
+        // The C on the A string and the one on the B string.
+        assert_eq!(svg.matches("diagram-root").count(), 2);
+        assert_eq!(count(&svg, "diagram-label"), 6);
+        assert!(!svg.contains("fr</text>"));
+    }
+
+    #[test]
+    fn barre_shape_is_drawn_from_its_position() {
+        let mut b_minor = GuitarFingering {
+            base_fret: 2,
+            fret_span: 2,
+            difficulty: 0,
+            ..shape("B2 F#3 B3 D4 F#4")
+        };
+        let frets = [None, Some(2), Some(4), Some(4), Some(3), Some(2)];
+        let fingers = [None, Some(1), Some(3), Some(4), Some(2), Some(1)];
+        for ((string, fret), finger) in b_minor.strings.iter_mut().zip(frets).zip(fingers) {
+            string.fret = fret;
+            string.finger = finger;
+        }
+        let options = DiagramOptions::default().with_fret_rows(2);
+        let svg = fingering_svg(&b_minor, &options);
+
+        assert_eq!(count(&svg, "diagram-nut"), 0);
+        assert!(svg.contains(">2fr</text>"));
+        assert_eq!(count(&svg, "diagram-barre"), 1);
+        assert!(svg.contains(r#"<rect class="diagram-barre" x="45" y="46" width="114""#));
+        assert_eq!(count(&svg, "diagram-dot"), 3);
+        assert_eq!(count(&svg, "diagram-fret"), 4);
+    }
+
+    #[test]
+    fn keyboard_spans_whole_octaves_and_spells_pressed_keys() {
+        let chord = Chord::new("C4 E4 G4 B-4").unwrap();
+        let svg = chord.keyboard_svg(&DiagramOptions::default());
+
+        assert_eq!(count(&svg, "diagram-white-key"), 14);
+        assert_eq!(count(&svg, "diagram-black-key"), 10);
+        assert_eq!(svg.matches("diagram-pressed").count(), 4);
+        assert_eq!(svg.matches("diagram-root").count(), 1);
+        assert!(svg.contains(">B♭</text>"));
+
+        let wide = Chord::new("C2 G5")
+            .unwrap()
+            .keyboard_svg(&DiagramOptions::default());
+        assert_eq!(count(&wide, "diagram-white-key"), 28);
+        let unlabelled = chord.keyboard_svg(&DiagramOptions::default().with_labels(false));
+        assert_eq!(count(&unlabelled, "diagram-label"), 0);
+    }
+
+    #[test]
+    fn clock_places_members_by_layout() {
+        assert_eq!(ClockLayout::Chromatic.position(7), 7);
+        assert_eq!(ClockLayout::Fifths.position(7), 1);
+        assert_eq!(ClockLayout::Fifths.position(6), 6);
+
+        let set = PitchClassSet::new([0, 4, 7]);
+        let svg = set.clock_svg(ClockLayout::Fifths, &DiagramOptions::default());
+        assert_eq!(count(&svg, "diagram-dot"), 3);
+        assert_eq!(count(&svg, "diagram-point"), 9);
+        assert_eq!(count(&svg, "diagram-label"), 12);
+        // C at the top, G one step clockwise and E four steps.
+        assert!(svg.contains(r#"points="100,28 136,37.65 162.35,136""#));
+
+        let chord_svg = Chord::new("E4 G4 C5").unwrap().clock_svg(
+            ClockLayout::Chromatic,
+            &DiagramOptions::default().with_scale(2.0),
+        );
+        assert!(chord_svg.contains(r#"width="400" height="400" viewBox="0 0 200 200""#));
+        assert!(chord_svg.contains(r#"class="diagram-dot diagram-root" cx="100" cy="28""#));
+    }
+}
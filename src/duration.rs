@@ -1,5 +1,5 @@
 use crate::{
-    defaults::{FloatType, IntegerType},
+    defaults::{FloatType, FractionType, IntegerType},
     error::{Error, Result},
 };
 
@@ -119,6 +119,23 @@ impl DurationType {
         }
     }
 
+    /// Returns the exact length of one undotted note of this type.
+    pub fn quarter_length_fraction(self) -> FractionType {
+        match self {
+            Self::Zero => FractionType::from(0),
+            _ => fraction_from_float(self.quarter_length()),
+        }
+    }
+
+    /// Returns the exact length of this type carrying `dots` augmentation
+    /// dots.
+    pub fn quarter_length_fraction_with_dots(self, dots: u32) -> FractionType {
+        let dots = dots.min(16);
+        let base = self.quarter_length_fraction();
+        let power = FractionType::new(1, 1 << dots);
+        base * (FractionType::from(2) - power)
+    }
+
     /// Parses a music21 type name.
     pub fn from_music21_name(name: &str) -> Option<Self> {
         match name {
@@ -174,18 +191,122 @@ impl FromStr for DurationType {
     }
 }
 
+/// Most augmentation dots inferred for a single note value.
+const MAX_DOTS: u32 = 4;
+/// Most augmentation dots inferred under an inferred tuplet.
+const MAX_TUPLET_DOTS: u32 = 1;
+/// Largest denominator kept when a float quarter length becomes a ratio,
+/// matching music21's `opFrac`.
+const MAX_DENOMINATOR: i64 = 65535;
+
+/// A tuplet bracket: `actual` notes played in the time of `normal` ones.
+///
+/// A triplet is `3:2`, three notes in the time of two, so each note lasts
+/// two thirds of its written value.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Tuplet {
+    actual: u32,
+    normal: u32,
+}
+
+impl Tuplet {
+    /// Creates an `actual:normal` tuplet.
+    pub fn new(actual: u32, normal: u32) -> Result<Self> {
+        if actual == 0 || normal == 0 || actual > 1024 || normal > 1024 {
+            return Err(Error::Ordinal(format!(
+                "tuplet {actual}:{normal} needs counts from 1 to 1024"
+            )));
+        }
+        Ok(Self { actual, normal })
+    }
+
+    /// Returns a `3:2` triplet.
+    pub fn triplet() -> Self {
+        Self {
+            actual: 3,
+            normal: 2,
+        }
+    }
+
+    /// Returns the usual tuplet for `actual` notes: in the time of the
+    /// largest power of two below it, so `5:4`, `6:4` and `7:4`.
+    ///
+    /// Returns `None` for counts below 3 or above 1024.
+    pub fn for_actual(actual: u32) -> Option<Self> {
+        if !(3..=1024).contains(&actual) {
+            return None;
+        }
+        let normal = 1 << (31 - (actual - 1).leading_zeros());
+        Some(Self { actual, normal })
+    }
+
+    /// Returns the number of notes played.
+    pub fn actual(self) -> u32 {
+        self.actual
+    }
+
+    /// Returns the number of notes whose time they take.
+    pub fn normal(self) -> u32 {
+        self.normal
+    }
+
+    /// Returns the factor applied to each note's written length,
+    /// `normal / actual`.
+    pub fn multiplier(self) -> FractionType {
+        FractionType::new(self.normal as IntegerType, self.actual as IntegerType)
+    }
+}
+
+impl Display for Tuplet {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.actual, self.normal)
+    }
+}
+
+impl FromStr for Tuplet {
+    type Err = Error;
+
+    /// Parses `"3:2"`, or a bare `"5"` for the usual tuplet of that count.
+    fn from_str(value: &str) -> Result<Self> {
+        let invalid = || Error::Ordinal(format!("invalid tuplet {value:?}"));
+        match value.trim().split_once(':') {
+            Some((actual, normal)) => Self::new(
+                actual.trim().parse().map_err(|_| invalid())?,
+                normal.trim().parse().map_err(|_| invalid())?,
+            ),
+            None => {
+                Self::for_actual(value.trim().parse().map_err(|_| invalid())?).ok_or_else(invalid)
+            }
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// Rhythmic duration measured in quarter lengths.
 ///
 /// A quarter note has a quarter length of `1.0`; an eighth note is `0.5`;
-/// a whole note is `4.0`.
+/// a whole note is `4.0`. The length is kept as an exact ratio, so a triplet
+/// eighth is exactly one third and three of them make exactly one quarter.
+///
+/// The note value, dots and tuplets that notate a duration are inferred
+/// from its length, unless tuplets were given with [`Duration::with_tuplet`]:
+/// up to four dots, and one usual tuplet carrying at most one dot.
+/// Lengths no single note value can show, such as `2.5`, are *complex*; see
+/// [`Duration::components`].
 pub struct Duration {
-    quarter_length: FloatType,
+    numerator: IntegerType,
+    denominator: IntegerType,
+    #[cfg_attr(feature = "serde", serde(default))]
+    tuplets: Vec<Tuplet>,
 }
 
 impl Duration {
     /// Creates a duration from a quarter-length value.
+    ///
+    /// The value is read as the nearest ratio with a denominator up to
+    /// 65535, as music21 does, so `1.0 / 3.0` is exactly a triplet eighth.
     pub fn new(quarter_length: FloatType) -> Result<Self> {
         if !quarter_length.is_finite() || quarter_length < 0.0 {
             return Err(Error::Ordinal(format!(
@@ -193,7 +314,27 @@ impl Duration {
             )));
         }
 
-        Ok(Self { quarter_length })
+        Self::from_fraction(fraction_from_float(quarter_length))
+    }
+
+    /// Creates a duration from an exact quarter length.
+    pub fn from_fraction(quarter_length: FractionType) -> Result<Self> {
+        let (Some(numerator), Some(denominator)) = (quarter_length.numer(), quarter_length.denom())
+        else {
+            return Err(Error::Ordinal(format!(
+                "duration quarter length must be finite, got {quarter_length}"
+            )));
+        };
+        if quarter_length < FractionType::from(0) {
+            return Err(Error::Ordinal(format!(
+                "duration quarter length must be non-negative, got {quarter_length}"
+            )));
+        }
+        Ok(Self {
+            numerator: numerator.abs(),
+            denominator: denominator.abs(),
+            tuplets: Vec::new(),
+        })
     }
 
     /// Returns a quarter-note duration.
@@ -203,64 +344,168 @@ impl Duration {
 
     /// Returns a half-note duration.
     pub fn half() -> Self {
-        Self::new(2.0).expect("constant duration is valid")
+        Self::from_type(DurationType::Half)
     }
 
     /// Returns a whole-note duration.
     pub fn whole() -> Self {
-        Self::new(4.0).expect("constant duration is valid")
+        Self::from_type(DurationType::Whole)
     }
 
     /// Returns an eighth-note duration.
     pub fn eighth() -> Self {
-        Self::new(0.5).expect("constant duration is valid")
+        Self::from_type(DurationType::Eighth)
     }
 
     /// Creates a duration from a note-value type.
     pub fn from_type(duration_type: DurationType) -> Self {
-        Self {
-            quarter_length: duration_type.quarter_length(),
-        }
+        Self::from_type_with_dots(duration_type, 0)
     }
 
     /// Creates a duration from a note-value type carrying augmentation dots.
     pub fn from_type_with_dots(duration_type: DurationType, dots: u32) -> Self {
-        Self {
-            quarter_length: duration_type.quarter_length_with_dots(dots),
-        }
+        Self::from_fraction(duration_type.quarter_length_fraction_with_dots(dots))
+            .expect("note values are finite and non-negative")
     }
 
-    /// Returns the note-value type whose undotted length this duration is.
+    /// Returns the duration inside one more tuplet bracket.
     ///
-    /// Returns `None` for a length that is not a plain note value, such as a
-    /// dotted or tuplet duration.
+    /// The length is scaled by [`Tuplet::multiplier`] and the tuplet is
+    /// recorded, outermost first, so brackets can be nested: an eighth in a
+    /// triplet inside a quintuplet lasts `1/2 × 2/3 × 4/5` quarters.
+    pub fn with_tuplet(self, tuplet: Tuplet) -> Self {
+        let mut tuplets = self.tuplets.clone();
+        tuplets.push(tuplet);
+        let mut duration =
+            Self::from_fraction(self.quarter_length_fraction() * tuplet.multiplier())
+                .expect("tuplets keep a duration non-negative");
+        duration.tuplets = tuplets;
+        duration
+    }
+
+    /// Returns the note-value type that notates this duration.
+    ///
+    /// The type is inferred with any dots and tuplets, so a dotted half is
+    /// [`DurationType::Half`] and a triplet eighth [`DurationType::Eighth`].
+    /// Returns `None` for a complex duration.
     pub fn duration_type(&self) -> Option<DurationType> {
-        DurationType::from_quarter_length(self.quarter_length)
+        self.notation().map(|(duration_type, _, _)| duration_type)
+    }
+
+    /// Returns the number of augmentation dots that notate this duration, or
+    /// `0` for a complex duration.
+    pub fn dots(&self) -> u32 {
+        self.notation().map_or(0, |(_, dots, _)| dots)
+    }
+
+    /// Returns the tuplets that notate this duration, outermost first.
+    ///
+    /// These are the tuplets given with [`Duration::with_tuplet`], or else
+    /// the single usual tuplet inferred from the length, such as `3:2` for a
+    /// third of a quarter.
+    pub fn tuplets(&self) -> Vec<Tuplet> {
+        self.notation()
+            .map_or_else(|| self.tuplets.clone(), |(_, _, tuplets)| tuplets)
+    }
+
+    /// Returns whether no single note value, with dots and tuplets, notates
+    /// this duration, as for `2.5` or `1.25` quarters.
+    pub fn is_complex(&self) -> bool {
+        self.notation().is_none()
+    }
+
+    /// Splits the duration into notatable parts, to be tied together.
+    ///
+    /// A notatable duration is returned whole. Otherwise the longest note
+    /// values that fit are taken first, so `2.5` becomes a half and an
+    /// eighth, and `5/3` a quarter and a triplet quarter. Any remainder
+    /// shorter than a 2048th is kept as a final complex part.
+    pub fn components(&self) -> Vec<Self> {
+        if !self.is_complex() {
+            return vec![self.clone()];
+        }
+        let mut components = Vec::new();
+        let mut remaining = self.quarter_length_fraction();
+        let zero = FractionType::from(0);
+        while remaining > zero {
+            let part = Self::from_fraction(remaining).expect("remainder is non-negative");
+            let largest = DurationType::ALL
+                .into_iter()
+                .filter(|duration_type| *duration_type != DurationType::Zero)
+                .find(|duration_type| duration_type.quarter_length_fraction() <= remaining);
+            match largest {
+                Some(_) if !part.is_complex() => {
+                    components.push(part);
+                    break;
+                }
+                Some(duration_type) => {
+                    components.push(Self::from_type(duration_type));
+                    remaining -= duration_type.quarter_length_fraction();
+                }
+                None => {
+                    components.push(part);
+                    break;
+                }
+            }
+        }
+        components
     }
 
     /// Returns the duration in quarter lengths.
     pub fn quarter_length(&self) -> FloatType {
-        self.quarter_length
+        FloatType::from(self.numerator) / FloatType::from(self.denominator)
+    }
+
+    /// Returns the exact duration in quarter lengths.
+    pub fn quarter_length_fraction(&self) -> FractionType {
+        FractionType::new(self.numerator, self.denominator)
     }
 
-    /// Updates the duration in quarter lengths.
+    /// Updates the duration in quarter lengths, clearing any tuplets.
     pub fn set_quarter_length(&mut self, quarter_length: FloatType) -> Result<()> {
         *self = Self::new(quarter_length)?;
         Ok(())
     }
+
+    /// The note value, dots and tuplets notating this duration.
+    fn notation(&self) -> Option<(DurationType, u32, Vec<Tuplet>)> {
+        let quarter_length = self.quarter_length_fraction();
+        if self.numerator == 0 {
+            return Some((DurationType::Zero, 0, Vec::new()));
+        }
+        if !self.tuplets.is_empty() {
+            let multiplier = self
+                .tuplets
+                .iter()
+                .fold(FractionType::from(1), |product, tuplet| {
+                    product * tuplet.multiplier()
+                });
+            return note_value(quarter_length / multiplier)
+                .map(|(duration_type, dots)| (duration_type, dots, self.tuplets.clone()));
+        }
+        if let Some((duration_type, dots)) = note_value(quarter_length) {
+            return Some((duration_type, dots, Vec::new()));
+        }
+        (3..=32)
+            .filter(|actual: &u32| !actual.is_power_of_two())
+            .filter_map(Tuplet::for_actual)
+            .find_map(|tuplet| {
+                note_value(quarter_length / tuplet.multiplier())
+                    .filter(|(_, dots)| *dots <= MAX_TUPLET_DOTS)
+                    .map(|(duration_type, dots)| (duration_type, dots, vec![tuplet]))
+            })
+    }
 }
 
 impl Default for Duration {
     fn default() -> Self {
-        Self {
-            quarter_length: 1.0,
-        }
+        Self::from_type(DurationType::Quarter)
     }
 }
 
 impl PartialEq for Duration {
     fn eq(&self, other: &Self) -> bool {
-        self.quarter_length == other.quarter_length
+        self.numerator == other.numerator && self.denominator == other.denominator
     }
 }
 
@@ -276,10 +521,67 @@ impl TryFrom<IntegerType> for Duration {
     type Error = Error;
 
     fn try_from(value: IntegerType) -> Result<Self> {
-        Self::new(value as FloatType)
+        Self::from_fraction(FractionType::from(value))
+    }
+}
+
+impl TryFrom<FractionType> for Duration {
+    type Error = Error;
+
+    fn try_from(value: FractionType) -> Result<Self> {
+        Self::from_fraction(value)
     }
 }
 
+/// The note value and dots whose length is exactly `quarter_length`.
+fn note_value(quarter_length: FractionType) -> Option<(DurationType, u32)> {
+    DurationType::ALL
+        .into_iter()
+        .filter(|duration_type| *duration_type != DurationType::Zero)
+        .find_map(|duration_type| {
+            (0..=MAX_DOTS)
+                .find(|dots| {
+                    duration_type.quarter_length_fraction_with_dots(*dots) == quarter_length
+                })
+                .map(|dots| (duration_type, dots))
+        })
+}
+
+/// The ratio nearest `value` with a denominator up to 65535, found from its
+/// continued fraction.
+pub(crate) fn fraction_from_float(value: FloatType) -> FractionType {
+    if !value.is_finite() {
+        return FractionType::from(value);
+    }
+    let negative = value < 0.0;
+    let target = value.abs();
+    let (mut previous_numerator, mut numerator) = (0i64, 1i64);
+    let (mut previous_denominator, mut denominator) = (1i64, 0i64);
+    let mut remainder = target;
+    loop {
+        let whole = remainder.floor();
+        let next_denominator = whole as i64 * denominator + previous_denominator;
+        if next_denominator > MAX_DENOMINATOR || whole > IntegerType::MAX as FloatType {
+            break;
+        }
+        let next_numerator = whole as i64 * numerator + previous_numerator;
+        (previous_numerator, numerator) = (numerator, next_numerator);
+        (previous_denominator, denominator) = (denominator, next_denominator);
+        let fractional = remainder - whole;
+        if fractional < 1e-12
+            || (numerator as FloatType / denominator as FloatType - target).abs() < 1e-12
+        {
+            break;
+        }
+        remainder = 1.0 / fractional;
+    }
+    if denominator == 0 || numerator > IntegerType::MAX as i64 {
+        return FractionType::from(value);
+    }
+    let fraction = FractionType::new(numerator as IntegerType, denominator as IntegerType);
+    if negative { -fraction } else { fraction }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -365,13 +667,14 @@ mod tests {
             Duration::from_type_with_dots(DurationType::Half, 1).quarter_length(),
             3.0
         );
-        // A dotted value is not itself a note value.
-        assert_eq!(
-            Duration::from_type_with_dots(DurationType::Half, 1).duration_type(),
-            None
-        );
-        // Nor is a triplet eighth.
-        assert_eq!(Duration::new(1.0 / 3.0).unwrap().duration_type(), None);
+        // Dots and tuplets are inferred from the length.
+        let dotted_half = Duration::from_type_with_dots(DurationType::Half, 1);
+        assert_eq!(dotted_half.duration_type(), Some(DurationType::Half));
+        assert_eq!(dotted_half.dots(), 1);
+        let triplet_eighth = Duration::new(1.0 / 3.0).unwrap();
+        assert_eq!(triplet_eighth.duration_type(), Some(DurationType::Eighth));
+        assert_eq!(triplet_eighth.tuplets(), vec![Tuplet::triplet()]);
+        assert_eq!(triplet_eighth.dots(), 0);
     }
 
     #[test]
@@ -411,4 +714,95 @@ mod tests {
         assert_eq!(duration, Duration::try_from(1.5).unwrap());
         assert!(duration.set_quarter_length(FloatType::NAN).is_err());
     }
+
+    #[test]
+    fn quarter_lengths_are_exact_ratios() {
+        let third = Duration::new(1.0 / 3.0).unwrap();
+        assert_eq!(third.quarter_length_fraction(), FractionType::new(1, 3));
+        let sum = (0..3).fold(FractionType::from(0), |sum, _| {
+            sum + third.quarter_length_fraction()
+        });
+        assert_eq!(sum, FractionType::from(1));
+        assert_eq!(
+            Duration::new(0.1).unwrap().quarter_length_fraction(),
+            FractionType::new(1, 10)
+        );
+        assert_eq!(
+            Duration::from_fraction(FractionType::new(5, 7))
+                .unwrap()
+                .quarter_length(),
+            5.0 / 7.0
+        );
+        assert!(Duration::from_fraction(FractionType::new(-1, 2)).is_err());
+        assert!(Duration::from_fraction(FractionType::nan()).is_err());
+        assert_eq!(
+            Duration::default(),
+            Duration::try_from(FractionType::from(1)).unwrap()
+        );
+    }
+
+    #[test]
+    fn tuplets_scale_and_nest() {
+        assert_eq!(Tuplet::for_actual(5), Some(Tuplet::new(5, 4).unwrap()));
+        assert_eq!(Tuplet::for_actual(7).unwrap().to_string(), "7:4");
+        assert_eq!(Tuplet::for_actual(9).unwrap().normal(), 8);
+        assert_eq!(Tuplet::for_actual(2), None);
+        assert_eq!("3:2".parse::<Tuplet>().unwrap(), Tuplet::triplet());
+        assert_eq!("6".parse::<Tuplet>().unwrap(), Tuplet::new(6, 4).unwrap());
+        assert!("3:0".parse::<Tuplet>().is_err());
+
+        let quintuplet = Tuplet::new(5, 4).unwrap();
+        let nested = Duration::eighth()
+            .with_tuplet(Tuplet::triplet())
+            .with_tuplet(quintuplet);
+        assert_eq!(nested.quarter_length_fraction(), FractionType::new(4, 15));
+        assert_eq!(nested.duration_type(), Some(DurationType::Eighth));
+        assert_eq!(nested.tuplets(), vec![Tuplet::triplet(), quintuplet]);
+
+        // The inferred tuplet prefers the fewest notes: a sixth of a quarter
+        // is a triplet sixteenth, not a sextuplet eighth.
+        let sixth = Duration::from_fraction(FractionType::new(1, 6)).unwrap();
+        assert_eq!(sixth.duration_type(), Some(DurationType::Sixteenth));
+        assert_eq!(sixth.tuplets(), vec![Tuplet::triplet()]);
+        let dotted_quintuplet = Duration::from_fraction(FractionType::new(3, 10)).unwrap();
+        assert_eq!(
+            dotted_quintuplet.duration_type(),
+            Some(DurationType::Sixteenth)
+        );
+        assert_eq!(dotted_quintuplet.dots(), 1);
+        assert_eq!(dotted_quintuplet.tuplets(), vec![quintuplet]);
+    }
+
+    #[test]
+    fn complex_lengths_decompose_into_tied_values() {
+        let lengths = |quarter_length: FractionType| {
+            Duration::from_fraction(quarter_length)
+                .unwrap()
+                .components()
+                .iter()
+                .map(Duration::quarter_length_fraction)
+                .collect::<Vec<_>>()
+        };
+        assert!(!Duration::new(3.75).unwrap().is_complex());
+        assert_eq!(
+            lengths(FractionType::new(15, 4)),
+            vec![FractionType::new(15, 4)]
+        );
+        assert!(Duration::new(2.5).unwrap().is_complex());
+        assert_eq!(Duration::new(2.5).unwrap().duration_type(), None);
+        assert_eq!(
+            lengths(FractionType::new(5, 2)),
+            vec![FractionType::from(2), FractionType::new(1, 2)]
+        );
+        assert_eq!(
+            lengths(FractionType::from(5)),
+            vec![FractionType::from(4), FractionType::from(1)]
+        );
+        assert_eq!(
+            lengths(FractionType::new(5, 3)),
+            vec![FractionType::from(1), FractionType::new(2, 3)]
+        );
+        let parts = Duration::new(2.5).unwrap().components();
+        assert!(parts.iter().all(|part| !part.is_complex()));
+    }
 }
@@ -41,6 +41,14 @@ pub enum Error {
     Meter(String),
     /// Error associated with Xenakis sieve parsing or evaluation.
     Sieve(String),
+    /// Error associated with twelve-tone rows and serial operations.
+    Serial(String),
+    /// Error associated with tablature import or export.
+    Tablature(String),
+    /// Error associated with instrument lookup or transposition.
+    Instrument(String),
+    /// Error associated with rhythm spelling or notation export.
+    Notation(String),
 }
 
 impl fmt::Display for Error {
@@ -63,6 +71,10 @@ impl fmt::Display for Error {
             Error::Analysis(msg) => write!(f, "Analysis error: {msg}"),
             Error::Meter(msg) => write!(f, "Meter error: {msg}"),
             Error::Sieve(msg) => write!(f, "Sieve error: {msg}"),
+            Error::Serial(msg) => write!(f, "Serial error: {msg}"),
+            Error::Tablature(msg) => write!(f, "Tablature error: {msg}"),
+            Error::Instrument(msg) => write!(f, "Instrument error: {msg}"),
+            Error::Notation(msg) => write!(f, "Notation error: {msg}"),
         }
     }
 }
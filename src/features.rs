@@ -0,0 +1,356 @@
+//! Melodic and intervallic features for corpus statistics, after jSymbolic
+//! and music21's `features` package.
+//!
+//! [`extract_features`] reduces a [`Stream`] to a [`FeatureVector`]: a flat
+//! list of named numbers that always has the same names in the same order,
+//! so vectors from different pieces line up as rows of a table. The melody
+//! is read from the highest pitch of each onset; histograms count every
+//! sounding pitch.
+
+use std::collections::BTreeMap;
+
+use crate::{
+    analysis::{KeyFindingOptions, estimate_key_from_stream},
+    defaults::FloatType,
+    error::Result,
+    pitch::Pitch,
+    stream::{Stream, StreamElement},
+};
+
+/// Melodic intervals of this many semitones or more share the last bin of
+/// the interval histogram.
+const INTERVAL_BINS: usize = 13;
+const OFFSET_EPSILON: FloatType = 1e-9;
+
+/// One named feature value.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Feature {
+    name: String,
+    value: FloatType,
+}
+
+impl Feature {
+    /// Returns the feature's name, such as `"average_melodic_interval"`.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the feature's value.
+    pub fn value(&self) -> FloatType {
+        self.value
+    }
+}
+
+/// A flat, ordered vector of named features.
+///
+/// Every vector from [`extract_features`] has the names of
+/// [`FeatureVector::names`] in the same order, whatever the input.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FeatureVector {
+    features: Vec<Feature>,
+}
+
+impl FeatureVector {
+    /// Returns the features in order.
+    pub fn features(&self) -> &[Feature] {
+        &self.features
+    }
+
+    /// Returns the feature names in order.
+    pub fn names(&self) -> Vec<&str> {
+        self.features.iter().map(Feature::name).collect()
+    }
+
+    /// Returns the feature values in order, ready for a numeric pipeline.
+    pub fn values(&self) -> Vec<FloatType> {
+        self.features.iter().map(Feature::value).collect()
+    }
+
+    /// Returns the value of the feature called `name`.
+    pub fn get(&self, name: &str) -> Option<FloatType> {
+        self.features
+            .iter()
+            .find(|feature| feature.name == name)
+            .map(Feature::value)
+    }
+
+    /// Returns the features keyed by name.
+    pub fn to_map(&self) -> BTreeMap<String, FloatType> {
+        self.features
+            .iter()
+            .map(|feature| (feature.name.clone(), feature.value))
+            .collect()
+    }
+
+    /// Returns the number of features.
+    pub fn len(&self) -> usize {
+        self.features.len()
+    }
+
+    /// Returns whether the vector has no features.
+    pub fn is_empty(&self) -> bool {
+        self.features.is_empty()
+    }
+
+    fn push(&mut self, name: impl Into<String>, value: FloatType) {
+        self.features.push(Feature {
+            name: name.into(),
+            value,
+        });
+    }
+}
+
+/// Computes the melodic and intervallic features of `stream`.
+///
+/// The vector holds, in order:
+///
+/// - `pitch_class_histogram_0` to `_11`: the share of sounding pitches on
+///   each pitch class from C.
+/// - `interval_histogram_0` to `_12`: the share of melodic intervals of each
+///   size in semitones, with octaves and wider in the last bin.
+/// - `melodic_range`: semitones from the lowest to the highest pitch.
+/// - `average_melodic_interval`: mean melodic interval size in semitones.
+/// - `repeated_notes`, `stepwise_motion`, `melodic_leaps`: the shares of
+///   melodic intervals of 0, 1–2 and 3 or more semitones.
+/// - `direction_changes`: the share of consecutive moving intervals that
+///   turn around.
+/// - `chromatic_motion`: the share of melodic semitones.
+/// - `chromaticism`: the share of sounding pitches outside the scale of the
+///   key estimated by [`estimate_key_from_stream`].
+/// - `note_density`: onsets per quarter note.
+/// - `average_note_duration` and `rhythmic_variability`: the mean and
+///   standard deviation of note and chord lengths in quarters.
+///
+/// Shares are 0 when there is nothing to count, so an empty stream gives a
+/// vector of zeros.
+pub fn extract_features(stream: &Stream) -> Result<FeatureVector> {
+    let mut onsets: Vec<(FloatType, FloatType, Vec<Pitch>)> = Vec::new();
+    for event in stream.events() {
+        if matches!(event.element(), StreamElement::Rest(_)) {
+            continue;
+        }
+        onsets.push((
+            event.offset(),
+            event.element().quarter_length(),
+            event.element().pitches(),
+        ));
+    }
+    onsets.sort_by(|left, right| left.0.total_cmp(&right.0));
+
+    let pitches = onsets
+        .iter()
+        .flat_map(|(_, _, pitches)| pitches)
+        .collect::<Vec<_>>();
+    let mut melody: Vec<FloatType> = Vec::new();
+    let mut previous_onset = None;
+    for (offset, _, chord) in &onsets {
+        let Some(top) = chord.iter().map(Pitch::ps).max_by(FloatType::total_cmp) else {
+            continue;
+        };
+        match (previous_onset, melody.last_mut()) {
+            (Some(previous), Some(last)) if offset - previous < OFFSET_EPSILON => {
+                *last = last.max(top);
+            }
+            _ => melody.push(top),
+        }
+        previous_onset = Some(*offset);
+    }
+    let intervals = melody
+        .windows(2)
+        .map(|pair| pair[1] - pair[0])
+        .collect::<Vec<_>>();
+    let sizes = intervals
+        .iter()
+        .map(|interval| interval.abs().round() as usize)
+        .collect::<Vec<_>>();
+
+    let mut features = FeatureVector::default();
+
+    let mut pitch_classes = [0.0; 12];
+    for pitch in &pitches {
+        pitch_classes[usize::from(pitch.pitch_class_number())] += 1.0;
+    }
+    for (pc, count) in pitch_classes.iter().enumerate() {
+        features.push(
+            format!("pitch_class_histogram_{pc}"),
+            share(*count, pitches.len()),
+        );
+    }
+
+    let mut interval_bins = [0.0; INTERVAL_BINS];
+    for size in &sizes {
+        interval_bins[(*size).min(INTERVAL_BINS - 1)] += 1.0;
+    }
+    for (size, count) in interval_bins.iter().enumerate() {
+        features.push(
+            format!("interval_histogram_{size}"),
+            share(*count, sizes.len()),
+        );
+    }
+
+    let (lowest, highest) = pitches
+        .iter()
+        .map(|pitch| pitch.ps())
+        .fold(None, |range: Option<(FloatType, FloatType)>, ps| {
+            Some(range.map_or((ps, ps), |(low, high)| (low.min(ps), high.max(ps))))
+        })
+        .unwrap_or((0.0, 0.0));
+    features.push("melodic_range", highest - lowest);
+    features.push(
+        "average_melodic_interval",
+        mean(intervals.iter().map(|interval| interval.abs())),
+    );
+    let count_sizes = |keep: fn(usize) -> bool| {
+        share(
+            sizes.iter().filter(|size| keep(**size)).count() as FloatType,
+            sizes.len(),
+        )
+    };
+    features.push("repeated_notes", count_sizes(|size| size == 0));
+    features.push(
+        "stepwise_motion",
+        count_sizes(|size| (1..=2).contains(&size)),
+    );
+    features.push("melodic_leaps", count_sizes(|size| size >= 3));
+
+    let directions = intervals
+        .iter()
+        .filter(|interval| interval.abs() > OFFSET_EPSILON)
+        .map(|interval| interval.signum())
+        .collect::<Vec<_>>();
+    let turns = directions
+        .windows(2)
+        .filter(|pair| pair[0] != pair[1])
+        .count();
+    features.push(
+        "direction_changes",
+        share(turns as FloatType, directions.len().saturating_sub(1)),
+    );
+    features.push("chromatic_motion", count_sizes(|size| size == 1));
+    features.push("chromaticism", chromaticism(stream, &pitches)?);
+
+    let end_offset = stream.end_offset();
+    features.push(
+        "note_density",
+        if end_offset > 0.0 {
+            onsets.len() as FloatType / end_offset
+        } else {
+            0.0
+        },
+    );
+    let lengths = onsets
+        .iter()
+        .map(|(_, length, _)| *length)
+        .collect::<Vec<_>>();
+    let average_length = mean(lengths.iter().copied());
+    features.push("average_note_duration", average_length);
+    features.push(
+        "rhythmic_variability",
+        mean(
+            lengths
+                .iter()
+                .map(|length| (length - average_length).powi(2)),
+        )
+        .sqrt(),
+    );
+
+    Ok(features)
+}
+
+impl Stream {
+    /// Computes the stream's melodic and intervallic features.
+    ///
+    /// See [`extract_features`].
+    pub fn features(&self) -> Result<FeatureVector> {
+        extract_features(self)
+    }
+}
+
+fn chromaticism(stream: &Stream, pitches: &[&Pitch]) -> Result<FloatType> {
+    if pitches.is_empty() {
+        return Ok(0.0);
+    }
+    let estimates = estimate_key_from_stream(stream, &KeyFindingOptions::default())?;
+    let scale = estimates[0]
+        .key()
+        .pitches()?
+        .iter()
+        .map(Pitch::pitch_class_number)
+        .collect::<Vec<_>>();
+    let outside = pitches
+        .iter()
+        .filter(|pitch| !scale.contains(&pitch.pitch_class_number()))
+        .count();
+    Ok(share(outside as FloatType, pitches.len()))
+}
+
+fn share(count: FloatType, total: usize) -> FloatType {
+    if total == 0 {
+        0.0
+    } else {
+        count / total as FloatType
+    }
+}
+
+fn mean(values: impl Iterator<Item = FloatType>) -> FloatType {
+    let (sum, count) = values.fold((0.0, 0), |(sum, count), value| (sum + value, count + 1));
+    share(sum, count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{chord::Chord, rest::Rest, stream::test_stream};
+
+    #[test]
+    fn describes_a_melody() {
+        // C D E C | G (leap) G# (chromatic) G, with one half note.
+        let stream = test_stream(&[
+            ("C4", 1.0),
+            ("D4", 1.0),
+            ("E4", 1.0),
+            ("C4", 1.0),
+            ("G4", 2.0),
+            ("G#4", 1.0),
+            ("G4", 1.0),
+        ]);
+        let features = stream.features().unwrap();
+        let feature = |name| features.get(name).unwrap();
+
+        assert_eq!(feature("pitch_class_histogram_7"), 2.0 / 7.0);
+        assert_eq!(feature("interval_histogram_2"), 2.0 / 6.0);
+        assert_eq!(feature("melodic_range"), 8.0);
+        assert_eq!(feature("average_melodic_interval"), 17.0 / 6.0);
+        assert_eq!(feature("stepwise_motion"), 4.0 / 6.0);
+        assert_eq!(feature("melodic_leaps"), 2.0 / 6.0);
+        assert_eq!(feature("repeated_notes"), 0.0);
+        // Up up down up up down: three turns in five chances.
+        assert_eq!(feature("direction_changes"), 3.0 / 5.0);
+        assert_eq!(feature("chromatic_motion"), 2.0 / 6.0);
+        assert_eq!(feature("chromaticism"), 1.0 / 7.0);
+        assert_eq!(feature("note_density"), 7.0 / 8.0);
+        assert_eq!(feature("average_note_duration"), 8.0 / 7.0);
+        assert!((feature("rhythmic_variability") - (6.0_f64 / 49.0).sqrt()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn vectors_share_names_and_order() {
+        let empty = extract_features(&Stream::new()).unwrap();
+        let mut stream = test_stream(&[("E4", 1.0), ("G4", 1.0)]);
+        stream.push(Rest::from_quarter_length(1.0).unwrap());
+        stream.push(Chord::new("C4 E4 A4").unwrap());
+        let features = extract_features(&stream).unwrap();
+
+        assert_eq!(empty.names(), features.names());
+        assert_eq!(features.len(), 12 + 13 + 11);
+        assert!(empty.values().iter().all(|value| *value == 0.0));
+        assert_eq!(features.values().len(), features.len());
+        assert_eq!(features.to_map().len(), features.len());
+        // The chord's top note carries the melody: E4 G4 A4.
+        assert_eq!(features.get("average_melodic_interval"), Some(2.5));
+        assert_eq!(features.get("note_density"), Some(0.75));
+        assert_eq!(features.get("nonexistent"), None);
+    }
+}
@@ -0,0 +1,460 @@
+//! Built-in table of orchestral, band, keyboard and vocal instruments.
+//!
+//! Each [`Instrument`] carries its sounding ranges, its transposition from
+//! concert to written pitch, a General MIDI program and a default clef.
+//! Ranges are sounding pitches after the usual orchestration references:
+//! the full range is what a capable player or singer can be asked for, the
+//! comfortable range what suits most. Range analysis in [`crate::range`]
+//! checks parts against them.
+
+use std::fmt;
+
+use crate::{
+    error::{Error, Result},
+    interval::Interval,
+    pitch::Pitch,
+    range::Ambitus,
+    stream::Stream,
+};
+
+/// Built-in instruments: name, full and comfortable sounding ranges, the
+/// interval from concert to written pitch, 0-based General MIDI program and
+/// default clef.
+const INSTRUMENTS: [(&str, [&str; 4], &str, u8, Clef); 42] = [
+    ("Piccolo", ["D5", "C8", "D5", "A7"], "-P8", 72, Clef::Treble),
+    ("Flute", ["C4", "D7", "D4", "A6"], "P1", 73, Clef::Treble),
+    (
+        "Alto Flute",
+        ["G3", "G6", "A3", "C6"],
+        "P4",
+        73,
+        Clef::Treble,
+    ),
+    ("Oboe", ["B-3", "A6", "C4", "E6"], "P1", 68, Clef::Treble),
+    (
+        "English Horn",
+        ["E3", "C6", "G3", "G5"],
+        "P5",
+        69,
+        Clef::Treble,
+    ),
+    (
+        "Clarinet",
+        ["D3", "B-6", "F3", "B-5"],
+        "M2",
+        71,
+        Clef::Treble,
+    ),
+    (
+        "Bass Clarinet",
+        ["D2", "F5", "E-2", "B-4"],
+        "M9",
+        71,
+        Clef::Treble,
+    ),
+    ("Bassoon", ["B-1", "E5", "C2", "C5"], "P1", 70, Clef::Bass),
+    (
+        "Contrabassoon",
+        ["B-0", "F4", "D1", "C4"],
+        "P8",
+        70,
+        Clef::Bass,
+    ),
+    (
+        "Soprano Saxophone",
+        ["A-3", "E6", "A-3", "C6"],
+        "M2",
+        64,
+        Clef::Treble,
+    ),
+    (
+        "Alto Saxophone",
+        ["D-3", "A5", "D-3", "F5"],
+        "M6",
+        65,
+        Clef::Treble,
+    ),
+    (
+        "Tenor Saxophone",
+        ["A-2", "E5", "A-2", "C5"],
+        "M9",
+        66,
+        Clef::Treble,
+    ),
+    (
+        "Baritone Saxophone",
+        ["D-2", "A4", "D-2", "F4"],
+        "M13",
+        67,
+        Clef::Treble,
+    ),
+    ("Horn", ["B1", "F5", "C3", "C5"], "P5", 60, Clef::Treble),
+    ("Trumpet", ["E3", "C6", "F#3", "G5"], "M2", 56, Clef::Treble),
+    ("Cornet", ["E3", "B-5", "F#3", "G5"], "M2", 56, Clef::Treble),
+    ("Trombone", ["E2", "F5", "A2", "B-4"], "P1", 57, Clef::Bass),
+    (
+        "Bass Trombone",
+        ["B-1", "B-4", "C2", "F4"],
+        "P1",
+        57,
+        Clef::Bass,
+    ),
+    (
+        "Euphonium",
+        ["B-1", "B-4", "D2", "F4"],
+        "P1",
+        58,
+        Clef::Bass,
+    ),
+    ("Tuba", ["D1", "F4", "F1", "C4"], "P1", 58, Clef::Bass),
+    ("Timpani", ["D2", "C4", "F2", "A3"], "P1", 47, Clef::Bass),
+    (
+        "Xylophone",
+        ["F4", "C8", "F4", "C8"],
+        "-P8",
+        13,
+        Clef::Treble,
+    ),
+    (
+        "Glockenspiel",
+        ["G5", "C8", "G5", "C8"],
+        "-P15",
+        9,
+        Clef::Treble,
+    ),
+    (
+        "Vibraphone",
+        ["F3", "F6", "F3", "F6"],
+        "P1",
+        11,
+        Clef::Treble,
+    ),
+    ("Marimba", ["C2", "C7", "C2", "C7"], "P1", 12, Clef::Treble),
+    ("Harp", ["C1", "G7", "C2", "G6"], "P1", 46, Clef::Treble),
+    ("Piano", ["A0", "C8", "A0", "C8"], "P1", 0, Clef::Treble),
+    (
+        "Harpsichord",
+        ["F1", "F6", "F1", "F6"],
+        "P1",
+        6,
+        Clef::Treble,
+    ),
+    ("Celesta", ["C4", "C8", "C4", "C8"], "-P8", 8, Clef::Treble),
+    ("Organ", ["C2", "C7", "C2", "C7"], "P1", 19, Clef::Treble),
+    (
+        "Acoustic Guitar",
+        ["E2", "B5", "E2", "E5"],
+        "P1",
+        24,
+        Clef::TrebleOctaveDown,
+    ),
+    (
+        "Electric Bass",
+        ["E1", "G4", "E1", "C4"],
+        "P8",
+        33,
+        Clef::Bass,
+    ),
+    ("Violin", ["G3", "E7", "G3", "A6"], "P1", 40, Clef::Treble),
+    ("Viola", ["C3", "E6", "C3", "A5"], "P1", 41, Clef::Alto),
+    (
+        "Violoncello",
+        ["C2", "A5", "C2", "E5"],
+        "P1",
+        42,
+        Clef::Bass,
+    ),
+    ("Contrabass", ["E1", "G4", "E1", "D3"], "P8", 43, Clef::Bass),
+    ("Soprano", ["C4", "C6", "D4", "A5"], "P1", 52, Clef::Treble),
+    (
+        "Mezzo-Soprano",
+        ["A3", "A5", "B3", "F#5"],
+        "P1",
+        52,
+        Clef::Treble,
+    ),
+    ("Alto", ["F3", "F5", "G3", "D5"], "P1", 52, Clef::Treble),
+    (
+        "Tenor",
+        ["C3", "C5", "D3", "A4"],
+        "P1",
+        52,
+        Clef::TrebleOctaveDown,
+    ),
+    ("Baritone", ["A2", "A4", "B2", "E4"], "P1", 52, Clef::Bass),
+    ("Bass", ["E2", "E4", "F2", "D4"], "P1", 52, Clef::Bass),
+];
+
+/// Common alternative names for built-in instruments.
+const ALIASES: [(&str, &str); 9] = [
+    ("Cello", "Violoncello"),
+    ("Double Bass", "Contrabass"),
+    ("French Horn", "Horn"),
+    ("Cor Anglais", "English Horn"),
+    ("Guitar", "Acoustic Guitar"),
+    ("Bass Guitar", "Electric Bass"),
+    ("Alto Sax", "Alto Saxophone"),
+    ("Tenor Sax", "Tenor Saxophone"),
+    ("Baritone Sax", "Baritone Saxophone"),
+];
+
+/// A clef an instrument's part is written in by default.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Clef {
+    /// G clef on the second line.
+    Treble,
+    /// G clef on the second line, sounding an octave lower than written, as
+    /// for guitar and tenor voice.
+    TrebleOctaveDown,
+    /// C clef on the third line.
+    Alto,
+    /// C clef on the fourth line.
+    Tenor,
+    /// F clef on the fourth line.
+    Bass,
+}
+
+impl Clef {
+    /// Returns the clef's music21 name, such as `"treble8vb"`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Clef::Treble => "treble",
+            Clef::TrebleOctaveDown => "treble8vb",
+            Clef::Alto => "alto",
+            Clef::Tenor => "tenor",
+            Clef::Bass => "bass",
+        }
+    }
+}
+
+impl fmt::Display for Clef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// An instrument or voice from the built-in table, after music21's
+/// `instrument` module.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Instrument {
+    name: String,
+    range: Ambitus,
+    comfortable_range: Ambitus,
+    transposition: String,
+    midi_program: u8,
+    clef: Clef,
+}
+
+impl Instrument {
+    /// Looks up a built-in instrument by name, ignoring case, spaces and
+    /// hyphens, so `"english horn"` and `"EnglishHorn"` both work. Common
+    /// alternatives such as `"cello"` and `"French horn"` are accepted too.
+    pub fn from_name(name: &str) -> Result<Self> {
+        let mut wanted = normalize(name);
+        if let Some((_, canonical)) = ALIASES.iter().find(|(alias, _)| normalize(alias) == wanted) {
+            wanted = normalize(canonical);
+        }
+        let (
+            name,
+            [lowest, highest, comfortable_lowest, comfortable_highest],
+            transposition,
+            midi_program,
+            clef,
+        ) = INSTRUMENTS
+            .iter()
+            .find(|(known, ..)| normalize(known) == wanted)
+            .ok_or_else(|| Error::Instrument(format!("unknown instrument {name:?}")))?;
+        Ok(Self {
+            name: name.to_string(),
+            range: Ambitus::between(Pitch::from_name(*lowest)?, Pitch::from_name(*highest)?),
+            comfortable_range: Ambitus::between(
+                Pitch::from_name(*comfortable_lowest)?,
+                Pitch::from_name(*comfortable_highest)?,
+            ),
+            transposition: transposition.to_string(),
+            midi_program: *midi_program,
+            clef: *clef,
+        })
+    }
+
+    /// Returns every built-in instrument, woodwinds first, in score order.
+    pub fn all() -> Vec<Self> {
+        INSTRUMENTS
+            .iter()
+            .map(|(name, ..)| Self::from_name(name).expect("built-in instruments are valid"))
+            .collect()
+    }
+
+    /// Returns the names of the built-in instruments, in score order.
+    pub fn names() -> impl Iterator<Item = &'static str> {
+        INSTRUMENTS.iter().map(|(name, ..)| *name)
+    }
+
+    /// Returns the instrument's name, such as `"English Horn"`.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the instrument's full sounding range.
+    pub fn range(&self) -> &Ambitus {
+        &self.range
+    }
+
+    /// Returns the sounding range most players are comfortable in.
+    pub fn comfortable_range(&self) -> &Ambitus {
+        &self.comfortable_range
+    }
+
+    /// Returns the interval from concert (sounding) to written pitch: a
+    /// major second up for a B-flat clarinet, an octave down for a piccolo,
+    /// a perfect unison for concert-pitch instruments.
+    ///
+    /// Octave clefs are not transpositions here: guitar and tenor voice are
+    /// written at sounding pitch in [`Clef::TrebleOctaveDown`].
+    pub fn written_interval(&self) -> Result<Interval> {
+        Interval::from_name(self.transposition.as_str())
+    }
+
+    /// Returns the interval from written to concert pitch, the reverse of
+    /// [`Instrument::written_interval`].
+    pub fn sounding_interval(&self) -> Result<Interval> {
+        self.written_interval()?.reversed()
+    }
+
+    /// Returns whether the instrument's part is written at a different
+    /// pitch than it sounds.
+    pub fn is_transposing(&self) -> bool {
+        self.transposition != "P1"
+    }
+
+    /// Returns the instrument's written range.
+    pub fn written_range(&self) -> Result<Ambitus> {
+        let interval = self.written_interval()?;
+        Ok(Ambitus::between(
+            interval.transpose_pitch(self.range.lowest())?,
+            interval.transpose_pitch(self.range.highest())?,
+        ))
+    }
+
+    /// Returns the 0-based General MIDI program number, such as 40 for
+    /// violin.
+    pub fn midi_program(&self) -> u8 {
+        self.midi_program
+    }
+
+    /// Returns the clef the instrument's part is usually written in.
+    pub fn clef(&self) -> Clef {
+        self.clef
+    }
+}
+
+impl fmt::Display for Instrument {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.name)
+    }
+}
+
+/// Returns `stream`, written at concert pitch, as the part `instrument`
+/// reads.
+pub fn concert_to_written(stream: &Stream, instrument: &Instrument) -> Result<Stream> {
+    stream.transpose(&instrument.written_interval()?)
+}
+
+/// Returns `stream`, a part written for `instrument`, at concert pitch.
+pub fn written_to_concert(stream: &Stream, instrument: &Instrument) -> Result<Stream> {
+    stream.transpose(&instrument.sounding_interval()?)
+}
+
+impl Stream {
+    /// Returns the stream, at concert pitch, as written for `instrument`.
+    ///
+    /// See [`concert_to_written`].
+    pub fn to_written_pitch(&self, instrument: &Instrument) -> Result<Stream> {
+        concert_to_written(self, instrument)
+    }
+
+    /// Returns the stream, written for `instrument`, at concert pitch.
+    ///
+    /// See [`written_to_concert`].
+    pub fn to_concert_pitch(&self, instrument: &Instrument) -> Result<Stream> {
+        written_to_concert(self, instrument)
+    }
+}
+
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|ch| !matches!(ch, ' ' | '-' | '_'))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{chord::Chord, note::Note};
+
+    #[test]
+    fn looks_up_built_in_instruments() {
+        let horn = Instrument::from_name("english-horn").unwrap();
+        assert_eq!(horn.to_string(), "English Horn");
+        assert_eq!(horn.range().lowest().name_with_octave(), "E3");
+        assert_eq!(horn.midi_program(), 69);
+        assert_eq!(horn.clef(), Clef::Treble);
+        assert_eq!(
+            Instrument::from_name("viola").unwrap().clef().name(),
+            "alto"
+        );
+        assert!(Instrument::from_name("theremin").is_err());
+
+        let all = Instrument::all();
+        assert_eq!(all.len(), Instrument::names().count());
+        assert!(all.iter().all(|instrument| {
+            instrument.range().semitones() > 12.0
+                && instrument
+                    .range()
+                    .contains_ambitus(instrument.comfortable_range())
+                && instrument.midi_program() < 128
+        }));
+    }
+
+    #[test]
+    fn transposes_between_concert_and_written_pitch() {
+        let clarinet = Instrument::from_name("Clarinet").unwrap();
+        assert!(clarinet.is_transposing());
+        assert_eq!(clarinet.written_interval().unwrap().name(), "Major Second");
+        let written = clarinet.written_range().unwrap();
+        assert_eq!(written.lowest().name_with_octave(), "E3");
+        assert_eq!(written.highest().name_with_octave(), "C7");
+
+        let mut concert = Stream::new();
+        concert.push(Note::from_name("B-4").unwrap());
+        concert.push(Chord::new("F4 A4 C5").unwrap());
+        let part = concert.to_written_pitch(&clarinet).unwrap();
+        let names = |stream: &Stream| {
+            stream
+                .pitches()
+                .iter()
+                .map(Pitch::name_with_octave)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(&part), vec!["C5", "G4", "B4", "D5"]);
+        assert_eq!(
+            names(&part.to_concert_pitch(&clarinet).unwrap()),
+            names(&concert)
+        );
+
+        let piccolo = Instrument::from_name("piccolo").unwrap();
+        assert_eq!(
+            names(&concert_to_written(&concert, &piccolo).unwrap())[0],
+            "B-3"
+        );
+        let violin = Instrument::from_name("violin").unwrap();
+        assert!(!violin.is_transposing());
+        assert_eq!(
+            names(&concert.to_written_pitch(&violin).unwrap()),
+            names(&concert)
+        );
+    }
+}
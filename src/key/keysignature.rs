@@ -108,6 +108,23 @@ impl KeySignature {
         self.sharps
     }
 
+    /// Returns the semitones the signature alters `step` by, as music21's
+    /// `KeySignature.accidentalByStep` does: `1` for F in G major, `-1` for B
+    /// in F major and `0` for an unaltered step. Signatures past seven sharps
+    /// or flats alter the first steps twice.
+    pub(crate) fn alter_for_step(&self, step: char) -> IntegerType {
+        const SHARP_ORDER: &str = "FCGDAEB";
+        let step = step.to_ascii_uppercase();
+        let Some(position) = SHARP_ORDER.find(step) else {
+            return 0;
+        };
+        if self.sharps >= 0 {
+            (self.sharps + 6 - position as IntegerType).div_euclid(7)
+        } else {
+            -(-self.sharps + position as IntegerType).div_euclid(7)
+        }
+    }
+
     /// Converts this signature to a key in the given mode.
     pub fn as_key(&self, mode: &str) -> Key {
         self.try_as_key(Some(mode), None).unwrap_or_else(|_| {
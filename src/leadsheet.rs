@@ -0,0 +1,717 @@
+//! Lead sheets: a melody with a chord-symbol timeline.
+//!
+//! A [`LeadSheet`] keeps the melody as an ordinary [`Stream`] and the changes
+//! as [`ChordSymbol`]s placed at quarter-length offsets, together with the
+//! [`Key`] and [`TimeSignature`] a band chart is read in. Transposition
+//! respells the key, melody and symbols together, and exports reuse the
+//! [`crate::abc`] and [`crate::midi`] helpers.
+
+use std::collections::HashMap;
+
+use crate::{
+    abc::{abc_duration, abc_note, abc_rest},
+    chordsymbol::ChordSymbol,
+    defaults::{FloatType, IntegerType},
+    error::{Error, Result},
+    instrument::Instrument,
+    interval::Interval,
+    key::{Key, KeySignature},
+    meter::TimeSignature,
+    midi::{MidiNote, midi_notes_from_stream, write_midi_bytes},
+    pitch::Pitch,
+    stream::{Stream, StreamElement},
+};
+
+/// Offsets closer than this are treated as the same moment.
+const OFFSET_EPSILON: FloatType = 1e-9;
+
+/// MIDI channel used for the comping part; the melody stays on channel 0.
+const COMPING_CHANNEL: u8 = 1;
+
+/// Comping velocity, a little under the melody's so the tune stays on top.
+const COMPING_VELOCITY: u8 = 56;
+
+/// Keys with more accidentals than this are respelled enharmonically.
+const MAX_KEY_ACCIDENTALS: IntegerType = 6;
+
+/// A chord symbol placed on a lead sheet's timeline.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChordSymbolEvent {
+    offset: FloatType,
+    quarter_length: Option<FloatType>,
+    symbol: ChordSymbol,
+}
+
+impl ChordSymbolEvent {
+    /// Creates a chord change at an offset measured in quarter lengths.
+    ///
+    /// Without a quarter length the chord lasts until the next change.
+    pub fn new(offset: FloatType, symbol: ChordSymbol, quarter_length: Option<FloatType>) -> Self {
+        Self {
+            offset,
+            quarter_length,
+            symbol,
+        }
+    }
+
+    /// Returns the offset in quarter lengths.
+    pub fn offset(&self) -> FloatType {
+        self.offset
+    }
+
+    /// Returns the explicit duration in quarter lengths, if one was given.
+    pub fn quarter_length(&self) -> Option<FloatType> {
+        self.quarter_length
+    }
+
+    /// Returns the chord symbol.
+    pub fn symbol(&self) -> &ChordSymbol {
+        &self.symbol
+    }
+}
+
+/// A melody, chord-symbol timeline, key, and time signature.
+#[derive(Clone, Debug)]
+pub struct LeadSheet {
+    title: Option<String>,
+    key: Key,
+    time_signature: TimeSignature,
+    melody: Stream,
+    chords: Vec<ChordSymbolEvent>,
+}
+
+impl LeadSheet {
+    /// Creates an empty lead sheet in a key and meter.
+    pub fn new(key: Key, time_signature: TimeSignature) -> Self {
+        Self {
+            title: None,
+            key,
+            time_signature,
+            melody: Stream::new(),
+            chords: Vec::new(),
+        }
+    }
+
+    /// Returns the lead sheet with a title.
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Returns the lead sheet with a melody.
+    pub fn with_melody(mut self, melody: Stream) -> Self {
+        self.melody = melody;
+        self
+    }
+
+    /// Returns the title, if any.
+    pub fn title(&self) -> Option<&str> {
+        self.title.as_deref()
+    }
+
+    /// Returns the key.
+    pub fn key(&self) -> &Key {
+        &self.key
+    }
+
+    /// Returns the time signature.
+    pub fn time_signature(&self) -> &TimeSignature {
+        &self.time_signature
+    }
+
+    /// Returns the melody.
+    pub fn melody(&self) -> &Stream {
+        &self.melody
+    }
+
+    /// Returns the chord changes in offset order.
+    pub fn chords(&self) -> &[ChordSymbolEvent] {
+        &self.chords
+    }
+
+    /// Appends a chord lasting `quarter_length` after the last change.
+    pub fn push_chord(&mut self, symbol: ChordSymbol, quarter_length: FloatType) -> Result<()> {
+        let offset = self
+            .chords
+            .iter()
+            .map(|event| event.offset + event.quarter_length.unwrap_or(0.0))
+            .fold(0.0, FloatType::max);
+        self.insert_chord(offset, symbol, Some(quarter_length))
+    }
+
+    /// Inserts a chord change at a quarter-length offset.
+    ///
+    /// Without a quarter length the chord lasts until the next change.
+    pub fn insert_chord(
+        &mut self,
+        offset: FloatType,
+        symbol: ChordSymbol,
+        quarter_length: Option<FloatType>,
+    ) -> Result<()> {
+        if !offset.is_finite() || offset < 0.0 {
+            return Err(Error::Chord(format!(
+                "chord symbol offset must be finite and non-negative, got {offset}"
+            )));
+        }
+        if let Some(quarter_length) = quarter_length
+            && (!quarter_length.is_finite() || quarter_length <= 0.0)
+        {
+            return Err(Error::Chord(format!(
+                "chord symbol duration must be finite and positive, got {quarter_length}"
+            )));
+        }
+
+        let index = self
+            .chords
+            .partition_point(|event| event.offset <= offset + OFFSET_EPSILON);
+        self.chords
+            .insert(index, ChordSymbolEvent::new(offset, symbol, quarter_length));
+        Ok(())
+    }
+
+    /// Inserts a chord change at a one-based measure and beat, such as beat
+    /// `3` of measure `2`; fractional beats fall between beats.
+    ///
+    /// The chord lasts until the next change.
+    pub fn insert_chord_at_beat(
+        &mut self,
+        measure: u32,
+        beat: FloatType,
+        symbol: ChordSymbol,
+    ) -> Result<()> {
+        let offset = self.offset_for_beat(measure, beat)?;
+        self.insert_chord(offset, symbol, None)
+    }
+
+    /// Returns the quarter-length offset of a one-based measure and beat.
+    pub fn offset_for_beat(&self, measure: u32, beat: FloatType) -> Result<FloatType> {
+        let beat_count = FloatType::from(self.time_signature.beat_count());
+        if measure == 0 || !beat.is_finite() || beat < 1.0 || beat >= beat_count + 1.0 {
+            return Err(Error::Meter(format!(
+                "measure {measure}, beat {beat} is outside a {} bar",
+                self.time_signature
+            )));
+        }
+        // Beats may be unequal, as in 2+2+3/8, so a fractional beat is a
+        // fraction of the beat it falls in.
+        let index = beat.floor() as usize - 1;
+        Ok(
+            FloatType::from(measure - 1) * self.time_signature.bar_quarter_length()
+                + self.time_signature.beat_offsets()[index]
+                + beat.fract() * self.time_signature.beat_quarter_lengths()[index],
+        )
+    }
+
+    /// Returns the chord sounding at an offset, if any.
+    pub fn chord_at(&self, offset: FloatType) -> Option<&ChordSymbol> {
+        self.chord_spans()
+            .into_iter()
+            .find(|(start, end, _)| {
+                *start <= offset + OFFSET_EPSILON && offset + OFFSET_EPSILON < *end
+            })
+            .map(|(_, _, symbol)| symbol)
+    }
+
+    /// Returns the lead sheet transposed by an interval.
+    ///
+    /// When the interval would land in a key with more than six sharps or
+    /// flats, it is respelled enharmonically — up an augmented unison from
+    /// C-sharp major gives D major rather than C-double-sharp — and the melody
+    /// and chord symbols follow the same spelling.
+    pub fn transpose(&self, interval: &Interval) -> Result<Self> {
+        let interval = self.respelled_interval(interval)?;
+        let key = Key::from_tonic_mode(
+            &interval.transpose_pitch(self.key.tonic_pitch())?.name(),
+            self.key.mode(),
+        )?;
+        let chords = self
+            .chords
+            .iter()
+            .map(|event| {
+                Ok(ChordSymbolEvent::new(
+                    event.offset,
+                    event.symbol.transpose(&interval)?,
+                    event.quarter_length,
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            title: self.title.clone(),
+            key,
+            time_signature: self.time_signature.clone(),
+            melody: self.melody.transpose(&interval)?,
+            chords,
+        })
+    }
+
+    /// Returns the chart a guitarist reads with a capo at `fret`.
+    ///
+    /// Everything moves down by the capo's semitones, so a song in E-flat
+    /// with a capo at the first fret is played with D shapes.
+    pub fn capo_view(&self, fret: u8) -> Result<Self> {
+        self.transpose(&Interval::from_semitones(-IntegerType::from(fret))?)
+    }
+
+    /// Returns the part a transposing instrument reads.
+    ///
+    /// A concert-pitch instrument gets the lead sheet unchanged.
+    pub fn instrument_view(&self, instrument: &Instrument) -> Result<Self> {
+        self.transpose(&instrument.written_interval()?)
+    }
+
+    /// Writes the lead sheet as an ABC tune with `"Cmaj7"`-style chord
+    /// annotations.
+    ///
+    /// Notes are split with ties at barlines and at chord changes, so every
+    /// change lands on a note or rest that can carry its annotation.
+    /// Accidentals are written against the key signature and the accidentals
+    /// already written in the bar, with `=` for naturals.
+    pub fn to_abc(&self) -> Result<String> {
+        let mut abc = String::from("X:1\n");
+        if let Some(title) = &self.title {
+            abc.push_str(&format!("T:{title}\n"));
+        }
+        abc.push_str(&format!("M:{}\n", self.time_signature));
+        abc.push_str("L:1/4\n");
+        abc.push_str(&format!("K:{}\n", abc_key(&self.key)));
+
+        let bar = self.time_signature.bar_quarter_length();
+        let chord_spans = self.chord_spans();
+        let end = self.end_offset();
+        let mut boundaries = chord_spans
+            .iter()
+            .map(|(start, _, _)| *start)
+            .collect::<Vec<_>>();
+        let mut barline = bar;
+        while barline < end - OFFSET_EPSILON {
+            boundaries.push(barline);
+            barline += bar;
+        }
+        boundaries.sort_by(FloatType::total_cmp);
+        boundaries.dedup_by(|a, b| (*a - *b).abs() < OFFSET_EPSILON);
+
+        let mut segments = Vec::new();
+        let mut cursor = 0.0;
+        for event in self.melody.events() {
+            let start = event.offset().max(cursor);
+            if start > cursor + OFFSET_EPSILON {
+                segments.push((cursor, start, None));
+            }
+            let stop = event.offset() + event.element().quarter_length();
+            if stop > start + OFFSET_EPSILON {
+                segments.push((start, stop, Some(event.element())));
+                cursor = stop;
+            }
+        }
+        if end > cursor + OFFSET_EPSILON {
+            segments.push((cursor, end, None));
+        }
+
+        let mut body = String::new();
+        let mut speller = AbcSpeller::new(self.key.key_signature());
+        let mut next_chord = 0;
+        for (start, stop, element) in segments {
+            let mut cuts = boundaries
+                .iter()
+                .copied()
+                .filter(|offset| {
+                    *offset > start + OFFSET_EPSILON && *offset < stop - OFFSET_EPSILON
+                })
+                .collect::<Vec<_>>();
+            cuts.push(stop);
+
+            let mut piece_start = start;
+            for (index, piece_stop) in cuts.iter().copied().enumerate() {
+                while next_chord < chord_spans.len()
+                    && chord_spans[next_chord].0 < piece_start + OFFSET_EPSILON
+                {
+                    if (chord_spans[next_chord].0 - piece_start).abs() < OFFSET_EPSILON {
+                        body.push_str(&format!("\"{}\"", chord_spans[next_chord].2));
+                    }
+                    next_chord += 1;
+                }
+
+                let token = match element {
+                    Some(StreamElement::Note(note)) => speller.note(note.pitch())?,
+                    Some(StreamElement::Chord(chord)) => speller.chord(&chord.pitches())?,
+                    Some(StreamElement::Rest(_)) | None => abc_rest().to_string(),
+                };
+                body.push_str(&token);
+                body.push_str(&abc_length(piece_stop - piece_start)?);
+                let sounding = matches!(
+                    element,
+                    Some(StreamElement::Note(_) | StreamElement::Chord(_))
+                );
+                if sounding && index + 1 < cuts.len() {
+                    body.push('-');
+                }
+
+                let position = piece_stop / bar;
+                if (position - position.round()).abs() < OFFSET_EPSILON
+                    && piece_stop < end - OFFSET_EPSILON
+                {
+                    body.push_str(" | ");
+                    speller.end_bar();
+                } else {
+                    body.push(' ');
+                }
+                piece_start = piece_stop;
+            }
+        }
+
+        abc.push_str(body.trim_end());
+        abc.push_str(" |]\n");
+        Ok(abc)
+    }
+
+    /// Returns block-chord comping for the chord timeline as MIDI notes.
+    ///
+    /// Each change sounds its root (or slash bass) in the second octave and
+    /// its chord tones in close position from G3 up, on MIDI channel 1.
+    pub fn comping_midi_notes(&self) -> Result<Vec<MidiNote>> {
+        let mut notes = Vec::new();
+        for (start, end, symbol) in self.chord_spans() {
+            for pitch in comping_voicing(symbol)? {
+                notes.push(MidiNote::with_channel(
+                    pitch,
+                    start,
+                    end - start,
+                    COMPING_VELOCITY,
+                    COMPING_CHANNEL,
+                )?);
+            }
+        }
+        Ok(notes)
+    }
+
+    /// Returns the melody on MIDI channel 0 followed by the comping.
+    pub fn midi_notes(&self) -> Result<Vec<MidiNote>> {
+        let mut notes = midi_notes_from_stream(&self.melody)?;
+        notes.extend(self.comping_midi_notes()?);
+        Ok(notes)
+    }
+
+    /// Writes the melody and comping as a Standard MIDI File.
+    pub fn to_midi_bytes(&self, tempo_bpm: FloatType) -> Result<Vec<u8>> {
+        write_midi_bytes(&self.midi_notes()?, tempo_bpm)
+    }
+
+    /// Each change with the offset where it stops sounding.
+    ///
+    /// A change never sounds past the next one. Without a duration it lasts
+    /// until the next change; the last lasts until the melody ends or, failing
+    /// that, to the end of its bar.
+    pub(crate) fn chord_spans(&self) -> Vec<(FloatType, FloatType, &ChordSymbol)> {
+        let bar = self.time_signature.bar_quarter_length();
+        self.chords
+            .iter()
+            .enumerate()
+            .map(|(index, event)| {
+                let end = match (event.quarter_length, self.chords.get(index + 1)) {
+                    (Some(quarter_length), Some(next)) => {
+                        (event.offset + quarter_length).min(next.offset)
+                    }
+                    (Some(quarter_length), None) => event.offset + quarter_length,
+                    (None, Some(next)) => next.offset,
+                    (None, None) => {
+                        let bar_end = ((event.offset + OFFSET_EPSILON) / bar).ceil() * bar;
+                        self.melody.end_offset().max(bar_end)
+                    }
+                };
+                (event.offset, end, &event.symbol)
+            })
+            .collect()
+    }
+
+    fn end_offset(&self) -> FloatType {
+        self.chord_spans()
+            .iter()
+            .map(|(_, end, _)| *end)
+            .fold(self.melody.end_offset(), FloatType::max)
+    }
+
+    /// The interval itself, or an enharmonic respelling of it when the
+    /// target key would need more than six sharps or flats.
+    fn respelled_interval(&self, interval: &Interval) -> Result<Interval> {
+        let tonic = Pitch::from_name_and_octave(self.key.tonic_pitch().name(), 4)?;
+        let target = interval.transpose_pitch(&tonic)?;
+        let sharps = |pitch: &Pitch| {
+            Key::from_tonic_mode(&pitch.name(), self.key.mode())
+                .map(|key| key.sharps().abs())
+                .ok()
+        };
+        if sharps(&target).is_some_and(|sharps| sharps <= MAX_KEY_ACCIDENTALS) {
+            return Ok(interval.clone());
+        }
+
+        let best = [
+            target.get_lower_enharmonic()?,
+            target.get_higher_enharmonic()?,
+        ]
+        .into_iter()
+        .filter_map(|pitch| sharps(&pitch).map(|sharps| (sharps, pitch)))
+        .min_by_key(|(sharps, _)| *sharps);
+        match best {
+            Some((_, pitch)) => Interval::between_pitches(&tonic, &pitch),
+            None => Ok(interval.clone()),
+        }
+    }
+}
+
+/// An ABC `K:` field such as `Bb`, `F#m`, or `Ddor`.
+fn abc_key(key: &Key) -> String {
+    let tonic = key.tonic_pitch().name().replace('-', "b");
+    let mode = match key.mode() {
+        "major" => "",
+        "minor" => "m",
+        mode => &mode[..mode.len().min(3)],
+    };
+    format!("{tonic}{mode}")
+}
+
+/// Writes ABC notes with the accidentals a reader needs. In ABC, as on the
+/// staff, the key signature alters a step in every octave, and an accidental
+/// holds for the same note in the same octave until the bar line.
+struct AbcSpeller {
+    key_signature: KeySignature,
+    bar: HashMap<(char, IntegerType), IntegerType>,
+}
+
+impl AbcSpeller {
+    fn new(key_signature: KeySignature) -> Self {
+        Self {
+            key_signature,
+            bar: HashMap::new(),
+        }
+    }
+
+    fn note(&mut self, pitch: &Pitch) -> Result<String> {
+        let written = abc_note(pitch)?;
+        let body = written.trim_start_matches(['^', '_']);
+        let step = pitch.step().as_char();
+        let alter = pitch.alter().round() as IntegerType;
+        let place = (step, pitch.octave().unwrap_or(4));
+        let current = self
+            .bar
+            .get(&place)
+            .copied()
+            .unwrap_or_else(|| self.key_signature.alter_for_step(step));
+        if alter == current {
+            return Ok(body.to_string());
+        }
+        self.bar.insert(place, alter);
+        let accidental = match alter {
+            0 => "=".to_string(),
+            sharps if sharps > 0 => "^".repeat(sharps as usize),
+            flats => "_".repeat(flats.unsigned_abs() as usize),
+        };
+        Ok(format!("{accidental}{body}"))
+    }
+
+    fn chord(&mut self, pitches: &[Pitch]) -> Result<String> {
+        let notes = pitches
+            .iter()
+            .map(|pitch| self.note(pitch))
+            .collect::<Result<Vec<_>>>()?;
+        if notes.is_empty() {
+            Ok(abc_rest().to_string())
+        } else {
+            Ok(format!("[{}]", notes.join("")))
+        }
+    }
+
+    fn end_bar(&mut self) {
+        self.bar.clear();
+    }
+}
+
+/// An ABC length suffix for a quarter-length duration under `L:1/4`.
+fn abc_length(quarter_length: FloatType) -> Result<String> {
+    let denominator = (1..=96)
+        .find(|denominator| {
+            let scaled = quarter_length * FloatType::from(*denominator);
+            (scaled - scaled.round()).abs() < 1e-6
+        })
+        .ok_or_else(|| {
+            Error::Music21Object(format!(
+                "cannot write a quarter length of {quarter_length} as an ABC length"
+            ))
+        })?;
+    let numerator = (quarter_length * FloatType::from(denominator)).round() as u32;
+    abc_duration(numerator, denominator)
+}
+
+fn comping_voicing(symbol: &ChordSymbol) -> Result<Vec<u8>> {
+    let bass = symbol.bass().unwrap_or(symbol.root()).pitch_class_number();
+    let mut pitches = vec![36 + bass];
+    for pc in symbol.to_chord()?.pitch_classes() {
+        pitches.push(55 + (pc + 5) % 12);
+    }
+    pitches[1..].sort_unstable();
+    pitches.dedup();
+    Ok(pitches)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{duration::Duration, note::Note, rest::Rest};
+
+    fn symbol(figure: &str) -> ChordSymbol {
+        ChordSymbol::parse(figure).unwrap()
+    }
+
+    fn sheet() -> LeadSheet {
+        let mut melody = Stream::new();
+        for (name, quarter_length) in [("E4", 2.0), ("G4", 1.0), ("B-4", 3.0), ("A4", 2.0)] {
+            melody.push(
+                Note::from_name(name)
+                    .unwrap()
+                    .with_duration(Duration::new(quarter_length).unwrap()),
+            );
+        }
+
+        let mut sheet = LeadSheet::new(
+            Key::from_tonic_mode("F", "major").unwrap(),
+            TimeSignature::common(),
+        )
+        .with_title("Blues")
+        .with_melody(melody);
+        sheet.push_chord(symbol("C7"), 4.0).unwrap();
+        sheet.push_chord(symbol("Fmaj7"), 4.0).unwrap();
+        sheet
+    }
+
+    fn symbols(sheet: &LeadSheet) -> Vec<String> {
+        sheet
+            .chords()
+            .iter()
+            .map(|event| event.symbol().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn places_chords_by_duration_and_beat() {
+        let mut sheet = sheet();
+        sheet.insert_chord_at_beat(2, 3.0, symbol("D7")).unwrap();
+        assert_eq!(symbols(&sheet), vec!["C7", "Fmaj7", "D7"]);
+        assert_eq!(sheet.chords()[2].offset(), 6.0);
+        assert_eq!(
+            sheet.chord_at(5.0).map(ToString::to_string).as_deref(),
+            Some("Fmaj7")
+        );
+        assert_eq!(
+            sheet.chord_at(7.0).map(ToString::to_string).as_deref(),
+            Some("D7")
+        );
+        assert!(sheet.insert_chord_at_beat(0, 1.0, symbol("C")).is_err());
+        assert!(sheet.insert_chord_at_beat(1, 5.0, symbol("C")).is_err());
+    }
+
+    #[test]
+    fn transposes_melody_symbols_and_key_together() {
+        let up = sheet()
+            .transpose(&Interval::from_name("M2").unwrap())
+            .unwrap();
+        assert_eq!(up.key().tonic().name(), "G");
+        assert_eq!(symbols(&up), vec!["D7", "Gmaj7"]);
+        assert_eq!(
+            up.melody()
+                .pitches()
+                .iter()
+                .map(Pitch::name_with_octave)
+                .collect::<Vec<_>>(),
+            vec!["F#4", "A4", "C5", "B4"]
+        );
+
+        let sharp = LeadSheet::new(
+            Key::from_tonic_mode("C#", "major").unwrap(),
+            TimeSignature::common(),
+        );
+        let respelled = sharp
+            .transpose(&Interval::from_name("a1").unwrap())
+            .unwrap();
+        assert_eq!(respelled.key().tonic().name(), "D");
+    }
+
+    #[test]
+    fn capo_and_instrument_views() {
+        let capo = sheet().capo_view(1).unwrap();
+        assert_eq!(capo.key().tonic().name(), "E");
+        assert_eq!(symbols(&capo), vec!["B7", "Emaj7"]);
+
+        let view = |name: &str| {
+            sheet()
+                .instrument_view(&Instrument::from_name(name).unwrap())
+                .unwrap()
+        };
+        assert_eq!(view("trumpet").key().tonic().name(), "G");
+        let alto = view("alto sax");
+        assert_eq!(alto.key().tonic().name(), "D");
+        assert_eq!(symbols(&alto), vec!["A7", "Dmaj7"]);
+        assert_eq!(view("horn").key().tonic().name(), "C");
+        assert_eq!(view("flute").key().tonic().name(), "F");
+    }
+
+    #[test]
+    fn writes_abc_with_chord_annotations() {
+        let mut sheet = sheet();
+        sheet.insert_chord(6.0, symbol("Bb6"), None).unwrap();
+        let abc = sheet.to_abc().unwrap();
+        assert_eq!(
+            abc,
+            "X:1\nT:Blues\nM:4/4\nL:1/4\nK:F\n\"C7\"E2 G B- | \"Fmaj7\"B2 \"Bb6\"A2 |]\n"
+        );
+
+        let mut rests = LeadSheet::new(
+            Key::from_tonic_mode("A", "minor").unwrap(),
+            TimeSignature::from_ratio_string("3/4").unwrap(),
+        )
+        .with_melody(Stream::from_events([crate::stream::StreamEvent::new(
+            1.0,
+            Rest::from_quarter_length(0.5).unwrap(),
+        )]));
+        rests.push_chord(symbol("Am"), 3.0).unwrap();
+        assert_eq!(
+            rests.to_abc().unwrap(),
+            "X:1\nM:3/4\nL:1/4\nK:Am\n\"Am\"z z/2 z3/2 |]\n"
+        );
+    }
+
+    #[test]
+    fn writes_abc_accidentals_against_the_key_and_bar() {
+        let mut melody = Stream::new();
+        for name in ["F4", "F4", "F#4", "F#5", "F4", "C#4", "F#4", "B-4"] {
+            melody.push(Note::from_name(name).unwrap());
+        }
+        let sheet = LeadSheet::new(
+            Key::from_tonic_mode("G", "major").unwrap(),
+            TimeSignature::common(),
+        )
+        .with_melody(melody);
+        // The natural holds to the bar line and only in its own octave, while
+        // the key signature covers every octave and every bar.
+        assert_eq!(
+            sheet.to_abc().unwrap(),
+            "X:1\nM:4/4\nL:1/4\nK:G\n=F F ^F f | =F ^C ^F _B |]\n"
+        );
+    }
+
+    #[test]
+    fn comps_chords_as_midi() {
+        let notes = sheet().comping_midi_notes().unwrap();
+        let first = notes
+            .iter()
+            .filter(|note| note.start == 0.0)
+            .map(|note| note.pitch)
+            .collect::<Vec<_>>();
+        assert_eq!(first, vec![36, 55, 58, 60, 64]);
+        assert!(notes.iter().all(|note| note.channel == 1));
+
+        let all = sheet().midi_notes().unwrap();
+        assert_eq!(all.iter().filter(|note| note.channel == 0).count(), 4);
+        let bytes = sheet().to_midi_bytes(120.0).unwrap();
+        assert_eq!(&bytes[..4], b"MThd");
+    }
+}
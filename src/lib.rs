@@ -12,42 +12,81 @@ pub mod abc;
 pub mod analysis;
 /// Chord construction, common-name analysis and chord input conversion traits.
 pub mod chord;
-/// Lead-sheet chord-symbol parsing.
+/// Vertical slicing of streams into chords.
+pub mod chordify;
+/// Lead-sheet chord-symbol parsing and rendering.
 pub mod chordsymbol;
 pub(crate) mod common;
 pub(crate) mod defaults;
+/// SVG diagrams of guitar fingerings, keyboards and pitch-class clocks.
+pub mod diagram;
 pub(crate) mod display;
 /// Rhythmic duration primitives.
 pub mod duration;
 /// Error and result types used by the crate.
 pub mod error;
+/// Melodic and intervallic feature vectors for corpus statistics.
+pub mod features;
 
 pub(crate) mod fraction_pow;
+/// Built-in instruments with ranges, transpositions, MIDI programs and clefs.
+pub mod instrument;
 /// Public interval parsing, naming and transposition helpers.
 pub mod interval;
 /// Public key and key-signature helpers.
 pub mod key;
-/// Minimal MIDI import/export helpers.
+/// Lead sheets pairing a melody with a chord-symbol timeline.
+pub mod leadsheet;
+/// LilyPond export for streams, instrument parts and lead sheets.
+pub mod lilypond;
+/// Time signatures and meter partition trees.
 pub mod meter;
+/// Minimal MIDI import/export helpers.
 pub mod midi;
+/// Melodic pattern search by pitch, interval, contour and rhythm.
+pub mod motif;
+/// Nashville number-system chords relative to a key.
+pub mod nashville;
+/// Neo-Riemannian transformations and Tonnetz coordinates.
+pub mod neoriemannian;
+/// Rhythm spelling for notation export: ties, beams and tuplet brackets.
+pub mod notation;
 /// Note construction and pitch access helpers.
 pub mod note;
 /// Pitch construction, spelling and pitch-space helpers.
 pub mod pitch;
+/// Pitch-class sets, set classes and their twelve-tone operations.
+pub mod pitchclassset;
 /// Polyrhythm timing and pitch-set helpers.
 pub mod polyrhythm;
+/// Chord progressions and jazz reharmonization options.
+pub mod progression;
+/// Ambitus, tessitura and instrument range checks.
+pub mod range;
 /// Silent duration-bearing musical event.
 pub mod rest;
 /// Roman numeral parsing and compact harmonic analysis.
 pub mod roman;
 /// Public scale helpers.
 pub mod scale;
+/// Twelve-tone rows, serial matrices and combinatoriality.
+pub mod serial;
 pub mod sieve;
+/// Set-class similarity measures and nearest-set-class search.
+pub mod similarity;
 pub(crate) mod stepname;
 /// Small ordered timeline container.
 pub mod stream;
+/// Guitar tablature reading and writing.
+pub mod tablature;
+/// Ties between notes of the same pitch.
+pub mod tie;
 /// Tuning-system ratios, labels and frequency helpers.
 pub mod tuningsystem;
+/// Minimal voice leadings between chords.
+pub mod voiceleading;
+/// Close, spread, drop, rootless and quartal chord voicings.
+pub mod voicing;
 // #[macro_use]
 // pub(crate) mod macros;
 
@@ -55,37 +94,82 @@ pub use abc::{
     abc_chord, abc_duration, abc_note, abc_rest, pitch_name_from_abc_note,
     pitch_names_from_abc_chord,
 };
-pub use analysis::{KeyEstimate, estimate_key_from_chords, estimate_key_from_pitches};
+pub use analysis::{
+    KeyEstimate, KeyFindingOptions, KeyProfile, WindowedKey, analyze_key_windows,
+    estimate_key_from_chords, estimate_key_from_histogram, estimate_key_from_pitches,
+    estimate_key_from_stream, pitch_class_histogram, tonal_certainty,
+};
 pub use chord::{
-    Chord, ChordResolutionSuggestion, GuitarFingering, GuitarStringFingering, GuitarTuning,
-    GuitarTuningString, IntoNotes, KnownChordType,
+    Chord, ChordResolutionSuggestion, FingeringOptions, GuitarFingering, GuitarStringFingering,
+    GuitarTuning, GuitarTuningString, IntoNotes, KnownChordType, optimize_fingering_sequence,
 };
+pub use chordify::{ChordifyOptions, chordify};
 pub use chordsymbol::{
-    ChordAlteration, ChordQuality, ChordSymbol, Music21ChordType, known_chord_symbol_types,
+    ChordAlteration, ChordQuality, ChordSymbol, ChordSymbolDisplay, ChordSymbolStyle,
+    Music21ChordType, known_chord_symbol_types,
 };
 pub use defaults::{FloatType, FractionType, IntegerType, Octave, UnsignedIntegerType};
-pub use duration::{Duration, DurationType};
+pub use diagram::{
+    ClockLayout, DiagramOptions, fingering_svg, keyboard_svg, pitch_class_clock_svg,
+};
+pub use duration::{Duration, DurationType, Tuplet};
 pub use error::{Error, Result};
+pub use features::{Feature, FeatureVector, extract_features};
+pub use instrument::{Clef, Instrument, concert_to_written, written_to_concert};
 pub use interval::{Interval, IntervalDirection};
 pub use key::{Key, KeySignature};
-pub use meter::{BeatDivision, TimeSignature};
+pub use leadsheet::{ChordSymbolEvent, LeadSheet};
+pub use lilypond::{
+    LilyPondOptions, lilypond_chord_symbol, lilypond_duration, lilypond_pitch, parts_to_lilypond,
+    stream_to_lilypond,
+};
+pub use meter::{BeatDivision, MeterSequence, TimeSignature};
 pub use midi::{
-    DEFAULT_TICKS_PER_QUARTER, MidiNote, midi_notes_from_stream, read_midi_bytes,
-    read_midi_bytes_with_tempo, stream_from_midi_notes, write_midi_bytes,
+    DEFAULT_TICKS_PER_QUARTER, MidiNote, midi_notes_from_stream, midi_notes_from_stream_on_channel,
+    read_midi_bytes, read_midi_bytes_with_tempo, stream_from_midi_notes, write_midi_bytes,
+    write_midi_bytes_with_programs, write_midi_parts,
+};
+pub use motif::{
+    MelodicMatch, MotifOccurrence, MotifSearchOptions, RhythmMatch, contour_class, contour_segment,
+    find_motif,
 };
+pub use nashville::NashvilleNumber;
+pub use neoriemannian::{
+    NeoRiemannianTransform, Tonnetz, TonnetzTriad, TriadQuality, shortest_transform_path,
+};
+pub use notation::{Beam, SpelledElement, SpelledMeasure, TupletBracket, spell_rhythm};
 pub use note::{IntoNote, Note};
 pub use pitch::{
     Accidental, AccidentalSpecifier, CHROMATIC_PITCH_CLASS_NAMES, Microtone, MicrotoneSpecifier,
     Pitch, PitchClass, PitchClassSpecifier, PitchName, PitchOptions, pitch_class_name,
 };
+pub use pitchclassset::{PackingConvention, PitchClassSet};
 pub use polyrhythm::{Polyrhythm, PolyrhythmAnalysis, PolyrhythmEvent, PolyrhythmRatioTone};
+pub use progression::{Progression, Substitution, SubstitutionKind};
+pub use range::{
+    Ambitus, RangeViolation, Tessitura, check_range, pitch_space_histogram, tessitura,
+};
 pub use rest::Rest;
 pub use roman::{RomanNumeral, analyze_chord, analyze_chord_with_root};
 pub use scale::{BluesForm, DiatonicScale, Scale, ScaleType, StepScale, WeightedHexatonicBlues};
+pub use serial::{
+    Combinatoriality, NamedToneRow, RowForm, RowFormKind, RowLabel, ToneRow, named_tone_rows,
+};
 pub use sieve::Sieve;
+pub use similarity::{
+    SimilarityMeasure, closest_set_classes, isaacson_icvsim, lewin_rel, morris_asim, morris_sim,
+    rahn_atmemb, voice_leading_distance,
+};
 pub use stream::{Stream, StreamElement, StreamEvent};
+pub use tablature::{TabOptions, read_alphatex, read_tab, write_fingering_tab, write_tab};
+pub use tie::Tie;
 pub use tuningsystem::{
     ALL_TUNING_SYSTEMS, COMMON_EQUAL_TEMPERAMENTS, COMMON_TWELVE_TONE_TUNING_SYSTEMS, Fraction,
     HISTORICAL_TEMPERAMENTS, TuningSystem,
     scala::{ScalaArchive, ScalaDegree, ScalaScale},
 };
+pub use voiceleading::{
+    VoiceLeading, VoiceLeadingNorm, VoiceLeadingOptions, VoiceLeadingSpace, VoiceMotion,
+    minimal_voice_leading,
+};
+pub use voicing::{Voicing, VoicingOptions, VoicingStyle, voice_chord, voice_chord_symbol};
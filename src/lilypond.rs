@@ -0,0 +1,728 @@
+//! LilyPond export.
+//!
+//! Writes `.ly` source for a [`Stream`], a set of instrument parts or a
+//! [`LeadSheet`]. Rhythms go through [`spell_rhythm`] first, so bars, ties,
+//! beams and tuplet brackets come out as an engraver would write them. Pitches
+//! use LilyPond's default Dutch note names, with the quarter-tone suffixes
+//! `ih`, `eh`, `isih` and `eseh` for microtonal accidentals.
+
+use crate::{
+    chordsymbol::ChordSymbol,
+    defaults::FloatType,
+    duration::{Duration, DurationType, fraction_from_float},
+    error::{Error, Result},
+    instrument::{Clef, Instrument},
+    key::Key,
+    leadsheet::LeadSheet,
+    meter::TimeSignature,
+    notation::{Beam, SpelledMeasure, spell_rhythm},
+    pitch::Pitch,
+    stream::{Stream, StreamElement},
+    tie::Tie,
+};
+
+/// The LilyPond release whose syntax the writers target.
+const LILYPOND_VERSION: &str = "2.24.0";
+
+/// Diatonic steps in LilyPond's order, `c` first.
+const STEPS: [char; 7] = ['C', 'D', 'E', 'F', 'G', 'A', 'B'];
+
+/// The pitch `\relative` blocks start from, `c'`, as a diatonic step number.
+const RELATIVE_START: i32 = 4 * 7;
+
+/// Modes LilyPond's `\key` command knows.
+const MODES: [&str; 9] = [
+    "major",
+    "minor",
+    "ionian",
+    "dorian",
+    "phrygian",
+    "lydian",
+    "mixolydian",
+    "aeolian",
+    "locrian",
+];
+
+/// Chord-mode modifiers for common chord-tone sets, keyed by the explicit
+/// step list they abbreviate.
+const CHORD_MODIFIERS: [(&str, &str); 18] = [
+    ("3.5", ""),
+    ("3-.5", "m"),
+    ("3-.5-", "dim"),
+    ("3.5+", "aug"),
+    ("2.5", "sus2"),
+    ("4.5", "sus4"),
+    ("3.5.6", "6"),
+    ("3-.5.6", "m6"),
+    ("3.5.7", "7"),
+    ("3.5.7+", "maj7"),
+    ("3-.5.7", "m7"),
+    ("3-.5.7+", "m7+"),
+    ("3-.5-.7", "m7.5-"),
+    ("3-.5-.7-", "dim7"),
+    ("3.5+.7", "aug7"),
+    ("3.5.7.9", "9"),
+    ("3.5.7+.9", "maj9"),
+    ("3-.5.7.9", "m9"),
+];
+
+/// Settings for the LilyPond writers.
+#[derive(Clone, Debug)]
+pub struct LilyPondOptions {
+    relative: bool,
+    time_signature: TimeSignature,
+    key: Option<Key>,
+    title: Option<String>,
+    clef: Option<Clef>,
+}
+
+impl Default for LilyPondOptions {
+    /// Absolute pitches in `4/4`, with no key, title or clef written.
+    fn default() -> Self {
+        Self {
+            relative: false,
+            time_signature: TimeSignature::common(),
+            key: None,
+            title: None,
+            clef: None,
+        }
+    }
+}
+
+impl LilyPondOptions {
+    /// Returns options writing pitches inside `\relative c'` blocks rather
+    /// than with absolute octave marks.
+    pub fn with_relative_pitches(mut self, relative: bool) -> Self {
+        self.relative = relative;
+        self
+    }
+
+    /// Returns options spelling rhythms in, and writing, `time_signature`.
+    pub fn with_time_signature(mut self, time_signature: TimeSignature) -> Self {
+        self.time_signature = time_signature;
+        self
+    }
+
+    /// Returns options writing a `\key` command for `key`.
+    pub fn with_key(mut self, key: Key) -> Self {
+        self.key = Some(key);
+        self
+    }
+
+    /// Returns options writing `title` in the header.
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Returns options writing `clef` on a single staff. Instrument parts
+    /// always take their instrument's clef.
+    pub fn with_clef(mut self, clef: Clef) -> Self {
+        self.clef = Some(clef);
+        self
+    }
+
+    /// Returns whether pitches are written relative.
+    pub fn uses_relative_pitches(&self) -> bool {
+        self.relative
+    }
+
+    /// Returns the time signature.
+    pub fn time_signature(&self) -> &TimeSignature {
+        &self.time_signature
+    }
+
+    /// Returns the key, if one is written.
+    pub fn key(&self) -> Option<&Key> {
+        self.key.as_ref()
+    }
+
+    /// Returns the title, if one is written.
+    pub fn title(&self) -> Option<&str> {
+        self.title.as_deref()
+    }
+
+    /// Returns the clef of a single staff, if one is written.
+    pub fn clef(&self) -> Option<Clef> {
+        self.clef
+    }
+}
+
+/// Returns the LilyPond name of a pitch with absolute octave marks, such as
+/// `fis''` for F#5 or `beh,` for B half-flat 2.
+///
+/// Octave-less pitches are written in octave 4, matching the crate's default
+/// pitch-space behavior. Alterations are rounded to the nearest quarter tone,
+/// the finest LilyPond names; beyond a double sharp or flat they are an error.
+pub fn lilypond_pitch(pitch: &Pitch) -> Result<String> {
+    let marks = pitch.octave().unwrap_or(4) - 3;
+    Ok(format!("{}{}", note_name(pitch)?, octave_marks(marks)))
+}
+
+/// Returns the LilyPond duration of a note value, such as `4.` for a dotted
+/// quarter or `\breve`.
+///
+/// Tuplets are not written here; see [`stream_to_lilypond`]. Complex and
+/// zero durations are an error, so spell the rhythm first.
+pub fn lilypond_duration(duration: &Duration) -> Result<String> {
+    let value = match duration.duration_type() {
+        Some(DurationType::Maxima) => "\\maxima".to_string(),
+        Some(DurationType::Longa) => "\\longa".to_string(),
+        Some(DurationType::Breve) => "\\breve".to_string(),
+        Some(DurationType::Zero) | Some(DurationType::DuplexMaxima) | None => {
+            return Err(Error::Notation(format!(
+                "cannot write a duration of {} quarters in LilyPond",
+                duration.quarter_length()
+            )));
+        }
+        Some(duration_type) => ((4.0 / duration_type.quarter_length()) as u32).to_string(),
+    };
+    Ok(format!("{value}{}", ".".repeat(duration.dots() as usize)))
+}
+
+/// Returns a chord symbol in LilyPond's `\chordmode`, lasting `duration`,
+/// such as `d2:m7` or `c1:maj7/e`.
+///
+/// Common chords take LilyPond's modifiers; any other set of chord tones is
+/// listed step by step from the root, as `c4:1.3.5.7.9+` for `C7#9`.
+pub fn lilypond_chord_symbol(symbol: &ChordSymbol, duration: &Duration) -> Result<String> {
+    let root = symbol.root();
+    let steps = symbol
+        .chord_degrees()?
+        .into_iter()
+        .filter(|(degree, _)| *degree != 1)
+        .map(|(degree, pitch)| {
+            let default = match degree {
+                2 | 9 => 2,
+                3 => 4,
+                4 | 11 => 5,
+                5 => 7,
+                6 | 13 => 9,
+                7 => 10,
+                _ => 0,
+            };
+            let semitones = (pitch.ps() - root.ps()).round() as i32;
+            let alteration = (semitones - default + 6).rem_euclid(12) - 6;
+            let sign = if alteration < 0 { "-" } else { "+" };
+            format!(
+                "{degree}{}",
+                sign.repeat(alteration.unsigned_abs() as usize)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(".");
+    let modifier = CHORD_MODIFIERS
+        .iter()
+        .find(|(explicit, _)| *explicit == steps)
+        .map(|(_, modifier)| modifier.to_string())
+        .unwrap_or_else(|| {
+            if steps.is_empty() {
+                "1".to_string()
+            } else {
+                format!("1.{steps}")
+            }
+        });
+    let mut token = format!("{}{}", note_name(root)?, lilypond_duration(duration)?);
+    if !modifier.is_empty() {
+        token.push(':');
+        token.push_str(&modifier);
+    }
+    if let Some(bass) = symbol.bass() {
+        token.push('/');
+        token.push_str(&note_name(bass)?);
+    }
+    Ok(token)
+}
+
+/// Writes `stream` as a LilyPond file with one staff.
+///
+/// The rhythm is spelled in the options' time signature, with bar checks
+/// after every measure and beams written out so they follow the meter. The
+/// stream must be a single voice; see [`spell_rhythm`].
+pub fn stream_to_lilypond(stream: &Stream, options: &LilyPondOptions) -> Result<String> {
+    let mut ly = preamble(options.title());
+    ly.push_str("\\score {\n");
+    ly.push_str(&staff(stream, options, options.clef(), None)?);
+    ly.push_str("  \\layout { }\n}\n");
+    Ok(ly)
+}
+
+/// Writes one staff per part, grouped, as a LilyPond file.
+///
+/// Each staff is labelled with its instrument's name and written in its
+/// clef. Pitches are written as they sound; for a transposing score, pass
+/// parts already at written pitch, as [`Stream::to_written_pitch`] gives.
+pub fn parts_to_lilypond(
+    parts: &[(&Stream, &Instrument)],
+    options: &LilyPondOptions,
+) -> Result<String> {
+    let mut ly = preamble(options.title());
+    ly.push_str("\\score {\n  \\new StaffGroup <<\n");
+    for (stream, instrument) in parts {
+        let staff = staff(
+            stream,
+            options,
+            Some(instrument.clef()),
+            Some(instrument.name()),
+        )?;
+        for line in staff.lines() {
+            ly.push_str("  ");
+            ly.push_str(line);
+            ly.push('\n');
+        }
+    }
+    ly.push_str("  >>\n  \\layout { }\n}\n");
+    Ok(ly)
+}
+
+impl Stream {
+    /// Writes the stream as a LilyPond file with one staff.
+    ///
+    /// See [`stream_to_lilypond`].
+    pub fn to_lilypond(&self, options: &LilyPondOptions) -> Result<String> {
+        stream_to_lilypond(self, options)
+    }
+}
+
+impl LeadSheet {
+    /// Writes the lead sheet as a LilyPond file: the changes in a
+    /// `ChordNames` context over the melody.
+    ///
+    /// A change is only named where it changes, even when it is held across
+    /// several note values.
+    pub fn to_lilypond(&self) -> Result<String> {
+        let mut options = LilyPondOptions::default()
+            .with_time_signature(self.time_signature().clone())
+            .with_key(self.key().clone());
+        if let Some(title) = self.title() {
+            options = options.with_title(title);
+        }
+
+        let mut chords = Vec::new();
+        let mut cursor = 0.0;
+        for (start, end, symbol) in self.chord_spans() {
+            if start > cursor {
+                for piece in pieces(start - cursor)? {
+                    chords.push(format!("s{}", lilypond_duration(&piece)?));
+                }
+            }
+            for piece in pieces(end - start)? {
+                chords.push(lilypond_chord_symbol(symbol, &piece)?);
+            }
+            cursor = end;
+        }
+
+        let mut ly = preamble(options.title());
+        ly.push_str("\\score {\n  <<\n");
+        ly.push_str("    \\new ChordNames \\chordmode {\n");
+        ly.push_str("      \\set chordChanges = ##t\n");
+        ly.push_str(&format!("      {}\n", chords.join(" ")));
+        ly.push_str("    }\n");
+        for line in staff(self.melody(), &options, None, None)?.lines() {
+            ly.push_str("  ");
+            ly.push_str(line);
+            ly.push('\n');
+        }
+        ly.push_str("  >>\n  \\layout { }\n}\n");
+        Ok(ly)
+    }
+}
+
+/// The version statement and, with a title, the header.
+fn preamble(title: Option<&str>) -> String {
+    let mut ly = format!("\\version \"{LILYPOND_VERSION}\"\n\n");
+    if let Some(title) = title {
+        ly.push_str(&format!("\\header {{\n  title = {}\n}}\n\n", quoted(title)));
+    }
+    ly
+}
+
+/// One `\new Staff` block, indented for a `\score`.
+fn staff(
+    stream: &Stream,
+    options: &LilyPondOptions,
+    clef: Option<Clef>,
+    instrument_name: Option<&str>,
+) -> Result<String> {
+    let mut ly = String::from("  \\new Staff ");
+    if let Some(name) = instrument_name {
+        ly.push_str(&format!("\\with {{ instrumentName = {} }} ", quoted(name)));
+    }
+    if options.uses_relative_pitches() {
+        ly.push_str("\\relative c' ");
+    }
+    ly.push_str("{\n");
+    if let Some(clef) = clef {
+        ly.push_str(&format!("    \\clef \"{}\"\n", clef_name(clef)));
+    }
+    if let Some(key) = options.key() {
+        ly.push_str(&format!("    {}\n", key_command(key)?));
+    }
+    ly.push_str(&format!("    {}\n", time_command(options.time_signature())));
+    ly.push_str("    \\autoBeamOff\n");
+    let measures = spell_rhythm(stream, options.time_signature())?;
+    for line in music(&measures, options.uses_relative_pitches())? {
+        ly.push_str("    ");
+        ly.push_str(&line);
+        ly.push('\n');
+    }
+    ly.push_str("  }\n");
+    Ok(ly)
+}
+
+/// One line of music per measure, each ending in a bar check.
+fn music(measures: &[SpelledMeasure], relative: bool) -> Result<Vec<String>> {
+    let mut reference = RELATIVE_START;
+    let pitch_token = |pitch: &Pitch, reference: &mut i32| -> Result<String> {
+        if !relative {
+            return lilypond_pitch(pitch);
+        }
+        let position = diatonic_position(pitch)?;
+        let nearest =
+            *reference + ((position.rem_euclid(7) - reference.rem_euclid(7) + 3).rem_euclid(7) - 3);
+        *reference = position;
+        Ok(format!(
+            "{}{}",
+            note_name(pitch)?,
+            octave_marks((position - nearest) / 7)
+        ))
+    };
+
+    let mut lines = Vec::new();
+    for measure in measures {
+        let mut tokens = Vec::new();
+        for spelled in measure.elements() {
+            let mut token = String::new();
+            if let Some(bracket) = spelled.tuplet().filter(|bracket| bracket.starts()) {
+                let tuplet = bracket.tuplet();
+                token.push_str(&format!(
+                    "\\tuplet {}/{} {{ ",
+                    tuplet.actual(),
+                    tuplet.normal()
+                ));
+            }
+            let element = spelled.element();
+            let duration = lilypond_duration(&element.duration().cloned().unwrap_or_default())?;
+            let tie = match element {
+                StreamElement::Note(note) => {
+                    token.push_str(&pitch_token(note.pitch(), &mut reference)?);
+                    note.tie()
+                }
+                StreamElement::Chord(chord) => {
+                    let mut inner = reference;
+                    let mut pitches = Vec::new();
+                    for (index, pitch) in chord.pitches().iter().enumerate() {
+                        pitches.push(pitch_token(pitch, &mut inner)?);
+                        if index == 0 {
+                            reference = inner;
+                        }
+                    }
+                    token.push_str(&format!("<{}>", pitches.join(" ")));
+                    chord.notes().first().and_then(|note| note.tie())
+                }
+                StreamElement::Rest(_) => {
+                    token.push('r');
+                    None
+                }
+            };
+            token.push_str(&duration);
+            if tie.is_some_and(Tie::holds_into_next) {
+                token.push('~');
+            }
+            match spelled.beams().first() {
+                Some(Beam::Start) => token.push('['),
+                Some(Beam::Stop) => token.push(']'),
+                _ => {}
+            }
+            if spelled.tuplet().is_some_and(|bracket| bracket.stops()) {
+                token.push_str(" }");
+            }
+            tokens.push(token);
+        }
+        tokens.push("|".to_string());
+        lines.push(tokens.join(" "));
+    }
+    Ok(lines)
+}
+
+/// The note name without octave, such as `bes` or `cih`.
+fn note_name(pitch: &Pitch) -> Result<String> {
+    let step = pitch.step().as_char().to_ascii_lowercase();
+    let suffix = match (pitch.alter() * 2.0).round() as i32 {
+        -4 => "eses",
+        -3 => "eseh",
+        -2 => "es",
+        -1 => "eh",
+        0 => "",
+        1 => "ih",
+        2 => "is",
+        3 => "isih",
+        4 => "isis",
+        _ => {
+            return Err(Error::Notation(format!(
+                "cannot write an alteration of {} semitones in LilyPond",
+                pitch.alter()
+            )));
+        }
+    };
+    Ok(format!("{step}{suffix}"))
+}
+
+/// The pitch's diatonic step counted from C0.
+fn diatonic_position(pitch: &Pitch) -> Result<i32> {
+    let step = pitch.step().as_char();
+    let index = STEPS
+        .iter()
+        .position(|candidate| *candidate == step)
+        .ok_or_else(|| Error::Notation(format!("cannot write step {step:?} in LilyPond")))?;
+    Ok(pitch.octave().unwrap_or(4) * 7 + index as i32)
+}
+
+fn octave_marks(octaves: i32) -> String {
+    if octaves >= 0 {
+        "'".repeat(octaves as usize)
+    } else {
+        ",".repeat(octaves.unsigned_abs() as usize)
+    }
+}
+
+fn key_command(key: &Key) -> Result<String> {
+    let mode = key.mode().to_lowercase();
+    if !MODES.contains(&mode.as_str()) {
+        return Err(Error::Notation(format!(
+            "LilyPond has no \\key mode for {mode:?}"
+        )));
+    }
+    Ok(format!("\\key {} \\{mode}", note_name(key.tonic_pitch())?))
+}
+
+/// The `\time` command, or `\compoundMeter` for summed signatures.
+///
+/// Additive numerators are written as LilyPond's beat structure, `2,2,3/8`.
+/// A decimal numerator has no LilyPond spelling, so the whole bar is written
+/// as one ratio instead.
+fn time_command(time_signature: &TimeSignature) -> String {
+    let terms = time_signature
+        .terms()
+        .iter()
+        .map(|term| {
+            let ratio = term.ratio_string();
+            let (numerators, denominator) = ratio
+                .split_once('/')
+                .expect("a time signature term is `numerators/denominator`");
+            (
+                numerators
+                    .split('+')
+                    .map(str::to_string)
+                    .collect::<Vec<_>>(),
+                denominator.to_string(),
+            )
+        })
+        .collect::<Vec<_>>();
+    if time_signature.is_fractional() {
+        return format!(
+            "\\time {}/{}",
+            time_signature.numerator(),
+            time_signature.denominator()
+        );
+    }
+    match terms.as_slice() {
+        [(numerators, denominator)] => format!("\\time {}/{denominator}", numerators.join(",")),
+        _ => {
+            let terms = terms
+                .iter()
+                .map(|(numerators, denominator)| {
+                    format!("({} {denominator})", numerators.join(" "))
+                })
+                .collect::<Vec<_>>();
+            format!("\\compoundMeter #'({})", terms.join(" "))
+        }
+    }
+}
+
+fn clef_name(clef: Clef) -> &'static str {
+    match clef {
+        Clef::Treble => "treble",
+        Clef::TrebleOctaveDown => "treble_8",
+        Clef::Alto => "alto",
+        Clef::Tenor => "tenor",
+        Clef::Bass => "bass",
+    }
+}
+
+/// A LilyPond string literal.
+fn quoted(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// The note values that make up `quarter_length`, longest first.
+fn pieces(quarter_length: FloatType) -> Result<Vec<Duration>> {
+    Ok(Duration::from_fraction(fraction_from_float(quarter_length))?.components())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{chord::Chord, note::Note, rest::Rest};
+
+    fn note(name: &str, quarter_length: FloatType) -> Note {
+        Note::from_name(name)
+            .unwrap()
+            .with_duration(Duration::new(quarter_length).unwrap())
+    }
+
+    #[test]
+    fn writes_pitches_with_quarter_tones() {
+        let ly = |name: &str| lilypond_pitch(&Pitch::from_name(name).unwrap()).unwrap();
+        assert_eq!(ly("C4"), "c'");
+        assert_eq!(ly("F#5"), "fis''");
+        assert_eq!(ly("B-2"), "bes,");
+        assert_eq!(ly("E~3"), "eih");
+        assert_eq!(ly("A`4"), "aeh'");
+        assert_eq!(ly("C#~4"), "cisih'");
+        assert_eq!(ly("D--4"), "deses'");
+        assert!(lilypond_pitch(&Pitch::from_name("C###4").unwrap()).is_err());
+
+        assert_eq!(
+            lilypond_duration(&Duration::new(1.5).unwrap()).unwrap(),
+            "4."
+        );
+        assert_eq!(
+            lilypond_duration(&Duration::new(8.0).unwrap()).unwrap(),
+            "\\breve"
+        );
+        assert!(lilypond_duration(&Duration::new(2.5).unwrap()).is_err());
+    }
+
+    #[test]
+    fn writes_chord_symbols_in_chord_mode() {
+        let ly = |figure: &str| {
+            lilypond_chord_symbol(&ChordSymbol::parse(figure).unwrap(), &Duration::half()).unwrap()
+        };
+        assert_eq!(ly("C"), "c2");
+        assert_eq!(ly("Dm7"), "d2:m7");
+        assert_eq!(ly("Bbmaj7"), "bes2:maj7");
+        assert_eq!(ly("Bm7b5"), "b2:m7.5-");
+        assert_eq!(ly("C/E"), "c2/e");
+        assert_eq!(ly("C7#9"), "c2:1.3.5.7.9+");
+    }
+
+    #[test]
+    fn writes_a_spelled_staff() {
+        let mut stream = Stream::new();
+        stream.push(note("C4", 1.5));
+        stream.push(note("D4", 0.5));
+        stream.push(note("E4", 0.5));
+        stream.push(note("F4", 0.5));
+        stream.push(
+            Chord::new("G4 B4")
+                .unwrap()
+                .with_duration(Duration::new(2.0).unwrap()),
+        );
+        stream.push(Rest::from_quarter_length(1.0).unwrap());
+        stream.push(note("A5", 2.0));
+        let options = LilyPondOptions::default()
+            .with_key(Key::from_tonic_mode("G", "major").unwrap())
+            .with_title("Étude \"1\"")
+            .with_clef(Clef::Treble);
+        let ly = stream.to_lilypond(&options).unwrap();
+        assert!(ly.starts_with("\\version \"2.24.0\"\n"));
+        assert!(ly.contains("title = \"Étude \\\"1\\\"\""));
+        assert!(ly.contains("\\clef \"treble\"\n    \\key g \\major\n    \\time 4/4\n"));
+        assert!(ly.contains("    c'4. d'8 e'8[ f'8] <g' b'>4~ |\n    <g' b'>4 r4 a''2 |\n"));
+
+        let relative = stream
+            .to_lilypond(&LilyPondOptions::default().with_relative_pitches(true))
+            .unwrap();
+        assert!(relative.contains("\\new Staff \\relative c' {"));
+        assert!(relative.contains("    c4. d8 e8[ f8] <g b>4~ |\n    <g b>4 r4 a'2 |\n"));
+    }
+
+    #[test]
+    fn writes_tuplets_and_meters() {
+        let triplet = Duration::eighth().with_tuplet(crate::Tuplet::triplet());
+        let mut stream = Stream::new();
+        for name in ["C4", "D4", "E4"] {
+            stream.push(
+                Note::from_name(name)
+                    .unwrap()
+                    .with_duration(triplet.clone()),
+            );
+        }
+        stream.push(note("F4", 2.5));
+        let aksak = TimeSignature::from_ratio_string("2+2+3/8").unwrap();
+        let ly = stream
+            .to_lilypond(&LilyPondOptions::default().with_time_signature(aksak))
+            .unwrap();
+        assert!(ly.contains("\\time 2,2,3/8"));
+        assert!(ly.contains("\\tuplet 3/2 { c'8[ d'8 e'8] } f'4~ f'4. |"));
+
+        let summed = TimeSignature::from_ratio_string("3/4+2/8").unwrap();
+        assert_eq!(time_command(&summed), "\\compoundMeter #'((3 4) (2 8))");
+    }
+
+    #[test]
+    fn brackets_whole_beats_in_five_six() {
+        let staff = |lengths: &[FloatType]| {
+            let mut stream = Stream::new();
+            for (name, quarter_length) in ["C4", "D4", "E4", "F4", "G4", "A4", "B4"]
+                .into_iter()
+                .zip(lengths)
+            {
+                stream.push(note(name, *quarter_length));
+            }
+            let five_six = TimeSignature::from_ratio_string("5/6").unwrap();
+            let ly = stream
+                .to_lilypond(&LilyPondOptions::default().with_time_signature(five_six))
+                .unwrap();
+            ly.lines()
+                .find(|line| line.ends_with('|'))
+                .unwrap()
+                .trim()
+                .to_string()
+        };
+
+        // The last beat holds the end of a plain quarter and a triplet
+        // eighth, so the quarter is written as a triplet dotted quarter
+        // rather than leaving the eighth alone in its bracket.
+        assert_eq!(
+            staff(&[1.0, 1.0, 1.0, 1.0 / 3.0]),
+            "c'4 \\tuplet 3/2 { d'8~ d'4 } \\tuplet 3/2 { e'4. f'8 } |"
+        );
+        // Eighths cut at the beats of 5/6 are bracketed whole, and no beam
+        // runs across a bracket's edge.
+        assert_eq!(
+            staff(&[0.5, 0.5, 0.5, 0.5, 1.0 / 3.0, 1.0 / 3.0, 2.0 / 3.0]),
+            "c'8 \\tuplet 3/2 { d'16~ d'8 } \\tuplet 3/2 { e'8~ e'16 } f'8 \\tuplet 3/2 { g'8[ a'8] b'4 } |"
+        );
+    }
+
+    #[test]
+    fn writes_parts_and_lead_sheets() {
+        let mut melody = Stream::new();
+        melody.push(note("E4", 4.0));
+        let cello = Instrument::from_name("cello").unwrap();
+        let violin = Instrument::from_name("violin").unwrap();
+        let ly = parts_to_lilypond(
+            &[(&melody, &violin), (&melody, &cello)],
+            &Default::default(),
+        )
+        .unwrap();
+        assert!(ly.contains("\\new StaffGroup <<"));
+        assert!(ly.contains("\\new Staff \\with { instrumentName = \"Violin\" } {"));
+        assert!(ly.contains("\\clef \"bass\""));
+
+        let mut sheet = LeadSheet::new(Key::from_tonic("C").unwrap(), TimeSignature::common())
+            .with_melody(melody);
+        sheet
+            .insert_chord(0.0, ChordSymbol::parse("Am7").unwrap(), None)
+            .unwrap();
+        sheet
+            .insert_chord(2.0, ChordSymbol::parse("D7").unwrap(), None)
+            .unwrap();
+        let ly = sheet.to_lilypond().unwrap();
+        assert!(ly.contains("\\new ChordNames \\chordmode {"));
+        assert!(ly.contains("      a2:m7 d2:7\n"));
+        assert!(ly.contains("\\key c \\major"));
+    }
+}
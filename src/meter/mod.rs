@@ -0,0 +1,1030 @@
+//! Time signatures, ported from music21's `meter` package.
+//!
+//! [`TimeSignature`] covers the part of music21's meter handling that is a pure
+//! function of the written signature: how long a bar is, how many beats it
+//! carries, how long each beat is, and how that beat subdivides. Signatures may
+//! be plain (`6/8`), additive (`2+2+3/8`), summed (`3/4+2/8`), fractional
+//! (`2.5/4`) or irrational (`4/3`).
+//!
+//! music21 derives all of this from a `MeterSequence` partition tree. The beat
+//! count and division are still computed directly from the ratio — the
+//! partition rule is music21's `_setDefaultBeatPartitions`, verified against
+//! upstream by the `meter_parity` fixture — but the tree itself is available as
+//! [`MeterSequence`], and each time signature builds the trees music21 keeps
+//! alongside it: beats, beam groups, accents and display. The accent tree gives
+//! the metrical weight of any offset through [`TimeSignature::beat_strength`].
+
+pub use sequence::MeterSequence;
+
+/// Meter partition trees.
+pub mod sequence;
+
+use num::integer::gcd;
+
+use crate::defaults::{FloatType, UnsignedIntegerType};
+use crate::duration::Duration;
+use crate::error::{Error, Result};
+
+/// Names music21 gives a partition count, indexed by the count itself.
+///
+/// Index 0 is music21's `Empty`, which a valid time signature never reaches.
+const BEAT_COUNT_NAMES: [&str; 9] = [
+    "Empty",
+    "Single",
+    "Duple",
+    "Triple",
+    "Quadruple",
+    "Quintuple",
+    "Sextuple",
+    "Septuple",
+    "Octuple",
+];
+
+/// How the beat of a meter subdivides.
+///
+/// Mirrors music21's `beatDivisionCountName`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BeatDivision {
+    /// A single-beat meter, which music21 reports as `Other` rather than as a
+    /// division — there is no lower level to divide.
+    Other,
+    /// Beats divide in two.
+    Simple,
+    /// Beats divide in three.
+    Compound,
+}
+
+impl BeatDivision {
+    /// Returns music21's name for this division.
+    pub fn music21_name(self) -> &'static str {
+        match self {
+            Self::Other => "Other",
+            Self::Simple => "Simple",
+            Self::Compound => "Compound",
+        }
+    }
+
+    /// Returns the number of divisions in one beat.
+    ///
+    /// Matches music21's `beatDivisionCount`, which reports `1` rather than
+    /// raising for a single-beat meter.
+    pub fn count(self) -> UnsignedIntegerType {
+        match self {
+            Self::Other => 1,
+            Self::Simple => 2,
+            Self::Compound => 3,
+        }
+    }
+}
+
+/// A time signature, such as `4/4`, `6/8`, `2+2+3/8` or `3/4+2/8`.
+///
+/// Besides a plain numerator over a denominator, a signature may add several
+/// numerators over one denominator (`2+2+3/8`), add whole signatures
+/// (`3/4+2/8`), and use decimal numerators (`2.5/4`). Denominators need not be
+/// powers of two: music21 accepts irrational meters such as `4/3` and `5/6`,
+/// and so does this.
+///
+/// ```
+/// use music21_rs::TimeSignature;
+///
+/// let six_eight = TimeSignature::from_ratio_string("6/8")?;
+/// assert_eq!(six_eight.beat_count(), 2);
+/// assert_eq!(six_eight.beat_quarter_length(), 1.5);
+/// assert_eq!(six_eight.classification(), "Compound Duple");
+///
+/// let aksak = TimeSignature::from_ratio_string("2+2+3/8")?;
+/// assert_eq!(aksak.beat_offsets(), [0.0, 1.0, 2.0]);
+/// assert_eq!(aksak.bar_quarter_length(), 3.5);
+/// # Ok::<(), music21_rs::Error>(())
+/// ```
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TimeSignature {
+    terms: Vec<MeterTerm>,
+}
+
+/// One added term of a time signature: `3/4` in `3/4+2/8`, or the whole of
+/// `2+2+3/8`.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct MeterTerm {
+    numerators: Vec<Numerator>,
+    denominator: UnsignedIntegerType,
+}
+
+/// A numerator as written, kept as its digits and decimal places so that
+/// `2.5` reads back as `2.5`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct Numerator {
+    digits: UnsignedIntegerType,
+    decimal_places: UnsignedIntegerType,
+}
+
+impl Numerator {
+    /// The most decimal places a numerator may be written with.
+    const MAX_DECIMAL_PLACES: UnsignedIntegerType = 4;
+
+    fn whole(value: UnsignedIntegerType) -> Self {
+        Self {
+            digits: value,
+            decimal_places: 0,
+        }
+    }
+
+    fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+        let (whole, fraction) = match text.split_once('.') {
+            Some((_, "")) => return None,
+            Some(parts) => parts,
+            None => (text, ""),
+        };
+        let decimal_places = UnsignedIntegerType::try_from(fraction.len()).ok()?;
+        if whole.is_empty()
+            || decimal_places > Self::MAX_DECIMAL_PLACES
+            || !whole
+                .chars()
+                .chain(fraction.chars())
+                .all(|c| c.is_ascii_digit())
+        {
+            return None;
+        }
+        let digits = format!("{whole}{fraction}").parse().ok()?;
+        (digits > 0).then_some(Self {
+            digits,
+            decimal_places,
+        })
+    }
+
+    fn scale(self) -> UnsignedIntegerType {
+        10_u32.pow(self.decimal_places)
+    }
+
+    fn is_whole(self) -> bool {
+        self.decimal_places == 0
+    }
+
+    /// Returns the span of this numerator over `denominator`, with the
+    /// decimal scale cancelled as far as it goes: `2.5` over `4` is `5/8`.
+    fn span(self, denominator: UnsignedIntegerType) -> Result<MeterSequence> {
+        let common = gcd(self.digits, self.scale());
+        let denominator = denominator
+            .checked_mul(self.scale() / common)
+            .ok_or_else(|| {
+                Error::Meter(format!(
+                    "{self}/{denominator} is too fine a span to measure"
+                ))
+            })?;
+        MeterSequence::new(self.digits / common, denominator)
+    }
+}
+
+impl std::fmt::Display for Numerator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_whole() {
+            return write!(f, "{}", self.digits);
+        }
+        write!(
+            f,
+            "{}.{:0width$}",
+            self.digits / self.scale(),
+            self.digits % self.scale(),
+            width = self.decimal_places as usize
+        )
+    }
+}
+
+impl MeterTerm {
+    fn ratio_string(&self) -> String {
+        let numerators = self
+            .numerators
+            .iter()
+            .map(Numerator::to_string)
+            .collect::<Vec<_>>();
+        format!("{}/{}", numerators.join("+"), self.denominator)
+    }
+
+    /// Returns one span per added numerator.
+    fn addends(&self) -> Result<Vec<MeterSequence>> {
+        self.numerators
+            .iter()
+            .map(|numerator| numerator.span(self.denominator))
+            .collect()
+    }
+
+    /// Returns the beats of an additive or fractional term: one per addend
+    /// for `2+2+3/8`, and for `2.5/4` one per whole unit of the denominator
+    /// followed by the fractional remainder.
+    fn beats(&self) -> Result<Vec<MeterSequence>> {
+        let [numerator] = self.numerators.as_slice() else {
+            return self.addends();
+        };
+        let unit = MeterSequence::new(1, self.denominator)?;
+        let mut beats = vec![unit; (numerator.digits / numerator.scale()) as usize];
+        let remainder = Numerator {
+            digits: numerator.digits % numerator.scale(),
+            ..*numerator
+        };
+        if remainder.digits > 0 {
+            beats.push(remainder.span(self.denominator)?);
+        }
+        Ok(beats)
+    }
+
+    /// Returns whether every beat of the term divides in three.
+    fn is_compound(&self) -> bool {
+        match self.numerators.as_slice() {
+            [numerator] if numerator.is_whole() => {
+                let signature = TimeSignature {
+                    terms: vec![self.clone()],
+                };
+                signature.is_compound()
+                    || (signature.beat_count() == 1 && numerator.digits.is_multiple_of(3))
+            }
+            numerators => numerators
+                .iter()
+                .all(|numerator| numerator.is_whole() && numerator.digits.is_multiple_of(3)),
+        }
+    }
+}
+
+impl Default for TimeSignature {
+    /// Returns `4/4`, matching music21's default `TimeSignature()`.
+    fn default() -> Self {
+        Self::common()
+    }
+}
+
+impl TimeSignature {
+    /// Creates a time signature from a numerator and denominator.
+    ///
+    /// Both must be non-zero. The denominator need not be a power of two.
+    pub fn new(numerator: UnsignedIntegerType, denominator: UnsignedIntegerType) -> Result<Self> {
+        if numerator == 0 {
+            return Err(Error::Meter(
+                "time signature numerator must be non-zero".to_string(),
+            ));
+        }
+        Self::additive(&[numerator], denominator)
+    }
+
+    /// Creates a time signature adding `numerators` over one denominator,
+    /// such as `2+2+3/8`.
+    ///
+    /// Each numerator is a beat of its own.
+    pub fn additive(
+        numerators: &[UnsignedIntegerType],
+        denominator: UnsignedIntegerType,
+    ) -> Result<Self> {
+        if numerators.is_empty() || numerators.contains(&0) {
+            return Err(Error::Meter(format!(
+                "time signature numerators {numerators:?} must be non-empty and non-zero"
+            )));
+        }
+        if denominator == 0 {
+            return Err(Error::Meter(
+                "time signature denominator must be non-zero".to_string(),
+            ));
+        }
+        Self {
+            terms: vec![MeterTerm {
+                numerators: numerators.iter().copied().map(Numerator::whole).collect(),
+                denominator,
+            }],
+        }
+        .checked()
+    }
+
+    /// Creates a time signature summing `signatures`, such as `3/4+2/8`.
+    ///
+    /// The bar is the sum of the signatures' bars, and each keeps its own
+    /// beats.
+    pub fn from_terms(signatures: &[TimeSignature]) -> Result<Self> {
+        if signatures.is_empty() {
+            return Err(Error::Meter(
+                "a summed time signature needs at least one term".to_string(),
+            ));
+        }
+        Self {
+            terms: signatures
+                .iter()
+                .flat_map(|signature| signature.terms.iter().cloned())
+                .collect(),
+        }
+        .checked()
+    }
+
+    /// Parses a ratio string such as `"6/8"`, `"2+2+3/8"`, `"3/4+2/8"` or
+    /// `"2.5/4"`.
+    ///
+    /// Numerators run up to the next denominator, so `"3+2/8+3/4"` is
+    /// `3+2/8` plus `3/4`. A decimal numerator needs digits on both sides of
+    /// its point, so every string this accepts reads back unchanged from
+    /// [`TimeSignature::ratio_string`], except for surrounding whitespace and
+    /// leading zeros.
+    pub fn from_ratio_string(ratio: &str) -> Result<Self> {
+        let mut terms = Vec::new();
+        let mut numerators = Vec::new();
+        for text in ratio.split('+') {
+            let (numerator, denominator) = match text.split_once('/') {
+                Some((numerator, denominator)) => (numerator, Some(denominator)),
+                None => (text, None),
+            };
+            numerators.push(Numerator::parse(numerator).ok_or_else(|| {
+                Error::Meter(format!(
+                    "cannot read a numerator from {numerator:?} in {ratio:?}"
+                ))
+            })?);
+            if let Some(denominator) = denominator {
+                let denominator = denominator
+                    .trim()
+                    .parse::<UnsignedIntegerType>()
+                    .ok()
+                    .filter(|denominator| *denominator > 0)
+                    .ok_or_else(|| {
+                        Error::Meter(format!(
+                            "cannot read a denominator from {denominator:?} in {ratio:?}"
+                        ))
+                    })?;
+                terms.push(MeterTerm {
+                    numerators: std::mem::take(&mut numerators),
+                    denominator,
+                });
+            }
+        }
+        if !numerators.is_empty() {
+            return Err(Error::Meter(format!(
+                "time signature {ratio:?} is not `numerator/denominator`"
+            )));
+        }
+        Self { terms }.checked()
+    }
+
+    /// Returns common time, `4/4`.
+    pub fn common() -> Self {
+        Self::new(4, 4).expect("4/4 is a valid time signature")
+    }
+
+    /// Returns cut time, `2/2`.
+    pub fn cut() -> Self {
+        Self::new(2, 2).expect("2/2 is a valid time signature")
+    }
+
+    /// Returns the numerator of the whole bar as one ratio.
+    ///
+    /// For `2+2+3/8` this is `7`. Summed terms are brought over their least
+    /// common denominator, so `3/4+2/8` gives `8`, and decimal numerators
+    /// are cleared, so `2.5/4` gives `5`.
+    pub fn numerator(&self) -> UnsignedIntegerType {
+        self.bar_sequence().numerator()
+    }
+
+    /// Returns the denominator of the whole bar as one ratio.
+    ///
+    /// See [`TimeSignature::numerator`]: `3/4+2/8` gives `8`, and `2.5/4`
+    /// gives `8`.
+    pub fn denominator(&self) -> UnsignedIntegerType {
+        self.bar_sequence().denominator()
+    }
+
+    /// Returns the signature as written, such as `"6/8"` or `"3+2+2/8"`.
+    pub fn ratio_string(&self) -> String {
+        self.terms
+            .iter()
+            .map(MeterTerm::ratio_string)
+            .collect::<Vec<_>>()
+            .join("+")
+    }
+
+    /// Returns the signature split at each `+` between whole signatures:
+    /// `3/4` and `2/8` for `3/4+2/8`, and just itself otherwise.
+    pub fn terms(&self) -> Vec<TimeSignature> {
+        self.terms
+            .iter()
+            .map(|term| Self {
+                terms: vec![term.clone()],
+            })
+            .collect()
+    }
+
+    /// Returns `true` when the signature adds numerators or signatures, as
+    /// `2+2+3/8` and `3/4+2/8` do.
+    pub fn is_additive(&self) -> bool {
+        self.terms.len() > 1 || self.terms[0].numerators.len() > 1
+    }
+
+    /// Returns `true` when a numerator is written with decimals, as in
+    /// `2.5/4`.
+    pub fn is_fractional(&self) -> bool {
+        self.terms
+            .iter()
+            .flat_map(|term| &term.numerators)
+            .any(|numerator| !numerator.is_whole())
+    }
+
+    /// Returns the length of one bar in quarter lengths.
+    pub fn bar_quarter_length(&self) -> FloatType {
+        self.bar_sequence().quarter_length()
+    }
+
+    /// Returns the length of one bar as a [`Duration`].
+    pub fn bar_duration(&self) -> Duration {
+        Duration::new(self.bar_quarter_length())
+            .expect("a non-zero numerator and denominator give a positive finite bar length")
+    }
+
+    /// Returns how many beats one bar carries.
+    ///
+    /// For a plain signature this is music21's `beatCount`, which follows the
+    /// numerator rather than the denominator — except at `3`, where `3/4` is
+    /// three beats but `3/8` is one. Additive numerators are a beat each,
+    /// summed signatures bring their own beats, and `2.5/4` has a short third
+    /// beat.
+    pub fn beat_count(&self) -> UnsignedIntegerType {
+        let Some((numerator, denominator)) = self.plain() else {
+            return UnsignedIntegerType::try_from(self.beat_sequence().parts().len().max(1))
+                .expect("a bar has fewer beats than u32::MAX");
+        };
+        match numerator {
+            1 => 1,
+            2 => 2,
+            // music21 treats 3 as a single beat once the denominator is short
+            // enough that the bar reads as one compound unit.
+            3 if denominator > 4 => 1,
+            3 => 3,
+            4 => 4,
+            6 => 2,
+            9 => 3,
+            12 => 4,
+            numerator if numerator >= 15 && numerator.is_multiple_of(3) => numerator / 3,
+            numerator => numerator,
+        }
+    }
+
+    /// Returns music21's name for the beat count, such as `"Duple"`.
+    ///
+    /// Counts above eight are spelled as `"<n>-uple"`, as music21 does.
+    pub fn beat_count_name(&self) -> String {
+        let count = self.beat_count();
+        BEAT_COUNT_NAMES
+            .get(count as usize)
+            .map_or_else(|| format!("{count}-uple"), |name| (*name).to_string())
+    }
+
+    /// Returns the mean length of a beat in quarter lengths.
+    ///
+    /// Plain signatures have equal beats, so this is the length of every beat
+    /// and, unlike music21's `beatDuration`, never fails. For unequal beats,
+    /// as in `2+2+3/8`, see [`TimeSignature::beat_quarter_lengths`].
+    pub fn beat_quarter_length(&self) -> FloatType {
+        self.bar_quarter_length() / FloatType::from(self.beat_count())
+    }
+
+    /// Returns the length of each beat in quarter lengths: `[1.0, 1.0, 1.5]`
+    /// for `2+2+3/8`.
+    pub fn beat_quarter_lengths(&self) -> Vec<FloatType> {
+        let beats = self.beat_sequence();
+        if beats.is_leaf() {
+            return vec![beats.quarter_length()];
+        }
+        beats
+            .parts()
+            .iter()
+            .map(MeterSequence::quarter_length)
+            .collect()
+    }
+
+    /// Returns the mean length of a beat as a [`Duration`].
+    pub fn beat_duration(&self) -> Duration {
+        Duration::new(self.beat_quarter_length())
+            .expect("a positive bar length divided by a positive beat count stays positive")
+    }
+
+    /// Returns how the beat subdivides.
+    ///
+    /// An additive or summed signature is compound only when every one of its
+    /// beats divides in three, as in `3+3/8`.
+    pub fn beat_division(&self) -> BeatDivision {
+        if self.beat_count() == 1 {
+            return BeatDivision::Other;
+        }
+        let compound = match self.plain() {
+            Some((numerator, _)) => {
+                matches!(numerator, 6 | 9 | 12) || (numerator >= 15 && numerator.is_multiple_of(3))
+            }
+            None => self.terms.iter().all(MeterTerm::is_compound),
+        };
+        if compound {
+            BeatDivision::Compound
+        } else {
+            BeatDivision::Simple
+        }
+    }
+
+    /// Returns the number of divisions in one beat.
+    pub fn beat_division_count(&self) -> UnsignedIntegerType {
+        self.beat_division().count()
+    }
+
+    /// Returns `true` when beats divide in three.
+    pub fn is_compound(&self) -> bool {
+        self.beat_division() == BeatDivision::Compound
+    }
+
+    /// Returns music21's `classification`, such as `"Compound Duple"`.
+    pub fn classification(&self) -> String {
+        format!(
+            "{} {}",
+            self.beat_division().music21_name(),
+            self.beat_count_name()
+        )
+    }
+
+    /// Returns the quarter-length offset of each beat within one bar.
+    pub fn beat_offsets(&self) -> Vec<FloatType> {
+        self.beat_sequence().offsets()
+    }
+
+    /// Returns the one-based beat containing `offset` quarter lengths into a bar.
+    ///
+    /// Matches music21's `getBeat`. Offsets at or beyond the end of the bar are
+    /// rejected rather than wrapping.
+    pub fn beat_at_offset(&self, offset: FloatType) -> Result<UnsignedIntegerType> {
+        if !offset.is_finite() || offset < 0.0 || offset >= self.bar_quarter_length() {
+            return Err(Error::Meter(format!(
+                "offset {offset} is outside a {} bar of {} quarter lengths",
+                self.ratio_string(),
+                self.bar_quarter_length()
+            )));
+        }
+        let started = self
+            .beat_offsets()
+            .into_iter()
+            .filter(|start| *start <= offset)
+            .count();
+        Ok(UnsignedIntegerType::try_from(started).expect("a bar has fewer beats than u32::MAX"))
+    }
+
+    /// Returns the numerator and denominator of a signature that is a single
+    /// whole numerator over a denominator, the only kind music21's default
+    /// beat rules cover.
+    fn plain(&self) -> Option<(UnsignedIntegerType, UnsignedIntegerType)> {
+        match self.terms.as_slice() {
+            [
+                MeterTerm {
+                    numerators,
+                    denominator,
+                },
+            ] => match numerators.as_slice() {
+                [numerator] if numerator.is_whole() => Some((numerator.digits, *denominator)),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+impl TimeSignature {
+    /// Returns the bar partitioned into its beats, music21's `beatSequence`.
+    ///
+    /// A single-beat meter such as `3/8` is one unpartitioned span. Summed
+    /// signatures list the beats of every term in order.
+    pub fn beat_sequence(&self) -> MeterSequence {
+        self.checked_beat_sequence()
+            .expect("a time signature's sequences are checked when it is made")
+    }
+
+    fn checked_beat_sequence(&self) -> Result<MeterSequence> {
+        let beats = match (self.plain(), self.terms.as_slice()) {
+            (Some(_), _) => {
+                let bar = self.checked_bar_sequence()?;
+                if self.beat_count() == 1 {
+                    return Ok(bar);
+                }
+                return bar.partitioned(self.beat_count());
+            }
+            (None, [term]) => term.beats()?,
+            (None, _) => {
+                let mut beats = Vec::new();
+                for term in self.terms() {
+                    beats.extend(top_level(term.checked_beat_sequence()?));
+                }
+                beats
+            }
+        };
+        MeterSequence::from_parts(beats)
+    }
+
+    /// Returns the bar partitioned into its default beam groups, music21's
+    /// `beamSequence`.
+    ///
+    /// Meters on halves and quarters beam by the beat, compound meters by the
+    /// dotted beat, and `3/8` as one group. Other meters on eighths or shorter
+    /// beam in quarter-note groups, the last group taking any remainder too
+    /// short to stand alone: `5/8` beams `2+3`, `7/8` `2+2+3` and `7/16`
+    /// `4+3`. Meters with a denominator that is not a power of two beam by the
+    /// beat, as do additive numerators, and summed signatures beam each term
+    /// on its own.
+    pub fn beam_sequence(&self) -> MeterSequence {
+        self.checked_beam_sequence()
+            .expect("a time signature's sequences are checked when it is made")
+    }
+
+    fn checked_beam_sequence(&self) -> Result<MeterSequence> {
+        let Some((numerator, denominator)) = self.plain() else {
+            let groups = match self.terms.as_slice() {
+                [term] => term.beats()?,
+                _ => {
+                    let mut groups = Vec::new();
+                    for term in self.terms() {
+                        groups.extend(top_level(term.checked_beam_sequence()?));
+                    }
+                    groups
+                }
+            };
+            return MeterSequence::from_parts(groups);
+        };
+        let units_per_quarter = denominator / 4;
+        if denominator < 8
+            || !denominator.is_power_of_two()
+            || self.is_compound()
+            || self.beat_count() == 1
+        {
+            return self.checked_beat_sequence();
+        }
+        let mut groups = vec![units_per_quarter; (numerator / units_per_quarter) as usize];
+        match numerator % units_per_quarter {
+            0 => {}
+            remainder if remainder >= 2 => groups.push(remainder),
+            remainder => match groups.last_mut() {
+                Some(last) => *last += remainder,
+                None => groups.push(remainder),
+            },
+        }
+        self.checked_bar_sequence()?.partitioned_by(&groups)
+    }
+
+    /// Returns the tree [`TimeSignature::beat_strength`] reads, music21's
+    /// `accentSequence`.
+    ///
+    /// Beats sit one level below the bar, except in meters of four or eight
+    /// beats, whose beats pair up first: in `4/4` the third beat outweighs
+    /// the second and fourth. In a summed signature each term sits one level
+    /// below the bar, with its own accents below that.
+    pub fn accent_sequence(&self) -> MeterSequence {
+        self.checked_accent_sequence()
+            .expect("a time signature's sequences are checked when it is made")
+    }
+
+    fn checked_accent_sequence(&self) -> Result<MeterSequence> {
+        if self.plain().is_none() {
+            let accents = match self.terms.as_slice() {
+                [term] => term.beats()?,
+                _ => self
+                    .terms()
+                    .iter()
+                    .map(TimeSignature::checked_accent_sequence)
+                    .collect::<Result<_>>()?,
+            };
+            return MeterSequence::from_parts(accents);
+        }
+        let beats = self.beat_count();
+        if beats < 4 || !beats.is_power_of_two() {
+            return self.checked_beat_sequence();
+        }
+        pair_up(self.checked_bar_sequence()?, beats)
+    }
+
+    /// Returns the bar as the signature is displayed, music21's
+    /// `displaySequence`: one span per written numerator, so `7/8` is one
+    /// span and `2+2+3/8` three.
+    pub fn display_sequence(&self) -> MeterSequence {
+        self.checked_display_sequence()
+            .expect("a time signature's sequences are checked when it is made")
+    }
+
+    fn checked_display_sequence(&self) -> Result<MeterSequence> {
+        let spans = self
+            .terms
+            .iter()
+            .map(|term| MeterSequence::from_parts(term.addends()?))
+            .collect::<Result<_>>()?;
+        MeterSequence::from_parts(spans)
+    }
+
+    /// Returns the metrical weight of `offset` quarter lengths into a bar.
+    ///
+    /// The downbeat weighs 1 and each level of the
+    /// [`accent_sequence`](TimeSignature::accent_sequence) halves the weight,
+    /// as music21's `beatStrength` does: in `4/4` the beats weigh 1, 0.25,
+    /// 0.5 and 0.25 and the eighths between them 0.125. Offsets past the
+    /// bar wrap into the following bars, so offsets from the start of a
+    /// piece can be passed directly. See [`MeterSequence::beat_strength`].
+    pub fn beat_strength(&self, offset: FloatType) -> Result<FloatType> {
+        self.accent_sequence().beat_strength(offset)
+    }
+
+    /// Returns the whole bar as one unpartitioned span.
+    fn bar_sequence(&self) -> MeterSequence {
+        self.checked_bar_sequence()
+            .expect("a time signature's sequences are checked when it is made")
+    }
+
+    fn checked_bar_sequence(&self) -> Result<MeterSequence> {
+        let mut spans = Vec::new();
+        for term in &self.terms {
+            spans.extend(term.addends()?);
+        }
+        let bar = MeterSequence::from_parts(spans)?;
+        MeterSequence::new(bar.numerator(), bar.denominator())
+    }
+
+    /// Returns the signature once every sequence it derives is known to fit
+    /// in the integer type, so the queries that build them cannot overflow.
+    fn checked(self) -> Result<Self> {
+        self.checked_bar_sequence()?;
+        self.checked_beat_sequence()?;
+        self.checked_beam_sequence()?;
+        self.checked_accent_sequence()?;
+        self.checked_display_sequence()?;
+        Ok(self)
+    }
+}
+
+/// Returns the parts of `sequence`, or the sequence itself when it is a leaf.
+fn top_level(sequence: MeterSequence) -> Vec<MeterSequence> {
+    if sequence.is_leaf() {
+        vec![sequence]
+    } else {
+        sequence.parts().to_vec()
+    }
+}
+
+/// Splits `span` in halves, and each half in halves, until it holds `beats`
+/// parts.
+fn pair_up(span: MeterSequence, beats: UnsignedIntegerType) -> Result<MeterSequence> {
+    if beats <= 2 {
+        return span.partitioned(beats);
+    }
+    span.partitioned(2)?
+        .map_parts(|half| pair_up(half.clone(), beats / 2))
+}
+
+impl std::fmt::Display for TimeSignature {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.ratio_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ts(ratio: &str) -> TimeSignature {
+        TimeSignature::from_ratio_string(ratio).expect("valid time signature")
+    }
+
+    #[test]
+    fn common_and_cut_time_match_their_ratios() {
+        assert_eq!(TimeSignature::common().ratio_string(), "4/4");
+        assert_eq!(TimeSignature::cut().ratio_string(), "2/2");
+        assert_eq!(TimeSignature::default(), TimeSignature::common());
+    }
+
+    #[test]
+    fn bar_and_beat_lengths_follow_the_ratio() {
+        assert_eq!(ts("4/4").bar_quarter_length(), 4.0);
+        assert_eq!(ts("5/16").bar_quarter_length(), 1.25);
+        assert_eq!(ts("3/8").bar_quarter_length(), 1.5);
+        assert_eq!(ts("6/8").beat_quarter_length(), 1.5);
+        assert_eq!(ts("4/4").beat_quarter_length(), 1.0);
+        assert_eq!(ts("2/2").beat_duration().quarter_length(), 2.0);
+    }
+
+    #[test]
+    fn compound_meters_beat_in_threes() {
+        for (ratio, beats, division) in [
+            ("6/8", 2, BeatDivision::Compound),
+            ("9/8", 3, BeatDivision::Compound),
+            ("12/8", 4, BeatDivision::Compound),
+            ("15/8", 5, BeatDivision::Compound),
+            ("18/8", 6, BeatDivision::Compound),
+            ("24/8", 8, BeatDivision::Compound),
+        ] {
+            assert_eq!(ts(ratio).beat_count(), beats, "{ratio}");
+            assert_eq!(ts(ratio).beat_division(), division, "{ratio}");
+            assert!(ts(ratio).is_compound(), "{ratio}");
+        }
+    }
+
+    #[test]
+    fn three_is_the_one_denominator_sensitive_numerator() {
+        // 3/2 and 3/4 read as three beats; 3/8 and shorter read as one.
+        assert_eq!(ts("3/2").beat_count(), 3);
+        assert_eq!(ts("3/4").beat_count(), 3);
+        assert_eq!(ts("3/8").beat_count(), 1);
+        assert_eq!(ts("3/16").beat_count(), 1);
+        assert_eq!(ts("3/32").beat_count(), 1);
+        // Every other numerator ignores the denominator entirely.
+        for denominator in [2, 4, 8, 16] {
+            assert_eq!(TimeSignature::new(6, denominator).unwrap().beat_count(), 2);
+            assert_eq!(TimeSignature::new(5, denominator).unwrap().beat_count(), 5);
+        }
+    }
+
+    #[test]
+    fn classification_joins_division_and_count() {
+        assert_eq!(ts("4/4").classification(), "Simple Quadruple");
+        assert_eq!(ts("6/8").classification(), "Compound Duple");
+        assert_eq!(ts("3/8").classification(), "Other Single");
+        assert_eq!(ts("5/4").classification(), "Simple Quintuple");
+        assert_eq!(ts("13/8").classification(), "Simple 13-uple");
+        assert_eq!(ts("21/16").classification(), "Compound Septuple");
+    }
+
+    #[test]
+    fn beat_offsets_partition_the_bar() {
+        assert_eq!(ts("4/4").beat_offsets(), [0.0, 1.0, 2.0, 3.0]);
+        assert_eq!(ts("6/8").beat_offsets(), [0.0, 1.5]);
+        assert_eq!(ts("5/8").beat_offsets(), [0.0, 0.5, 1.0, 1.5, 2.0]);
+    }
+
+    #[test]
+    fn beat_at_offset_is_one_based_and_bounded() {
+        assert_eq!(ts("4/4").beat_at_offset(1.5).unwrap(), 2);
+        assert_eq!(ts("6/8").beat_at_offset(1.5).unwrap(), 2);
+        assert_eq!(ts("5/8").beat_at_offset(1.5).unwrap(), 4);
+        assert_eq!(ts("4/4").beat_at_offset(0.0).unwrap(), 1);
+        assert!(ts("4/4").beat_at_offset(4.0).is_err());
+        assert!(ts("4/4").beat_at_offset(-0.5).is_err());
+        assert!(ts("4/4").beat_at_offset(FloatType::NAN).is_err());
+    }
+
+    #[test]
+    fn irrational_denominators_are_accepted_as_music21_accepts_them() {
+        let four_three = ts("4/3");
+        assert!((four_three.bar_quarter_length() - 16.0 / 3.0).abs() < 1e-12);
+        assert_eq!(four_three.beat_count(), 4);
+    }
+
+    #[test]
+    fn malformed_ratios_error_instead_of_panicking() {
+        for ratio in [
+            "", "4", "4/", "/4", "4/4/4", "x/4", "4/x", "0/4", "4/0", "-1/4", "2./4", ".5/4",
+        ] {
+            assert!(
+                TimeSignature::from_ratio_string(ratio).is_err(),
+                "{ratio:?} should not parse"
+            );
+        }
+    }
+
+    #[test]
+    fn spans_too_fine_to_measure_are_rejected() {
+        for ratio in ["0.0001/1000000", "1/65536+1/65537"] {
+            assert!(
+                matches!(
+                    TimeSignature::from_ratio_string(ratio),
+                    Err(Error::Meter(_))
+                ),
+                "{ratio:?} should not parse"
+            );
+        }
+    }
+
+    #[test]
+    fn beat_strength_follows_the_accent_tree() {
+        let strengths = |ratio: &str, step: FloatType| {
+            let meter = ts(ratio);
+            let count = (meter.bar_quarter_length() / step).round() as u32;
+            (0..count)
+                .map(|index| meter.beat_strength(FloatType::from(index) * step).unwrap())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(strengths("4/4", 1.0), [1.0, 0.25, 0.5, 0.25]);
+        assert_eq!(strengths("3/4", 0.5), [1.0, 0.25, 0.5, 0.25, 0.5, 0.25]);
+        assert_eq!(strengths("6/8", 0.5), [1.0, 0.25, 0.25, 0.5, 0.25, 0.25]);
+        assert_eq!(strengths("12/8", 1.5), [1.0, 0.25, 0.5, 0.25]);
+        assert_eq!(strengths("3/8", 0.5), [1.0, 0.5, 0.5]);
+        assert_eq!(strengths("2/2", 1.0), [1.0, 0.25, 0.5, 0.25]);
+        assert_eq!(ts("4/4").beat_strength(5.0).unwrap(), 0.25);
+        assert_eq!(
+            ts("4/4").accent_sequence().to_string(),
+            "{{1/4+1/4}+{1/4+1/4}}"
+        );
+    }
+
+    #[test]
+    fn beat_and_beam_sequences_partition_the_bar() {
+        let spelled = |sequence: MeterSequence| sequence.to_string();
+        assert_eq!(spelled(ts("6/8").beat_sequence()), "{3/8+3/8}");
+        assert_eq!(spelled(ts("3/8").beat_sequence()), "3/8");
+        assert_eq!(spelled(ts("4/4").beam_sequence()), "{1/4+1/4+1/4+1/4}");
+        assert_eq!(spelled(ts("9/8").beam_sequence()), "{3/8+3/8+3/8}");
+        assert_eq!(spelled(ts("5/8").beam_sequence()), "{2/8+3/8}");
+        assert_eq!(spelled(ts("7/8").beam_sequence()), "{2/8+2/8+3/8}");
+        assert_eq!(spelled(ts("4/8").beam_sequence()), "{2/8+2/8}");
+        assert_eq!(spelled(ts("7/16").beam_sequence()), "{4/16+3/16}");
+        assert_eq!(spelled(ts("5/16").beam_sequence()), "5/16");
+        assert_eq!(spelled(ts("7/8").display_sequence()), "7/8");
+        for meter in ["4/4", "6/8", "5/8", "7/16", "4/3"] {
+            let meter = ts(meter);
+            assert_eq!(
+                meter.beat_sequence().offsets(),
+                meter.beat_offsets(),
+                "{meter}"
+            );
+        }
+    }
+
+    #[test]
+    fn additive_and_summed_meters_beat_by_term() {
+        let aksak = ts("2+2+3/8");
+        assert!(aksak.is_additive());
+        assert_eq!((aksak.numerator(), aksak.denominator()), (7, 8));
+        assert_eq!(aksak.bar_quarter_length(), 3.5);
+        assert_eq!(aksak.beat_count(), 3);
+        assert_eq!(aksak.beat_offsets(), [0.0, 1.0, 2.0]);
+        assert_eq!(aksak.beat_quarter_lengths(), [1.0, 1.0, 1.5]);
+        assert_eq!(aksak.beat_at_offset(2.5).unwrap(), 3);
+        assert_eq!(aksak.classification(), "Simple Triple");
+        assert_eq!(aksak.display_sequence().to_string(), "{2/8+2/8+3/8}");
+        assert_eq!(aksak.beat_strength(1.0).unwrap(), 0.5);
+        assert_eq!(ts("3+3/8").beat_division(), BeatDivision::Compound);
+
+        let summed = ts("3/4+2/8");
+        assert_eq!(summed.terms(), [ts("3/4"), ts("2/8")]);
+        assert_eq!((summed.numerator(), summed.denominator()), (8, 8));
+        assert_eq!(summed.bar_quarter_length(), 4.0);
+        assert_eq!(summed.beat_offsets(), [0.0, 1.0, 2.0, 3.0, 3.5]);
+        assert_eq!(summed.beat_strength(3.0).unwrap(), 0.5);
+        assert_eq!(summed.beat_strength(1.0).unwrap(), 0.25);
+        assert_eq!(
+            summed.accent_sequence().to_string(),
+            "{{1/4+1/4+1/4}+{1/8+1/8}}"
+        );
+        assert_eq!(
+            TimeSignature::from_terms(&[ts("3/4"), ts("2/8")]).unwrap(),
+            summed
+        );
+        assert_eq!(TimeSignature::additive(&[2, 2, 3], 8).unwrap(), aksak);
+        assert!(TimeSignature::additive(&[2, 0], 8).is_err());
+    }
+
+    #[test]
+    fn fractional_and_irrational_meters_stay_consistent() {
+        let fractional = ts("2.5/4");
+        assert!(fractional.is_fractional());
+        assert_eq!((fractional.numerator(), fractional.denominator()), (5, 8));
+        assert_eq!(fractional.bar_quarter_length(), 2.5);
+        assert_eq!(fractional.beat_offsets(), [0.0, 1.0, 2.0]);
+        assert_eq!(fractional.beat_quarter_lengths(), [1.0, 1.0, 0.5]);
+
+        for (ratio, bar, beats) in [("4/3", 16.0 / 3.0, 4), ("5/6", 10.0 / 3.0, 5)] {
+            let meter = ts(ratio);
+            assert!((meter.bar_quarter_length() - bar).abs() < 1e-12, "{ratio}");
+            assert_eq!(meter.beat_count(), beats, "{ratio}");
+            let lengths = meter.beat_quarter_lengths();
+            assert!((lengths.iter().sum::<FloatType>() - bar).abs() < 1e-12);
+        }
+
+        for meter in [
+            ts("2+2+3/8"),
+            ts("3/4+2/8"),
+            ts("2.5/4"),
+            ts("4/3"),
+            ts("5/6"),
+            ts("3+2/8+3/4"),
+        ] {
+            let offsets = meter.beat_offsets();
+            assert_eq!(offsets.len(), meter.beat_count() as usize, "{meter}");
+            let end = offsets.last().unwrap() + meter.beat_quarter_lengths().last().unwrap();
+            assert!((end - meter.bar_quarter_length()).abs() < 1e-12, "{meter}");
+        }
+    }
+
+    #[test]
+    fn ratio_strings_round_trip() {
+        for ratio in [
+            "4/4",
+            "2+2+3/8",
+            "3/4+2/8",
+            "2.5/4",
+            "0.25/4",
+            "4/3",
+            "3+2/8+3/4",
+        ] {
+            assert_eq!(ts(ratio).ratio_string(), ratio);
+        }
+        for bad in ["4", "4/0", "0/4", "3+/8", "2+2", "1.2.3/4", "3/4+"] {
+            assert!(TimeSignature::from_ratio_string(bad).is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn display_is_the_ratio_string() {
+        assert_eq!(ts("7/8").to_string(), "7/8");
+    }
+}
@@ -0,0 +1,469 @@
+//! Meter partition trees, after music21's `MeterSequence`.
+//!
+//! A [`MeterSequence`] is a span of the bar, such as `3/8`, optionally
+//! partitioned into consecutive smaller spans, each of which may be
+//! partitioned in turn. [`TimeSignature`](super::TimeSignature) builds one
+//! tree per purpose: beats, beam groups, accents and display.
+
+use std::{fmt, str::FromStr};
+
+use num::integer::gcd;
+
+use crate::{
+    defaults::{FloatType, UnsignedIntegerType},
+    error::{Error, Result},
+};
+
+const OFFSET_EPSILON: FloatType = 1e-9;
+/// How many levels below a leaf [`MeterSequence::beat_strength`] looks for
+/// an offset before giving up and weighing it 0.
+const MAX_IMPLICIT_LEVELS: u32 = 8;
+
+/// A span of a bar, optionally partitioned into smaller spans.
+///
+/// ```
+/// use music21_rs::MeterSequence;
+///
+/// let aksak = MeterSequence::from_ratio_string("3+2+2/8")?;
+/// assert_eq!(aksak.to_string(), "{3/8+2/8+2/8}");
+/// assert_eq!(aksak.offsets(), [0.0, 1.5, 2.5]);
+/// assert_eq!(aksak.beat_strength(1.5)?, 0.5);
+/// # Ok::<(), music21_rs::Error>(())
+/// ```
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MeterSequence {
+    numerator: UnsignedIntegerType,
+    denominator: UnsignedIntegerType,
+    parts: Vec<MeterSequence>,
+}
+
+impl MeterSequence {
+    /// Creates an unpartitioned span of `numerator/denominator` whole notes.
+    pub fn new(numerator: UnsignedIntegerType, denominator: UnsignedIntegerType) -> Result<Self> {
+        if numerator == 0 || denominator == 0 {
+            return Err(Error::Meter(format!(
+                "meter span {numerator}/{denominator} must be non-zero"
+            )));
+        }
+        Ok(Self {
+            numerator,
+            denominator,
+            parts: Vec::new(),
+        })
+    }
+
+    /// Creates a span partitioned into `parts`, in order.
+    ///
+    /// The span is the parts' sum over their largest denominator, so `3/4`
+    /// and `2/8` make `8/8`. A single part is returned as it is.
+    pub fn from_parts(parts: Vec<MeterSequence>) -> Result<Self> {
+        if parts.len() <= 1 {
+            return parts.into_iter().next().ok_or_else(|| {
+                Error::Meter("a meter sequence needs at least one part".to_string())
+            });
+        }
+        let too_fine = || Error::Meter("meter sequence is too fine to measure".to_string());
+        let denominator = parts
+            .iter()
+            .try_fold(1, |denominator: UnsignedIntegerType, part| {
+                (denominator / gcd(denominator, part.denominator))
+                    .checked_mul(part.denominator)
+                    .ok_or_else(too_fine)
+            })?;
+        let numerator = parts.iter().try_fold(0, |sum: UnsignedIntegerType, part| {
+            part.numerator
+                .checked_mul(denominator / part.denominator)
+                .and_then(|numerator| sum.checked_add(numerator))
+                .ok_or_else(too_fine)
+        })?;
+        Ok(Self {
+            numerator,
+            denominator,
+            parts,
+        })
+    }
+
+    /// Parses a meter such as `"7/8"`, an additive meter such as
+    /// `"3+2+2/8"`, or a sum such as `"3/4+2/8"`, into a sequence with one
+    /// part per added term.
+    pub fn from_ratio_string(ratio: &str) -> Result<Self> {
+        let invalid = || Error::Meter(format!("cannot read meter {ratio:?}"));
+        let parse = |text: &str| {
+            text.trim()
+                .parse::<UnsignedIntegerType>()
+                .map_err(|_| invalid())
+        };
+        let mut parts = Vec::new();
+        let mut pending = Vec::new();
+        for term in ratio.split('+') {
+            match term.split_once('/') {
+                Some((numerator, denominator)) => {
+                    let denominator = parse(denominator)?;
+                    pending.push(parse(numerator)?);
+                    for numerator in pending.drain(..) {
+                        parts.push(Self::new(numerator, denominator)?);
+                    }
+                }
+                None => pending.push(parse(term)?),
+            }
+        }
+        if !pending.is_empty() || parts.is_empty() {
+            return Err(invalid());
+        }
+        Self::from_parts(parts)
+    }
+
+    /// Returns the span's numerator.
+    pub fn numerator(&self) -> UnsignedIntegerType {
+        self.numerator
+    }
+
+    /// Returns the span's denominator.
+    pub fn denominator(&self) -> UnsignedIntegerType {
+        self.denominator
+    }
+
+    /// Returns the span's length in quarter lengths.
+    pub fn quarter_length(&self) -> FloatType {
+        FloatType::from(self.numerator) * 4.0 / FloatType::from(self.denominator)
+    }
+
+    /// Returns the partition of the span, empty when it is not partitioned.
+    pub fn parts(&self) -> &[MeterSequence] {
+        &self.parts
+    }
+
+    /// Returns whether the span is unpartitioned.
+    pub fn is_leaf(&self) -> bool {
+        self.parts.is_empty()
+    }
+
+    /// Returns how many levels of partition lie below the span: 0 for a
+    /// leaf.
+    pub fn depth(&self) -> usize {
+        self.parts
+            .iter()
+            .map(|part| part.depth() + 1)
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// Returns the quarter-length offset of each part within the span, or
+    /// `[0.0]` for a leaf.
+    pub fn offsets(&self) -> Vec<FloatType> {
+        if self.is_leaf() {
+            return vec![0.0];
+        }
+        self.parts
+            .iter()
+            .scan(0.0, |offset, part| {
+                let start = *offset;
+                *offset += part.quarter_length();
+                Some(start)
+            })
+            .collect()
+    }
+
+    /// Returns whether every part has the same length.
+    pub fn is_uniform(&self) -> bool {
+        self.parts.windows(2).all(|pair| {
+            (pair[0].quarter_length() - pair[1].quarter_length()).abs() < OFFSET_EPSILON
+        })
+    }
+
+    /// Returns the compact spelling: `"7/8"` for a leaf, `"3+2+2/8"` when
+    /// the parts share a denominator, and `"3/4+2/8"` otherwise.
+    pub fn ratio_string(&self) -> String {
+        if self.is_leaf() {
+            return format!("{}/{}", self.numerator, self.denominator);
+        }
+        let denominator = self.parts[0].denominator;
+        if self
+            .parts
+            .iter()
+            .all(|part| part.denominator == denominator)
+        {
+            let numerators = self
+                .parts
+                .iter()
+                .map(|part| part.numerator.to_string())
+                .collect::<Vec<_>>();
+            format!("{}/{denominator}", numerators.join("+"))
+        } else {
+            self.parts
+                .iter()
+                .map(MeterSequence::ratio_string)
+                .collect::<Vec<_>>()
+                .join("+")
+        }
+    }
+
+    /// Returns the span split into `count` equal parts, replacing any
+    /// existing partition.
+    ///
+    /// The denominator grows as needed, so `3/4` in two is `3/8+3/8` and
+    /// `1/4` in three is three `1/12`s, as in music21.
+    pub fn partitioned(&self, count: UnsignedIntegerType) -> Result<Self> {
+        if count == 0 {
+            return Err(Error::Meter("cannot partition into zero parts".to_string()));
+        }
+        self.partitioned_by(&vec![1; count as usize])
+    }
+
+    /// Returns the span split into parts proportional to `weights`,
+    /// replacing any existing partition. `[3, 2, 2]` splits `7/8` into
+    /// `3/8+2/8+2/8`.
+    pub fn partitioned_by(&self, weights: &[UnsignedIntegerType]) -> Result<Self> {
+        let invalid = || {
+            Error::Meter(format!(
+                "cannot partition {} by {weights:?}",
+                self.ratio_string()
+            ))
+        };
+        if weights.is_empty() || weights.contains(&0) {
+            return Err(invalid());
+        }
+        let total = weights
+            .iter()
+            .try_fold(0, |sum: UnsignedIntegerType, weight| {
+                sum.checked_add(*weight)
+            })
+            .ok_or_else(invalid)?;
+        if weights.len() == 1 {
+            return Self::new(self.numerator, self.denominator);
+        }
+        // Scale numerator and denominator together until the weights divide
+        // the numerator.
+        let scale = total / gcd(self.numerator, total);
+        let (numerator, denominator) = self
+            .numerator
+            .checked_mul(scale)
+            .zip(self.denominator.checked_mul(scale))
+            .ok_or_else(invalid)?;
+        let unit = numerator / total;
+        let parts = weights
+            .iter()
+            .map(|weight| Self::new(unit * weight, denominator).map(Self::reduced))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            numerator: self.numerator,
+            denominator: self.denominator,
+            parts,
+        })
+    }
+
+    /// Returns the metrical weight of `offset` quarter lengths into the span.
+    ///
+    /// The span's start weighs 1, the starts of its parts 1/2, of their
+    /// parts 1/4, and so on down the tree. Below a leaf the usual division
+    /// continues implicitly: spans of 3, 6, 9… units in thirds, other even
+    /// spans in halves, 5 or 7 units one unit at a time, and a single unit
+    /// in halves. Offsets past the span wrap around, so offsets from the
+    /// start of a piece can be passed directly; offsets that fall on no
+    /// boundary within eight implicit levels weigh 0.
+    pub fn beat_strength(&self, offset: FloatType) -> Result<FloatType> {
+        if !offset.is_finite() || offset < 0.0 {
+            return Err(Error::Meter(format!(
+                "cannot weigh offset {offset} in {}",
+                self.ratio_string()
+            )));
+        }
+        let length = self.quarter_length();
+        let mut offset = offset % length;
+        if length - offset < OFFSET_EPSILON {
+            offset = 0.0;
+        }
+        Ok(self.weigh(offset, 1.0))
+    }
+
+    fn weigh(&self, offset: FloatType, weight: FloatType) -> FloatType {
+        if offset.abs() < OFFSET_EPSILON {
+            return weight;
+        }
+        if self.is_leaf() {
+            return implicit_weight(
+                self.numerator,
+                self.quarter_length() / FloatType::from(self.numerator),
+                offset,
+                weight,
+                MAX_IMPLICIT_LEVELS,
+            );
+        }
+        let mut start = 0.0;
+        for part in &self.parts {
+            let end = start + part.quarter_length();
+            if offset < end - OFFSET_EPSILON {
+                return part.weigh(offset - start, weight / 2.0);
+            }
+            start = end;
+        }
+        0.0
+    }
+
+    /// Returns the span with its numerator and denominator in lowest terms
+    /// while the denominator stays a whole number of the original's units.
+    fn reduced(self) -> Self {
+        let common = gcd(self.numerator, self.denominator);
+        // Keep power-of-two denominators readable: 2/8 stays 2/8, not 1/4,
+        // but 3/12 becomes 1/4.
+        if self.denominator.is_power_of_two() {
+            return self;
+        }
+        Self {
+            numerator: self.numerator / common,
+            denominator: self.denominator / common,
+            parts: self.parts,
+        }
+    }
+
+    /// Returns a copy with every top-level part partitioned by `partition`.
+    pub(crate) fn map_parts(mut self, partition: impl Fn(&Self) -> Result<Self>) -> Result<Self> {
+        self.parts = self
+            .parts
+            .iter()
+            .map(&partition)
+            .collect::<Result<Vec<_>>>()?;
+        Ok(self)
+    }
+}
+
+/// Weighs `offset` inside an unpartitioned span of `units` units of `unit`
+/// quarter lengths, whose start weighs `weight`.
+fn implicit_weight(
+    units: UnsignedIntegerType,
+    unit: FloatType,
+    offset: FloatType,
+    weight: FloatType,
+    levels: u32,
+) -> FloatType {
+    if offset.abs() < OFFSET_EPSILON {
+        return weight;
+    }
+    if levels == 0 {
+        return 0.0;
+    }
+    let (parts, units_per_part, part_unit) = match units {
+        1 => (2, 1, unit / 2.0),
+        units if units > 3 && units.is_multiple_of(2) => (2, units / 2, unit),
+        units if units.is_multiple_of(3) => (3, units / 3, unit),
+        units => (units, 1, unit),
+    };
+    let part_length = FloatType::from(units_per_part) * part_unit;
+    let index = ((offset + OFFSET_EPSILON) / part_length).floor();
+    if index >= FloatType::from(parts) {
+        return 0.0;
+    }
+    implicit_weight(
+        units_per_part,
+        part_unit,
+        offset - index * part_length,
+        weight / 2.0,
+        levels - 1,
+    )
+}
+
+impl fmt::Display for MeterSequence {
+    /// Writes music21's spelling: `3/8` for a leaf, `{3/8+2/8+2/8}` for a
+    /// partitioned span, nesting braces for deeper partitions.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_leaf() {
+            return write!(f, "{}/{}", self.numerator, self.denominator);
+        }
+        f.write_str("{")?;
+        for (index, part) in self.parts.iter().enumerate() {
+            if index > 0 {
+                f.write_str("+")?;
+            }
+            write!(f, "{part}")?;
+        }
+        f.write_str("}")
+    }
+}
+
+impl FromStr for MeterSequence {
+    type Err = Error;
+
+    fn from_str(ratio: &str) -> Result<Self> {
+        Self::from_ratio_string(ratio)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sequence(ratio: &str) -> MeterSequence {
+        MeterSequence::from_ratio_string(ratio).unwrap()
+    }
+
+    #[test]
+    fn parses_additive_and_summed_meters() {
+        let aksak = sequence("3+2+2/8");
+        assert_eq!((aksak.numerator(), aksak.denominator()), (7, 8));
+        assert_eq!(aksak.to_string(), "{3/8+2/8+2/8}");
+        assert_eq!(aksak.ratio_string(), "3+2+2/8");
+        assert_eq!(aksak.offsets(), [0.0, 1.5, 2.5]);
+        assert!(!aksak.is_uniform());
+
+        let summed = sequence("3/4+2/8");
+        assert_eq!((summed.numerator(), summed.denominator()), (8, 8));
+        assert_eq!(summed.ratio_string(), "3/4+2/8");
+        assert_eq!(summed.quarter_length(), 4.0);
+        assert_eq!(sequence("5/8").depth(), 0);
+        assert_eq!("2+3/8".parse::<MeterSequence>().unwrap().depth(), 1);
+
+        for ratio in ["", "3+2", "3/8+", "x/8", "0/4", "3/0"] {
+            assert!(
+                MeterSequence::from_ratio_string(ratio).is_err(),
+                "{ratio:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn partitions_scale_the_denominator() {
+        let three_four = sequence("3/4");
+        assert_eq!(three_four.partitioned(2).unwrap().to_string(), "{3/8+3/8}");
+        assert_eq!(
+            three_four.partitioned(3).unwrap().to_string(),
+            "{1/4+1/4+1/4}"
+        );
+        assert_eq!(
+            sequence("1/4").partitioned(3).unwrap().to_string(),
+            "{1/12+1/12+1/12}"
+        );
+        assert_eq!(
+            sequence("7/8")
+                .partitioned_by(&[2, 2, 3])
+                .unwrap()
+                .to_string(),
+            "{2/8+2/8+3/8}"
+        );
+        assert!(three_four.partitioned(0).is_err());
+        assert!(three_four.partitioned_by(&[2, 0]).is_err());
+    }
+
+    #[test]
+    fn beat_strength_halves_at_each_level() {
+        let aksak = sequence("3+2+2/8");
+        let strengths = (0..14)
+            .map(|sixteenth| {
+                aksak
+                    .beat_strength(FloatType::from(sixteenth) * 0.25)
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            strengths,
+            [
+                1.0, 0.125, 0.25, 0.125, 0.25, 0.125, // 3/8: eighths in thirds
+                0.5, 0.125, 0.25, 0.125, // 2/8
+                0.5, 0.125, 0.25, 0.125, // 2/8
+            ]
+        );
+        assert_eq!(aksak.beat_strength(3.5).unwrap(), 1.0);
+        assert_eq!(aksak.beat_strength(0.1).unwrap(), 0.0);
+        assert!(aksak.beat_strength(-1.0).is_err());
+    }
+}
@@ -4,6 +4,7 @@ use crate::{
     defaults::{FloatType, IntegerType},
     duration::Duration,
     error::{Error, Result},
+    instrument::Instrument,
     note::Note,
     pitch::Pitch,
     stream::{Stream, StreamElement},
@@ -74,17 +75,22 @@ impl MidiNote {
 
 /// Extracts MIDI note events from a stream.
 pub fn midi_notes_from_stream(stream: &Stream) -> Result<Vec<MidiNote>> {
+    midi_notes_from_stream_on_channel(stream, 0)
+}
+
+/// Extracts MIDI note events from a stream, all on `channel`.
+pub fn midi_notes_from_stream_on_channel(stream: &Stream, channel: u8) -> Result<Vec<MidiNote>> {
     let mut notes = Vec::new();
     for event in stream.events() {
         let start = event.offset();
         let duration = event.element().quarter_length();
         match event.element() {
             StreamElement::Note(note) => {
-                notes.push(note_to_midi_note(note, start, duration)?);
+                notes.push(note_to_midi_note(note, start, duration, channel)?);
             }
             StreamElement::Chord(chord) => {
                 for note in chord.notes() {
-                    notes.push(note_to_midi_note(note, start, duration)?);
+                    notes.push(note_to_midi_note(note, start, duration, channel)?);
                 }
             }
             StreamElement::Rest(_) => {}
@@ -106,21 +112,41 @@ pub fn stream_from_midi_notes(notes: &[MidiNote]) -> Result<Stream> {
 
 /// Writes a minimal format-0 Standard MIDI File.
 pub fn write_midi_bytes(notes: &[MidiNote], tempo_bpm: FloatType) -> Result<Vec<u8>> {
+    write_midi_bytes_with_programs(notes, tempo_bpm, &[])
+}
+
+/// Writes a minimal format-0 Standard MIDI File that starts by selecting a
+/// program on some channels.
+///
+/// `programs` pairs a channel with a 0-based General MIDI program number.
+pub fn write_midi_bytes_with_programs(
+    notes: &[MidiNote],
+    tempo_bpm: FloatType,
+    programs: &[(u8, u8)],
+) -> Result<Vec<u8>> {
     if !tempo_bpm.is_finite() || tempo_bpm <= 0.0 {
         return Err(Error::Midi(format!("invalid tempo: {tempo_bpm}")));
     }
 
     let mut events = Vec::new();
+    for (channel, program) in programs {
+        if *channel > 15 || *program > 127 {
+            return Err(Error::Midi(format!(
+                "program {program} on channel {channel} is out of range"
+            )));
+        }
+        events.push((0, 0_u8, vec![0xC0 | channel, *program]));
+    }
     for note in notes {
         validate_note(*note)?;
         let start_tick = quarter_to_tick(note.start)?;
         let end_tick = quarter_to_tick(note.start + note.duration)?;
         events.push((
             start_tick,
-            1_u8,
-            [0x90 | note.channel, note.pitch, note.velocity],
+            2_u8,
+            vec![0x90 | note.channel, note.pitch, note.velocity],
         ));
-        events.push((end_tick, 0_u8, [0x80 | note.channel, note.pitch, 0]));
+        events.push((end_tick, 1_u8, vec![0x80 | note.channel, note.pitch, 0]));
     }
     events.sort_by_key(|event| (event.0, event.1));
 
@@ -205,12 +231,39 @@ pub fn read_midi_bytes_with_tempo(bytes: &[u8]) -> Result<(Vec<MidiNote>, Option
     Ok((all_notes, first_tempo))
 }
 
-fn note_to_midi_note(note: &Note, start: FloatType, duration: FloatType) -> Result<MidiNote> {
+/// Writes one part per instrument to a minimal format-0 Standard MIDI File.
+///
+/// Each part takes the next free channel, skipping channel 9, which General
+/// MIDI keeps for percussion, and opens with its instrument's
+/// [`Instrument::midi_program`]. Parts are read at concert pitch.
+pub fn write_midi_parts(parts: &[(&Stream, &Instrument)], tempo_bpm: FloatType) -> Result<Vec<u8>> {
+    let channels = (0..16_u8).filter(|channel| *channel != 9);
+    if parts.len() > channels.clone().count() {
+        return Err(Error::Midi(format!(
+            "{} parts do not fit on 15 melodic channels",
+            parts.len()
+        )));
+    }
+    let mut notes = Vec::new();
+    let mut programs = Vec::new();
+    for ((stream, instrument), channel) in parts.iter().zip(channels) {
+        notes.extend(midi_notes_from_stream_on_channel(stream, channel)?);
+        programs.push((channel, instrument.midi_program()));
+    }
+    write_midi_bytes_with_programs(&notes, tempo_bpm, &programs)
+}
+
+fn note_to_midi_note(
+    note: &Note,
+    start: FloatType,
+    duration: FloatType,
+    channel: u8,
+) -> Result<MidiNote> {
     let pitch = note.pitch().ps().round() as IntegerType;
     if !(0..=127).contains(&pitch) {
         return Err(Error::Midi(format!("pitch {pitch} is outside MIDI range")));
     }
-    MidiNote::new(pitch as u8, start, duration, 64)
+    MidiNote::with_channel(pitch as u8, start, duration, 64, channel)
 }
 
 fn validate_note(note: MidiNote) -> Result<()> {
@@ -415,6 +468,30 @@ mod tests {
         assert_eq!(notes[0].duration, 2.0);
     }
 
+    #[test]
+    fn instrument_parts_get_channels_and_programs() {
+        let mut melody = Stream::new();
+        melody.push(Note::from_name("E5").unwrap());
+        let mut bass = Stream::new();
+        bass.push(Note::from_name("C3").unwrap());
+        let flute = Instrument::from_name("flute").unwrap();
+        let cello = Instrument::from_name("cello").unwrap();
+        let bytes = write_midi_parts(&[(&melody, &flute), (&bass, &cello)], 90.0).unwrap();
+
+        let notes = read_midi_bytes(&bytes).unwrap();
+        let channels = notes
+            .iter()
+            .map(|note| (note.pitch, note.channel))
+            .collect::<Vec<_>>();
+        assert_eq!(channels, vec![(76, 0), (48, 1)]);
+        assert!(bytes.windows(2).any(|pair| pair == [0xC0, 73]));
+        assert!(bytes.windows(2).any(|pair| pair == [0xC1, 42]));
+
+        let crowd = vec![(&melody, &flute); 16];
+        assert!(write_midi_parts(&crowd, 90.0).is_err());
+        assert!(write_midi_bytes_with_programs(&[], 90.0, &[(16, 0)]).is_err());
+    }
+
     #[test]
     fn midi_note_validation_rejects_invalid_values() {
         assert!(MidiNote::with_channel(128, 0.0, 1.0, 64, 0).is_err());
@@ -0,0 +1,526 @@
+//! Melodic pattern search, after music21's `search` module.
+//!
+//! A motif is any [`Stream`]; [`find_motif`] reports every place its melody
+//! recurs in another stream. What counts as a recurrence is set by
+//! [`MotifSearchOptions`]: the pitch test ([`MelodicMatch`]) runs from exact
+//! pitches through chromatic and diatonic intervals down to bare contour,
+//! the rhythm test ([`RhythmMatch`]) can ignore rhythm or accept augmented
+//! and diminished statements, and inverted statements can be admitted too.
+//! Together they find every entry of a fugue subject, answer and inversion
+//! included.
+
+use crate::{
+    defaults::{FloatType, IntegerType},
+    error::{Error, Result},
+    interval::Interval,
+    pitch::Pitch,
+    stream::{Stream, StreamElement},
+};
+
+const OFFSET_EPSILON: FloatType = 1e-9;
+
+/// How the pitches of a candidate must agree with the motif.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MelodicMatch {
+    /// The same pitches, compared in pitch space so enharmonics agree.
+    Pitches,
+    /// The same chromatic intervals in semitones, at any transposition.
+    #[default]
+    Intervals,
+    /// The same diatonic steps, by [`Interval::generic_number`]: a major
+    /// and a minor third agree, as in a tonal answer or a sequence.
+    Diatonic,
+    /// The same directions only: up, down or repeated.
+    Contour,
+    /// The same [`contour_segment`]: every note keeps its rank among the
+    /// motif's distinct pitches.
+    ContourSegment,
+    /// The same [`contour_class`], which admits the segment's inversion,
+    /// retrograde and retrograde inversion.
+    ContourClass,
+}
+
+/// How the rhythm of a candidate must agree with the motif.
+///
+/// Rhythm is compared between onsets, so a longer or shorter final note, or
+/// a rest between notes moving the next onset, is judged by where the
+/// following note starts.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RhythmMatch {
+    /// Any rhythm.
+    Ignore,
+    /// The same time between consecutive onsets.
+    #[default]
+    Exact,
+    /// The motif's rhythm scaled by one factor, so augmented and diminished
+    /// statements match.
+    Proportional,
+}
+
+/// Options for [`find_motif`].
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MotifSearchOptions {
+    melodic: MelodicMatch,
+    rhythm: RhythmMatch,
+    inversions: bool,
+}
+
+impl MotifSearchOptions {
+    /// Returns the options with a different pitch test. Chromatic intervals
+    /// by default.
+    pub fn with_melodic_match(mut self, melodic: MelodicMatch) -> Self {
+        self.melodic = melodic;
+        self
+    }
+
+    /// Returns the options with a different rhythm test. Exact by default.
+    pub fn with_rhythm_match(mut self, rhythm: RhythmMatch) -> Self {
+        self.rhythm = rhythm;
+        self
+    }
+
+    /// Returns the options admitting, or not, the motif's melodic inversion.
+    /// Off by default.
+    ///
+    /// The inversion mirrors every interval, or every contour direction,
+    /// around the first note. [`MelodicMatch::ContourClass`] admits
+    /// inversions whatever this option says.
+    pub fn with_inversions(mut self, inversions: bool) -> Self {
+        self.inversions = inversions;
+        self
+    }
+
+    /// Returns the pitch test.
+    pub fn melodic_match(&self) -> MelodicMatch {
+        self.melodic
+    }
+
+    /// Returns the rhythm test.
+    pub fn rhythm_match(&self) -> RhythmMatch {
+        self.rhythm
+    }
+
+    /// Returns whether inverted statements match.
+    pub fn includes_inversions(&self) -> bool {
+        self.inversions
+    }
+}
+
+/// One statement of a motif found by [`find_motif`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MotifOccurrence {
+    offset: FloatType,
+    end: FloatType,
+    inverted: bool,
+    rhythm_scale: FloatType,
+}
+
+impl MotifOccurrence {
+    /// Returns the offset of the statement's first note.
+    pub fn offset(&self) -> FloatType {
+        self.offset
+    }
+
+    /// Returns the offset at which the statement's last note ends.
+    pub fn end(&self) -> FloatType {
+        self.end
+    }
+
+    /// Returns whether the statement matched the motif's inversion rather
+    /// than the motif itself.
+    pub fn is_inverted(&self) -> bool {
+        self.inverted
+    }
+
+    /// Returns how much slower the statement moves than the motif: 2 for an
+    /// augmentation, 0.5 for a diminution. Always 1 under
+    /// [`RhythmMatch::Exact`] and [`RhythmMatch::Ignore`].
+    pub fn rhythm_scale(&self) -> FloatType {
+        self.rhythm_scale
+    }
+}
+
+/// A note of a melody: its onset, release and sounding pitch.
+struct MelodyNote {
+    offset: FloatType,
+    end: FloatType,
+    pitch: Pitch,
+}
+
+/// Returns every offset in `stream` where the melody of `motif` recurs.
+///
+/// Both streams are read as single melodic lines: notes in order of onset,
+/// the highest pitch where several sound together, rests skipped. Search a
+/// multi-voice texture one voice at a time. Statements may overlap and are
+/// returned in order; one that matches both the motif and its inversion is
+/// reported once, as the motif.
+pub fn find_motif(
+    stream: &Stream,
+    motif: &Stream,
+    options: &MotifSearchOptions,
+) -> Result<Vec<MotifOccurrence>> {
+    let pattern = melody(motif);
+    if pattern.is_empty() {
+        return Err(Error::Analysis(
+            "motif search needs a motif with at least one note".to_string(),
+        ));
+    }
+    let line = melody(stream);
+    let pattern_shape = Shape::new(&pattern, options.melodic)?;
+    let pattern_rhythm = inter_onsets(&pattern);
+
+    let mut occurrences = Vec::new();
+    for window in line.windows(pattern.len()) {
+        let Some(rhythm_scale) = rhythm_scale(&pattern_rhythm, &inter_onsets(window), options)
+        else {
+            continue;
+        };
+        let shape = Shape::new(window, options.melodic)?;
+        let inverted = if shape == pattern_shape {
+            false
+        } else if options.inversions && shape == pattern_shape.inversion() {
+            true
+        } else {
+            continue;
+        };
+        occurrences.push(MotifOccurrence {
+            offset: window[0].offset,
+            end: window[window.len() - 1].end,
+            inverted,
+            rhythm_scale,
+        });
+    }
+    Ok(occurrences)
+}
+
+/// Returns Morris's contour segment (CSEG) of `pitches`: each pitch's rank
+/// among the distinct pitches, from 0 for the lowest.
+///
+/// `C4 G4 E4 C4` has the segment `[0, 2, 1, 0]`.
+pub fn contour_segment(pitches: &[Pitch]) -> Vec<usize> {
+    let mut distinct = pitches.iter().map(Pitch::ps).collect::<Vec<_>>();
+    distinct.sort_by(FloatType::total_cmp);
+    distinct.dedup_by(|later, earlier| (*later - *earlier).abs() < OFFSET_EPSILON);
+    pitches
+        .iter()
+        .map(|pitch| {
+            distinct
+                .iter()
+                .position(|ps| (ps - pitch.ps()).abs() < OFFSET_EPSILON)
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+/// Returns the contour class of `pitches`: the smallest of its
+/// [`contour_segment`], that segment's inversion, retrograde and retrograde
+/// inversion, compared element by element.
+///
+/// Segments related by inversion or retrograde share a class, so
+/// `C4 G4 E4` (`[0, 2, 1]`) and `G4 C4 E4` (`[2, 0, 1]`) both belong to
+/// `[0, 2, 1]`.
+pub fn contour_class(pitches: &[Pitch]) -> Vec<usize> {
+    let prime = contour_segment(pitches);
+    let top = prime.iter().copied().max().unwrap_or_default();
+    let inversion = prime.iter().map(|rank| top - rank).collect::<Vec<_>>();
+    let retrograde = prime.iter().rev().copied().collect::<Vec<_>>();
+    let retrograde_inversion = inversion.iter().rev().copied().collect::<Vec<_>>();
+    [prime, inversion, retrograde, retrograde_inversion]
+        .into_iter()
+        .min()
+        .unwrap_or_default()
+}
+
+impl Stream {
+    /// Returns every offset where the melody of `motif` recurs in the
+    /// stream.
+    ///
+    /// See [`find_motif`].
+    pub fn find_motif(
+        &self,
+        motif: &Stream,
+        options: &MotifSearchOptions,
+    ) -> Result<Vec<MotifOccurrence>> {
+        find_motif(self, motif, options)
+    }
+}
+
+/// The melodic shape a window is compared by, one variant per
+/// [`MelodicMatch`].
+#[derive(Debug, PartialEq)]
+enum Shape {
+    Pitches(Vec<IntegerType>),
+    Intervals(Vec<IntegerType>),
+    Diatonic(Vec<IntegerType>),
+    Contour(Vec<IntegerType>),
+    ContourSegment(Vec<usize>),
+    ContourClass(Vec<usize>),
+}
+
+impl Shape {
+    fn new(notes: &[MelodyNote], melodic: MelodicMatch) -> Result<Self> {
+        let pitches = notes
+            .iter()
+            .map(|note| note.pitch.clone())
+            .collect::<Vec<_>>();
+        let semitones = pitches
+            .iter()
+            .map(|pitch| pitch.ps().round() as IntegerType)
+            .collect::<Vec<_>>();
+        let steps = semitones
+            .windows(2)
+            .map(|pair| pair[1] - pair[0])
+            .collect::<Vec<_>>();
+        Ok(match melodic {
+            MelodicMatch::Pitches => Shape::Pitches(semitones),
+            MelodicMatch::Intervals => Shape::Intervals(steps),
+            MelodicMatch::Contour => {
+                Shape::Contour(steps.iter().map(|step| step.signum()).collect())
+            }
+            MelodicMatch::Diatonic => Shape::Diatonic(
+                pitches
+                    .windows(2)
+                    .map(|pair| {
+                        Interval::between_pitches(&pair[0], &pair[1])
+                            .map(|interval| interval.generic_number())
+                    })
+                    .collect::<Result<_>>()?,
+            ),
+            MelodicMatch::ContourSegment => Shape::ContourSegment(contour_segment(&pitches)),
+            MelodicMatch::ContourClass => Shape::ContourClass(contour_class(&pitches)),
+        })
+    }
+
+    /// Returns the shape of the melodic inversion about the first note.
+    fn inversion(&self) -> Self {
+        let negate = |values: &[IntegerType]| values.iter().map(|value| -value).collect();
+        match self {
+            Shape::Pitches(semitones) => Shape::Pitches(
+                semitones
+                    .iter()
+                    .map(|semitone| 2 * semitones[0] - semitone)
+                    .collect(),
+            ),
+            Shape::Intervals(steps) => Shape::Intervals(negate(steps)),
+            Shape::Contour(directions) => Shape::Contour(negate(directions)),
+            Shape::Diatonic(generics) => Shape::Diatonic(
+                generics
+                    .iter()
+                    .map(|generic| if generic.abs() == 1 { 1 } else { -generic })
+                    .collect(),
+            ),
+            Shape::ContourSegment(ranks) => {
+                let top = ranks.iter().copied().max().unwrap_or_default();
+                Shape::ContourSegment(ranks.iter().map(|rank| top - rank).collect())
+            }
+            Shape::ContourClass(class) => Shape::ContourClass(class.clone()),
+        }
+    }
+}
+
+fn melody(stream: &Stream) -> Vec<MelodyNote> {
+    let mut notes: Vec<MelodyNote> = Vec::new();
+    for event in stream.events() {
+        if matches!(event.element(), StreamElement::Rest(_)) {
+            continue;
+        }
+        let Some(pitch) = event
+            .element()
+            .pitches()
+            .into_iter()
+            .max_by(|left, right| left.ps().total_cmp(&right.ps()))
+        else {
+            continue;
+        };
+        notes.push(MelodyNote {
+            offset: event.offset(),
+            end: event.offset() + event.element().quarter_length(),
+            pitch,
+        });
+    }
+    notes.sort_by(|left, right| left.offset.total_cmp(&right.offset));
+    notes.dedup_by(|later, earlier| {
+        if later.offset - earlier.offset > OFFSET_EPSILON {
+            return false;
+        }
+        if later.pitch.ps() > earlier.pitch.ps() {
+            std::mem::swap(later, earlier);
+        }
+        true
+    });
+    notes
+}
+
+fn inter_onsets(notes: &[MelodyNote]) -> Vec<FloatType> {
+    notes
+        .windows(2)
+        .map(|pair| pair[1].offset - pair[0].offset)
+        .collect()
+}
+
+/// Returns the factor by which `candidate` stretches `pattern`, or `None`
+/// when the rhythms disagree under `options`.
+fn rhythm_scale(
+    pattern: &[FloatType],
+    candidate: &[FloatType],
+    options: &MotifSearchOptions,
+) -> Option<FloatType> {
+    let scale = match options.rhythm {
+        RhythmMatch::Ignore => return Some(1.0),
+        RhythmMatch::Exact => 1.0,
+        RhythmMatch::Proportional => match (pattern.first(), candidate.first()) {
+            (Some(pattern), Some(candidate)) => candidate / pattern,
+            _ => 1.0,
+        },
+    };
+    pattern
+        .iter()
+        .zip(candidate)
+        .all(|(pattern, candidate)| (pattern * scale - candidate).abs() < OFFSET_EPSILON)
+        .then_some(scale)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stream::test_stream;
+
+    fn offsets(occurrences: &[MotifOccurrence]) -> Vec<FloatType> {
+        occurrences.iter().map(MotifOccurrence::offset).collect()
+    }
+
+    /// A four-note subject: C (1) D (0.5) E (0.5) C (2).
+    fn subject() -> Stream {
+        test_stream(&[("C4", 1.0), ("D4", 0.5), ("E4", 0.5), ("C4", 2.0)])
+    }
+
+    #[test]
+    fn finds_exact_and_transposed_statements() {
+        let stream = test_stream(&[
+            ("C4", 1.0),
+            ("D4", 0.5),
+            ("E4", 0.5),
+            ("C4", 2.0),
+            ("G4", 1.0),
+            ("A4", 0.5),
+            ("B4", 0.5),
+            ("G4", 2.0),
+            ("r", 1.0),
+            ("C5", 1.0),
+            ("D5", 1.0),
+            ("E5", 1.0),
+            ("C5", 1.0),
+        ]);
+        let exact = MotifSearchOptions::default().with_melodic_match(MelodicMatch::Pitches);
+        assert_eq!(
+            offsets(&stream.find_motif(&subject(), &exact).unwrap()),
+            vec![0.0]
+        );
+
+        let transposed = MotifSearchOptions::default();
+        assert_eq!(
+            offsets(&stream.find_motif(&subject(), &transposed).unwrap()),
+            vec![0.0, 4.0]
+        );
+
+        let any_rhythm = transposed.with_rhythm_match(RhythmMatch::Ignore);
+        let found = stream.find_motif(&subject(), &any_rhythm).unwrap();
+        assert_eq!(offsets(&found), vec![0.0, 4.0, 9.0]);
+        assert_eq!(found[2].end(), 13.0);
+        assert!(find_motif(&stream, &Stream::new(), &transposed).is_err());
+    }
+
+    #[test]
+    fn finds_inverted_and_augmented_fugue_subjects() {
+        // The subject, its inversion from G, then an augmentation from F.
+        let stream = test_stream(&[
+            ("C4", 1.0),
+            ("D4", 0.5),
+            ("E4", 0.5),
+            ("C4", 2.0),
+            ("G4", 1.0),
+            ("F4", 0.5),
+            ("E-4", 0.5),
+            ("G4", 2.0),
+            ("F4", 2.0),
+            ("G4", 1.0),
+            ("A4", 1.0),
+            ("F4", 4.0),
+        ]);
+        let options = MotifSearchOptions::default()
+            .with_inversions(true)
+            .with_rhythm_match(RhythmMatch::Proportional);
+        let found = stream.find_motif(&subject(), &options).unwrap();
+        let summary = found
+            .iter()
+            .map(|found| (found.offset(), found.is_inverted(), found.rhythm_scale()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            vec![(0.0, false, 1.0), (4.0, true, 1.0), (8.0, false, 2.0)]
+        );
+
+        let exact_rhythm = options.with_rhythm_match(RhythmMatch::Exact);
+        assert_eq!(
+            offsets(&stream.find_motif(&subject(), &exact_rhythm).unwrap()),
+            vec![0.0, 4.0]
+        );
+    }
+
+    #[test]
+    fn diatonic_and_contour_matches_loosen_the_intervals() {
+        // A tonal answer: E F# G E keeps the steps but not their sizes, and
+        // C E G C keeps only the contour.
+        let stream = test_stream(&[
+            ("E4", 1.0),
+            ("F#4", 1.0),
+            ("G4", 1.0),
+            ("E4", 1.0),
+            ("C4", 1.0),
+            ("E4", 1.0),
+            ("G4", 1.0),
+            ("C4", 1.0),
+        ]);
+        let motif = test_stream(&[("C4", 1.0), ("D4", 1.0), ("E4", 1.0), ("C4", 1.0)]);
+        let found = |melodic| {
+            let options = MotifSearchOptions::default().with_melodic_match(melodic);
+            offsets(&find_motif(&stream, &motif, &options).unwrap())
+        };
+        assert_eq!(found(MelodicMatch::Intervals), Vec::<FloatType>::new());
+        assert_eq!(found(MelodicMatch::Diatonic), vec![0.0]);
+        assert_eq!(found(MelodicMatch::Contour), vec![0.0, 4.0]);
+        assert_eq!(found(MelodicMatch::ContourSegment), vec![0.0, 4.0]);
+    }
+
+    #[test]
+    fn contour_segments_and_classes() {
+        let pitches = |names: &[&str]| {
+            names
+                .iter()
+                .map(|name| Pitch::from_name(*name).unwrap())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            contour_segment(&pitches(&["C4", "G4", "E4", "C4"])),
+            vec![0, 2, 1, 0]
+        );
+        assert_eq!(contour_class(&pitches(&["C4", "G4", "E4"])), vec![0, 2, 1]);
+        assert_eq!(contour_class(&pitches(&["G4", "C4", "E4"])), vec![0, 2, 1]);
+        assert_eq!(contour_class(&pitches(&["G4", "E4", "C4"])), vec![0, 1, 2]);
+
+        // The retrograde of the motif shares its contour class only.
+        let motif = test_stream(&[("C4", 1.0), ("G4", 1.0), ("E4", 1.0)]);
+        let stream = test_stream(&[("D4", 1.0), ("A4", 1.0), ("B4", 1.0), ("E4", 1.0)]);
+        let options = MotifSearchOptions::default().with_melodic_match(MelodicMatch::ContourClass);
+        assert_eq!(
+            offsets(&find_motif(&stream, &motif, &options).unwrap()),
+            vec![1.0]
+        );
+        let segment = options.with_melodic_match(MelodicMatch::ContourSegment);
+        assert!(find_motif(&stream, &motif, &segment).unwrap().is_empty());
+    }
+}
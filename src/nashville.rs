@@ -0,0 +1,576 @@
+//! Nashville number-system chords.
+//!
+//! A Nashville number names a chord by the scale degree of its root, so the
+//! same chart can be read in any key: `1 4/6 5sus b7 2m7` is `C F/A Gsus C...`
+//! in C major and `G C/E Dsus F Am7` in G major. Degrees count from the major
+//! scale on the key's tonic in every mode, as Nashville charts do, so in A
+//! minor the relative major is `b3` and `b7` is G. The quality after the
+//! number uses the [`ChordSymbol`] model, written in
+//! [`ChordSymbolStyle::Nashville`].
+//!
+//! Chart marks are kept as metadata: a diamond (`<1>`) holds the chord and
+//! lets it ring, and a push (`^4`) anticipates the beat.
+
+use crate::{
+    chord::Chord,
+    chordsymbol::{ChordAlteration, ChordQuality, ChordSymbol, ChordSymbolStyle},
+    defaults::IntegerType,
+    error::{Error, Result},
+    key::Key,
+    pitch::Pitch,
+    roman::alter_pitch,
+};
+use std::fmt;
+use std::str::FromStr;
+
+/// A chord written as a Nashville number, such as `4/6`, `5sus`, or `b7`.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NashvilleNumber {
+    degree: u8,
+    accidental: i8,
+    quality: ChordQuality,
+    extensions: Vec<u8>,
+    alterations: Vec<ChordAlteration>,
+    additions: Vec<ChordAlteration>,
+    omissions: Vec<u8>,
+    bass: Option<(u8, i8)>,
+    diamond: bool,
+    push: bool,
+}
+
+impl NashvilleNumber {
+    /// Parses a Nashville number such as `"1"`, `"4/6"`, `"5sus"`, `"b7"`,
+    /// `"2m7"`, `"<1>"`, or `"^5"`.
+    ///
+    /// Minor may be written `m` or `-`, so `2-7` and `2m7` are the same
+    /// chord. Everything else after the number reads like a chord-symbol
+    /// suffix.
+    pub fn parse(text: &str) -> Result<Self> {
+        let trimmed = text.trim();
+        if trimmed.is_empty() {
+            return Err(Error::Chord("Nashville number cannot be empty".to_string()));
+        }
+
+        let (push, unpushed) = match trimmed.strip_prefix('^') {
+            Some(rest) => (true, rest.trim_start()),
+            None => (false, trimmed),
+        };
+        let (diamond, body) = match unpushed
+            .strip_prefix('<')
+            .and_then(|rest| rest.strip_suffix('>'))
+        {
+            Some(inner) => (true, inner.trim()),
+            None => (false, unpushed),
+        };
+
+        let (chord, bass) = match body.split_once('/') {
+            Some((chord, bass)) => (chord, Some(bass)),
+            None => (body, None),
+        };
+        let (accidental, degree, suffix) = split_degree(chord)
+            .ok_or_else(|| Error::Chord(format!("invalid Nashville number {text:?}")))?;
+        let bass = bass
+            .map(|bass| match split_degree(bass) {
+                Some((accidental, degree, "")) => Ok((degree, accidental)),
+                _ => Err(Error::Chord(format!(
+                    "invalid Nashville bass degree in {text:?}"
+                ))),
+            })
+            .transpose()?;
+
+        let template = ChordSymbol::parse(template_figure(suffix))?;
+        Ok(Self {
+            degree,
+            accidental,
+            quality: template.quality(),
+            extensions: implied_extensions(&template),
+            alterations: template.alterations().to_vec(),
+            additions: template.additions().to_vec(),
+            omissions: template.omissions().to_vec(),
+            bass,
+            diamond,
+            push,
+        })
+    }
+
+    /// Describes a chord symbol as a Nashville number in a key.
+    ///
+    /// Roots and slash basses outside the key get a flat or sharp, so `Bb`
+    /// in C major is `b7`.
+    pub fn from_chord_symbol(symbol: &ChordSymbol, key: &Key) -> Result<Self> {
+        let (degree, accidental) = degree_in_key(symbol.root(), key)?;
+        let bass = symbol
+            .bass()
+            .map(|bass| degree_in_key(bass, key))
+            .transpose()?;
+
+        Ok(Self {
+            degree,
+            accidental,
+            quality: symbol.quality(),
+            extensions: implied_extensions(symbol),
+            alterations: symbol.alterations().to_vec(),
+            additions: symbol.additions().to_vec(),
+            omissions: symbol.omissions().to_vec(),
+            bass,
+            diamond: false,
+            push: false,
+        })
+    }
+
+    /// Describes a chord as a Nashville number in a key, using the chord
+    /// symbol [`Chord::chord_symbol`] finds for it.
+    ///
+    /// Returns `None` when the chord has no chord symbol.
+    pub fn from_chord(chord: &Chord, key: &Key) -> Result<Option<Self>> {
+        let Some(figure) = chord.chord_symbol() else {
+            return Ok(None);
+        };
+        let symbol = ChordSymbol::parse(figure)?;
+        Self::from_chord_symbol(&symbol, key).map(Some)
+    }
+
+    /// Returns the one-based scale degree of the root.
+    pub fn degree(&self) -> u8 {
+        self.degree
+    }
+
+    /// Returns the chromatic alteration of the root degree in semitones.
+    ///
+    /// `b7` returns `-1` and `#4` returns `1`.
+    pub fn accidental(&self) -> i8 {
+        self.accidental
+    }
+
+    /// Returns the chord quality.
+    pub fn quality(&self) -> ChordQuality {
+        self.quality
+    }
+
+    /// Returns the slash-bass degree and its alteration, if any.
+    pub fn bass(&self) -> Option<(u8, i8)> {
+        self.bass
+    }
+
+    /// Returns `true` for a diamond, a chord held and left to ring.
+    pub fn is_diamond(&self) -> bool {
+        self.diamond
+    }
+
+    /// Returns `true` for a push, a chord anticipating its beat.
+    pub fn is_push(&self) -> bool {
+        self.push
+    }
+
+    /// Returns the number with its diamond mark set or cleared.
+    pub fn with_diamond(mut self, diamond: bool) -> Self {
+        self.diamond = diamond;
+        self
+    }
+
+    /// Returns the number with its push mark set or cleared.
+    pub fn with_push(mut self, push: bool) -> Self {
+        self.push = push;
+        self
+    }
+
+    /// Spells the number as a chord symbol in a key.
+    pub fn to_chord_symbol(&self, key: &Key) -> Result<ChordSymbol> {
+        let root = pitch_for_degree(key, self.degree, self.accidental)?;
+        let bass = self
+            .bass
+            .map(|(degree, accidental)| pitch_for_degree(key, degree, accidental))
+            .transpose()?;
+
+        Ok(ChordSymbol::new(
+            root,
+            self.quality,
+            self.extensions.iter().copied(),
+            self.alterations.iter().cloned(),
+            bass,
+        )
+        .with_additions(self.additions.iter().cloned())
+        .with_omissions(self.omissions.iter().copied()))
+    }
+
+    /// Realizes the number as a chord in a key.
+    pub fn to_chord(&self, key: &Key) -> Result<Chord> {
+        self.to_chord_symbol(key)?.to_chord()
+    }
+
+    fn suffix(&self) -> String {
+        // Quality text is key independent, so any root will do.
+        let template = ChordSymbol::new(
+            Pitch::from_name("C").expect("C is a valid pitch"),
+            self.quality,
+            self.extensions.iter().copied(),
+            self.alterations.iter().cloned(),
+            None,
+        )
+        .with_additions(self.additions.iter().cloned())
+        .with_omissions(self.omissions.iter().copied());
+        canonical_suffix(self.quality, template.suffix(ChordSymbolStyle::Nashville))
+    }
+}
+
+impl fmt::Display for NashvilleNumber {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.push {
+            formatter.write_str("^")?;
+        }
+        if self.diamond {
+            formatter.write_str("<")?;
+        }
+        write!(
+            formatter,
+            "{}{}{}",
+            accidental_prefix(self.accidental),
+            self.degree,
+            self.suffix()
+        )?;
+        if let Some((degree, accidental)) = self.bass {
+            write!(formatter, "/{}{degree}", accidental_prefix(accidental))?;
+        }
+        if self.diamond {
+            formatter.write_str(">")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for NashvilleNumber {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        Self::parse(value)
+    }
+}
+
+impl TryFrom<&str> for NashvilleNumber {
+    type Error = Error;
+
+    fn try_from(value: &str) -> Result<Self> {
+        Self::parse(value)
+    }
+}
+
+impl TryFrom<String> for NashvilleNumber {
+    type Error = Error;
+
+    fn try_from(value: String) -> Result<Self> {
+        Self::parse(&value)
+    }
+}
+
+/// Splits `b7sus` into its accidental, degree and suffix.
+fn split_degree(value: &str) -> Option<(i8, u8, &str)> {
+    let mut accidental = 0;
+    let mut rest = value.trim();
+    loop {
+        if let Some(stripped) = rest.strip_prefix(['b', '\u{266d}']) {
+            accidental -= 1;
+            rest = stripped;
+        } else if let Some(stripped) = rest.strip_prefix(['#', '\u{266f}']) {
+            accidental += 1;
+            rest = stripped;
+        } else {
+            break;
+        }
+    }
+
+    let degree = rest.chars().next()?.to_digit(10)? as u8;
+    if !(1..=7).contains(&degree) {
+        return None;
+    }
+    Some((accidental, degree, &rest[1..]))
+}
+
+/// A chord-symbol figure on C that carries a Nashville suffix.
+fn template_figure(suffix: &str) -> String {
+    let suffix = match suffix.strip_prefix('-') {
+        Some(rest) => format!("m{rest}"),
+        None => suffix.to_string(),
+    };
+    // An altered ninth, eleventh or thirteenth implies a dominant seventh,
+    // as in `5b9`; other alterations go in parentheses, since `C` followed
+    // by `b5` would read as C-flat.
+    let altered_upper = suffix.strip_prefix(['b', '#']).is_some_and(|rest| {
+        ["9", "11", "13"]
+            .iter()
+            .any(|degree| rest.starts_with(degree))
+    });
+    if altered_upper {
+        format!("C7{suffix}")
+    } else if suffix.starts_with(['b', '#']) {
+        format!("C({suffix})")
+    } else {
+        format!("C{suffix}")
+    }
+}
+
+/// The symbol's extensions, with the seventh a bare `ø` implies written out
+/// so that `7ø` and `7ø7` are the same number.
+fn implied_extensions(symbol: &ChordSymbol) -> Vec<u8> {
+    let mut extensions = symbol.extensions().to_vec();
+    if symbol.quality() == ChordQuality::HalfDiminished && extensions.is_empty() {
+        extensions.push(7);
+    }
+    extensions
+}
+
+/// Leaves out the sevenths a chart implies, so every chord has one spelling:
+/// a half-diminished seventh is `7ø`, and an altered ninth, eleventh or
+/// thirteenth brings its dominant seventh along, as in `5b9`.
+fn canonical_suffix(quality: ChordQuality, suffix: String) -> String {
+    match quality {
+        // Keep the 7 when a number or alteration follows, as in `2ø7b9`.
+        ChordQuality::HalfDiminished => match suffix.strip_prefix("\u{00f8}7") {
+            Some(rest)
+                if !rest.starts_with(|c: char| c.is_ascii_digit() || c == 'b' || c == '#') =>
+            {
+                format!("\u{00f8}{rest}")
+            }
+            _ => suffix,
+        },
+        ChordQuality::Dominant => match suffix.strip_prefix('7') {
+            Some(rest) if template_figure(rest).starts_with("C7") => rest.to_string(),
+            _ => suffix,
+        },
+        _ => suffix,
+    }
+}
+
+fn degree_in_key(pitch: &Pitch, key: &Key) -> Result<(u8, i8)> {
+    let step = pitch.step().step_to_dnn_offset();
+    let tonic_step = key.tonic_pitch().step().step_to_dnn_offset();
+    let degree = ((step - tonic_step).rem_euclid(7) + 1) as u8;
+    let diatonic = major_scale(key)?.pitch_from_degree(degree as usize)?;
+
+    let mut accidental = (pitch.pitch_class_number() as IntegerType
+        - diatonic.pitch_class_number() as IntegerType)
+        .rem_euclid(12);
+    if accidental > 6 {
+        accidental -= 12;
+    }
+    Ok((degree, accidental as i8))
+}
+
+fn pitch_for_degree(key: &Key, degree: u8, accidental: i8) -> Result<Pitch> {
+    let pitch = major_scale(key)?.pitch_from_degree(degree as usize)?;
+    if accidental == 0 {
+        return Ok(pitch);
+    }
+    // Augmented unisons keep the letter name: `b2` in C is D-flat, not C-sharp.
+    alter_pitch(&pitch, accidental)
+}
+
+/// The major scale on the key's tonic, which Nashville degrees count from
+/// whatever the mode.
+fn major_scale(key: &Key) -> Result<Key> {
+    Key::from_tonic_mode(&key.tonic_pitch().name(), "major")
+}
+
+fn accidental_prefix(accidental: i8) -> String {
+    let sign = if accidental < 0 { "b" } else { "#" };
+    sign.repeat(accidental.unsigned_abs() as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn symbols(chart: &str, key: &Key) -> Vec<String> {
+        chart
+            .split_whitespace()
+            .map(|number| {
+                NashvilleNumber::parse(number)
+                    .unwrap()
+                    .to_chord_symbol(key)
+                    .unwrap()
+                    .to_string()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn reads_charts_in_any_key() {
+        let chart = "1 4/6 5sus b7 2m7 <1> ^5";
+        let c_major = Key::from_tonic_mode("C", "major").unwrap();
+        let g_major = Key::from_tonic_mode("G", "major").unwrap();
+        let e_flat_major = Key::from_tonic_mode("E-", "major").unwrap();
+
+        assert_eq!(
+            symbols(chart, &c_major),
+            vec!["C", "F/A", "Gsus4", "Bb", "Dm7", "C", "G"]
+        );
+        assert_eq!(
+            symbols(chart, &g_major),
+            vec!["G", "C/E", "Dsus4", "F", "Am7", "G", "D"]
+        );
+        assert_eq!(
+            symbols(chart, &e_flat_major),
+            vec!["Eb", "Ab/C", "Bbsus4", "Db", "Fm7", "Eb", "Bb"]
+        );
+    }
+
+    #[test]
+    fn parses_marks_and_renders_them_back() {
+        for text in [
+            "1",
+            "4/6",
+            "5sus",
+            "b7",
+            "2m7",
+            "<1>",
+            "^5",
+            "^<4maj7/3>",
+            "5/b7",
+        ] {
+            let number = NashvilleNumber::parse(text).unwrap();
+            assert_eq!(number.to_string(), text);
+        }
+
+        let diamond = NashvilleNumber::parse("<1>").unwrap();
+        assert!(diamond.is_diamond());
+        assert!(!diamond.is_push());
+        let push = NashvilleNumber::parse("^2-7").unwrap();
+        assert!(push.is_push());
+        assert_eq!(push.quality(), ChordQuality::Minor);
+        assert_eq!(push.to_string(), "^2m7");
+        assert_eq!(NashvilleNumber::parse("4/6").unwrap().bass(), Some((6, 0)));
+
+        // An altered ninth implies the dominant seventh.
+        let key = Key::from_tonic_mode("C", "major").unwrap();
+        let altered = NashvilleNumber::parse("5b9").unwrap();
+        assert_eq!(altered.quality(), ChordQuality::Dominant);
+        assert_eq!(altered.to_string(), "5b9");
+        assert_eq!(NashvilleNumber::parse("57b9").unwrap(), altered);
+        assert_eq!(altered.to_chord_symbol(&key).unwrap().to_string(), "G7b9");
+    }
+
+    #[test]
+    fn converts_from_chord_symbols_and_chords() {
+        let key = Key::from_tonic_mode("C", "major").unwrap();
+        for (figure, expected) in [
+            ("C", "1"),
+            ("F/A", "4/6"),
+            ("G7sus4", "57sus"),
+            ("Bb", "b7"),
+            ("Dm7", "2m7"),
+            ("Db", "b2"),
+            ("F#m7b5", "#4\u{00f8}"),
+        ] {
+            let symbol = ChordSymbol::parse(figure).unwrap();
+            let number = NashvilleNumber::from_chord_symbol(&symbol, &key).unwrap();
+            assert_eq!(number.to_string(), expected, "{figure}");
+            assert_eq!(NashvilleNumber::parse(expected).unwrap(), number);
+            assert_eq!(
+                number.to_chord(&key).unwrap().pitch_classes(),
+                symbol.to_chord().unwrap().pitch_classes()
+            );
+        }
+
+        let dominant = Chord::new("G3 B3 D4 F4").unwrap();
+        let number = NashvilleNumber::from_chord(&dominant, &key)
+            .unwrap()
+            .unwrap();
+        assert_eq!(number.to_string(), "57");
+    }
+
+    #[test]
+    fn every_quality_has_one_spelling_that_round_trips() {
+        let key = Key::from_tonic_mode("C", "major").unwrap();
+        let cases = [
+            ("1", ChordQuality::Major),
+            ("4maj7", ChordQuality::Major),
+            ("2m", ChordQuality::Minor),
+            ("6m7", ChordQuality::Minor),
+            ("57", ChordQuality::Dominant),
+            ("59", ChordQuality::Dominant),
+            ("5b9", ChordQuality::Dominant),
+            ("5#9b13", ChordQuality::Dominant),
+            ("57b5", ChordQuality::Dominant),
+            ("7\u{00b0}", ChordQuality::Diminished),
+            ("7\u{00b0}7", ChordQuality::Diminished),
+            ("1+", ChordQuality::Augmented),
+            ("7\u{00f8}", ChordQuality::HalfDiminished),
+            ("2\u{00f8}9", ChordQuality::HalfDiminished),
+            ("5sus2", ChordQuality::Suspended2),
+            ("5sus", ChordQuality::Suspended4),
+            ("57sus", ChordQuality::Suspended4),
+            ("15", ChordQuality::Power),
+            ("1pedal", ChordQuality::Pedal),
+            ("b2N6", ChordQuality::Neapolitan),
+            ("b6It+6", ChordQuality::ItalianAugmentedSixth),
+            ("b6Fr+6", ChordQuality::FrenchAugmentedSixth),
+            ("b6Gr+6", ChordQuality::GermanAugmentedSixth),
+            ("4tristan", ChordQuality::Tristan),
+        ];
+        for (text, quality) in cases {
+            let number = NashvilleNumber::parse(text).unwrap();
+            assert_eq!(number.quality(), quality, "{text}");
+            assert_eq!(number.to_string(), text);
+            let symbol = number.to_chord_symbol(&key).unwrap();
+            assert_eq!(
+                NashvilleNumber::from_chord_symbol(&symbol, &key).unwrap(),
+                number,
+                "{text}"
+            );
+        }
+
+        for (text, canonical) in [
+            ("7\u{00f8}7", "7\u{00f8}"),
+            ("7m7b5", "7\u{00f8}"),
+            ("57b9", "5b9"),
+            ("2-7", "2m7"),
+            ("57sus4", "57sus"),
+        ] {
+            let number = NashvilleNumber::parse(text).unwrap();
+            assert_eq!(number, NashvilleNumber::parse(canonical).unwrap(), "{text}");
+            assert_eq!(number.to_string(), canonical);
+        }
+
+        // The exhaustive match fails to compile when a quality is added
+        // without a case above.
+        for (_, quality) in cases {
+            match quality {
+                ChordQuality::Major
+                | ChordQuality::Minor
+                | ChordQuality::Dominant
+                | ChordQuality::Diminished
+                | ChordQuality::Augmented
+                | ChordQuality::HalfDiminished
+                | ChordQuality::Suspended2
+                | ChordQuality::Suspended4
+                | ChordQuality::Power
+                | ChordQuality::Pedal
+                | ChordQuality::Neapolitan
+                | ChordQuality::ItalianAugmentedSixth
+                | ChordQuality::FrenchAugmentedSixth
+                | ChordQuality::GermanAugmentedSixth
+                | ChordQuality::Tristan => {}
+            }
+        }
+    }
+
+    #[test]
+    fn minor_keys_count_from_the_minor_tonic() {
+        let key = Key::from_tonic_mode("A", "minor").unwrap();
+        assert_eq!(
+            symbols("1m 4m 5 b6 b7 b3 4m/b7", &key),
+            vec!["Am", "Dm", "E", "F", "G", "C", "Dm/G"]
+        );
+        let relative_major = ChordSymbol::parse("C").unwrap();
+        let number = NashvilleNumber::from_chord_symbol(&relative_major, &key).unwrap();
+        assert_eq!(number.to_string(), "b3");
+    }
+
+    #[test]
+    fn rejects_invalid_numbers() {
+        assert!(NashvilleNumber::parse("").is_err());
+        assert!(NashvilleNumber::parse("8").is_err());
+        assert!(NashvilleNumber::parse("Cmaj7").is_err());
+        assert!(NashvilleNumber::parse("4/x").is_err());
+    }
+}
@@ -0,0 +1,579 @@
+//! Neo-Riemannian transformations and Tonnetz navigation.
+//!
+//! The three basic transformations each hold two notes of a major or minor
+//! triad and move the third by step: [`NeoRiemannianTransform::Parallel`]
+//! (C major ↔ C minor), [`NeoRiemannianTransform::LeadingTone`] (C major ↔
+//! E minor) and [`NeoRiemannianTransform::Relative`] (C major ↔ A minor).
+//! N, S and H are the usual compounds of these.
+//!
+//! [`Chord`] triads are transformed note by note, so voicing, doublings and
+//! spelling carry over. [`Tonnetz`] works with abstract [`TonnetzTriad`]s in
+//! any equal temperament.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt,
+    str::FromStr,
+};
+
+use crate::{
+    chord::Chord,
+    defaults::{FloatType, IntegerType, UnsignedIntegerType},
+    error::{Error, Result},
+    interval::Interval,
+    note::Note,
+    tuningsystem::TuningSystem,
+};
+
+/// A neo-Riemannian transformation between major and minor triads.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum NeoRiemannianTransform {
+    /// P: swaps a triad with the one sharing its root and fifth.
+    Parallel,
+    /// L: swaps a triad with the one sharing its major third.
+    LeadingTone,
+    /// R: swaps a triad with the one sharing its minor third.
+    Relative,
+    /// N (Nebenverwandt), R then L then P: C major ↔ F minor.
+    Nebenverwandt,
+    /// S (Slide), L then P then R: C major ↔ C♯ minor.
+    Slide,
+    /// H (hexatonic pole), L then P then L: C major ↔ A♭ minor.
+    HexatonicPole,
+}
+
+impl NeoRiemannianTransform {
+    /// The three basic transformations.
+    pub const BASIC: [Self; 3] = [Self::Parallel, Self::LeadingTone, Self::Relative];
+
+    /// Every transformation, basic ones first.
+    pub const ALL: [Self; 6] = [
+        Self::Parallel,
+        Self::LeadingTone,
+        Self::Relative,
+        Self::Nebenverwandt,
+        Self::Slide,
+        Self::HexatonicPole,
+    ];
+
+    /// Returns the transformation's letter, such as `"P"`.
+    pub fn letter(self) -> &'static str {
+        match self {
+            Self::Parallel => "P",
+            Self::LeadingTone => "L",
+            Self::Relative => "R",
+            Self::Nebenverwandt => "N",
+            Self::Slide => "S",
+            Self::HexatonicPole => "H",
+        }
+    }
+
+    /// Returns the basic transformations this one applies, in order.
+    pub fn components(self) -> &'static [Self] {
+        match self {
+            Self::Parallel => &[Self::Parallel],
+            Self::LeadingTone => &[Self::LeadingTone],
+            Self::Relative => &[Self::Relative],
+            Self::Nebenverwandt => &[Self::Relative, Self::LeadingTone, Self::Parallel],
+            Self::Slide => &[Self::LeadingTone, Self::Parallel, Self::Relative],
+            Self::HexatonicPole => &[Self::LeadingTone, Self::Parallel, Self::LeadingTone],
+        }
+    }
+
+    /// Parses a sequence of letters such as `"PLR"` or `"P L R"`, applied
+    /// left to right.
+    pub fn parse_sequence(sequence: &str) -> Result<Vec<Self>> {
+        sequence
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .map(|c| c.to_string().parse())
+            .collect()
+    }
+
+    /// Applies the transformation to a major or minor triad.
+    ///
+    /// Each note moves by its role, so octaves, doublings and spellings are
+    /// kept: P on C–E–G gives C–E♭–G, and L gives B–E–G.
+    pub fn apply(self, chord: &Chord) -> Result<Chord> {
+        self.components()
+            .iter()
+            .try_fold(chord.clone(), |chord, step| step.apply_basic(&chord))
+    }
+
+    fn apply_basic(self, chord: &Chord) -> Result<Chord> {
+        let triad = TonnetzTriad::from_chord(chord)?;
+        // The interval each role moves by: root, third, fifth.
+        let motions = match (triad.quality, self) {
+            (TriadQuality::Major, Self::Parallel) => [None, Some("-a1"), None],
+            (TriadQuality::Major, Self::LeadingTone) => [Some("-m2"), None, None],
+            (TriadQuality::Major, Self::Relative) => [None, None, Some("M2")],
+            (TriadQuality::Minor, Self::Parallel) => [None, Some("a1"), None],
+            (TriadQuality::Minor, Self::LeadingTone) => [None, None, Some("m2")],
+            (TriadQuality::Minor, Self::Relative) => [Some("-M2"), None, None],
+            _ => unreachable!("compound transformations are applied by components"),
+        };
+        let third = match triad.quality {
+            TriadQuality::Major => 4,
+            TriadQuality::Minor => 3,
+        };
+        let notes = chord
+            .notes()
+            .iter()
+            .map(|note| {
+                let role = match (note.pitch().pitch_class_number() + 12 - triad.root as u8) % 12 {
+                    0 => 0,
+                    7 => 2,
+                    pc if pc == third => 1,
+                    _ => unreachable!("the chord was identified as this triad"),
+                };
+                match motions[role] {
+                    Some(name) => Interval::from_name(name)?.transpose_note(note),
+                    None => Ok(note.clone()),
+                }
+            })
+            .collect::<Result<Vec<Note>>>()?;
+        let transformed = Chord::new(notes)?;
+        Ok(match chord.duration() {
+            Some(duration) => transformed.with_duration(duration.clone()),
+            None => transformed,
+        })
+    }
+}
+
+impl fmt::Display for NeoRiemannianTransform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.letter())
+    }
+}
+
+impl FromStr for NeoRiemannianTransform {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|transform| transform.letter().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| Error::Chord(format!("unknown neo-Riemannian transformation {s:?}")))
+    }
+}
+
+impl Chord {
+    /// Applies a neo-Riemannian transformation; see
+    /// [`NeoRiemannianTransform::apply`].
+    pub fn neo_riemannian(&self, transform: NeoRiemannianTransform) -> Result<Chord> {
+        transform.apply(self)
+    }
+
+    /// Applies a sequence of transformations such as `"PLR"`, left to right.
+    pub fn neo_riemannian_sequence(&self, sequence: &str) -> Result<Chord> {
+        NeoRiemannianTransform::parse_sequence(sequence)?
+            .into_iter()
+            .try_fold(self.clone(), |chord, transform| transform.apply(&chord))
+    }
+}
+
+/// Finds the shortest sequence of P, L and R leading from one triad to
+/// another.
+///
+/// Both chords must be major or minor triads. Of several shortest paths, the
+/// one trying P before L before R at each step is returned.
+pub fn shortest_transform_path(from: &Chord, to: &Chord) -> Result<Vec<NeoRiemannianTransform>> {
+    Tonnetz::default()
+        .shortest_path(
+            TonnetzTriad::from_chord(from)?,
+            TonnetzTriad::from_chord(to)?,
+            &NeoRiemannianTransform::BASIC,
+        )
+        .ok_or_else(|| Error::Chord("no transformation path between the triads".to_string()))
+}
+
+/// Major or minor.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TriadQuality {
+    /// Major third below minor third.
+    Major,
+    /// Minor third below major third.
+    Minor,
+}
+
+/// A major or minor triad on a Tonnetz, identified by its root step.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TonnetzTriad {
+    root: UnsignedIntegerType,
+    quality: TriadQuality,
+}
+
+impl TonnetzTriad {
+    /// Creates a major triad on `root`.
+    pub fn major(root: UnsignedIntegerType) -> Self {
+        Self {
+            root,
+            quality: TriadQuality::Major,
+        }
+    }
+
+    /// Creates a minor triad on `root`.
+    pub fn minor(root: UnsignedIntegerType) -> Self {
+        Self {
+            root,
+            quality: TriadQuality::Minor,
+        }
+    }
+
+    /// Identifies a twelve-tone major or minor triad, the chords
+    /// [`Chord::common_name`] calls `"major triad"` and `"minor triad"`.
+    pub fn from_chord(chord: &Chord) -> Result<Self> {
+        let set = chord.pitch_class_set();
+        if set.len() == 3 {
+            for root in 0..12u8 {
+                if set.contains(root) && set.contains((root + 7) % 12) {
+                    if set.contains((root + 4) % 12) {
+                        return Ok(Self::major(root.into()));
+                    }
+                    if set.contains((root + 3) % 12) {
+                        return Ok(Self::minor(root.into()));
+                    }
+                }
+            }
+        }
+        Err(Error::Chord(format!(
+            "{} is not a major or minor triad",
+            chord.common_name()
+        )))
+    }
+
+    /// Returns the root step.
+    pub fn root(&self) -> UnsignedIntegerType {
+        self.root
+    }
+
+    /// Returns whether the triad is major or minor.
+    pub fn quality(&self) -> TriadQuality {
+        self.quality
+    }
+}
+
+/// A triangular pitch lattice with perfect fifths along one axis and major
+/// thirds along the other, in some equal division of the octave.
+///
+/// Coordinates `(x, y)` name the pitch class `x` fifths and `y` major thirds
+/// above step 0.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Tonnetz {
+    divisions: UnsignedIntegerType,
+    fifth: UnsignedIntegerType,
+    major_third: UnsignedIntegerType,
+}
+
+impl Default for Tonnetz {
+    fn default() -> Self {
+        Self {
+            divisions: 12,
+            fifth: 7,
+            major_third: 4,
+        }
+    }
+}
+
+impl Tonnetz {
+    /// Creates a Tonnetz from explicit step sizes.
+    ///
+    /// The major third must be smaller than the fifth, which must be smaller
+    /// than the octave.
+    pub fn new(
+        divisions: UnsignedIntegerType,
+        fifth: UnsignedIntegerType,
+        major_third: UnsignedIntegerType,
+    ) -> Result<Self> {
+        if !(0 < major_third && major_third < fifth && fifth < divisions) {
+            return Err(Error::TuningSystem(format!(
+                "a Tonnetz needs 0 < major third < fifth < octave, got {major_third} < {fifth} < {divisions}"
+            )));
+        }
+        Ok(Self {
+            divisions,
+            fifth,
+            major_third,
+        })
+    }
+
+    /// Creates the Tonnetz of an equal temperament, using its closest
+    /// approximations of 3:2 and 5:4.
+    pub fn for_tuning_system(tuning_system: TuningSystem) -> Result<Self> {
+        let TuningSystem::EqualTemperament { octave_size } = tuning_system else {
+            return Err(Error::TuningSystem(format!(
+                "a Tonnetz needs an equal temperament, not {}",
+                tuning_system.display_name()
+            )));
+        };
+        let steps = |ratio: FloatType| {
+            (FloatType::from(octave_size) * ratio.log2()).round() as UnsignedIntegerType
+        };
+        Self::new(octave_size, steps(1.5), steps(1.25))
+    }
+
+    /// Returns the number of steps in the octave.
+    pub fn divisions(&self) -> UnsignedIntegerType {
+        self.divisions
+    }
+
+    /// Returns the size of the fifth in steps.
+    pub fn fifth(&self) -> UnsignedIntegerType {
+        self.fifth
+    }
+
+    /// Returns the size of the major third in steps.
+    pub fn major_third(&self) -> UnsignedIntegerType {
+        self.major_third
+    }
+
+    /// Returns the size of the minor third in steps.
+    pub fn minor_third(&self) -> UnsignedIntegerType {
+        self.fifth - self.major_third
+    }
+
+    /// Returns the steps of a triad as root, third and fifth.
+    pub fn triad_steps(&self, triad: TonnetzTriad) -> [UnsignedIntegerType; 3] {
+        let third = match triad.quality {
+            TriadQuality::Major => self.major_third,
+            TriadQuality::Minor => self.minor_third(),
+        };
+        [triad.root, triad.root + third, triad.root + self.fifth].map(|step| step % self.divisions)
+    }
+
+    /// Returns the coordinates closest to the origin for a pitch-class step.
+    ///
+    /// Among equally close positions, the one with fewer thirds is chosen,
+    /// then non-negative coordinates. Returns `None` when the lattice never
+    /// reaches the step, as when the fifth and third share a factor with the
+    /// octave.
+    pub fn pitch_class_coordinates(
+        &self,
+        step: UnsignedIntegerType,
+    ) -> Option<(IntegerType, IntegerType)> {
+        let n = self.divisions as IntegerType;
+        let target = (step % self.divisions) as IntegerType;
+        (-n..=n)
+            .flat_map(|x| (-n..=n).map(move |y| (x, y)))
+            .filter(|(x, y)| {
+                (x * self.fifth as IntegerType + y * self.major_third as IntegerType).rem_euclid(n)
+                    == target
+            })
+            .min_by_key(|(x, y)| (x.abs() + y.abs(), y.abs(), *x < 0, *y < 0))
+    }
+
+    /// Returns the triangle a triad occupies: root, fifth and third.
+    ///
+    /// The root takes its [`Self::pitch_class_coordinates`]; a major third
+    /// lies one step up the thirds axis from the root, and a minor third one
+    /// step down from the fifth.
+    pub fn triad_coordinates(
+        &self,
+        triad: TonnetzTriad,
+    ) -> Option<[(IntegerType, IntegerType); 3]> {
+        let (x, y) = self.pitch_class_coordinates(triad.root)?;
+        let third = match triad.quality {
+            TriadQuality::Major => (x, y + 1),
+            TriadQuality::Minor => (x + 1, y - 1),
+        };
+        Some([(x, y), (x + 1, y), third])
+    }
+
+    /// Applies a transformation to an abstract triad.
+    pub fn transform(
+        &self,
+        triad: TonnetzTriad,
+        transform: NeoRiemannianTransform,
+    ) -> TonnetzTriad {
+        transform.components().iter().fold(triad, |triad, step| {
+            let n = self.divisions;
+            let shift = |by: UnsignedIntegerType, up: bool| {
+                if up {
+                    (triad.root + by) % n
+                } else {
+                    (triad.root + n - by % n) % n
+                }
+            };
+            match (triad.quality, step) {
+                (TriadQuality::Major, NeoRiemannianTransform::Parallel) => {
+                    TonnetzTriad::minor(triad.root)
+                }
+                (TriadQuality::Major, NeoRiemannianTransform::LeadingTone) => {
+                    TonnetzTriad::minor(shift(self.major_third, true))
+                }
+                (TriadQuality::Major, NeoRiemannianTransform::Relative) => {
+                    TonnetzTriad::minor(shift(self.minor_third(), false))
+                }
+                (TriadQuality::Minor, NeoRiemannianTransform::Parallel) => {
+                    TonnetzTriad::major(triad.root)
+                }
+                (TriadQuality::Minor, NeoRiemannianTransform::LeadingTone) => {
+                    TonnetzTriad::major(shift(self.major_third, false))
+                }
+                (TriadQuality::Minor, NeoRiemannianTransform::Relative) => {
+                    TonnetzTriad::major(shift(self.minor_third(), true))
+                }
+                _ => unreachable!("components are basic transformations"),
+            }
+        })
+    }
+
+    /// Finds a shortest sequence of `transforms` from one triad to another,
+    /// trying them in the given order at each step.
+    ///
+    /// Returns `None` when `to` cannot be reached.
+    pub fn shortest_path(
+        &self,
+        from: TonnetzTriad,
+        to: TonnetzTriad,
+        transforms: &[NeoRiemannianTransform],
+    ) -> Option<Vec<NeoRiemannianTransform>> {
+        let normalize = |triad: TonnetzTriad| TonnetzTriad {
+            root: triad.root % self.divisions,
+            ..triad
+        };
+        let (from, to) = (normalize(from), normalize(to));
+        let mut previous = HashMap::from([(from, None)]);
+        let mut queue = VecDeque::from([from]);
+        while let Some(triad) = queue.pop_front() {
+            if triad == to {
+                let mut path = Vec::new();
+                let mut current = triad;
+                while let Some(Some((before, transform))) = previous.get(&current) {
+                    path.push(*transform);
+                    current = *before;
+                }
+                path.reverse();
+                return Some(path);
+            }
+            for transform in transforms {
+                let next = self.transform(triad, *transform);
+                previous.entry(next).or_insert_with(|| {
+                    queue.push_back(next);
+                    Some((triad, *transform))
+                });
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(chord: &Chord) -> Vec<String> {
+        chord
+            .pitches()
+            .iter()
+            .map(|p| p.name_with_octave())
+            .collect()
+    }
+
+    #[test]
+    fn transforms_chords_by_voice() {
+        let c_major = Chord::new("C4 E4 G4 C5").unwrap();
+        let p = c_major
+            .neo_riemannian(NeoRiemannianTransform::Parallel)
+            .unwrap();
+        assert_eq!(names(&p), vec!["C4", "E-4", "G4", "C5"]);
+        let l = c_major
+            .neo_riemannian(NeoRiemannianTransform::LeadingTone)
+            .unwrap();
+        assert_eq!(names(&l), vec!["B3", "E4", "G4", "B4"]);
+        let r = c_major
+            .neo_riemannian(NeoRiemannianTransform::Relative)
+            .unwrap();
+        assert_eq!(names(&r), vec!["C4", "E4", "A4", "C5"]);
+        assert_eq!(
+            names(&p.neo_riemannian(NeoRiemannianTransform::Parallel).unwrap()),
+            names(&c_major)
+        );
+
+        let triad = Chord::new("C4 E4 G4").unwrap();
+        let root = |transform| {
+            let chord = triad.neo_riemannian(transform).unwrap();
+            let triad = TonnetzTriad::from_chord(&chord).unwrap();
+            (triad.root(), triad.quality())
+        };
+        assert_eq!(
+            root(NeoRiemannianTransform::Nebenverwandt),
+            (5, TriadQuality::Minor)
+        );
+        assert_eq!(
+            root(NeoRiemannianTransform::Slide),
+            (1, TriadQuality::Minor)
+        );
+        assert_eq!(
+            root(NeoRiemannianTransform::HexatonicPole),
+            (8, TriadQuality::Minor)
+        );
+        assert_eq!(
+            names(&triad.neo_riemannian_sequence("PLR").unwrap()),
+            names(&triad.neo_riemannian_sequence("p l r").unwrap())
+        );
+        assert!(
+            Chord::new("C4 E4 G#4")
+                .unwrap()
+                .neo_riemannian(NeoRiemannianTransform::Parallel)
+                .is_err()
+        );
+        assert!("Q".parse::<NeoRiemannianTransform>().is_err());
+    }
+
+    #[test]
+    fn finds_shortest_paths() {
+        let c_major = Chord::new("C E G").unwrap();
+        let path = shortest_transform_path(&c_major, &Chord::new("A- C E-").unwrap()).unwrap();
+        assert_eq!(path.len(), 2);
+        let path = shortest_transform_path(&c_major, &Chord::new("F# A# C#").unwrap()).unwrap();
+        assert_eq!(path.len(), 4);
+        let reached = path.iter().fold(c_major.clone(), |chord, transform| {
+            chord.neo_riemannian(*transform).unwrap()
+        });
+        assert_eq!(reached.pitch_classes(), vec![1, 6, 10]);
+        assert!(
+            shortest_transform_path(&c_major, &c_major)
+                .unwrap()
+                .is_empty()
+        );
+
+        let tonnetz = Tonnetz::default();
+        let hexatonic = tonnetz
+            .shortest_path(
+                TonnetzTriad::major(0),
+                TonnetzTriad::minor(8),
+                &NeoRiemannianTransform::ALL,
+            )
+            .unwrap();
+        assert_eq!(hexatonic.len(), 1);
+        assert_eq!(hexatonic[0], NeoRiemannianTransform::HexatonicPole);
+    }
+
+    #[test]
+    fn tonnetz_coordinates() {
+        let tonnetz = Tonnetz::default();
+        assert_eq!(tonnetz.pitch_class_coordinates(0), Some((0, 0)));
+        assert_eq!(tonnetz.pitch_class_coordinates(7), Some((1, 0)));
+        assert_eq!(tonnetz.pitch_class_coordinates(4), Some((0, 1)));
+        assert_eq!(tonnetz.pitch_class_coordinates(3), Some((1, -1)));
+        assert_eq!(
+            tonnetz.triad_coordinates(TonnetzTriad::minor(9)),
+            Some([(-1, 1), (0, 1), (0, 0)])
+        );
+
+        let nineteen =
+            Tonnetz::for_tuning_system(TuningSystem::EqualTemperament { octave_size: 19 }).unwrap();
+        assert_eq!((nineteen.fifth(), nineteen.major_third()), (11, 6));
+        let minor = nineteen.transform(TonnetzTriad::major(0), NeoRiemannianTransform::Relative);
+        assert_eq!(minor, TonnetzTriad::minor(14));
+        assert_eq!(nineteen.triad_steps(minor), [14, 0, 6]);
+        assert!(nineteen.pitch_class_coordinates(1).is_some());
+        assert!(Tonnetz::for_tuning_system(TuningSystem::PythagoreanTuning).is_err());
+        assert!(Tonnetz::new(12, 4, 7).is_err());
+    }
+}
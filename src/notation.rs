@@ -0,0 +1,1148 @@
+//! Rhythm spelling for notation export.
+//!
+//! A [`Stream`] records when each element sounds, not how it is written. For a
+//! score, durations must be cut at bar lines, tied across the beats of the
+//! meter where a single note would hide them, beamed in the meter's groups and
+//! bracketed where they are tuplets. [`spell_rhythm`] does this once for any
+//! writer: ABC, MusicXML and LilyPond all want the same measures.
+//!
+//! The rules follow common engraving practice. A note may cross a metrical
+//! boundary only if it starts on a boundary at least as strong, as the
+//! [`TimeSignature::accent_sequence`] weighs them, so a dotted quarter on the
+//! downbeat of `4/4` stays whole but one on the second beat is tied across
+//! the middle of the bar. Notes tied in the input are joined before spelling,
+//! so a quarter tied to an eighth on the downbeat becomes a dotted quarter.
+
+use crate::{
+    defaults::{FloatType, FractionType, IntegerType},
+    duration::{Duration, DurationType, Tuplet, fraction_from_float},
+    error::{Error, Result},
+    meter::{MeterSequence, TimeSignature},
+    rest::Rest,
+    stream::{Stream, StreamElement, fraction_to_float},
+    tie::Tie,
+};
+
+/// The role of a note in the beam at one level: the eighth-note beam, the
+/// sixteenth-note beam and so on.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Beam {
+    /// The beam starts at this note.
+    Start,
+    /// The beam runs through this note.
+    Continue,
+    /// The beam ends at this note.
+    Stop,
+    /// A beamlet pointing back at the previous note.
+    PartialLeft,
+    /// A beamlet pointing on at the next note.
+    PartialRight,
+}
+
+impl Beam {
+    /// Returns the music21 beam type, such as `"start"` or `"partial-left"`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Start => "start",
+            Self::Continue => "continue",
+            Self::Stop => "stop",
+            Self::PartialLeft => "partial-left",
+            Self::PartialRight => "partial-right",
+        }
+    }
+}
+
+impl std::fmt::Display for Beam {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// An element's place under a tuplet bracket.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TupletBracket {
+    tuplet: Tuplet,
+    starts: bool,
+    stops: bool,
+}
+
+impl TupletBracket {
+    /// Returns the bracketed tuplet, the outermost one for nested tuplets.
+    pub fn tuplet(self) -> Tuplet {
+        self.tuplet
+    }
+
+    /// Returns whether the bracket opens at this element.
+    pub fn starts(self) -> bool {
+        self.starts
+    }
+
+    /// Returns whether the bracket closes at this element.
+    pub fn stops(self) -> bool {
+        self.stops
+    }
+}
+
+/// One element of a spelled measure.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SpelledElement {
+    offset: FloatType,
+    element: StreamElement,
+    beams: Vec<Beam>,
+    tuplet: Option<TupletBracket>,
+}
+
+impl SpelledElement {
+    /// Returns the offset from the start of the measure.
+    pub fn offset(&self) -> FloatType {
+        self.offset
+    }
+
+    /// Returns the exact offset from the start of the measure.
+    pub fn offset_fraction(&self) -> FractionType {
+        fraction_from_float(self.offset)
+    }
+
+    /// Returns the note, chord or rest, with a notatable duration and the
+    /// ties that join it to its neighbours.
+    pub fn element(&self) -> &StreamElement {
+        &self.element
+    }
+
+    /// Returns the element's beams, eighth-note level first; empty when the
+    /// element is not beamed.
+    pub fn beams(&self) -> &[Beam] {
+        &self.beams
+    }
+
+    /// Returns the tuplet bracket the element sits under, if any.
+    pub fn tuplet(&self) -> Option<TupletBracket> {
+        self.tuplet
+    }
+}
+
+/// One bar of spelled rhythm.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SpelledMeasure {
+    number: usize,
+    offset: FloatType,
+    elements: Vec<SpelledElement>,
+}
+
+impl SpelledMeasure {
+    /// Returns the one-based measure number.
+    pub fn number(&self) -> usize {
+        self.number
+    }
+
+    /// Returns the offset of the measure in the stream.
+    pub fn offset(&self) -> FloatType {
+        self.offset
+    }
+
+    /// Returns the measure's elements in order.
+    pub fn elements(&self) -> &[SpelledElement] {
+        &self.elements
+    }
+}
+
+/// A metrical boundary within a bar.
+struct Boundary {
+    offset: FractionType,
+    weight: FloatType,
+    beam: bool,
+}
+
+/// An element of a measure at its offset from the barline.
+type Placed = (FractionType, StreamElement);
+
+/// A run of consecutive elements under one tuplet.
+struct TupletRun {
+    tuplet: Tuplet,
+    first: usize,
+    last: usize,
+    full: bool,
+}
+
+/// A stretch of a measure written under one tuplet bracket.
+#[derive(Clone, Copy)]
+struct TupletWindow {
+    tuplet: Tuplet,
+    start: FractionType,
+    end: FractionType,
+}
+
+/// Spells the rhythm of `stream` in `time_signature`, bar by bar.
+///
+/// The stream must be a single voice: overlapping events are rejected, so
+/// chordify polyphony first. Gaps become rests, and tied notes of the same
+/// pitches are joined before being split again as the meter asks: at every
+/// bar line, across boundaries stronger than a note's start, and wherever
+/// a length needs more than one note value. The pieces are tied together.
+/// Notes under a tuplet are only split at bar lines, so a triplet quarter
+/// may cross a beat.
+///
+/// Beamable elements — eighths and shorter — are beamed within the groups of
+/// [`TimeSignature::beam_sequence`], broken by rests and by the edges of
+/// tuplet brackets. Consecutive elements of one tuplet are bracketed until
+/// the bracket is full, the tuplet changes or the bar ends. A run that stops
+/// short of a full bracket, as the pieces of a plain note cut at the beats of
+/// `5/6` do, is bracketed over the whole beats around it instead: the plain
+/// notes in those beats are written in the tuplet, tied where they cross the
+/// bracket's edges. The last measure is not padded to a full bar.
+pub fn spell_rhythm(
+    stream: &Stream,
+    time_signature: &TimeSignature,
+) -> Result<Vec<SpelledMeasure>> {
+    let bar = span(time_signature.numerator(), time_signature.denominator());
+    let boundaries = boundaries(time_signature)?;
+    let mut measures: Vec<Vec<(FractionType, StreamElement)>> = Vec::new();
+    for (start, element) in timeline(stream)? {
+        let length = element.quarter_length_fraction();
+        let mut pieces = Vec::new();
+        let mut cursor = start;
+        let end = start + length;
+        loop {
+            let index = bar_index(cursor, bar);
+            let bar_start = bar * FractionType::from(index as IntegerType);
+            let segment_end = if end < bar_start + bar {
+                end
+            } else {
+                bar_start + bar
+            };
+            let in_bar = cursor - bar_start;
+            let lengths = if segment_end == end && cursor == start && keeps_whole(&element) {
+                vec![length]
+            } else {
+                spell_span(in_bar, segment_end - bar_start, &boundaries, time_signature)?
+            };
+            let mut offset = in_bar;
+            for piece in lengths {
+                pieces.push((index, offset, piece));
+                offset += piece;
+            }
+            cursor = segment_end;
+            if cursor >= end {
+                break;
+            }
+        }
+        let elements = if pieces.len() == 1 {
+            vec![element]
+        } else {
+            let durations = pieces
+                .iter()
+                .map(|(_, _, piece)| Duration::from_fraction(*piece))
+                .collect::<Result<Vec<_>>>()?;
+            element.split_into(&durations)
+        };
+        for ((index, offset, _), element) in pieces.into_iter().zip(elements) {
+            if measures.len() <= index {
+                measures.resize_with(index + 1, Vec::new);
+            }
+            measures[index].push((offset, element));
+        }
+    }
+
+    let beam_groups = time_signature.beam_sequence();
+    let group_starts = fraction_offsets(&beam_groups, FractionType::from(0));
+    let beat_starts = fraction_offsets(&time_signature.beat_sequence(), FractionType::from(0));
+    measures
+        .into_iter()
+        .enumerate()
+        .map(|(index, elements)| {
+            let (elements, windows) = bracket_fragments(elements, &beat_starts)?;
+            let tuplets = bracket(&elements, &windows);
+            let beams = beam(&elements, &group_starts, &tuplets);
+            Ok(SpelledMeasure {
+                number: index + 1,
+                offset: fraction_to_float(bar * FractionType::from(index as IntegerType)),
+                elements: elements
+                    .into_iter()
+                    .zip(beams.into_iter().zip(tuplets))
+                    .map(|((offset, element), (beams, tuplet))| SpelledElement {
+                        offset: fraction_to_float(offset),
+                        element,
+                        beams,
+                        tuplet,
+                    })
+                    .collect(),
+            })
+        })
+        .collect()
+}
+
+impl Stream {
+    /// Spells the stream's rhythm in a time signature, bar by bar.
+    ///
+    /// See [`spell_rhythm`].
+    pub fn spell_rhythm(&self, time_signature: &TimeSignature) -> Result<Vec<SpelledMeasure>> {
+        spell_rhythm(self, time_signature)
+    }
+}
+
+/// Returns the stream as one gapless voice, with rests in the gaps and tied
+/// notes joined.
+fn timeline(stream: &Stream) -> Result<Vec<(FractionType, StreamElement)>> {
+    let mut placed: Vec<(FractionType, StreamElement)> = Vec::new();
+    let mut end = FractionType::from(0);
+    for event in stream.events() {
+        let start = event.offset_fraction();
+        let element = event.element();
+        if start < end {
+            return Err(Error::Notation(format!(
+                "rhythm spelling needs a single voice, but an event at {} overlaps the one \
+                 before; chordify the stream first",
+                event.offset()
+            )));
+        }
+        if start > end {
+            placed.push((
+                end,
+                StreamElement::Rest(Rest::new(Duration::from_fraction(start - end)?)),
+            ));
+        }
+        let length = element.quarter_length_fraction();
+        match placed.last_mut() {
+            Some((previous_start, previous))
+                if *previous_start + previous.quarter_length_fraction() == start
+                    && continues_tie(previous, element) =>
+            {
+                let joined = previous.quarter_length_fraction() + length;
+                let into_next = tie_of(element).is_some_and(Tie::holds_into_next);
+                *previous = rewritten(previous, Duration::from_fraction(joined)?, into_next);
+            }
+            _ => placed.push((start, element.clone())),
+        }
+        end = start + length;
+    }
+    Ok(placed)
+}
+
+/// Returns whether `next` carries on a tie from `previous` on the same
+/// pitches.
+fn continues_tie(previous: &StreamElement, next: &StreamElement) -> bool {
+    let ps = |element: &StreamElement| {
+        element
+            .pitches()
+            .iter()
+            .map(|pitch| pitch.ps())
+            .collect::<Vec<_>>()
+    };
+    !matches!(previous, StreamElement::Rest(_))
+        && tie_of(previous).is_some_and(Tie::holds_into_next)
+        && tie_of(next).is_some_and(Tie::holds_from_previous)
+        && ps(previous) == ps(next)
+}
+
+/// Returns the tie shared by every note of the element.
+fn tie_of(element: &StreamElement) -> Option<Tie> {
+    match element {
+        StreamElement::Note(note) => note.tie(),
+        StreamElement::Chord(chord) => {
+            let first = chord.notes().first()?.tie();
+            chord
+                .notes()
+                .iter()
+                .all(|note| note.tie() == first)
+                .then_some(first)
+                .flatten()
+        }
+        StreamElement::Rest(_) => None,
+    }
+}
+
+/// Returns `element` lasting `duration`, keeping whether it is held from the
+/// previous note and setting whether it is held into the next.
+fn rewritten(element: &StreamElement, duration: Duration, into_next: bool) -> StreamElement {
+    let from_previous = tie_of(element).is_some_and(Tie::holds_from_previous);
+    let tie = Tie::from_holds(from_previous, into_next);
+    match element {
+        StreamElement::Note(note) => {
+            let mut note = note.clone().with_duration(duration);
+            note.set_tie(tie);
+            StreamElement::Note(note)
+        }
+        StreamElement::Chord(chord) => {
+            let mut chord = chord.clone();
+            for note in &mut chord._notes {
+                note.set_tie(tie);
+            }
+            chord.set_duration(duration);
+            StreamElement::Chord(chord)
+        }
+        StreamElement::Rest(_) => StreamElement::Rest(Rest::new(duration)),
+    }
+}
+
+/// Returns whether an element that fits in its bar keeps its written
+/// duration: tuplet notes and grace notes are never split within a bar.
+fn keeps_whole(element: &StreamElement) -> bool {
+    let duration = element.duration().cloned().unwrap_or_default();
+    duration.quarter_length_fraction() == FractionType::from(0)
+        || (!duration.is_complex() && !duration.tuplets().is_empty())
+}
+
+/// Returns the lengths that spell the span from `start` to `end` of a bar.
+///
+/// A span is one note when its length is a single note value and it crosses
+/// no boundary stronger than its start. Otherwise it is cut at the strongest
+/// boundary inside it — a beam-group boundary, then the earliest, breaking
+/// ties — and failing any, into [`Duration::components`].
+fn spell_span(
+    start: FractionType,
+    end: FractionType,
+    boundaries: &[Boundary],
+    time_signature: &TimeSignature,
+) -> Result<Vec<FractionType>> {
+    let length = end - start;
+    let inside = boundaries
+        .iter()
+        .filter(|boundary| start < boundary.offset && boundary.offset < end)
+        .collect::<Vec<_>>();
+    let start_weight = time_signature.beat_strength(fraction_to_float(start))?;
+    let notatable = !Duration::from_fraction(length)?.is_complex();
+    if notatable
+        && inside
+            .iter()
+            .all(|boundary| boundary.weight <= start_weight)
+    {
+        return Ok(vec![length]);
+    }
+    let strongest = inside
+        .iter()
+        .fold(None::<&&Boundary>, |best, boundary| match best {
+            Some(best) if (best.weight, best.beam) >= (boundary.weight, boundary.beam) => {
+                Some(best)
+            }
+            _ => Some(boundary),
+        });
+    match strongest {
+        Some(boundary) => {
+            let mut lengths = spell_span(start, boundary.offset, boundaries, time_signature)?;
+            lengths.extend(spell_span(
+                boundary.offset,
+                end,
+                boundaries,
+                time_signature,
+            )?);
+            Ok(lengths)
+        }
+        None => Ok(Duration::from_fraction(length)?
+            .components()
+            .iter()
+            .map(Duration::quarter_length_fraction)
+            .collect()),
+    }
+}
+
+/// Returns every boundary inside a bar of `time_signature`: each node of its
+/// accent and beam trees except the downbeat, weighed by beat strength.
+fn boundaries(time_signature: &TimeSignature) -> Result<Vec<Boundary>> {
+    let mut offsets = Vec::new();
+    collect_offsets(
+        &time_signature.accent_sequence(),
+        FractionType::from(0),
+        &mut offsets,
+    );
+    let beam_starts = fraction_offsets(&time_signature.beam_sequence(), FractionType::from(0));
+    offsets.extend(beam_starts.iter().copied());
+    offsets.sort();
+    offsets.dedup();
+    offsets
+        .into_iter()
+        .filter(|offset| *offset > FractionType::from(0))
+        .map(|offset| {
+            Ok(Boundary {
+                offset,
+                weight: time_signature.beat_strength(fraction_to_float(offset))?,
+                beam: beam_starts.contains(&offset),
+            })
+        })
+        .collect()
+}
+
+/// Collects the offset of every node below `sequence`, which starts at
+/// `start`.
+fn collect_offsets(sequence: &MeterSequence, start: FractionType, out: &mut Vec<FractionType>) {
+    let mut offset = start;
+    for part in sequence.parts() {
+        out.push(offset);
+        collect_offsets(part, offset, out);
+        offset += span(part.numerator(), part.denominator());
+    }
+}
+
+/// Returns the exact offsets of the top-level parts of `sequence`, or of the
+/// sequence itself when it is a leaf.
+fn fraction_offsets(sequence: &MeterSequence, start: FractionType) -> Vec<FractionType> {
+    let mut offsets = vec![start];
+    let mut offset = start;
+    for part in sequence.parts() {
+        offset += span(part.numerator(), part.denominator());
+        offsets.push(offset);
+    }
+    if !sequence.is_leaf() {
+        offsets.pop();
+    }
+    offsets
+}
+
+/// Returns the quarter length of `numerator/denominator` whole notes.
+fn span(numerator: u32, denominator: u32) -> FractionType {
+    FractionType::new(4 * numerator as IntegerType, denominator as IntegerType)
+}
+
+/// Returns the zero-based bar holding `offset`.
+fn bar_index(offset: FractionType, bar: FractionType) -> usize {
+    let bars = offset / bar;
+    match (bars.numer(), bars.denom()) {
+        (Some(numerator), Some(denominator)) => (numerator / denominator) as usize,
+        _ => 0,
+    }
+}
+
+/// Returns how many beams an element carries: one for an eighth, two for a
+/// sixteenth, and none for quarters, longer values and rests.
+fn flags(element: &StreamElement) -> usize {
+    if matches!(element, StreamElement::Rest(_)) {
+        return 0;
+    }
+    let quarter = DurationType::ALL
+        .iter()
+        .position(|duration_type| *duration_type == DurationType::Quarter)
+        .expect("the quarter is a duration type");
+    element
+        .duration()
+        .cloned()
+        .unwrap_or_default()
+        .duration_type()
+        .filter(|duration_type| *duration_type != DurationType::Zero)
+        .and_then(|duration_type| {
+            DurationType::ALL
+                .iter()
+                .position(|candidate| *candidate == duration_type)
+        })
+        .map_or(0, |position| position.saturating_sub(quarter))
+}
+
+/// Returns the beams of each element of a measure.
+///
+/// Beams stay inside tuplet brackets, so no beam crosses a bracket's edge.
+fn beam(
+    elements: &[(FractionType, StreamElement)],
+    group_starts: &[FractionType],
+    tuplets: &[Option<TupletBracket>],
+) -> Vec<Vec<Beam>> {
+    let group = |offset: FractionType| {
+        group_starts
+            .iter()
+            .filter(|start| **start <= offset)
+            .count()
+    };
+    let mut opened = 0;
+    let bracket_ids = tuplets
+        .iter()
+        .map(|bracket| {
+            bracket.map(|bracket| {
+                opened += usize::from(bracket.starts());
+                opened
+            })
+        })
+        .collect::<Vec<_>>();
+    let mut beams = vec![Vec::new(); elements.len()];
+    let mut index = 0;
+    while index < elements.len() {
+        // A run starts at each beamable element and takes the beamable
+        // elements after it in the same beam group.
+        let run_start = index;
+        index += 1;
+        if flags(&elements[run_start].1) == 0 {
+            continue;
+        }
+        while index < elements.len()
+            && flags(&elements[index].1) > 0
+            && group(elements[index].0) == group(elements[run_start].0)
+            && bracket_ids[index] == bracket_ids[run_start]
+        {
+            index += 1;
+        }
+        let run = &elements[run_start..index];
+        if run.len() < 2 {
+            continue;
+        }
+        for (position, (_, element)) in run.iter().enumerate() {
+            let neighbour = |offset: Option<usize>| {
+                offset
+                    .and_then(|offset| run.get(offset))
+                    .map_or(0, |(_, element)| flags(element))
+            };
+            let before = neighbour(position.checked_sub(1));
+            let after = neighbour(Some(position + 1));
+            beams[run_start + position] = (1..=flags(element))
+                .map(|level| match (before >= level, after >= level) {
+                    (false, true) => Beam::Start,
+                    (true, true) => Beam::Continue,
+                    (true, false) => Beam::Stop,
+                    (false, false) if position == 0 => Beam::PartialRight,
+                    (false, false) => Beam::PartialLeft,
+                })
+                .collect();
+        }
+    }
+    beams
+}
+
+/// Returns the tuplet bracket of each element of a measure.
+///
+/// Each window is one bracket. Elsewhere a run of one tuplet is bracketed on
+/// its own.
+fn bracket(elements: &[Placed], windows: &[TupletWindow]) -> Vec<Option<TupletBracket>> {
+    let mut brackets = vec![None::<TupletBracket>; elements.len()];
+    let mut mark = |tuplet: Tuplet, first: usize, last: usize| {
+        for (index, bracket) in brackets.iter_mut().enumerate().take(last + 1).skip(first) {
+            *bracket = Some(TupletBracket {
+                tuplet,
+                starts: index == first,
+                stops: index == last,
+            });
+        }
+    };
+    let windowed = elements
+        .iter()
+        .map(|(offset, _)| {
+            windows
+                .iter()
+                .position(|window| window.start <= *offset && *offset < window.end)
+        })
+        .collect::<Vec<_>>();
+    for (position, window) in windows.iter().enumerate() {
+        let first = windowed.iter().position(|found| *found == Some(position));
+        let last = windowed.iter().rposition(|found| *found == Some(position));
+        if let (Some(first), Some(last)) = (first, last) {
+            mark(window.tuplet, first, last);
+        }
+    }
+    let outside = windowed.iter().map(Option::is_none).collect::<Vec<_>>();
+    for run in tuplet_runs(elements, &outside) {
+        mark(run.tuplet, run.first, run.last);
+    }
+    brackets
+}
+
+/// Returns the runs of consecutive elements under one tuplet, among the
+/// elements marked in `included`.
+///
+/// A run is full, and ends, once it spans `normal` of its shortest written
+/// value, as three triplet eighths span two eighths.
+fn tuplet_runs(elements: &[Placed], included: &[bool]) -> Vec<TupletRun> {
+    let mut runs: Vec<TupletRun> = Vec::new();
+    let mut open: Option<(FractionType, FractionType)> = None;
+    for (index, (_, element)) in elements.iter().enumerate() {
+        let tuplet = tuplet_of(element).filter(|_| included[index]);
+        if runs.last().is_some_and(|run| Some(run.tuplet) != tuplet) {
+            open = None;
+        }
+        let Some(tuplet) = tuplet else {
+            continue;
+        };
+        let length = element.quarter_length_fraction();
+        let written = length / tuplet.multiplier();
+        if open.is_none() {
+            runs.push(TupletRun {
+                tuplet,
+                first: index,
+                last: index,
+                full: false,
+            });
+        }
+        let (filled, shortest) = open.get_or_insert((FractionType::from(0), written));
+        *filled += length;
+        if written < *shortest {
+            *shortest = written;
+        }
+        let run = runs.last_mut().expect("a run is open");
+        run.last = index;
+        if *filled >= *shortest * FractionType::from(tuplet.normal() as IntegerType) {
+            run.full = true;
+            open = None;
+        }
+    }
+    runs
+}
+
+/// Returns the outermost tuplet an element is written under.
+fn tuplet_of(element: &StreamElement) -> Option<Tuplet> {
+    element
+        .duration()
+        .cloned()
+        .unwrap_or_default()
+        .tuplets()
+        .first()
+        .copied()
+}
+
+/// Returns whether a length is one plain note value, with no tuplet.
+fn is_plain(length: FractionType) -> bool {
+    Duration::from_fraction(length)
+        .is_ok_and(|duration| !duration.is_complex() && duration.tuplets().is_empty())
+}
+
+/// Returns the plain note values that write `length` under `tuplet`, or
+/// `None` when they would need a second tuplet.
+fn tuplet_components(length: FractionType, tuplet: Tuplet) -> Option<Vec<Duration>> {
+    let written = Duration::from_fraction(length / tuplet.multiplier()).ok()?;
+    let components = written.components();
+    components
+        .iter()
+        .all(|component| !component.is_complex() && component.tuplets().is_empty())
+        .then(|| {
+            components
+                .into_iter()
+                .map(|component| component.with_tuplet(tuplet))
+                .collect()
+        })
+}
+
+/// Widens every tuplet run that stops short of a full bracket to the beats
+/// around it, and rewrites the measure so each widened window can be one
+/// bracket.
+///
+/// A window grows until no tuplet note, tuplet run or note whose outside
+/// part would itself need a tuplet crosses its edges. Plain notes inside it
+/// are written in its tuplet, cut and tied at its edges. A window holding
+/// another tuplet is dropped, and its run bracketed as it stands.
+fn bracket_fragments(
+    elements: Vec<Placed>,
+    beat_starts: &[FractionType],
+) -> Result<(Vec<Placed>, Vec<TupletWindow>)> {
+    let end_of = |(offset, element): &Placed| *offset + element.quarter_length_fraction();
+    let everything = vec![true; elements.len()];
+    let runs = tuplet_runs(&elements, &everything);
+    let Some(end) = elements.last().map(end_of) else {
+        return Ok((elements, Vec::new()));
+    };
+    if runs.iter().all(|run| run.full) {
+        return Ok((elements, Vec::new()));
+    }
+    let mut beats = beat_starts
+        .iter()
+        .copied()
+        .filter(|beat| *beat < end)
+        .collect::<Vec<_>>();
+    beats.push(end);
+    let beat_before = |offset: FractionType| {
+        beats
+            .iter()
+            .rev()
+            .find(|beat| **beat <= offset)
+            .copied()
+            .unwrap_or_default()
+    };
+    let beat_after = |offset: FractionType| {
+        beats
+            .iter()
+            .find(|beat| **beat >= offset)
+            .copied()
+            .unwrap_or(end)
+    };
+    let spans = runs
+        .iter()
+        .map(|run| (elements[run.first].0, end_of(&elements[run.last])))
+        .collect::<Vec<_>>();
+
+    // Each window may grow up to the nearest notes under another tuplet.
+    let mut windows = runs
+        .iter()
+        .zip(&spans)
+        .filter(|(run, _)| !run.full)
+        .map(|(run, (start, stop))| {
+            let foreign =
+                |placed: &&Placed| tuplet_of(&placed.1).is_some_and(|tuplet| tuplet != run.tuplet);
+            let low = elements
+                .iter()
+                .filter(foreign)
+                .map(end_of)
+                .filter(|foreign_end| foreign_end <= start)
+                .max()
+                .unwrap_or_default();
+            let high = elements
+                .iter()
+                .filter(foreign)
+                .map(|placed| placed.0)
+                .filter(|foreign_start| foreign_start >= stop)
+                .min()
+                .unwrap_or(end);
+            let window = TupletWindow {
+                tuplet: run.tuplet,
+                start: beat_before(*start).max(low),
+                end: beat_after(*stop).min(high),
+            };
+            (window, low, high)
+        })
+        .collect::<Vec<_>>();
+    loop {
+        windows.sort_by_key(|(window, _, _)| window.start);
+        let mut merged: Vec<(TupletWindow, FractionType, FractionType)> = Vec::new();
+        for (window, low, high) in windows {
+            match merged.last_mut() {
+                Some((last, last_low, last_high))
+                    if window.start < last.end && window.tuplet == last.tuplet =>
+                {
+                    last.end = last.end.max(window.end);
+                    *last_low = (*last_low).min(low);
+                    *last_high = (*last_high).max(high);
+                }
+                Some((last, _, _)) if window.start < last.end => {}
+                _ => merged.push((window, low, high)),
+            }
+        }
+        windows = merged;
+
+        let mut grown = false;
+        for (window, low, high) in &mut windows {
+            let crossings = elements
+                .iter()
+                .map(|placed| {
+                    let fixed = tuplet_of(&placed.1).is_some();
+                    (placed.0, end_of(placed), fixed)
+                })
+                .chain(spans.iter().map(|(start, stop)| (*start, *stop, true)));
+            for (start, stop, fixed) in crossings {
+                if start >= window.end || stop <= window.start {
+                    continue;
+                }
+                let before = beat_before(start).max(*low);
+                if start < window.start
+                    && (fixed || !is_plain(window.start - start))
+                    && before < window.start
+                {
+                    window.start = before;
+                    grown = true;
+                }
+                let after = beat_after(stop).min(*high);
+                if stop > window.end
+                    && (fixed || !is_plain(stop - window.end))
+                    && after > window.end
+                {
+                    window.end = after;
+                    grown = true;
+                }
+            }
+        }
+        if !grown {
+            break;
+        }
+    }
+    // A window that could not take in a note crossing its edge is dropped,
+    // as is one that overlaps a note it cannot write.
+    let mut windows = windows
+        .into_iter()
+        .map(|(window, _, _)| window)
+        .collect::<Vec<_>>();
+    windows.retain(|window| {
+        elements.iter().all(|placed| {
+            let (offset, stop) = (placed.0, end_of(placed));
+            let (start, finish) = (offset.max(window.start), stop.min(window.end));
+            if start >= finish {
+                return true;
+            }
+            match tuplet_of(&placed.1) {
+                Some(tuplet) => {
+                    tuplet == window.tuplet && offset >= window.start && stop <= window.end
+                }
+                None => {
+                    tuplet_components(finish - start, window.tuplet).is_some()
+                        && (offset >= window.start || is_plain(window.start - offset))
+                        && (stop <= window.end || is_plain(stop - window.end))
+                }
+            }
+        })
+    });
+
+    let mut rewritten = Vec::with_capacity(elements.len());
+    for placed in elements {
+        let (offset, element) = &placed;
+        let stop = end_of(&placed);
+        let inside = |at: FractionType| {
+            windows
+                .iter()
+                .find(|window| window.start <= at && at < window.end)
+        };
+        if *offset == stop
+            || tuplet_of(element).is_some()
+            || windows
+                .iter()
+                .all(|window| *offset >= window.end || stop <= window.start)
+        {
+            rewritten.push(placed);
+            continue;
+        }
+        let mut cuts = vec![*offset, stop];
+        for window in &windows {
+            cuts.extend(
+                [window.start, window.end]
+                    .into_iter()
+                    .filter(|edge| *offset < *edge && *edge < stop),
+            );
+        }
+        cuts.sort();
+        let mut offsets = Vec::new();
+        let mut durations = Vec::new();
+        for pair in cuts.windows(2) {
+            let mut at = pair[0];
+            let length = pair[1] - pair[0];
+            let pieces = match inside(at) {
+                Some(window) => tuplet_components(length, window.tuplet)
+                    .expect("windows hold only notes their tuplet can write"),
+                None => vec![Duration::from_fraction(length)?],
+            };
+            for piece in pieces {
+                offsets.push(at);
+                at += piece.quarter_length_fraction();
+                durations.push(piece);
+            }
+        }
+        rewritten.extend(offsets.into_iter().zip(element.split_into(&durations)));
+    }
+    Ok((rewritten, windows))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{chord::Chord, note::Note};
+
+    fn note(name: &str, quarter_length: FloatType) -> Note {
+        Note::from_name(name)
+            .unwrap()
+            .with_duration(Duration::new(quarter_length).unwrap())
+    }
+
+    fn ts(ratio: &str) -> TimeSignature {
+        TimeSignature::from_ratio_string(ratio).unwrap()
+    }
+
+    /// Returns each measure as `(offset, quarter length, tie)` triples.
+    fn shape(
+        measures: &[SpelledMeasure],
+    ) -> Vec<Vec<(FloatType, FloatType, Option<&'static str>)>> {
+        measures
+            .iter()
+            .map(|measure| {
+                measure
+                    .elements()
+                    .iter()
+                    .map(|spelled| {
+                        (
+                            spelled.offset(),
+                            spelled.element().quarter_length(),
+                            tie_of(spelled.element()).map(Tie::name),
+                        )
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn splits_at_bars_and_strong_beats() {
+        let mut stream = Stream::new();
+        stream.push(note("C4", 2.5));
+        stream.push(note("D4", 1.5));
+        stream.push(note("E4", 3.0));
+        let measures = stream.spell_rhythm(&TimeSignature::common()).unwrap();
+        assert_eq!(
+            shape(&measures),
+            vec![
+                vec![
+                    (0.0, 2.0, Some("start")),
+                    (2.0, 0.5, Some("stop")),
+                    (2.5, 0.5, Some("start")),
+                    (3.0, 1.0, Some("stop")),
+                ],
+                vec![(0.0, 3.0, None)],
+            ]
+        );
+        assert_eq!(measures[1].number(), 2);
+        assert_eq!(measures[1].offset(), 4.0);
+
+        // A gap becomes a rest; a note over the bar line is tied into a
+        // dotted half.
+        let mut stream = Stream::new();
+        stream.insert(2.0, note("G4", 4.0));
+        assert_eq!(
+            shape(&stream.spell_rhythm(&ts("3/4")).unwrap()),
+            vec![
+                vec![(0.0, 2.0, None), (2.0, 1.0, Some("start"))],
+                vec![(0.0, 3.0, Some("stop"))],
+            ]
+        );
+    }
+
+    #[test]
+    fn joins_tied_notes_into_conventional_dotted_values() {
+        let tied = |offset: FloatType| {
+            let mut stream = Stream::new();
+            stream.insert(offset, note("C4", 1.0).with_tie(Tie::Start));
+            stream.insert(offset + 1.0, note("C4", 0.5).with_tie(Tie::Stop));
+            stream
+        };
+        let on_the_beat = tied(0.0).spell_rhythm(&TimeSignature::common()).unwrap();
+        assert_eq!(shape(&on_the_beat), vec![vec![(0.0, 1.5, None)]]);
+        // A dotted quarter on the second beat would hide the middle of the
+        // bar, so it stays tied.
+        let off_the_beat = tied(1.0).spell_rhythm(&TimeSignature::common()).unwrap();
+        assert_eq!(
+            shape(&off_the_beat),
+            vec![vec![
+                (0.0, 1.0, None),
+                (1.0, 1.0, Some("start")),
+                (2.0, 0.5, Some("stop")),
+            ]]
+        );
+        // Additive meters group by their beams: a 5/8 bar is a quarter tied
+        // to a dotted quarter.
+        let mut stream = Stream::new();
+        stream.push(note("C4", 2.5));
+        assert_eq!(
+            shape(&stream.spell_rhythm(&ts("5/8")).unwrap()),
+            vec![vec![(0.0, 1.0, Some("start")), (1.0, 1.5, Some("stop"))]]
+        );
+    }
+
+    #[test]
+    fn beams_follow_the_meter_groups() {
+        let mut stream = Stream::new();
+        for _ in 0..6 {
+            stream.push(note("C4", 0.5));
+        }
+        let measures = stream.spell_rhythm(&ts("6/8")).unwrap();
+        let beams = measures[0]
+            .elements()
+            .iter()
+            .map(|spelled| spelled.beams().to_vec())
+            .collect::<Vec<_>>();
+        let triple = [vec![Beam::Start], vec![Beam::Continue], vec![Beam::Stop]];
+        assert_eq!(beams, [triple.clone(), triple].concat());
+
+        let mut stream = Stream::new();
+        stream.push(note("C4", 0.5));
+        stream.push(note("D4", 0.25));
+        stream.push(
+            Chord::new("E4 G4")
+                .unwrap()
+                .with_duration(Duration::new(0.25).unwrap()),
+        );
+        stream.push(Rest::from_quarter_length(0.5).unwrap());
+        stream.push(note("F4", 0.5));
+        let measures = stream.spell_rhythm(&ts("2/4")).unwrap();
+        let beams = measures[0]
+            .elements()
+            .iter()
+            .map(|spelled| spelled.beams().to_vec())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            beams,
+            vec![
+                vec![Beam::Start],
+                vec![Beam::Continue, Beam::Start],
+                vec![Beam::Stop, Beam::Stop],
+                vec![],
+                vec![],
+            ]
+        );
+    }
+
+    #[test]
+    fn beams_start_after_a_leading_rest_or_quarter() {
+        let beams = |stream: &Stream, ratio: &str| {
+            stream.spell_rhythm(&ts(ratio)).unwrap()[0]
+                .elements()
+                .iter()
+                .map(|spelled| spelled.beams().to_vec())
+                .collect::<Vec<_>>()
+        };
+        let mut stream = Stream::new();
+        stream.push(Rest::from_quarter_length(0.5).unwrap());
+        stream.push(note("C4", 0.5));
+        stream.push(note("D4", 0.5));
+        stream.push(note("E4", 1.5));
+        assert_eq!(
+            beams(&stream, "6/8"),
+            vec![vec![], vec![Beam::Start], vec![Beam::Stop], vec![]]
+        );
+
+        let mut stream = Stream::new();
+        stream.push(note("C4", 1.0));
+        stream.push(note("D4", 0.5));
+        stream.push(note("E4", 0.5));
+        stream.push(note("F4", 2.0));
+        assert_eq!(
+            beams(&stream, "2/2"),
+            vec![vec![], vec![Beam::Start], vec![Beam::Stop], vec![]]
+        );
+    }
+
+    #[test]
+    fn brackets_tuplets_and_keeps_them_whole() {
+        let triplet_eighth = Duration::eighth().with_tuplet(Tuplet::triplet());
+        let triplet_quarter = Duration::quarter().with_tuplet(Tuplet::triplet());
+        let mut stream = Stream::new();
+        for name in ["C4", "D4", "E4"] {
+            stream.push(
+                Note::from_name(name)
+                    .unwrap()
+                    .with_duration(triplet_eighth.clone()),
+            );
+        }
+        stream.push(note("F4", 1.0));
+        for name in ["G4", "A4", "B4"] {
+            stream.push(
+                Note::from_name(name)
+                    .unwrap()
+                    .with_duration(triplet_quarter.clone()),
+            );
+        }
+        let measures = stream.spell_rhythm(&TimeSignature::common()).unwrap();
+        let brackets = measures[0]
+            .elements()
+            .iter()
+            .map(|spelled| {
+                spelled
+                    .tuplet()
+                    .map(|bracket| (bracket.starts(), bracket.stops()))
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            brackets,
+            vec![
+                Some((true, false)),
+                Some((false, false)),
+                Some((false, true)),
+                None,
+                Some((true, false)),
+                Some((false, false)),
+                Some((false, true)),
+            ]
+        );
+        // The middle triplet quarter crosses the third beat untied.
+        assert_eq!(measures[0].elements().len(), 7);
+        assert_eq!(measures[0].elements()[0].beams(), [Beam::Start]);
+        assert_eq!(measures[0].elements()[2].beams(), [Beam::Stop]);
+    }
+
+    #[test]
+    fn rejects_overlapping_events() {
+        let mut stream = Stream::new();
+        stream.insert(0.0, note("C4", 2.0));
+        stream.insert(1.0, note("E4", 1.0));
+        assert!(matches!(
+            stream.spell_rhythm(&TimeSignature::common()),
+            Err(Error::Notation(_))
+        ));
+    }
+}
@@ -5,6 +5,7 @@ use crate::defaults::{FloatType, IntegerType};
 use crate::duration::Duration;
 use crate::error::Result;
 use crate::pitch::Pitch;
+use crate::tie::Tie;
 
 use generalnote::GeneralNoteTrait;
 use notrest::NotRest;
@@ -17,6 +18,8 @@ use std::str::FromStr;
 pub struct Note {
     notrest: NotRest,
     pub(crate) _pitch: Pitch,
+    #[cfg_attr(feature = "serde", serde(default))]
+    tie: Option<Tie>,
 }
 
 impl Note {
@@ -61,6 +64,22 @@ impl Note {
         self
     }
 
+    /// Returns the note's tie, if it is tied to a neighbour.
+    pub fn tie(&self) -> Option<Tie> {
+        self.tie
+    }
+
+    /// Assigns or clears the note's tie.
+    pub fn set_tie(&mut self, tie: Option<Tie>) {
+        self.tie = tie;
+    }
+
+    /// Returns a copy of this note with the supplied tie.
+    pub fn with_tie(mut self, tie: Tie) -> Self {
+        self.tie = Some(tie);
+        self
+    }
+
     pub(crate) fn new<T>(
         pitch: Option<T>,
         duration: Option<Duration>,
@@ -88,6 +107,7 @@ impl Note {
         Ok(Self {
             notrest: NotRest::new(duration),
             _pitch,
+            tie: None,
         })
     }
 }
@@ -584,6 +584,12 @@ impl Pitch {
         })
     }
 
+    /// Returns this pitch's pitch class as a number from 0 to 11, with
+    /// microtones rounded to the nearest semitone.
+    pub(crate) fn pitch_class_number(&self) -> u8 {
+        (self.ps().round() as IntegerType).rem_euclid(12) as u8
+    }
+
     pub(crate) fn octave_setter(&mut self, octave: Octave) {
         self._octave = octave;
     }
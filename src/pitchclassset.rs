@@ -0,0 +1,512 @@
+//! Unordered twelve-tone pitch-class sets and their set-class operations.
+//!
+//! [`PitchClassSet`] works on pitch-class integers alone, so atonal analysis
+//! does not need spelled pitches or a [`crate::chord::Chord`]. Forte names,
+//! Z-relations, and table lookups come from the same music21 Forte tables the
+//! chord helpers use; everything else is computed directly.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::{
+    chord::tables,
+    error::{Error, Result},
+};
+
+/// Tie-breaking rule for normal orders and prime forms.
+///
+/// The two conventions differ for a handful of set classes: Forte's prime
+/// form of 6-Z29 is `[0,1,3,6,8,9]`, Rahn's is `[0,2,3,6,7,9]`.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PackingConvention {
+    /// Allen Forte's rule: after the outer interval, compare the intervals
+    /// from the first pitch class to the second, third, and so on. music21's
+    /// tables use this convention.
+    #[default]
+    Forte,
+    /// John Rahn's rule: after the outer interval, compare the intervals from
+    /// the first pitch class to the second-to-last, third-to-last, and so on.
+    Rahn,
+}
+
+/// An unordered set of pitch classes `0` through `11`.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PitchClassSet {
+    bits: u16,
+}
+
+impl PitchClassSet {
+    /// The set of all twelve pitch classes.
+    pub const AGGREGATE: Self = Self { bits: 0x0fff };
+
+    /// Builds a set from pitch-class integers, reduced modulo 12.
+    pub fn new(pitch_classes: impl IntoIterator<Item = u8>) -> Self {
+        let bits = pitch_classes
+            .into_iter()
+            .fold(0, |bits, pc| bits | 1 << (pc % 12));
+        Self { bits }
+    }
+
+    /// Returns the empty set.
+    pub fn empty() -> Self {
+        Self::default()
+    }
+
+    /// Builds a set from a twelve-bit mask, where bit `n` is pitch class `n`.
+    pub fn from_bits(bits: u16) -> Self {
+        Self {
+            bits: bits & Self::AGGREGATE.bits,
+        }
+    }
+
+    /// Builds the prime form of a Forte set class such as `"3-11"` or
+    /// `"6-Z29"`; an `A` or `B` suffix picks the prime form or its inversion.
+    pub fn from_forte_class(name: &str) -> Result<Self> {
+        let invalid = || Error::PitchClass(format!("invalid Forte class {name:?}"));
+        let (cardinality, index) = name.trim().split_once('-').ok_or_else(invalid)?;
+        let index = index.trim_start_matches(['Z', 'z']);
+        let (index, inverted) = match index.strip_suffix(['A', 'a']) {
+            Some(index) => (index, false),
+            None => match index.strip_suffix(['B', 'b']) {
+                Some(index) => (index, true),
+                None => (index, false),
+            },
+        };
+        let cardinality = cardinality.parse::<u8>().map_err(|_| invalid())?;
+        let index = index.parse::<u8>().map_err(|_| invalid())?;
+
+        let entry = tables::forte_table_entries()
+            .into_iter()
+            .find(|entry| entry.cardinality == cardinality && entry.index == index)
+            .ok_or_else(invalid)?;
+        let prime = Self::new(entry.prime_form);
+        Ok(if inverted { prime.invert(0) } else { prime })
+    }
+
+    /// Returns the twelve-bit mask, where bit `n` is pitch class `n`.
+    pub fn bits(self) -> u16 {
+        self.bits
+    }
+
+    /// Returns the pitch classes in ascending order.
+    pub fn pitch_classes(self) -> Vec<u8> {
+        (0..12).filter(|pc| self.contains(*pc)).collect()
+    }
+
+    /// Returns the number of pitch classes.
+    pub fn len(self) -> usize {
+        self.bits.count_ones() as usize
+    }
+
+    /// Returns `true` for the empty set.
+    pub fn is_empty(self) -> bool {
+        self.bits == 0
+    }
+
+    /// Returns `true` when the set contains a pitch class.
+    pub fn contains(self, pitch_class: u8) -> bool {
+        pitch_class < 12 && self.bits & (1 << pitch_class) != 0
+    }
+
+    /// Transposes by `n` semitones (Tn).
+    pub fn transpose(self, n: u8) -> Self {
+        let n = n % 12;
+        Self::from_bits((self.bits << n) | (self.bits >> (12 - n)))
+    }
+
+    /// Inverts about 0 and then transposes by `n` (TnI), mapping `x` to
+    /// `n - x`.
+    pub fn invert(self, n: u8) -> Self {
+        Self::new(
+            self.pitch_classes()
+                .into_iter()
+                .map(|pc| (n % 12 + 12 - pc) % 12),
+        )
+    }
+
+    /// Multiplies every pitch class by `factor` modulo 12.
+    ///
+    /// [`Self::m5`] and [`Self::m7`] are the two multiplications that map
+    /// every set class onto a set class of the same size.
+    pub fn multiply(self, factor: u8) -> Self {
+        Self::new(
+            self.pitch_classes()
+                .into_iter()
+                .map(|pc| ((u16::from(pc) * u16::from(factor)) % 12) as u8),
+        )
+    }
+
+    /// Applies the M5 operation, exchanging the chromatic and fourth cycles.
+    pub fn m5(self) -> Self {
+        self.multiply(5)
+    }
+
+    /// Applies the M7 operation, exchanging the chromatic and fifth cycles.
+    pub fn m7(self) -> Self {
+        self.multiply(7)
+    }
+
+    /// Returns the pitch classes not in the set.
+    pub fn complement(self) -> Self {
+        Self::from_bits(!self.bits)
+    }
+
+    /// Returns the pitch classes in either set.
+    pub fn union(self, other: Self) -> Self {
+        Self::from_bits(self.bits | other.bits)
+    }
+
+    /// Returns the pitch classes in both sets.
+    pub fn intersection(self, other: Self) -> Self {
+        Self::from_bits(self.bits & other.bits)
+    }
+
+    /// Returns `true` when every pitch class of this set is in `other`.
+    pub fn is_subset_of(self, other: Self) -> bool {
+        self.bits & !other.bits == 0
+    }
+
+    /// Returns `true` when this set contains every pitch class of `other`.
+    pub fn is_superset_of(self, other: Self) -> bool {
+        other.is_subset_of(self)
+    }
+
+    /// Returns `true` when some transposition or inversion of this set is a
+    /// subset of `other`, the abstract inclusion relation used for set
+    /// classes under Tn/TnI equivalence.
+    pub fn is_abstract_subset_of(self, other: Self) -> bool {
+        (0..12).any(|n| self.transpose(n).is_subset_of(other) || self.invert(n).is_subset_of(other))
+    }
+
+    /// Returns the normal order: the rotation of the sorted pitch classes
+    /// spanning the smallest interval, ties broken by `convention`.
+    pub fn normal_order(self, convention: PackingConvention) -> Vec<u8> {
+        let pcs = self.pitch_classes();
+        if pcs.len() < 2 {
+            return pcs;
+        }
+        (0..pcs.len())
+            .map(|start| {
+                pcs[start..]
+                    .iter()
+                    .chain(&pcs[..start])
+                    .copied()
+                    .collect::<Vec<_>>()
+            })
+            .min_by_key(|rotation| (packing_key(&zeroed(rotation), convention), rotation[0]))
+            .unwrap_or_default()
+    }
+
+    /// Returns the normal order transposed to begin on 0, the Tn-type
+    /// representative, which keeps 3-11A and 3-11B apart.
+    pub fn transposed_normal_form(self, convention: PackingConvention) -> Vec<u8> {
+        zeroed(&self.normal_order(convention))
+    }
+
+    /// Returns the prime form, the TnI-type representative starting on 0.
+    pub fn prime_form(self, convention: PackingConvention) -> Vec<u8> {
+        let original = self.transposed_normal_form(convention);
+        let inverted = self.invert(0).transposed_normal_form(convention);
+        if packing_key(&inverted, convention) < packing_key(&original, convention) {
+            inverted
+        } else {
+            original
+        }
+    }
+
+    /// Returns `true` when one set is a transposition of the other.
+    pub fn is_tn_equivalent(self, other: Self) -> bool {
+        self.len() == other.len() && (0..12).any(|n| self.transpose(n) == other)
+    }
+
+    /// Returns `true` when one set is a transposition or inversion of the
+    /// other, that is, when both belong to the same set class.
+    pub fn is_tni_equivalent(self, other: Self) -> bool {
+        self.is_tn_equivalent(other) || self.invert(0).is_tn_equivalent(other)
+    }
+
+    /// Returns the Forte name with its Tn-type suffix, such as `"3-11B"` for a
+    /// major triad, or `None` for the empty set.
+    pub fn forte_class(self) -> Option<String> {
+        let address = tables::seek_chord_tables_address(&self.pitch_classes()).ok()?;
+        tables::address_to_forte_name(address, "tn").ok()
+    }
+
+    /// Returns the Forte name without a Tn-type suffix, such as `"3-11"`.
+    pub fn forte_class_tni(self) -> Option<String> {
+        let address = tables::seek_chord_tables_address(&self.pitch_classes()).ok()?;
+        tables::address_to_forte_name(address, "tni").ok()
+    }
+
+    /// Returns the Forte name of the Z-related set class, if there is one.
+    pub fn z_relation(self) -> Option<String> {
+        let address = tables::seek_chord_tables_address(&self.pitch_classes()).ok()?;
+        tables::z_relation_from_address(address).ok().flatten()
+    }
+
+    /// Returns the interval-class vector: how many pairs span each interval
+    /// class from 1 to 6.
+    pub fn interval_class_vector(self) -> [u8; 6] {
+        let ifunc = self.interval_function(self);
+        let mut vector = [0; 6];
+        for (class, count) in vector.iter_mut().enumerate() {
+            let interval = class + 1;
+            *count = if interval == 6 {
+                ifunc[6] / 2
+            } else {
+                ifunc[interval]
+            };
+        }
+        vector
+    }
+
+    /// Returns Robert Morris's invariance vector.
+    ///
+    /// The first four entries count the operations Tn, TnI, TnM, and TnMI
+    /// (with M = M5) that map the set onto itself; the last four count those
+    /// that map it into its complement.
+    pub fn invariance_vector(self) -> [u8; 8] {
+        let complement = self.complement();
+        let images = |n: u8| {
+            [
+                self.transpose(n),
+                self.invert(n),
+                self.m5().transpose(n),
+                self.m5().invert(n),
+            ]
+        };
+        let mut vector = [0; 8];
+        for n in 0..12 {
+            for (slot, image) in images(n).into_iter().enumerate() {
+                vector[slot] += u8::from(image == self);
+                vector[slot + 4] += u8::from(image.is_subset_of(complement));
+            }
+        }
+        vector
+    }
+
+    /// Returns David Lewin's interval function IFUNC(self, other): entry `i`
+    /// counts the pairs `(x, y)`, `x` in `self` and `y` in `other`, with
+    /// `y - x = i` modulo 12.
+    pub fn interval_function(self, other: Self) -> [u8; 12] {
+        let mut function = [0; 12];
+        for x in self.pitch_classes() {
+            for y in other.pitch_classes() {
+                function[usize::from((y + 12 - x) % 12)] += 1;
+            }
+        }
+        function
+    }
+}
+
+impl fmt::Display for PitchClassSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let pcs = self
+            .pitch_classes()
+            .iter()
+            .map(u8::to_string)
+            .collect::<Vec<_>>();
+        write!(f, "{{{}}}", pcs.join(","))
+    }
+}
+
+impl FromStr for PitchClassSet {
+    type Err = Error;
+
+    /// Parses `"0 4 7"`, `"[0,4,7]"`, `"{0,1,T,E}"`, or compact `"047te"`,
+    /// where `T`/`A` is 10 and `E`/`B` is 11.
+    fn from_str(value: &str) -> Result<Self> {
+        parse_pitch_class_list(value).map(Self::new)
+    }
+}
+
+impl TryFrom<&str> for PitchClassSet {
+    type Error = Error;
+
+    fn try_from(value: &str) -> Result<Self> {
+        value.parse()
+    }
+}
+
+impl TryFrom<String> for PitchClassSet {
+    type Error = Error;
+
+    fn try_from(value: String) -> Result<Self> {
+        value.parse()
+    }
+}
+
+impl FromIterator<u8> for PitchClassSet {
+    fn from_iter<I: IntoIterator<Item = u8>>(iter: I) -> Self {
+        Self::new(iter)
+    }
+}
+
+/// Parses `"0 4 7"`, `"[0,4,7]"`, `"{0,1,T,E}"`, or compact `"047te"`, where
+/// `T`/`A` is 10 and `E`/`B` is 11, keeping the written order.
+pub(crate) fn parse_pitch_class_list(value: &str) -> Result<Vec<u8>> {
+    let inner = value
+        .trim()
+        .trim_start_matches(['[', '{', '('])
+        .trim_end_matches([']', '}', ')']);
+    let separated = inner.contains(|ch: char| ch == ',' || ch.is_whitespace());
+    let tokens = if separated {
+        inner
+            .split(|ch: char| ch == ',' || ch.is_whitespace())
+            .filter(|token| !token.is_empty())
+            .map(str::to_string)
+            .collect::<Vec<_>>()
+    } else {
+        inner.chars().map(String::from).collect()
+    };
+
+    tokens
+        .iter()
+        .map(|token| match token.to_ascii_uppercase().as_str() {
+            "T" | "A" => Ok(10),
+            "E" | "B" => Ok(11),
+            digits => digits
+                .parse::<u8>()
+                .ok()
+                .filter(|pc| *pc < 12)
+                .ok_or_else(|| {
+                    Error::PitchClass(format!("invalid pitch class {token:?} in {value:?}"))
+                }),
+        })
+        .collect()
+}
+
+/// Pitch classes transposed so the first is 0.
+fn zeroed(ordered: &[u8]) -> Vec<u8> {
+    let first = ordered.first().copied().unwrap_or(0);
+    ordered.iter().map(|pc| (pc + 12 - first) % 12).collect()
+}
+
+/// Comparison key for a zero-based ordering: the outer interval first, then
+/// the inner intervals from the left (Forte) or from the right (Rahn).
+fn packing_key(zeroed: &[u8], convention: PackingConvention) -> Vec<u8> {
+    let Some((last, inner)) = zeroed.split_last() else {
+        return Vec::new();
+    };
+    let mut key = vec![*last];
+    match convention {
+        PackingConvention::Forte => key.extend(inner.iter().skip(1)),
+        PackingConvention::Rahn => key.extend(inner.iter().skip(1).rev()),
+    }
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(pcs: &str) -> PitchClassSet {
+        pcs.parse().unwrap()
+    }
+
+    #[test]
+    fn transforms_and_compares_sets() {
+        let major = set("0 4 7");
+        assert_eq!(major.transpose(2), set("2 6 9"));
+        assert_eq!(major.invert(7), set("0 3 7"));
+        assert_eq!(major.complement().len(), 9);
+        assert_eq!(set("0 1 2").m5(), set("0 5 t"));
+        assert_eq!(set("0 1 2").m7(), set("0 7 2"));
+        assert!(major.is_subset_of(set("0 2 4 5 7 9 11")));
+        assert!(set("0 2 4 5 7 9 11").is_superset_of(major));
+        assert!(set("037").is_abstract_subset_of(set("0 2 4 5 7 9 11")));
+        assert!(!set("0 1 2").is_abstract_subset_of(set("0 2 4 5 7 9 11")));
+        assert!(major.is_tn_equivalent(set("5 9 0")));
+        assert!(!major.is_tn_equivalent(set("0 3 7")));
+        assert!(major.is_tni_equivalent(set("0 3 7")));
+        assert_eq!(major.to_string(), "{0,4,7}");
+        assert_eq!(set("{0,1,T,E}").pitch_classes(), vec![0, 1, 10, 11]);
+        assert!("0 12".parse::<PitchClassSet>().is_err());
+    }
+
+    #[test]
+    fn names_set_classes() {
+        let major = set("0 4 7");
+        assert_eq!(major.normal_order(PackingConvention::Forte), vec![0, 4, 7]);
+        assert_eq!(
+            major.transposed_normal_form(PackingConvention::Forte),
+            vec![0, 4, 7]
+        );
+        assert_eq!(major.prime_form(PackingConvention::Forte), vec![0, 3, 7]);
+        assert_eq!(major.forte_class().as_deref(), Some("3-11B"));
+        assert_eq!(major.forte_class_tni().as_deref(), Some("3-11"));
+        assert_eq!(major.interval_class_vector(), [0, 0, 1, 1, 1, 0]);
+        assert_eq!(set("0 1 4 6").z_relation().as_deref(), Some("4-29"));
+        assert_eq!(
+            PitchClassSet::from_forte_class("3-11").unwrap(),
+            set("0 3 7")
+        );
+        assert_eq!(
+            PitchClassSet::from_forte_class("3-11B")
+                .unwrap()
+                .forte_class()
+                .as_deref(),
+            Some("3-11B")
+        );
+        assert_eq!(PitchClassSet::from_forte_class("6-Z29").unwrap().len(), 6);
+        assert!(PitchClassSet::from_forte_class("3-99").is_err());
+        assert_eq!(
+            set("t e 2 3").normal_order(PackingConvention::Forte),
+            vec![10, 11, 2, 3]
+        );
+
+        for (pcs, forte, rahn) in [
+            ("0 1 5 6 8", "0 1 3 7 8", "0 1 5 6 8"),
+            ("0 1 3 6 8 9", "0 1 3 6 8 9", "0 2 3 6 7 9"),
+            ("0 1 3 5 8 9", "0 1 3 5 8 9", "0 1 4 5 7 9"),
+            ("0 1 2 5 6 7 9", "0 1 2 4 7 8 9", "0 1 2 5 6 7 9"),
+            ("0 1 3 4 5 7 8 t", "0 1 2 4 5 7 9 t", "0 1 3 4 5 7 8 t"),
+        ] {
+            let pcs = set(pcs);
+            assert_eq!(
+                pcs.prime_form(PackingConvention::Forte),
+                set(forte).pitch_classes()
+            );
+            assert_eq!(
+                pcs.prime_form(PackingConvention::Rahn),
+                set(rahn).pitch_classes()
+            );
+        }
+    }
+
+    #[test]
+    fn computed_vectors_match_forte_tables() {
+        for entry in tables::forte_table_entries() {
+            let pcs = PitchClassSet::new(entry.prime_form.iter().copied());
+            let name = format!("{}-{}", entry.cardinality, entry.index);
+            assert_eq!(
+                pcs.prime_form(PackingConvention::Forte),
+                entry.prime_form,
+                "{name}"
+            );
+            let address = tables::seek_chord_tables_address(&entry.prime_form).unwrap();
+            assert_eq!(
+                pcs.interval_class_vector().to_vec(),
+                tables::interval_class_vector_from_address(address).unwrap(),
+                "{name}"
+            );
+            assert_eq!(
+                pcs.invariance_vector().to_vec(),
+                tables::invariance_vector_from_address(address).unwrap(),
+                "{name}"
+            );
+            assert_eq!(pcs.forte_class_tni().as_deref(), Some(name.as_str()));
+        }
+    }
+
+    #[test]
+    fn interval_function_counts_directed_intervals() {
+        let ifunc = set("0 4 7").interval_function(set("2 5"));
+        assert_eq!(ifunc.iter().map(|count| u32::from(*count)).sum::<u32>(), 6);
+        assert_eq!(ifunc[2], 1);
+        assert_eq!(ifunc[10], 2);
+        assert_eq!(set("0 4 7").interval_function(set("0 4 7"))[0], 3);
+    }
+}
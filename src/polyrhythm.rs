@@ -5,6 +5,7 @@ use crate::chord::Chord;
 use crate::defaults::{FloatType, IntegerType, UnsignedIntegerType};
 use crate::error::{Error, Result};
 use crate::interval::{Interval, IntervalArgument};
+use crate::meter::TimeSignature;
 use crate::pitch::Pitch;
 
 #[derive(Debug, Clone)]
@@ -103,6 +104,21 @@ impl Polyrhythm {
         Self::new(beats_per_measure, subdivisions)?.with_tempo(tempo)
     }
 
+    /// Creates a polyrhythm over the beats of a time signature, at a tempo in
+    /// beats per minute.
+    ///
+    /// The base is the signature's beat count, so `6/8` gives two beats and
+    /// `2+2+3/8` three. The beats are treated as equal: the dotted beat of
+    /// `2+2+3/8` lasts as long as the others, and the tempo counts beats
+    /// rather than quarter notes.
+    pub fn from_meter(
+        time_signature: &TimeSignature,
+        tempo: UnsignedIntegerType,
+        subdivisions: &[UnsignedIntegerType],
+    ) -> Result<Self> {
+        Self::from_time_signature(time_signature.beat_count(), tempo, subdivisions)
+    }
+
     /// Returns this polyrhythm with a nonzero tempo in beats per minute.
     pub fn with_tempo(mut self, tempo: UnsignedIntegerType) -> Result<Self> {
         self.set_tempo(tempo)?;
@@ -393,6 +409,20 @@ mod tests {
         assert!((tick_dur - 0.3333).abs() < 0.01);
     }
 
+    #[test]
+    fn test_from_meter_counts_beats() {
+        let aksak = TimeSignature::from_ratio_string("2+2+3/8").unwrap();
+        assert_eq!(
+            Polyrhythm::from_meter(&aksak, 120, &[2, 3]).unwrap().base,
+            3
+        );
+        let compound = TimeSignature::from_ratio_string("6/8").unwrap();
+        assert_eq!(
+            Polyrhythm::from_meter(&compound, 120, &[3]).unwrap().base,
+            2
+        );
+    }
+
     #[test]
     fn test_new_rejects_zero_base() {
         let err = Polyrhythm::new(0, &[2, 3]).unwrap_err();
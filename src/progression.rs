@@ -0,0 +1,595 @@
+//! Chord progressions and jazz reharmonization.
+//!
+//! A [`Progression`] is a sequence of [`ChordSymbol`]s read in a [`Key`].
+//! [`Progression::substitutions`] enumerates the reharmonizations a jazz
+//! player would reach for — tritone substitutes, related ii–Vs, backdoor
+//! dominants, diminished passing chords, and modal interchange — each as a
+//! labeled [`Substitution`] that can be applied to produce a new progression.
+
+use std::fmt;
+
+use crate::{
+    chordsymbol::{ChordQuality, ChordSymbol},
+    error::{Error, Result},
+    interval::Interval,
+    key::Key,
+    nashville::NashvilleNumber,
+    pitch::Pitch,
+    roman::RomanNumeral,
+};
+
+/// A family of reharmonization rules.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SubstitutionKind {
+    /// A dominant seventh replaced by the dominant a tritone away, such as
+    /// `Db7` for `G7`.
+    TritoneSubstitution,
+    /// A dominant preceded by its related ii chord, such as `Dm7 G7` for
+    /// `G7`; dominants resolving to minor chords get a half-diminished ii.
+    RelatedTwoFive,
+    /// A dominant replaced by the dominant a whole step below its target, such
+    /// as `Bb7` for `G7` before `Cmaj7`, optionally with its iv chord.
+    BackdoorDominant,
+    /// A diminished seventh inserted between chords whose roots are a whole
+    /// step apart, such as `C#°7` between `Cmaj7` and `Dm7`.
+    DiminishedPassing,
+    /// A diatonic chord replaced by the chord on the same degree of the
+    /// parallel key, such as `Fm7` for `Fmaj7` in C major.
+    ModalInterchange,
+}
+
+impl SubstitutionKind {
+    /// Returns a short human-readable name.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::TritoneSubstitution => "tritone substitution",
+            Self::RelatedTwoFive => "related ii\u{2013}V",
+            Self::BackdoorDominant => "backdoor dominant",
+            Self::DiminishedPassing => "diminished passing chord",
+            Self::ModalInterchange => "modal interchange",
+        }
+    }
+}
+
+impl fmt::Display for SubstitutionKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// One reharmonization option for a [`Progression`].
+///
+/// The option replaces the chords in `start..end` with [`Self::chords`]; an
+/// insertion has `start == end`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Substitution {
+    kind: SubstitutionKind,
+    start: usize,
+    end: usize,
+    chords: Vec<ChordSymbol>,
+    label: String,
+}
+
+impl Substitution {
+    /// Returns the rule that produced this option.
+    pub fn kind(&self) -> SubstitutionKind {
+        self.kind
+    }
+
+    /// Returns the index of the first replaced chord, or the insertion point.
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    /// Returns the index just past the last replaced chord.
+    pub fn end(&self) -> usize {
+        self.end
+    }
+
+    /// Returns the chords that take the replaced span's place.
+    pub fn chords(&self) -> &[ChordSymbol] {
+        &self.chords
+    }
+
+    /// Returns a description such as `"tritone substitution: Db7 for G7"`.
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+}
+
+impl fmt::Display for Substitution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.label)
+    }
+}
+
+/// A sequence of chord symbols in a key.
+#[derive(Clone, Debug)]
+pub struct Progression {
+    key: Key,
+    chords: Vec<ChordSymbol>,
+}
+
+impl Progression {
+    /// Creates a progression from chord symbols.
+    pub fn new(key: Key, chords: impl IntoIterator<Item = ChordSymbol>) -> Self {
+        Self {
+            key,
+            chords: chords.into_iter().collect(),
+        }
+    }
+
+    /// Parses whitespace-separated chord symbols; `|` barlines are ignored.
+    pub fn parse(key: Key, chords: &str) -> Result<Self> {
+        let chords = chords
+            .split(|ch: char| ch.is_whitespace() || ch == '|')
+            .filter(|token| !token.is_empty())
+            .map(ChordSymbol::parse)
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self::new(key, chords))
+    }
+
+    /// Generates a progression from Roman numeral figures such as
+    /// `["ii7", "V7", "Imaj7"]`.
+    pub fn from_roman_numerals<S: AsRef<str>>(key: Key, figures: &[S]) -> Result<Self> {
+        let chords = figures
+            .iter()
+            .map(|figure| {
+                let figure = figure.as_ref();
+                let chord = RomanNumeral::new(figure, key.clone())?.to_chord()?;
+                let symbol = chord.chord_symbol().ok_or_else(|| {
+                    Error::Chord(format!("Roman numeral {figure} has no chord symbol"))
+                })?;
+                ChordSymbol::parse(symbol)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self::new(key, chords))
+    }
+
+    /// Returns the key.
+    pub fn key(&self) -> &Key {
+        &self.key
+    }
+
+    /// Returns the chord symbols.
+    pub fn chords(&self) -> &[ChordSymbol] {
+        &self.chords
+    }
+
+    /// Returns the chords as Nashville numbers in the progression's key.
+    pub fn nashville_numbers(&self) -> Result<Vec<NashvilleNumber>> {
+        self.chords
+            .iter()
+            .map(|symbol| NashvilleNumber::from_chord_symbol(symbol, &self.key))
+            .collect()
+    }
+
+    /// Enumerates every substitution option, ordered by position and then by
+    /// rule.
+    pub fn substitutions(&self) -> Result<Vec<Substitution>> {
+        let mut options = Vec::new();
+        for kind in [
+            SubstitutionKind::TritoneSubstitution,
+            SubstitutionKind::RelatedTwoFive,
+            SubstitutionKind::BackdoorDominant,
+            SubstitutionKind::DiminishedPassing,
+            SubstitutionKind::ModalInterchange,
+        ] {
+            options.extend(self.substitutions_of(kind)?);
+        }
+        options.sort_by_key(|option| option.start);
+        Ok(options)
+    }
+
+    /// Enumerates the options one rule offers, in position order.
+    pub fn substitutions_of(&self, kind: SubstitutionKind) -> Result<Vec<Substitution>> {
+        let mut options = Vec::new();
+        for index in 0..self.chords.len() {
+            match kind {
+                SubstitutionKind::TritoneSubstitution => self.tritone(index, &mut options)?,
+                SubstitutionKind::RelatedTwoFive => self.related_two_five(index, &mut options)?,
+                SubstitutionKind::BackdoorDominant => self.backdoor(index, &mut options)?,
+                SubstitutionKind::DiminishedPassing => {
+                    self.diminished_passing(index, &mut options)?
+                }
+                SubstitutionKind::ModalInterchange => {
+                    self.modal_interchange(index, &mut options)?
+                }
+            }
+        }
+        options.sort_by_key(|option| option.start);
+        Ok(options)
+    }
+
+    /// Returns the progression with one substitution applied.
+    pub fn apply(&self, substitution: &Substitution) -> Result<Self> {
+        if substitution.start > substitution.end || substitution.end > self.chords.len() {
+            return Err(Error::Chord(format!(
+                "substitution span {}..{} is outside a progression of {} chords",
+                substitution.start,
+                substitution.end,
+                self.chords.len()
+            )));
+        }
+        let mut chords = self.chords.clone();
+        chords.splice(
+            substitution.start..substitution.end,
+            substitution.chords.iter().cloned(),
+        );
+        Ok(Self::new(self.key.clone(), chords))
+    }
+
+    /// Applies every non-overlapping option of one rule, taking the earliest
+    /// option wherever two compete.
+    pub fn reharmonize(&self, kind: SubstitutionKind) -> Result<Self> {
+        let mut chosen: Vec<Substitution> = Vec::new();
+        for option in self.substitutions_of(kind)? {
+            if chosen.last().is_none_or(|last| option.start >= last.end) {
+                chosen.push(option);
+            }
+        }
+
+        let mut progression = self.clone();
+        for option in chosen.iter().rev() {
+            progression = progression.apply(option)?;
+        }
+        Ok(progression)
+    }
+
+    fn tritone(&self, index: usize, options: &mut Vec<Substitution>) -> Result<()> {
+        let chord = &self.chords[index];
+        if !is_dominant(chord) {
+            return Ok(());
+        }
+
+        // Spell the substitute's root with the fewer accidentals, so F7 gives
+        // B7 rather than Cb7.
+        let down = Interval::from_name("d5")?;
+        let up = Interval::from_name("A4")?;
+        let interval = if down.transpose_pitch(chord.root())?.alter().abs()
+            <= up.transpose_pitch(chord.root())?.alter().abs()
+        {
+            down
+        } else {
+            up
+        };
+        let substitute = chord.transpose(&interval)?;
+        let label = format!(
+            "{}: {substitute} for {chord}",
+            SubstitutionKind::TritoneSubstitution
+        );
+        options.push(Substitution {
+            kind: SubstitutionKind::TritoneSubstitution,
+            start: index,
+            end: index + 1,
+            chords: vec![substitute],
+            label,
+        });
+        Ok(())
+    }
+
+    fn related_two_five(&self, index: usize, options: &mut Vec<Substitution>) -> Result<()> {
+        let chord = &self.chords[index];
+        if !is_dominant(chord) {
+            return Ok(());
+        }
+        let root = transpose(chord.root(), "P5")?;
+        if index > 0 && is_two_of(&self.chords[index - 1], chord) {
+            return Ok(());
+        }
+
+        let to_minor = self
+            .resolution(index)
+            .is_some_and(|target| target.quality() == ChordQuality::Minor);
+        let quality = if to_minor {
+            ChordQuality::HalfDiminished
+        } else {
+            ChordQuality::Minor
+        };
+        let two = ChordSymbol::new(root, quality, [7], [], None);
+        let label = format!(
+            "{}: {two} {chord} for {chord}",
+            SubstitutionKind::RelatedTwoFive
+        );
+        options.push(Substitution {
+            kind: SubstitutionKind::RelatedTwoFive,
+            start: index,
+            end: index + 1,
+            chords: vec![two, chord.clone()],
+            label,
+        });
+        Ok(())
+    }
+
+    fn backdoor(&self, index: usize, options: &mut Vec<Substitution>) -> Result<()> {
+        let chord = &self.chords[index];
+        if !is_dominant(chord) {
+            return Ok(());
+        }
+        let Some(target) = self.resolution(index) else {
+            return Ok(());
+        };
+        if target.quality() != ChordQuality::Major {
+            return Ok(());
+        }
+
+        let dominant = ChordSymbol::new(
+            transpose(target.root(), "m7")?,
+            ChordQuality::Dominant,
+            [7],
+            [],
+            None,
+        );
+        let label = format!(
+            "{}: {dominant} for {chord}",
+            SubstitutionKind::BackdoorDominant
+        );
+        options.push(Substitution {
+            kind: SubstitutionKind::BackdoorDominant,
+            start: index,
+            end: index + 1,
+            chords: vec![dominant.clone()],
+            label,
+        });
+
+        if index > 0 && is_two_of(&self.chords[index - 1], chord) {
+            let two = &self.chords[index - 1];
+            let four = ChordSymbol::new(
+                transpose(target.root(), "P4")?,
+                ChordQuality::Minor,
+                [7],
+                [],
+                None,
+            );
+            let label = format!(
+                "{}: {four} {dominant} for {two} {chord}",
+                SubstitutionKind::BackdoorDominant
+            );
+            options.push(Substitution {
+                kind: SubstitutionKind::BackdoorDominant,
+                start: index - 1,
+                end: index + 1,
+                chords: vec![four, dominant],
+                label,
+            });
+        }
+        Ok(())
+    }
+
+    fn diminished_passing(&self, index: usize, options: &mut Vec<Substitution>) -> Result<()> {
+        let Some(next) = self.chords.get(index + 1) else {
+            return Ok(());
+        };
+        let chord = &self.chords[index];
+        let step = (next.root().pitch_class_number() + 12 - chord.root().pitch_class_number()) % 12;
+        let alteration = match step {
+            2 => "a1",
+            10 => "-a1",
+            _ => return Ok(()),
+        };
+
+        let passing = ChordSymbol::new(
+            transpose(chord.root(), alteration)?,
+            ChordQuality::Diminished,
+            [7],
+            [],
+            None,
+        );
+        let label = format!(
+            "{}: {passing} between {chord} and {next}",
+            SubstitutionKind::DiminishedPassing
+        );
+        options.push(Substitution {
+            kind: SubstitutionKind::DiminishedPassing,
+            start: index + 1,
+            end: index + 1,
+            chords: vec![passing],
+            label,
+        });
+        Ok(())
+    }
+
+    fn modal_interchange(&self, index: usize, options: &mut Vec<Substitution>) -> Result<()> {
+        if !matches!(self.key.mode(), "major" | "minor") {
+            return Ok(());
+        }
+        let chord = &self.chords[index];
+        let number = NashvilleNumber::from_chord_symbol(chord, &self.key)?;
+        if number.accidental() != 0 || chord.bass().is_some() {
+            return Ok(());
+        }
+
+        let parallel = self.key.parallel()?;
+        let degree = usize::from(number.degree());
+        let borrowed = if chord.extensions().contains(&7) {
+            parallel.seventh_chord_from_degree(degree)?
+        } else {
+            parallel.triad_from_degree(degree)?
+        };
+        let Some(figure) = borrowed.chord_symbol() else {
+            return Ok(());
+        };
+        let borrowed = ChordSymbol::parse(figure)?;
+        if borrowed.to_chord()?.pitch_classes() == chord.to_chord()?.pitch_classes() {
+            return Ok(());
+        }
+
+        let label = format!(
+            "{} from {} {}: {borrowed} for {chord}",
+            SubstitutionKind::ModalInterchange,
+            parallel.tonic_pitch().name().replace('-', "b"),
+            parallel.mode()
+        );
+        options.push(Substitution {
+            kind: SubstitutionKind::ModalInterchange,
+            start: index,
+            end: index + 1,
+            chords: vec![borrowed],
+            label,
+        });
+        Ok(())
+    }
+
+    /// The chord a dominant at `index` resolves to down a fifth, if the next
+    /// chord does.
+    fn resolution(&self, index: usize) -> Option<&ChordSymbol> {
+        let chord = &self.chords[index];
+        self.chords.get(index + 1).filter(|next| {
+            (next.root().pitch_class_number() + 12 - chord.root().pitch_class_number()) % 12 == 5
+        })
+    }
+}
+
+impl fmt::Display for Progression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, chord) in self.chords.iter().enumerate() {
+            if index > 0 {
+                f.write_str(" ")?;
+            }
+            write!(f, "{chord}")?;
+        }
+        Ok(())
+    }
+}
+
+fn is_dominant(chord: &ChordSymbol) -> bool {
+    chord.quality() == ChordQuality::Dominant
+}
+
+/// Whether `two` is the related ii of `dominant`: a minor or half-diminished
+/// chord a fifth above it.
+fn is_two_of(two: &ChordSymbol, dominant: &ChordSymbol) -> bool {
+    matches!(
+        two.quality(),
+        ChordQuality::Minor | ChordQuality::HalfDiminished
+    ) && (two.root().pitch_class_number() + 12 - dominant.root().pitch_class_number()) % 12 == 7
+}
+
+fn transpose(pitch: &Pitch, interval: &str) -> Result<Pitch> {
+    Interval::from_name(interval)?.transpose_pitch(pitch)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn c_major() -> Key {
+        Key::from_tonic_mode("C", "major").unwrap()
+    }
+
+    fn labels(options: &[Substitution]) -> Vec<&str> {
+        options.iter().map(Substitution::label).collect()
+    }
+
+    #[test]
+    fn generates_progressions_from_roman_numerals() {
+        let progression = Progression::from_roman_numerals(c_major(), &["ii7", "V7", "I"]).unwrap();
+        assert_eq!(progression.to_string(), "Dm7 G7 C");
+        assert_eq!(
+            progression
+                .nashville_numbers()
+                .unwrap()
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            vec!["2m7", "57", "1"]
+        );
+    }
+
+    #[test]
+    fn substitutes_dominants() {
+        let progression = Progression::parse(c_major(), "Dm7 | G7 | Cmaj7").unwrap();
+        let tritone = progression
+            .substitutions_of(SubstitutionKind::TritoneSubstitution)
+            .unwrap();
+        assert_eq!(labels(&tritone), vec!["tritone substitution: Db7 for G7"]);
+        assert_eq!(
+            progression.apply(&tritone[0]).unwrap().to_string(),
+            "Dm7 Db7 Cmaj7"
+        );
+
+        let backdoor = progression
+            .substitutions_of(SubstitutionKind::BackdoorDominant)
+            .unwrap();
+        assert_eq!(
+            labels(&backdoor),
+            vec![
+                "backdoor dominant: Fm7 Bb7 for Dm7 G7",
+                "backdoor dominant: Bb7 for G7"
+            ]
+        );
+        assert_eq!(
+            progression.apply(&backdoor[1]).unwrap().to_string(),
+            "Dm7 Bb7 Cmaj7"
+        );
+        assert_eq!(
+            progression
+                .reharmonize(SubstitutionKind::BackdoorDominant)
+                .unwrap()
+                .to_string(),
+            "Fm7 Bb7 Cmaj7"
+        );
+
+        let f7 = Progression::parse(c_major(), "F7").unwrap();
+        assert_eq!(
+            f7.reharmonize(SubstitutionKind::TritoneSubstitution)
+                .unwrap()
+                .to_string(),
+            "B7"
+        );
+    }
+
+    #[test]
+    fn inserts_related_two_fives_and_passing_diminished_chords() {
+        let progression = Progression::parse(c_major(), "Cmaj7 A7 Dm7 G7 Cmaj7").unwrap();
+        assert_eq!(
+            progression
+                .reharmonize(SubstitutionKind::RelatedTwoFive)
+                .unwrap()
+                .to_string(),
+            "Cmaj7 Em7b5 A7 Dm7 G7 Cmaj7"
+        );
+
+        let passing = progression
+            .substitutions_of(SubstitutionKind::DiminishedPassing)
+            .unwrap();
+        assert!(passing.is_empty());
+
+        let rising = Progression::parse(c_major(), "Cmaj7 Dm7 Em7 Dm7").unwrap();
+        assert_eq!(
+            rising
+                .reharmonize(SubstitutionKind::DiminishedPassing)
+                .unwrap()
+                .to_string(),
+            "Cmaj7 C#dim7 Dm7 D#dim7 Em7 Ebdim7 Dm7"
+        );
+    }
+
+    #[test]
+    fn borrows_from_the_parallel_key() {
+        let progression = Progression::parse(c_major(), "Cmaj7 Fmaj7 G7 Am7").unwrap();
+        let borrowed = progression
+            .substitutions_of(SubstitutionKind::ModalInterchange)
+            .unwrap();
+        assert_eq!(
+            labels(&borrowed),
+            vec![
+                "modal interchange from C minor: Cm7 for Cmaj7",
+                "modal interchange from C minor: Fm7 for Fmaj7",
+                "modal interchange from C minor: Gm7 for G7",
+                "modal interchange from C minor: Abmaj7 for Am7",
+            ]
+        );
+
+        let all = progression.substitutions().unwrap();
+        assert!(
+            all.windows(2)
+                .all(|pair| pair[0].start() <= pair[1].start())
+        );
+        assert!(
+            all.iter()
+                .any(|option| option.kind() == SubstitutionKind::TritoneSubstitution)
+        );
+    }
+}
@@ -0,0 +1,368 @@
+//! Ambitus, tessitura and range checks for voices and instruments.
+//!
+//! The [`Ambitus`] is the span from the lowest to the highest pitch, as
+//! music21's `analysis.discrete.Ambitus` reports it. The [`Tessitura`] says
+//! where within that span a part mostly lies, weighing each pitch by how
+//! long it sounds. [`check_range`] flags the notes of a part that fall
+//! outside an [`Instrument`]'s range, for proofreading arrangements.
+
+use std::collections::BTreeMap;
+
+use crate::{
+    chord::Chord,
+    defaults::{FloatType, IntegerType},
+    error::Result,
+    instrument::Instrument,
+    interval::Interval,
+    pitch::Pitch,
+    stream::{Stream, StreamElement},
+};
+
+/// The span from a lowest to a highest pitch.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Ambitus {
+    lowest: Pitch,
+    highest: Pitch,
+}
+
+impl Ambitus {
+    /// Returns the ambitus of `pitches`, or `None` when there are none.
+    ///
+    /// Of two pitches at the same height, the first one keeps its spelling.
+    pub fn from_pitches<'a>(pitches: impl IntoIterator<Item = &'a Pitch>) -> Option<Self> {
+        let mut pitches = pitches.into_iter();
+        let first = pitches.next()?;
+        let (lowest, highest) = pitches.fold((first, first), |(lowest, highest), pitch| {
+            (
+                if pitch.ps() < lowest.ps() {
+                    pitch
+                } else {
+                    lowest
+                },
+                if pitch.ps() > highest.ps() {
+                    pitch
+                } else {
+                    highest
+                },
+            )
+        });
+        Some(Self {
+            lowest: lowest.clone(),
+            highest: highest.clone(),
+        })
+    }
+
+    /// Returns the ambitus from `lowest` to `highest`, swapping them if they
+    /// are given the wrong way round.
+    pub fn between(lowest: Pitch, highest: Pitch) -> Self {
+        if highest.ps() < lowest.ps() {
+            Self {
+                lowest: highest,
+                highest: lowest,
+            }
+        } else {
+            Self { lowest, highest }
+        }
+    }
+
+    /// Returns the lowest pitch.
+    pub fn lowest(&self) -> &Pitch {
+        &self.lowest
+    }
+
+    /// Returns the highest pitch.
+    pub fn highest(&self) -> &Pitch {
+        &self.highest
+    }
+
+    /// Returns the interval from the lowest to the highest pitch, spelled
+    /// from the two pitches.
+    pub fn interval(&self) -> Result<Interval> {
+        Interval::between_pitches(&self.lowest, &self.highest)
+    }
+
+    /// Returns the span in semitones.
+    pub fn semitones(&self) -> FloatType {
+        self.highest.ps() - self.lowest.ps()
+    }
+
+    /// Returns whether `pitch` lies within the span, ends included.
+    pub fn contains(&self, pitch: &Pitch) -> bool {
+        (self.lowest.ps()..=self.highest.ps()).contains(&pitch.ps())
+    }
+
+    /// Returns whether the whole of `other` lies within this span.
+    pub fn contains_ambitus(&self, other: &Ambitus) -> bool {
+        self.contains(&other.lowest) && self.contains(&other.highest)
+    }
+}
+
+/// Where a part mostly lies, weighted by duration.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Tessitura {
+    mean: FloatType,
+    standard_deviation: FloatType,
+}
+
+impl Tessitura {
+    /// Returns the duration-weighted mean pitch space, in MIDI numbers.
+    pub fn mean(&self) -> FloatType {
+        self.mean
+    }
+
+    /// Returns the duration-weighted standard deviation in semitones.
+    pub fn standard_deviation(&self) -> FloatType {
+        self.standard_deviation
+    }
+
+    /// Returns the pitch nearest the mean.
+    pub fn mean_pitch(&self) -> Result<Pitch> {
+        Pitch::from_midi(self.mean.round() as IntegerType)
+    }
+
+    /// Returns the band one standard deviation either side of the mean,
+    /// rounded outward to whole semitones.
+    pub fn band(&self) -> Result<Ambitus> {
+        Ok(Ambitus::between(
+            Pitch::from_midi((self.mean - self.standard_deviation).floor() as IntegerType)?,
+            Pitch::from_midi((self.mean + self.standard_deviation).ceil() as IntegerType)?,
+        ))
+    }
+}
+
+/// A note of a part that lies outside an instrument's range.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RangeViolation {
+    offset: FloatType,
+    pitch: Pitch,
+    semitones: FloatType,
+}
+
+impl RangeViolation {
+    /// Returns the offset of the offending note or chord.
+    pub fn offset(&self) -> FloatType {
+        self.offset
+    }
+
+    /// Returns the out-of-range pitch.
+    pub fn pitch(&self) -> &Pitch {
+        &self.pitch
+    }
+
+    /// Returns how far outside the range the pitch lies, in semitones:
+    /// negative below the lowest pitch, positive above the highest.
+    pub fn semitones(&self) -> FloatType {
+        self.semitones
+    }
+
+    /// Returns whether the pitch lies below the range.
+    pub fn is_too_low(&self) -> bool {
+        self.semitones < 0.0
+    }
+}
+
+/// Returns the duration-weighted tessitura of `stream`, or `None` when
+/// nothing sounds in it.
+///
+/// Each note and chord tone weighs its length in quarters.
+pub fn tessitura(stream: &Stream) -> Option<Tessitura> {
+    let weighted = stream
+        .events()
+        .iter()
+        .flat_map(|event| {
+            let length = event.element().quarter_length();
+            event
+                .element()
+                .pitches()
+                .into_iter()
+                .map(move |pitch| (pitch.ps(), length))
+        })
+        .filter(|(_, length)| *length > 0.0)
+        .collect::<Vec<_>>();
+    let total = weighted.iter().map(|(_, length)| length).sum::<FloatType>();
+    if total == 0.0 {
+        return None;
+    }
+    let mean = weighted
+        .iter()
+        .map(|(ps, length)| ps * length)
+        .sum::<FloatType>()
+        / total;
+    let variance = weighted
+        .iter()
+        .map(|(ps, length)| (ps - mean).powi(2) * length)
+        .sum::<FloatType>()
+        / total;
+    Some(Tessitura {
+        mean,
+        standard_deviation: variance.sqrt(),
+    })
+}
+
+/// Returns how long each pitch sounds in `stream`, in quarters, keyed by
+/// MIDI number.
+///
+/// Microtonal pitches count toward the nearest semitone. Only pitches that
+/// sound appear as keys.
+pub fn pitch_space_histogram(stream: &Stream) -> BTreeMap<IntegerType, FloatType> {
+    let mut histogram = BTreeMap::new();
+    for event in stream.events() {
+        let length = event.element().quarter_length();
+        for pitch in event.element().pitches() {
+            *histogram
+                .entry(pitch.ps().round() as IntegerType)
+                .or_insert(0.0) += length;
+        }
+    }
+    histogram
+}
+
+/// Returns every pitch of `stream` outside the range of `instrument`, in
+/// order.
+///
+/// Pitches are read as sounding pitches and compared with
+/// [`Instrument::range`].
+pub fn check_range(stream: &Stream, instrument: &Instrument) -> Vec<RangeViolation> {
+    let range = instrument.range();
+    let mut violations = Vec::new();
+    for event in stream.events() {
+        if matches!(event.element(), StreamElement::Rest(_)) {
+            continue;
+        }
+        for pitch in event.element().pitches() {
+            let ps = pitch.ps();
+            let semitones = if ps < range.lowest().ps() {
+                ps - range.lowest().ps()
+            } else if ps > range.highest().ps() {
+                ps - range.highest().ps()
+            } else {
+                continue;
+            };
+            violations.push(RangeViolation {
+                offset: event.offset(),
+                pitch,
+                semitones,
+            });
+        }
+    }
+    violations
+}
+
+impl Stream {
+    /// Returns the span from the stream's lowest to its highest pitch, or
+    /// `None` when it has no pitches.
+    pub fn ambitus(&self) -> Option<Ambitus> {
+        Ambitus::from_pitches(&self.pitches())
+    }
+
+    /// Returns the stream's duration-weighted tessitura.
+    ///
+    /// See [`tessitura`].
+    pub fn tessitura(&self) -> Option<Tessitura> {
+        tessitura(self)
+    }
+
+    /// Returns how long each pitch sounds, keyed by MIDI number.
+    ///
+    /// See [`pitch_space_histogram`].
+    pub fn pitch_space_histogram(&self) -> BTreeMap<IntegerType, FloatType> {
+        pitch_space_histogram(self)
+    }
+
+    /// Returns every pitch outside the range of `instrument`.
+    ///
+    /// See [`check_range`].
+    pub fn check_range(&self, instrument: &Instrument) -> Vec<RangeViolation> {
+        check_range(self, instrument)
+    }
+}
+
+impl Chord {
+    /// Returns the span from the chord's lowest to its highest pitch, or
+    /// `None` for an empty chord.
+    pub fn ambitus(&self) -> Option<Ambitus> {
+        Ambitus::from_pitches(&self.pitches())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stream::test_stream;
+
+    #[test]
+    fn ambitus_spans_lowest_to_highest() {
+        let stream = test_stream(&[("E4", 1.0), ("B-3", 1.0), ("r", 1.0), ("G5", 1.0)]);
+        let ambitus = stream.ambitus().unwrap();
+        assert_eq!(ambitus.lowest().name_with_octave(), "B-3");
+        assert_eq!(ambitus.highest().name_with_octave(), "G5");
+        assert_eq!(ambitus.semitones(), 21.0);
+        assert_eq!(ambitus.interval().unwrap().name(), "Major Thirteenth");
+        assert!(Stream::new().ambitus().is_none());
+
+        let chord = Chord::new("C4 B-4 D4").unwrap().ambitus().unwrap();
+        assert_eq!(chord.interval().unwrap().semitones(), 10);
+        assert!(ambitus.contains_ambitus(&chord));
+        assert!(!chord.contains(ambitus.highest()));
+    }
+
+    #[test]
+    fn tessitura_and_histogram_weigh_durations() {
+        // Mostly C4, with a short excursion to C5.
+        let stream = test_stream(&[("C4", 3.0), ("C5", 1.0)]);
+        let tessitura = stream.tessitura().unwrap();
+        assert_eq!(tessitura.mean(), 63.0);
+        assert!((tessitura.standard_deviation() - 27.0_f64.sqrt()).abs() < 1e-12);
+        assert_eq!(tessitura.mean_pitch().unwrap().ps(), 63.0);
+        let band = tessitura.band().unwrap();
+        assert_eq!((band.lowest().ps(), band.highest().ps()), (57.0, 69.0));
+        assert!(test_stream(&[("r", 2.0)]).tessitura().is_none());
+
+        let mut chords = stream.clone();
+        chords.push(Chord::new("C4 E4").unwrap());
+        let histogram = chords.pitch_space_histogram();
+        assert_eq!(
+            histogram.into_iter().collect::<Vec<_>>(),
+            vec![(60, 4.0), (64, 1.0), (72, 1.0)]
+        );
+    }
+
+    #[test]
+    fn flags_notes_outside_an_instrument_range() {
+        let violin = Instrument::from_name("violin").unwrap();
+        let stream = test_stream(&[
+            ("F3", 1.0),
+            ("G3", 1.0),
+            ("A5", 1.0),
+            ("r", 1.0),
+            ("B7", 1.0),
+        ]);
+        let violations = stream.check_range(&violin);
+        let summary = violations
+            .iter()
+            .map(|violation| {
+                (
+                    violation.offset(),
+                    violation.pitch().name_with_octave(),
+                    violation.semitones(),
+                    violation.is_too_low(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            vec![
+                (0.0, "F3".to_string(), -2.0, true),
+                (4.0, "B7".to_string(), 7.0, false),
+            ]
+        );
+        assert!(
+            test_stream(&[("G3", 1.0), ("E7", 1.0)])
+                .check_range(&violin)
+                .is_empty()
+        );
+    }
+}
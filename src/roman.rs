@@ -1,12 +1,12 @@
 use crate::{
     chord::Chord,
-    chordsymbol::{ChordQuality, ChordSymbol},
     defaults::IntegerType,
     error::{Error, Result},
     interval::Interval,
     key::Key,
     pitch::Pitch,
 };
+use std::collections::BTreeSet;
 use std::fmt;
 use std::sync::LazyLock;
 
@@ -22,15 +22,21 @@ pub struct RomanNumeral {
     degree: u8,
     accidental: i8,
     inversion: u8,
-    seventh: bool,
+    extension: u8,
+    major_seventh: bool,
     quality: RomanQuality,
     secondary: Option<String>,
     kind: RomanKind,
+    alterations: Vec<(u8, i8)>,
+    additions: Vec<(u8, i8)>,
+    omissions: Vec<u8>,
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 enum RomanKind {
     Diatonic,
+    Neapolitan,
+    CadentialSixFour,
     AugmentedSixth(AugmentedSixthKind),
 }
 
@@ -51,6 +57,15 @@ enum RomanQuality {
     Augmented,
 }
 
+/// Bracketed alterations, additions and omissions such as `[b9]`, `[add6]`
+/// and `[no5]`, in music21's `bracketedAlterations` syntax.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+struct BracketGroups {
+    alterations: Vec<(u8, i8)>,
+    additions: Vec<(u8, i8)>,
+    omissions: Vec<u8>,
+}
+
 impl AugmentedSixthKind {
     fn from_figure(figure: &str) -> Option<Self> {
         match figure.trim() {
@@ -98,8 +113,22 @@ impl AugmentedSixthKind {
 impl RomanNumeral {
     /// Parses a Roman numeral figure in a key.
     ///
-    /// Supports ordinary figures such as `V7/V` and augmented-sixth figures
-    /// such as `It+6`, `Fr+6`, `Ger+6`, and `Sw+6`.
+    /// Supports ordinary figures such as `V7/V`, `viiø7` and `IVmaj7`, ninth,
+    /// eleventh and thirteenth chords such as `V9`, music21's bracketed
+    /// alterations such as `V7[b9]`, `IV[add6]` and `V7[no5]`, the same
+    /// alterations and suspensions written without brackets after the figure,
+    /// as in `V7b9` and `V7sus4`, the Neapolitan `N6`, the cadential `Cad64`,
+    /// and augmented-sixth figures such as `It+6`, `Fr+6`, `Ger+6`, and
+    /// `Sw+6`.
+    ///
+    /// Figures in a minor key follow music21's default treatment of the sixth
+    /// and seventh degrees: a major or augmented chord sits on the lowered
+    /// degree (`VII` is B-flat in C minor) while a minor or diminished chord
+    /// sits on the raised one (`viio7` is built on B natural). Sevenths,
+    /// ninths, elevenths and thirteenths come from the key's scale, so `I7`
+    /// in C major has a B natural and `V9` in C minor an A-flat; `o7`, `ø7`,
+    /// `maj7` and bracketed alterations such as `[b7]` or `[b9]` fix them
+    /// instead.
     pub fn new(figure: impl Into<String>, key: Key) -> Result<Self> {
         let figure = figure.into();
         let trimmed = figure.trim();
@@ -114,36 +143,91 @@ impl RomanNumeral {
                 degree: 6,
                 accidental: -1,
                 inversion: 0,
-                seventh: false,
+                extension: 5,
+                major_seventh: false,
                 quality: RomanQuality::Augmented,
                 secondary: None,
                 kind: RomanKind::AugmentedSixth(kind),
+                alterations: Vec::new(),
+                additions: Vec::new(),
+                omissions: Vec::new(),
             });
         }
 
-        let (primary, secondary) = match trimmed.split_once('/') {
+        let (body, brackets) = split_bracket_groups(trimmed)?;
+        let (primary, secondary) = match body.split_once('/') {
             Some((primary, secondary)) => (primary, Some(secondary.to_string())),
-            None => (trimmed, None),
+            None => (body.as_str(), None),
         };
 
-        let (accidental, primary) = split_roman_accidental_prefix(primary);
-        let (roman, suffix) = split_roman_prefix(primary)?;
-        let degree = roman_degree(roman)?;
-        let quality = roman_quality(roman, suffix);
-        let inversion = parse_inversion(suffix);
-        let seventh = suffix_has_seventh(suffix);
-
-        Ok(Self {
-            figure: trimmed.to_string(),
-            key,
-            degree,
-            accidental,
-            inversion,
-            seventh,
-            quality,
-            secondary,
-            kind: RomanKind::Diatonic,
-        })
+        let mut numeral = if let Some(inversion) = neapolitan_inversion(primary) {
+            Self {
+                figure: trimmed.to_string(),
+                key,
+                degree: 2,
+                accidental: -1,
+                inversion,
+                extension: 5,
+                major_seventh: false,
+                quality: RomanQuality::Major,
+                secondary,
+                kind: RomanKind::Neapolitan,
+                alterations: Vec::new(),
+                additions: Vec::new(),
+                omissions: Vec::new(),
+            }
+        } else if primary == "Cad64" {
+            let quality = if key.mode() == "minor" {
+                RomanQuality::Minor
+            } else {
+                RomanQuality::Major
+            };
+            Self {
+                figure: trimmed.to_string(),
+                key,
+                degree: 1,
+                accidental: 0,
+                inversion: 2,
+                extension: 5,
+                major_seventh: false,
+                quality,
+                secondary,
+                kind: RomanKind::CadentialSixFour,
+                alterations: Vec::new(),
+                additions: Vec::new(),
+                omissions: Vec::new(),
+            }
+        } else {
+            let (accidental, primary) = split_roman_accidental_prefix(primary);
+            let (roman, suffix) = split_roman_prefix(primary)?;
+            let degree = roman_degree(roman)?;
+            let suffix = strip_roman_addition_groups(suffix);
+            let (suffix, inline) = split_inline_alterations(&suffix).ok_or_else(|| {
+                Error::Chord(format!("unsupported alteration in figure {trimmed:?}"))
+            })?;
+            let digits = normalize_figure(&figured_bass_digits(suffix)).to_string();
+
+            Self {
+                figure: trimmed.to_string(),
+                key,
+                degree,
+                accidental,
+                inversion: parse_inversion(&digits),
+                extension: parse_extension(&digits),
+                major_seventh: suffix_has_major_seventh(suffix),
+                quality: roman_quality(roman, suffix),
+                secondary,
+                kind: RomanKind::Diatonic,
+                alterations: inline.alterations,
+                additions: inline.additions,
+                omissions: inline.omissions,
+            }
+        };
+
+        numeral.alterations.extend(brackets.alterations);
+        numeral.additions.extend(brackets.additions);
+        numeral.omissions.extend(brackets.omissions);
+        Ok(numeral)
     }
 
     /// Returns the original figure.
@@ -179,6 +263,40 @@ impl RomanNumeral {
         &self.key
     }
 
+    /// Returns `true` for the Neapolitan figures `N`, `N6`, `N53` and `N64`.
+    pub fn is_neapolitan(&self) -> bool {
+        self.kind == RomanKind::Neapolitan
+    }
+
+    /// Returns `true` for the cadential six-four figure `Cad64`.
+    ///
+    /// music21 spells the chord as a second-inversion tonic triad while
+    /// recognizing that it functions as a dominant embellishment.
+    pub fn is_cadential_six_four(&self) -> bool {
+        self.kind == RomanKind::CadentialSixFour
+    }
+
+    /// Returns `true` when the chord is borrowed from the parallel key.
+    ///
+    /// Mirrors music21's `isMixture`: the chord must not be diatonic in its own
+    /// major or minor key but must be diatonic in the parallel one. In minor,
+    /// the raised sixth and seventh degrees count as diatonic. Applied chords,
+    /// augmented sixths and keys in other modes are never mixture.
+    pub fn is_mixture(&self) -> Result<bool> {
+        if self.secondary.is_some()
+            || matches!(self.kind, RomanKind::AugmentedSixth(_))
+            || !matches!(self.key.mode(), "major" | "minor")
+        {
+            return Ok(false);
+        }
+
+        let pitch_classes = self.to_chord()?.pitch_classes();
+        let own = diatonic_pitch_classes(&self.key)?;
+        let parallel = diatonic_pitch_classes(&self.key.parallel()?)?;
+        Ok(!pitch_classes.iter().all(|pc| own.contains(pc))
+            && pitch_classes.iter().all(|pc| parallel.contains(pc)))
+    }
+
     /// Realizes the Roman numeral as a chord.
     pub fn to_chord(&self) -> Result<Chord> {
         if let RomanKind::AugmentedSixth(kind) = self.kind {
@@ -187,14 +305,27 @@ impl RomanNumeral {
 
         let effective_key = self.effective_key()?;
         let mut root = effective_key.pitch_from_degree(self.degree as usize)?;
-        if self.accidental != 0 {
-            root =
-                Interval::from_semitones(self.accidental as IntegerType)?.transpose_pitch(&root)?;
+        let mut accidental = self.accidental;
+        if accidental == 0 && raises_minor_degree(&effective_key, self.degree, self.quality) {
+            accidental = 1;
         }
+        if accidental != 0 {
+            root = alter_pitch(&root, accidental)?;
+        }
+
+        let extensions = diatonic_extensions(&effective_key, self.degree)?;
         let mut pitches = self
-            .interval_names()
+            .chord_tones(&extensions)
             .into_iter()
-            .map(|name| Interval::from_name(name)?.transpose_pitch(&root))
+            .map(|(degree, semitones)| {
+                let name = interval_name_for_degree(degree, semitones).ok_or_else(|| {
+                    Error::Chord(format!(
+                        "cannot spell chord degree {degree} at {semitones} semitones in {:?}",
+                        self.figure
+                    ))
+                })?;
+                Interval::from_name(name)?.transpose_pitch(&root)
+            })
             .collect::<Result<Vec<_>>>()?;
 
         for _ in 0..self.inversion.min(pitches.len().saturating_sub(1) as u8) {
@@ -209,7 +340,7 @@ impl RomanNumeral {
     fn augmented_sixth_chord(&self, kind: AugmentedSixthKind) -> Result<Chord> {
         let mut lowered_sixth = self.key.pitch_from_degree(6)?;
         if self.key.mode() != "minor" {
-            lowered_sixth = Interval::from_semitones(-1)?.transpose_pitch(&lowered_sixth)?;
+            lowered_sixth = alter_pitch(&lowered_sixth, -1)?;
         }
         let pitches = kind
             .interval_names()
@@ -220,6 +351,11 @@ impl RomanNumeral {
     }
 
     /// Performs functional Roman-numeral analysis in a key.
+    ///
+    /// The root is the one the chord's own tables infer, so a figure built on
+    /// another member does not round-trip: an added-sixth chord such as
+    /// `IV[add6]` spells a seventh chord and comes back as `ii65`. Pass the
+    /// intended root to [`Self::analyze_with_root`] to keep such figures.
     pub fn analyze(chord: &Chord, key: Key) -> Result<Option<Self>> {
         let Some(root_name) = chord.root_pitch_name() else {
             return Ok(None);
@@ -233,38 +369,46 @@ impl RomanNumeral {
     /// This is useful for pitch-class-set browser views where the caller has
     /// already chosen a transposition root and does not want inversion or root
     /// inference to pick a different chord member.
+    ///
+    /// Ninth, eleventh and thirteenth chords are written as `V9`, `V11` and
+    /// `V13`; other tones are written as music21 bracketed alterations such as
+    /// `V7[b9]`, `IV[add6]` or `I7[no5]`, and an inverted major triad on the
+    /// lowered supertonic as `N6` or `N64`; in root position it stays `bII`,
+    /// so `N53` comes back as `bII`. Suspended chords are written the same
+    /// way, as `V[no3][add4]`, so every figure this returns parses back to the
+    /// analyzed pitches. A bare fifth reads as the triad it implies. Function
+    /// cannot be read from pitches, so a cadential six-four comes back as `I64`.
     pub fn analyze_with_root(chord: &Chord, key: Key, root: &Pitch) -> Result<Option<Self>> {
         if let Some(kind) = augmented_sixth_kind_for_key(chord, &key)? {
             return Self::new(kind.figure(), key).map(Some);
         }
 
-        let root_pc = pitch_class(root);
+        let root_pc = root.pitch_class_number();
         let intervals = intervals_above_root(chord, root_pc);
         if !intervals.contains(&0) {
             return Ok(None);
         }
 
-        let Some((degree, accidental)) = degree_for_root(&key, root)? else {
+        let Some((degree, mut accidental)) = degree_for_root(&key, root)? else {
             return Ok(None);
         };
 
-        let symbol = chord
-            .chord_symbols_with_root(root_pc)?
-            .into_iter()
-            .find_map(|figure| ChordSymbol::parse(figure).ok());
-        let quality = symbol
-            .as_ref()
-            .map(symbol_quality)
-            .unwrap_or_else(|| quality_from_intervals(&intervals));
-
-        let figure = roman_figure(
-            degree,
-            accidental,
-            quality,
-            symbol.as_ref(),
-            &intervals,
-            roman_inversion(chord),
-        );
+        let inversion = roman_inversion(chord, root_pc, &intervals);
+        let extensions = diatonic_extensions(&key, degree)?;
+        let shape = ChordShape::from_intervals(&intervals, &extensions);
+        if accidental == 1 && raises_minor_degree(&key, degree, shape.quality) {
+            accidental = 0;
+        }
+        let neapolitan = degree == 2 && accidental == -1 && shape.is_plain_major_triad();
+        let figure = if neapolitan && inversion == 1 {
+            "N6".to_string()
+        } else if neapolitan && inversion == 2 {
+            "N64".to_string()
+        } else {
+            let prefix = roman_accidental_prefix(accidental);
+            let body = roman_body_for_quality(degree_to_roman(degree), shape.quality);
+            format!("{prefix}{body}{}", shape.suffix(inversion))
+        };
 
         Self::new(figure, key).map(Some)
     }
@@ -279,7 +423,7 @@ impl RomanNumeral {
         let degree = roman_degree(roman)?;
         let mut tonic = self.key.pitch_from_degree(degree as usize)?;
         if accidental != 0 {
-            tonic = Interval::from_semitones(accidental as IntegerType)?.transpose_pitch(&tonic)?;
+            tonic = alter_pitch(&tonic, accidental)?;
         }
         let mode = if roman.chars().next().is_some_and(char::is_uppercase) {
             "major"
@@ -289,19 +433,53 @@ impl RomanNumeral {
         Key::from_tonic_mode(&tonic.name(), mode)
     }
 
-    fn interval_names(&self) -> Vec<&'static str> {
-        match (self.quality, self.seventh) {
-            (RomanQuality::Major, false) => vec!["P1", "M3", "P5"],
-            (RomanQuality::Major, true) => vec!["P1", "M3", "P5", "m7"],
-            (RomanQuality::Minor, false) => vec!["P1", "m3", "P5"],
-            (RomanQuality::Minor, true) => vec!["P1", "m3", "P5", "m7"],
-            (RomanQuality::Diminished, false) => vec!["P1", "m3", "d5"],
-            (RomanQuality::Diminished, true) => vec!["P1", "m3", "d5", "d7"],
-            (RomanQuality::HalfDiminished, false) => vec!["P1", "m3", "d5"],
-            (RomanQuality::HalfDiminished, true) => vec!["P1", "m3", "d5", "m7"],
-            (RomanQuality::Augmented, false) => vec!["P1", "M3", "a5"],
-            (RomanQuality::Augmented, true) => vec!["P1", "M3", "a5", "m7"],
+    /// Chord members as `(degree, semitones above the root)` pairs, after the
+    /// bracketed alterations, additions and omissions are applied.
+    ///
+    /// The seventh, ninth, eleventh and thirteenth come from `extensions`,
+    /// the scale above the degree, unless the figure names them: `o7`, `ø7`
+    /// and `maj7` fix the seventh, and a bracketed alteration or addition
+    /// fixes its degree at that many semitones from a major or perfect
+    /// interval.
+    fn chord_tones(&self, extensions: &[(u8, i8); 4]) -> Vec<(u8, i8)> {
+        let (third, fifth) = match self.quality {
+            RomanQuality::Major => (4, 7),
+            RomanQuality::Minor => (3, 7),
+            RomanQuality::Diminished | RomanQuality::HalfDiminished => (3, 6),
+            RomanQuality::Augmented => (4, 8),
+        };
+        let mut tones = vec![(1, 0), (3, third), (5, fifth)];
+
+        if self.extension >= 7 {
+            let seventh = match self.quality {
+                RomanQuality::Diminished => 9,
+                RomanQuality::HalfDiminished => 10,
+                _ if self.major_seventh => 11,
+                _ => extensions[0].1,
+            };
+            tones.push((7, seventh));
+        }
+        for &(degree, semitones) in &extensions[1..] {
+            if self.extension >= degree {
+                tones.push((degree, semitones));
+            }
+        }
+
+        for (degree, shift) in &self.alterations {
+            let semitones = natural_semitones(*degree) + shift;
+            match tones.iter_mut().find(|(tone, _)| tone == degree) {
+                Some(tone) => tone.1 = semitones,
+                None => tones.push((*degree, semitones)),
+            }
+        }
+        for (degree, shift) in &self.additions {
+            if !tones.iter().any(|(tone, _)| tone == degree) {
+                tones.push((*degree, natural_semitones(*degree) + shift));
+            }
         }
+        tones.retain(|(degree, _)| *degree == 1 || !self.omissions.contains(degree));
+        tones.sort_by_key(|(degree, semitones)| (*semitones, *degree));
+        tones
     }
 }
 
@@ -325,6 +503,57 @@ pub fn analyze_chord_with_root(
     RomanNumeral::analyze_with_root(chord, key, root)
 }
 
+/// The inversion a Neapolitan figure names, or `None` if it is not one.
+///
+/// music21 reads a bare `N` as `N6`, the position the chord is almost always
+/// found in.
+fn neapolitan_inversion(figure: &str) -> Option<u8> {
+    match figure {
+        "N" | "N6" | "N63" => Some(1),
+        "N53" => Some(0),
+        "N64" => Some(2),
+        _ => None,
+    }
+}
+
+/// Whether a minor key's sixth or seventh degree should be raised.
+///
+/// This is music21's default `Minor67Default.QUALITY`: minor and diminished
+/// chords on those degrees use the raised (melodic minor) form, major and
+/// augmented chords use the lowered (natural minor) one.
+fn raises_minor_degree(key: &Key, degree: u8, quality: RomanQuality) -> bool {
+    key.mode() == "minor"
+        && matches!(degree, 6 | 7)
+        && matches!(
+            quality,
+            RomanQuality::Minor | RomanQuality::Diminished | RomanQuality::HalfDiminished
+        )
+}
+
+/// Alters a pitch chromatically without changing its letter name.
+///
+/// A semitone-count transposition is free to respell, which turned `bII` in C
+/// major into a C-sharp chord; augmented unisons keep the step fixed.
+pub(crate) fn alter_pitch(pitch: &Pitch, semitones: i8) -> Result<Pitch> {
+    let augmentations = "a".repeat(semitones.unsigned_abs() as usize);
+    let direction = if semitones < 0 { "-" } else { "" };
+    Interval::from_name(format!("{direction}{augmentations}1"))?.transpose_pitch(pitch)
+}
+
+fn diatonic_pitch_classes(key: &Key) -> Result<BTreeSet<u8>> {
+    let mut pitch_classes = key
+        .pitches()?
+        .iter()
+        .map(Pitch::pitch_class_number)
+        .collect::<BTreeSet<_>>();
+    if key.mode() == "minor" {
+        for degree in [6, 7] {
+            pitch_classes.insert(key_degree_pitch_class(key, degree, 1)?);
+        }
+    }
+    Ok(pitch_classes)
+}
+
 fn split_roman_accidental_prefix(value: &str) -> (i8, &str) {
     let mut accidental = 0;
     let mut end = 0;
@@ -372,7 +601,7 @@ fn roman_degree(roman: &str) -> Result<u8> {
 
 fn roman_quality(roman: &str, suffix: &str) -> RomanQuality {
     let lower = suffix.to_ascii_lowercase();
-    if suffix.contains('\u{00f8}') || lower.contains("m7b5") {
+    if suffix.contains('\u{00f8}') || suffix.contains('%') || lower.contains("m7b5") {
         RomanQuality::HalfDiminished
     } else if lower.contains('o') || lower.contains("dim") {
         RomanQuality::Diminished
@@ -385,15 +614,62 @@ fn roman_quality(roman: &str, suffix: &str) -> RomanQuality {
     }
 }
 
-fn suffix_has_seventh(suffix: &str) -> bool {
-    let suffix = strip_roman_addition_groups(suffix);
-    suffix.contains('7')
-        || suffix.contains('9')
-        || suffix.contains("11")
-        || suffix.contains("13")
-        || suffix.contains("65")
-        || suffix.contains("43")
-        || suffix.contains("42")
+fn suffix_has_major_seventh(suffix: &str) -> bool {
+    suffix.to_ascii_lowercase().contains("maj") || suffix.contains('M')
+}
+
+/// Splits music21 bracket groups such as `[b9]`, `[add6]` and `[no5]` off a
+/// figure, returning the figure without them.
+fn split_bracket_groups(figure: &str) -> Result<(String, BracketGroups)> {
+    let mut body = String::with_capacity(figure.len());
+    let mut groups = BracketGroups::default();
+    let mut rest = figure;
+
+    while let Some(open) = rest.find('[') {
+        body.push_str(&rest[..open]);
+        let inner = &rest[open + 1..];
+        let close = inner
+            .find(']')
+            .ok_or_else(|| Error::Chord(format!("unclosed bracketed alteration in {figure:?}")))?;
+        parse_bracket_group(&inner[..close], &mut groups).ok_or_else(|| {
+            Error::Chord(format!(
+                "unsupported bracketed alteration [{}] in {figure:?}",
+                &inner[..close]
+            ))
+        })?;
+        rest = &inner[close + 1..];
+    }
+    body.push_str(rest);
+
+    Ok((body.trim().to_string(), groups))
+}
+
+fn parse_bracket_group(group: &str, groups: &mut BracketGroups) -> Option<()> {
+    let group = group.trim();
+    if let Some(addition) = group.strip_prefix("add") {
+        let (shift, degree) = split_roman_accidental_prefix(addition);
+        groups.additions.push((parse_degree(degree)?, shift));
+    } else if let Some(omission) = group
+        .strip_prefix("no")
+        .or_else(|| group.strip_prefix("omit"))
+    {
+        groups.omissions.push(parse_degree(omission)?);
+    } else {
+        let (shift, degree) = split_roman_accidental_prefix(group);
+        if shift == 0 {
+            return None;
+        }
+        groups.alterations.push((parse_degree(degree)?, shift));
+    }
+    Some(())
+}
+
+fn parse_degree(value: &str) -> Option<u8> {
+    value
+        .trim()
+        .parse::<u8>()
+        .ok()
+        .filter(|degree| (1..=14).contains(degree))
 }
 
 /// music21's `roman.figureShorthands`, mapping a full figured-bass string to
@@ -434,13 +710,15 @@ fn normalize_figure(figure: &str) -> &str {
         .map_or(figure, |(_, short)| *short)
 }
 
-fn parse_inversion(suffix: &str) -> u8 {
-    let suffix = strip_roman_addition_groups(suffix);
-    // Only the figured-bass digits decide the inversion; quality marks such as
-    // `o`, `+` and the half-diminished sign ride along in the suffix.
-    let digits: String = suffix.chars().filter(char::is_ascii_digit).collect();
+/// Only the figured-bass digits decide the inversion and extension; quality
+/// marks such as `o`, `+` and the half-diminished sign ride along in the
+/// suffix.
+fn figured_bass_digits(suffix: &str) -> String {
+    suffix.chars().filter(char::is_ascii_digit).collect()
+}
 
-    match normalize_figure(&digits) {
+fn parse_inversion(digits: &str) -> u8 {
+    match digits {
         "42" => 3,
         "43" | "64" => 2,
         "65" | "6" => 1,
@@ -448,6 +726,71 @@ fn parse_inversion(suffix: &str) -> u8 {
     }
 }
 
+/// The highest stacked chord degree a figure names: `5` for a triad, `7` for
+/// a seventh chord, and `9`, `11` or `13` for the extended chords.
+fn parse_extension(digits: &str) -> u8 {
+    match digits {
+        "13" => 13,
+        "11" => 11,
+        "9" => 9,
+        "7" | "65" | "43" | "42" => 7,
+        _ => 5,
+    }
+}
+
+/// Splits alterations written without brackets after a chord figure, such
+/// as the `b9` of `V7b9`, the `#11` of `V9#11` or the `sus4` of `V7sus4`,
+/// off the suffix, returning what is left and the groups they stand for.
+///
+/// Only a triad or a `7`, `9`, `11` or `13` figure takes them, so figured
+/// bass such as `6b5` is left alone. Returns `None` when the alterations
+/// cannot be read.
+fn split_inline_alterations(suffix: &str) -> Option<(&str, BracketGroups)> {
+    let mut groups = BracketGroups::default();
+    let rest = suffix
+        .trim_start_matches(['o', '\u{00f8}', '%', '+'])
+        .trim_start_matches("maj")
+        .trim_start_matches('M');
+    let figure = leading_digits(rest);
+    let mut tail = &rest[figure.len()..];
+    if !matches!(figure, "" | "7" | "9" | "11" | "13")
+        || !(tail.starts_with(['b', '#']) || tail.starts_with("sus"))
+    {
+        return Some((suffix, groups));
+    }
+    let head = &suffix[..suffix.len() - tail.len()];
+
+    while !tail.is_empty() {
+        if let Some(rest) = tail.strip_prefix("sus") {
+            let digits = leading_digits(rest);
+            let degree = match digits {
+                "" | "4" => 4,
+                "2" => 2,
+                _ => return None,
+            };
+            groups.omissions.push(3);
+            groups.additions.push((degree, 0));
+            tail = &rest[digits.len()..];
+        } else {
+            let (shift, rest) = split_roman_accidental_prefix(tail);
+            let digits = leading_digits(rest);
+            if shift == 0 {
+                return None;
+            }
+            groups.alterations.push((parse_degree(digits)?, shift));
+            tail = &rest[digits.len()..];
+        }
+    }
+    Some((head, groups))
+}
+
+fn leading_digits(value: &str) -> &str {
+    let end = value
+        .find(|ch: char| !ch.is_ascii_digit())
+        .unwrap_or(value.len());
+    &value[..end]
+}
+
 fn strip_roman_addition_groups(suffix: &str) -> String {
     let mut stripped = String::with_capacity(suffix.len());
     let mut rest = suffix;
@@ -464,14 +807,58 @@ fn strip_roman_addition_groups(suffix: &str) -> String {
     stripped
 }
 
+/// The seventh, ninth, eleventh and thirteenth above a scale degree, as
+/// `(chord degree, semitones above the degree)` pairs taken from the key's
+/// scale the way music21 stacks them.
+///
+/// They are measured from the unaltered degree, so `bVII7` keeps the minor
+/// seventh of `VII7` and the raised leading tone in minor keeps the sevenths
+/// of the natural minor scale.
+fn diatonic_extensions(key: &Key, degree: u8) -> Result<[(u8, i8); 4]> {
+    let degree = usize::from(degree.clamp(1, 7));
+    let root_pc = key.pitch_from_degree(degree)?.pitch_class_number();
+    let mut extensions = [(7, 0), (9, 0), (11, 0), (13, 0)];
+    for (chord_degree, semitones) in &mut extensions {
+        let scale_degree = (degree + usize::from(*chord_degree) - 2) % 7 + 1;
+        let pc = key.pitch_from_degree(scale_degree)?.pitch_class_number();
+        let octave = if *chord_degree > 7 { 12 } else { 0 };
+        *semitones = ((pc + 12 - root_pc) % 12) as i8 + octave;
+    }
+    Ok(extensions)
+}
+
+/// Semitones above the root of an unaltered degree: major for seconds,
+/// thirds, sixths and sevenths, perfect for the rest.
+fn natural_semitones(degree: u8) -> i8 {
+    const SIMPLE: [i8; 7] = [0, 2, 4, 5, 7, 9, 11];
+    let index = (degree.max(1) - 1) as usize;
+    SIMPLE[index % 7] + 12 * (index / 7) as i8
+}
+
+fn interval_name_for_degree(degree: u8, semitones: i8) -> Option<String> {
+    let perfect = matches!((degree.max(1) - 1) % 7, 0 | 3 | 4);
+    let specifier = match (perfect, semitones - natural_semitones(degree)) {
+        (true, -2) | (false, -3) => "dd",
+        (true, -1) | (false, -2) => "d",
+        (true, 0) => "P",
+        (false, -1) => "m",
+        (false, 0) => "M",
+        (_, 1) => "a",
+        (_, 2) => "aa",
+        _ => return None,
+    };
+    Some(format!("{specifier}{degree}"))
+}
+
 fn degree_for_root(key: &Key, root: &Pitch) -> Result<Option<(u8, i8)>> {
-    let root_pc = pitch_class(root);
+    let root_pc = root.pitch_class_number();
     let root_step = root.step();
     let mut best: Option<(u8, i8, bool)> = None;
 
     for degree in 1..=7 {
         let degree_pitch = key.pitch_from_degree(degree)?;
-        let diff = ((root_pc as i16 - pitch_class(&degree_pitch) as i16).rem_euclid(12)) as u8;
+        let diff =
+            ((root_pc as i16 - degree_pitch.pitch_class_number() as i16).rem_euclid(12)) as u8;
         let Some(accidental) = chromatic_diff_to_accidental(diff) else {
             continue;
         };
@@ -557,60 +944,191 @@ fn key_degree_pitch_class(key: &Key, degree: usize, semitones: IntegerType) -> R
     if semitones != 0 {
         pitch = Interval::from_semitones(semitones)?.transpose_pitch(&pitch)?;
     }
-    Ok(pitch_class(&pitch))
+    Ok(pitch.pitch_class_number())
 }
 
-fn roman_inversion(chord: &Chord) -> u8 {
-    if chord.pitches().iter().any(|pitch| pitch.octave().is_some()) {
-        chord.inversion().unwrap_or(0)
-    } else {
-        0
+/// The inversion implied by the bass, measured from the explicit root rather
+/// than from the root the chord tables would infer, so `IV[add6]` in root
+/// position is not mistaken for an inverted supertonic seventh.
+fn roman_inversion(chord: &Chord, root_pc: u8, intervals: &[u8]) -> u8 {
+    let pitches = chord.pitches();
+    if pitches.iter().all(|pitch| pitch.octave().is_none()) {
+        return 0;
     }
-}
+    let Some(bass) = pitches.iter().min_by(|a, b| {
+        a.ps()
+            .partial_cmp(&b.ps())
+            .unwrap_or(std::cmp::Ordering::Equal)
+    }) else {
+        return 0;
+    };
 
-fn symbol_quality(symbol: &ChordSymbol) -> RomanQuality {
-    match symbol.quality() {
-        ChordQuality::Major
-        | ChordQuality::Dominant
-        | ChordQuality::Suspended2
-        | ChordQuality::Suspended4
-        | ChordQuality::Power => RomanQuality::Major,
-        ChordQuality::Minor => RomanQuality::Minor,
-        ChordQuality::Diminished => RomanQuality::Diminished,
-        ChordQuality::HalfDiminished => RomanQuality::HalfDiminished,
-        ChordQuality::Augmented => RomanQuality::Augmented,
+    let diminished_seventh = intervals.contains(&9) && !intervals.contains(&7);
+    match (bass.pitch_class_number() + 12 - root_pc) % 12 {
+        3 | 4 => 1,
+        6..=8 => 2,
+        9 if diminished_seventh => 3,
+        10 | 11 => 3,
+        _ => 0,
     }
 }
 
-fn quality_from_intervals(intervals: &[u8]) -> RomanQuality {
-    if intervals.contains(&3) && intervals.contains(&6) {
-        if intervals.contains(&10) {
-            RomanQuality::HalfDiminished
+/// A chord read as stacked thirds above a known root, with whatever does not
+/// fit the stack kept aside as bracketed alterations.
+#[derive(Clone, Debug)]
+struct ChordShape {
+    quality: RomanQuality,
+    seventh: Option<u8>,
+    extension: u8,
+    upper: Vec<(u8, u8)>,
+    brackets: Vec<String>,
+}
+
+impl ChordShape {
+    /// Reads `intervals` above the root, stacking the ninth, eleventh and
+    /// thirteenth only where they match the scale's `extensions`.
+    fn from_intervals(intervals: &[u8], extensions: &[(u8, i8); 4]) -> Self {
+        let has = |interval: u8| intervals.contains(&interval);
+        let (quality, third, fifth) = if has(4) {
+            if has(8) && !has(7) {
+                (RomanQuality::Augmented, Some(4), Some(8))
+            } else {
+                (RomanQuality::Major, Some(4), has(7).then_some(7))
+            }
+        } else if has(3) {
+            if has(6) && !has(7) {
+                (RomanQuality::Diminished, Some(3), Some(6))
+            } else {
+                (RomanQuality::Minor, Some(3), has(7).then_some(7))
+            }
+        } else {
+            (RomanQuality::Major, None, has(7).then_some(7))
+        };
+
+        let mut quality = quality;
+        let seventh = if quality == RomanQuality::Diminished && has(9) && !has(10) {
+            Some(9)
+        } else if has(10) {
+            if quality == RomanQuality::Diminished {
+                quality = RomanQuality::HalfDiminished;
+            }
+            Some(10)
+        } else if has(11) && quality != RomanQuality::Diminished {
+            Some(11)
         } else {
-            RomanQuality::Diminished
+            None
+        };
+
+        let mut remaining = intervals
+            .iter()
+            .copied()
+            .filter(|interval| {
+                *interval != 0 && Some(*interval) != third && Some(*interval) != fifth
+            })
+            .filter(|interval| Some(*interval) != seventh)
+            .collect::<BTreeSet<_>>();
+
+        let mut extension = if seventh.is_some() { 7 } else { 5 };
+        let mut upper = Vec::new();
+        if seventh.is_some() {
+            for &(degree, semitones) in &extensions[1..] {
+                let interval = (semitones % 12) as u8;
+                if !remaining.remove(&interval) {
+                    break;
+                }
+                extension = degree;
+                upper.push((degree, interval));
+            }
+        }
+
+        let mut brackets = Vec::new();
+        // A plain `7` is the scale's seventh, so a minor seventh where the
+        // scale has a major one is written as an alteration.
+        if seventh == Some(10) && quality != RomanQuality::HalfDiminished && extensions[0].1 == 11 {
+            brackets.push("b7".to_string());
+        }
+        if fifth.is_none() {
+            match (remaining.remove(&6), remaining.remove(&8)) {
+                (true, _) if third.is_some() => brackets.push("b5".to_string()),
+                (_, true) if third == Some(3) => brackets.push("#5".to_string()),
+                (flat, sharp) => {
+                    if flat {
+                        remaining.insert(6);
+                    }
+                    if sharp {
+                        remaining.insert(8);
+                    }
+                    if intervals.len() >= 3 {
+                        brackets.push("no5".to_string());
+                    }
+                }
+            }
+        }
+        if third.is_none() && intervals.len() >= 3 {
+            brackets.push("no3".to_string());
+        }
+
+        for interval in remaining {
+            let token = match interval {
+                1 => "b9",
+                2 if third.is_none() && seventh.is_none() => "add2",
+                2 => "add9",
+                3 => "#9",
+                5 if seventh.is_some() => "add11",
+                5 => "add4",
+                6 => "#11",
+                8 => "b13",
+                9 if seventh.is_some() => "add13",
+                9 => "add6",
+                10 | 11 => "add7",
+                _ => continue,
+            };
+            brackets.push(token.to_string());
+        }
+
+        Self {
+            quality,
+            seventh,
+            extension,
+            upper,
+            brackets,
         }
-    } else if intervals.contains(&4) && intervals.contains(&8) {
-        RomanQuality::Augmented
-    } else if intervals.contains(&3) && intervals.contains(&7) {
-        RomanQuality::Minor
-    } else {
-        RomanQuality::Major
     }
-}
 
-fn roman_figure(
-    degree: u8,
-    accidental: i8,
-    quality: RomanQuality,
-    symbol: Option<&ChordSymbol>,
-    intervals: &[u8],
-    inversion: u8,
-) -> String {
-    let base = degree_to_roman(degree);
-    let prefix = roman_accidental_prefix(accidental);
-    let body = roman_body_for_quality(base, quality);
-    let suffix = functional_suffix(symbol, intervals, inversion, quality);
-    format!("{prefix}{body}{suffix}")
+    fn is_plain_major_triad(&self) -> bool {
+        self.quality == RomanQuality::Major && self.seventh.is_none() && self.brackets.is_empty()
+    }
+
+    fn suffix(&self, inversion: u8) -> String {
+        let figure = match (self.seventh, inversion) {
+            (Some(_), 1) => "65".to_string(),
+            (Some(_), 2) => "43".to_string(),
+            (Some(_), 3) => "42".to_string(),
+            (Some(_), _) => self.extension.to_string(),
+            (None, 1) => "6".to_string(),
+            (None, 2) => "64".to_string(),
+            (None, _) => String::new(),
+        };
+        let mut suffix = if self.seventh == Some(11) {
+            format!("maj{figure}")
+        } else {
+            figure
+        };
+
+        // An inverted extended chord keeps its upper tones as additions, since
+        // figured bass has no shorthand for them.
+        if inversion > 0 {
+            for &(degree, interval) in &self.upper {
+                let shift = interval as i8 - natural_semitones(degree) % 12;
+                let accidental = roman_accidental_prefix(shift);
+                suffix.push_str(&format!("[add{accidental}{degree}]"));
+            }
+        }
+        for bracket in &self.brackets {
+            suffix.push_str(&format!("[{bracket}]"));
+        }
+        suffix
+    }
 }
 
 fn roman_accidental_prefix(accidental: i8) -> String {
@@ -631,107 +1149,6 @@ fn roman_body_for_quality(base: &str, quality: RomanQuality) -> String {
     }
 }
 
-fn functional_suffix(
-    symbol: Option<&ChordSymbol>,
-    intervals: &[u8],
-    inversion: u8,
-    quality: RomanQuality,
-) -> String {
-    if let Some(symbol) = symbol
-        && needs_chord_symbol_suffix(symbol)
-    {
-        return chord_symbol_suffix_for_roman(symbol, quality);
-    }
-    figured_bass_suffix(intervals, inversion, quality)
-}
-
-fn needs_chord_symbol_suffix(symbol: &ChordSymbol) -> bool {
-    matches!(
-        symbol.quality(),
-        ChordQuality::Suspended2 | ChordQuality::Suspended4 | ChordQuality::Power
-    ) || !symbol.additions().is_empty()
-        || symbol.alterations().iter().any(|alteration| {
-            !(matches!(symbol.quality(), ChordQuality::HalfDiminished)
-                && alteration.degree() == 5
-                && alteration.semitones() == -1)
-        })
-        || symbol.extensions().iter().any(|degree| *degree != 7)
-        || chord_symbol_suffix(symbol).contains("maj7")
-}
-
-fn chord_symbol_suffix_for_roman(symbol: &ChordSymbol, quality: RomanQuality) -> String {
-    let suffix = chord_symbol_suffix(symbol);
-    let converted = match quality {
-        RomanQuality::Major => suffix.to_string(),
-        RomanQuality::Minor => suffix
-            .strip_prefix('m')
-            .filter(|rest| !rest.starts_with("aj"))
-            .unwrap_or(suffix)
-            .to_string(),
-        RomanQuality::Diminished => suffix.strip_prefix("dim").unwrap_or(suffix).to_string(),
-        RomanQuality::HalfDiminished => {
-            suffix.strip_prefix('m').unwrap_or(suffix).replace("b5", "")
-        }
-        RomanQuality::Augmented => suffix
-            .strip_prefix("aug")
-            .or_else(|| suffix.strip_prefix('+'))
-            .unwrap_or(suffix)
-            .to_string(),
-    };
-
-    if converted == "6" {
-        " add(13)".to_string()
-    } else {
-        converted
-    }
-}
-
-fn chord_symbol_suffix(symbol: &ChordSymbol) -> &str {
-    let body = symbol
-        .figure()
-        .split_once('/')
-        .map_or(symbol.figure(), |(body, _)| body);
-    let root_name = normalize_symbol_root_name(&symbol.root().name());
-    body.strip_prefix(&root_name).unwrap_or(body)
-}
-
-fn normalize_symbol_root_name(name: &str) -> String {
-    name.replace('-', "b")
-}
-
-fn figured_bass_suffix(intervals: &[u8], inversion: u8, quality: RomanQuality) -> String {
-    if has_seventh(intervals) {
-        let suffix = match inversion {
-            1 => "65",
-            2 => "43",
-            3 => "42",
-            _ => "7",
-        };
-        if matches!(quality, RomanQuality::Major) && intervals.contains(&11) {
-            format!("maj{suffix}")
-        } else {
-            suffix.to_string()
-        }
-    } else if has_triad_shape(intervals) {
-        match inversion {
-            1 => "6".to_string(),
-            2 => "64".to_string(),
-            _ => String::new(),
-        }
-    } else {
-        String::new()
-    }
-}
-
-fn has_seventh(intervals: &[u8]) -> bool {
-    intervals.contains(&10) || intervals.contains(&11) || intervals.contains(&9)
-}
-
-fn has_triad_shape(intervals: &[u8]) -> bool {
-    (intervals.contains(&3) || intervals.contains(&4))
-        && intervals.iter().any(|interval| matches!(interval, 6..=8))
-}
-
 fn degree_to_roman(degree: u8) -> &'static str {
     match degree {
         1 => "I",
@@ -761,10 +1178,6 @@ fn normalize_pitch_name(name: &str) -> String {
     normalized
 }
 
-fn pitch_class(pitch: &Pitch) -> u8 {
-    (pitch.ps().round() as IntegerType).rem_euclid(12) as u8
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -826,7 +1239,7 @@ mod tests {
         let rn = RomanNumeral::analyze_with_root(&seventh, key, &root)
             .unwrap()
             .unwrap();
-        assert_eq!(rn.figure(), "I7");
+        assert_eq!(rn.figure(), "I7[b7]");
     }
 
     #[test]
@@ -962,4 +1375,282 @@ mod tests {
                 .is_none()
         );
     }
+
+    fn pitch_names(numeral: &RomanNumeral) -> Vec<String> {
+        numeral
+            .to_chord()
+            .unwrap()
+            .pitches()
+            .into_iter()
+            .map(|pitch| pitch.name())
+            .collect()
+    }
+
+    #[test]
+    fn neapolitan_and_cadential_six_four_figures() {
+        let key = Key::from_tonic_mode("C", "minor").unwrap();
+        let neapolitan = RomanNumeral::new("N6", key.clone()).unwrap();
+        assert!(neapolitan.is_neapolitan());
+        assert_eq!(neapolitan.degree(), 2);
+        assert_eq!(neapolitan.accidental(), -1);
+        assert_eq!(neapolitan.inversion(), 1);
+        assert_eq!(pitch_names(&neapolitan), vec!["F", "A-", "D-"]);
+        assert_eq!(RomanNumeral::new("N", key.clone()).unwrap().inversion(), 1);
+
+        let cadential = RomanNumeral::new("Cad64", key.clone()).unwrap();
+        assert!(cadential.is_cadential_six_four());
+        assert_eq!(cadential.inversion(), 2);
+        assert_eq!(pitch_names(&cadential), vec!["G", "C", "E-"]);
+
+        let chord = Chord::new("F4 A-4 D-5").unwrap();
+        let rn = RomanNumeral::analyze(&chord, key).unwrap().unwrap();
+        assert_eq!(rn.figure(), "N6");
+        assert!(rn.is_neapolitan());
+    }
+
+    #[test]
+    fn chromatic_roots_keep_their_letter_names() {
+        let key = Key::from_tonic_mode("C", "major").unwrap();
+        assert_eq!(
+            pitch_names(&RomanNumeral::new("bII", key.clone()).unwrap()),
+            vec!["D-", "F", "A-"]
+        );
+        assert_eq!(
+            pitch_names(&RomanNumeral::new("bVI", key.clone()).unwrap()),
+            vec!["A-", "C", "E-"]
+        );
+        assert_eq!(
+            pitch_names(&RomanNumeral::new("#ivo7", key).unwrap()),
+            vec!["F#", "A", "C", "E-"]
+        );
+    }
+
+    #[test]
+    fn minor_keys_raise_sixth_and_seventh_for_minor_and_diminished_chords() {
+        let key = Key::from_tonic_mode("C", "minor").unwrap();
+        assert_eq!(
+            pitch_names(&RomanNumeral::new("viio7", key.clone()).unwrap()),
+            vec!["B", "D", "F", "A-"]
+        );
+        assert_eq!(
+            pitch_names(&RomanNumeral::new("VII", key.clone()).unwrap()),
+            vec!["B-", "D", "F"]
+        );
+        assert_eq!(
+            pitch_names(&RomanNumeral::new("VI", key.clone()).unwrap()),
+            vec!["A-", "C", "E-"]
+        );
+
+        let leading_tone = Chord::new("B D F A-").unwrap();
+        let rn = RomanNumeral::analyze(&leading_tone, key).unwrap().unwrap();
+        assert_eq!(rn.figure(), "viio7");
+    }
+
+    #[test]
+    fn mixture_is_diatonic_only_in_the_parallel_key() {
+        let major = Key::from_tonic_mode("C", "major").unwrap();
+        for (figure, expected) in [
+            ("iv", true),
+            ("bVI", true),
+            ("bIII", true),
+            ("ii\u{00f8}7", true),
+            ("IV", false),
+            ("V7", false),
+            ("V/V", false),
+            ("bII", false),
+        ] {
+            let numeral = RomanNumeral::new(figure, major.clone()).unwrap();
+            assert_eq!(numeral.is_mixture().unwrap(), expected, "{figure}");
+        }
+
+        let minor = Key::from_tonic_mode("C", "minor").unwrap();
+        assert!(
+            !RomanNumeral::new("IV", minor.clone())
+                .unwrap()
+                .is_mixture()
+                .unwrap()
+        );
+        assert!(
+            RomanNumeral::new("I", minor.clone())
+                .unwrap()
+                .is_mixture()
+                .unwrap()
+        );
+        assert!(!RomanNumeral::new("V", minor).unwrap().is_mixture().unwrap());
+    }
+
+    #[test]
+    fn extended_chords_and_bracketed_alterations() {
+        let key = Key::from_tonic_mode("C", "major").unwrap();
+        assert_eq!(
+            pitch_names(&RomanNumeral::new("V9", key.clone()).unwrap()),
+            vec!["G", "B", "D", "F", "A"]
+        );
+        assert_eq!(
+            pitch_names(&RomanNumeral::new("V7[b9]", key.clone()).unwrap()),
+            vec!["G", "B", "D", "F", "A-"]
+        );
+        assert_eq!(
+            pitch_names(&RomanNumeral::new("IV[add6]", key.clone()).unwrap()),
+            vec!["F", "A", "C", "D"]
+        );
+        assert_eq!(
+            pitch_names(&RomanNumeral::new("V7[no5]", key.clone()).unwrap()),
+            vec!["G", "B", "F"]
+        );
+        assert_eq!(
+            pitch_names(&RomanNumeral::new("IVmaj7", key.clone()).unwrap()),
+            vec!["F", "A", "C", "E"]
+        );
+        assert!(RomanNumeral::new("V7[b9", key.clone()).is_err());
+        assert!(RomanNumeral::new("V7[q9]", key).is_err());
+    }
+
+    #[test]
+    fn sevenths_and_extensions_come_from_the_scale() {
+        let major = Key::from_tonic_mode("C", "major").unwrap();
+        let minor = Key::from_tonic_mode("C", "minor").unwrap();
+        for (figure, key, expected) in [
+            ("V9", &minor, vec!["G", "B", "D", "F", "A-"]),
+            ("ii9", &minor, vec!["D", "F", "A", "C", "E-"]),
+            ("iii9", &major, vec!["E", "G", "B", "D", "F"]),
+            ("IV9", &major, vec!["F", "A", "C", "E", "G"]),
+            ("I9", &major, vec!["C", "E", "G", "B", "D"]),
+            ("V13", &major, vec!["G", "B", "D", "F", "A", "C", "E"]),
+            ("bVII7", &major, vec!["B-", "D", "F", "A-"]),
+            ("I7[b7]", &major, vec!["C", "E", "G", "B-"]),
+        ] {
+            let numeral = RomanNumeral::new(figure, key.clone()).unwrap();
+            assert_eq!(pitch_names(&numeral), expected, "{figure}");
+        }
+        assert!(
+            !RomanNumeral::new("IV9", major)
+                .unwrap()
+                .is_mixture()
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn extended_figures_round_trip_through_analysis() {
+        let major = Key::from_tonic_mode("C", "major").unwrap();
+        let minor = Key::from_tonic_mode("C", "minor").unwrap();
+        for (figure, key) in [
+            ("V9", &major),
+            ("V9", &minor),
+            ("Imaj9", &major),
+            ("I7[b7]", &major),
+            ("V7[b9]", &major),
+            ("V7[#9]", &major),
+            ("IV[add6]", &major),
+            ("I[add9]", &major),
+            ("IVmaj7", &major),
+            ("ii\u{00f8}7", &major),
+            ("viio7", &minor),
+            ("V7", &minor),
+            ("iv", &major),
+            ("bVI", &major),
+            ("N6", &minor),
+            ("N64", &major),
+            ("V65", &major),
+            ("I64", &major),
+        ] {
+            let numeral = RomanNumeral::new(figure, key.clone()).unwrap();
+            let chord = numeral.to_chord().unwrap();
+            let analyzed =
+                RomanNumeral::analyze_with_root(&chord, key.clone(), &numeral_root(&numeral))
+                    .unwrap()
+                    .unwrap_or_else(|| panic!("{figure} should analyze"));
+            assert_eq!(analyzed.figure(), figure);
+        }
+    }
+
+    #[test]
+    fn unbracketed_alterations_and_suspensions_apply() {
+        let key = Key::from_tonic_mode("C", "major").unwrap();
+        for (figure, expected) in [
+            ("V7b9", vec!["G", "B", "D", "F", "A-"]),
+            ("V7#9", vec!["G", "B", "D", "F", "A#"]),
+            ("V9#11", vec!["G", "B", "D", "F", "A", "C#"]),
+            ("V7sus4", vec!["G", "C", "D", "F"]),
+            ("Vsus4", vec!["G", "C", "D"]),
+            ("Vsus2", vec!["G", "A", "D"]),
+            ("V9", vec!["G", "B", "D", "F", "A"]),
+        ] {
+            assert_eq!(
+                pitch_names(&RomanNumeral::new(figure, key.clone()).unwrap()),
+                expected,
+                "{figure}"
+            );
+        }
+        for figure in ["V7b", "V7sus3", "V7#9x"] {
+            assert!(RomanNumeral::new(figure, key.clone()).is_err(), "{figure}");
+        }
+    }
+
+    #[test]
+    fn inferred_roots_read_added_sixths_as_sevenths() {
+        let key = Key::from_tonic_mode("C", "major").unwrap();
+        let analyze = |figure: &str| {
+            let chord = RomanNumeral::new(figure, key.clone())
+                .unwrap()
+                .to_chord()
+                .unwrap();
+            RomanNumeral::analyze(&chord, key.clone())
+                .unwrap()
+                .unwrap()
+                .figure()
+                .to_string()
+        };
+        assert_eq!(analyze("IV[add6]"), "ii65");
+        assert_eq!(analyze("N6"), "N6");
+        assert_eq!(analyze("N64"), "N64");
+        assert_eq!(analyze("N53"), "bII");
+    }
+
+    #[test]
+    fn analyzed_figures_parse_back_to_the_analyzed_pitches() {
+        let major = Key::from_tonic_mode("C", "major").unwrap();
+        let minor = Key::from_tonic_mode("C", "minor").unwrap();
+        for (pitches, root, key, figure) in [
+            ("G3 D4", "G", &major, "V"),
+            ("C4 F4 G4", "C", &major, "I[no3][add4]"),
+            ("C4 D4 G4", "C", &major, "I[no3][add2]"),
+            ("G3 C4 D4 F4", "G", &major, "V7[no3][add11]"),
+            ("G3 C4 E4", "C", &major, "I64"),
+            ("G3 C4 E-4", "C", &minor, "i64"),
+        ] {
+            let chord = Chord::new(pitches).unwrap();
+            let root = Pitch::from_name(root).unwrap();
+            let analyzed = RomanNumeral::analyze_with_root(&chord, key.clone(), &root)
+                .unwrap()
+                .unwrap();
+            assert_eq!(analyzed.figure(), figure);
+
+            let reparsed = RomanNumeral::new(analyzed.figure(), key.clone()).unwrap();
+            let mut expected = chord.pitch_classes();
+            let mut realized = reparsed.to_chord().unwrap().pitch_classes();
+            expected.sort_unstable();
+            expected.dedup();
+            realized.sort_unstable();
+            realized.dedup();
+            if figure == "V" {
+                // A bare fifth implies its triad.
+                expected.push(11);
+                expected.sort_unstable();
+            }
+            assert_eq!(realized, expected, "{figure}");
+        }
+        assert_eq!(
+            pitch_names(&RomanNumeral::new("Cad64", major).unwrap()),
+            vec!["G", "C", "E"]
+        );
+    }
+
+    fn numeral_root(numeral: &RomanNumeral) -> Pitch {
+        let mut pitches = numeral.to_chord().unwrap().pitches();
+        let inversion = numeral.inversion() as usize;
+        let len = pitches.len();
+        pitches.remove((len - inversion) % len)
+    }
 }
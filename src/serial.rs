@@ -0,0 +1,822 @@
+//! Twelve-tone rows, serial matrices and row analysis.
+//!
+//! Inspired by music21's `serial` module. Row forms are labeled by the pitch
+//! class they begin on: `P4` and `I4` start on E, and `R4` and `RI4` are the
+//! retrogrades of `P4` and `I4`, so they end on E.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::{
+    defaults::FloatType,
+    error::{Error, Result},
+    pitchclassset::{PitchClassSet, parse_pitch_class_list},
+    stream::Stream,
+};
+
+/// One of the four serial transformations.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RowFormKind {
+    /// The row transposed, `P`.
+    Prime,
+    /// The row inverted, `I`.
+    Inversion,
+    /// The row backwards, `R`.
+    Retrograde,
+    /// The inversion backwards, `RI`.
+    RetrogradeInversion,
+}
+
+impl RowFormKind {
+    /// Returns the label prefix: `P`, `I`, `R`, or `RI`.
+    pub fn abbreviation(self) -> &'static str {
+        match self {
+            Self::Prime => "P",
+            Self::Inversion => "I",
+            Self::Retrograde => "R",
+            Self::RetrogradeInversion => "RI",
+        }
+    }
+}
+
+/// A row form such as `P0`, `I5`, `R11`, or `RI3`.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RowForm {
+    kind: RowFormKind,
+    transposition: u8,
+}
+
+impl RowForm {
+    /// Creates a row form; the transposition is reduced modulo 12.
+    pub fn new(kind: RowFormKind, transposition: u8) -> Self {
+        Self {
+            kind,
+            transposition: transposition % 12,
+        }
+    }
+
+    /// Returns the transformation.
+    pub fn kind(self) -> RowFormKind {
+        self.kind
+    }
+
+    /// Returns the pitch class that labels the form.
+    pub fn transposition(self) -> u8 {
+        self.transposition
+    }
+
+    /// All 48 forms in `P`, `I`, `R`, `RI` order.
+    fn all() -> impl Iterator<Item = Self> {
+        [
+            RowFormKind::Prime,
+            RowFormKind::Inversion,
+            RowFormKind::Retrograde,
+            RowFormKind::RetrogradeInversion,
+        ]
+        .into_iter()
+        .flat_map(|kind| (0..12).map(move |n| Self::new(kind, n)))
+    }
+}
+
+impl fmt::Display for RowForm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.kind.abbreviation(), self.transposition)
+    }
+}
+
+impl FromStr for RowForm {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        let value = value.trim();
+        let (kind, number) = if let Some(number) = value.strip_prefix("RI") {
+            (RowFormKind::RetrogradeInversion, number)
+        } else if let Some(number) = value.strip_prefix('P') {
+            (RowFormKind::Prime, number)
+        } else if let Some(number) = value.strip_prefix('I') {
+            (RowFormKind::Inversion, number)
+        } else if let Some(number) = value.strip_prefix('R') {
+            (RowFormKind::Retrograde, number)
+        } else {
+            return Err(Error::Serial(format!("invalid row form {value:?}")));
+        };
+        let transposition = number
+            .parse::<u8>()
+            .ok()
+            .filter(|number| *number < 12)
+            .ok_or_else(|| Error::Serial(format!("invalid row form {value:?}")))?;
+        Ok(Self::new(kind, transposition))
+    }
+}
+
+/// Which forms of a row complete the aggregate with it hexachord by
+/// hexachord.
+///
+/// A form is combinatorial with the row when its first hexachord holds the
+/// pitch classes missing from the row's first hexachord. Every row is
+/// retrograde-combinatorial with its own retrograde.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Combinatoriality {
+    forms: Vec<RowForm>,
+}
+
+impl Combinatoriality {
+    /// Returns the combinatorial forms.
+    pub fn forms(&self) -> &[RowForm] {
+        &self.forms
+    }
+
+    /// Returns `true` when some transposition of the row is combinatorial.
+    pub fn is_prime_combinatorial(&self) -> bool {
+        self.has(RowFormKind::Prime)
+    }
+
+    /// Returns `true` when some inversion of the row is combinatorial, as in
+    /// Schoenberg's practice.
+    pub fn is_inversion_combinatorial(&self) -> bool {
+        self.has(RowFormKind::Inversion)
+    }
+
+    /// Returns `true` when some retrograde is combinatorial, which the row's
+    /// own retrograde always is.
+    pub fn is_retrograde_combinatorial(&self) -> bool {
+        self.has(RowFormKind::Retrograde)
+    }
+
+    /// Returns `true` when some retrograde inversion is combinatorial.
+    pub fn is_retrograde_inversion_combinatorial(&self) -> bool {
+        self.has(RowFormKind::RetrogradeInversion)
+    }
+
+    /// Returns `true` when the row is combinatorial under all four
+    /// transformations.
+    pub fn is_all_combinatorial(&self) -> bool {
+        self.is_prime_combinatorial()
+            && self.is_inversion_combinatorial()
+            && self.is_retrograde_combinatorial()
+            && self.is_retrograde_inversion_combinatorial()
+    }
+
+    /// Returns `true` when any form is combinatorial.
+    pub fn is_combinatorial(&self) -> bool {
+        !self.forms.is_empty()
+    }
+
+    fn has(&self, kind: RowFormKind) -> bool {
+        self.forms.iter().any(|form| form.kind() == kind)
+    }
+}
+
+/// Where a note of a melody falls in a row statement.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RowLabel {
+    /// Offset of the note in quarter lengths.
+    pub offset: FloatType,
+    /// The note's pitch class.
+    pub pitch_class: u8,
+    /// The row form being stated.
+    pub form: RowForm,
+    /// Zero-based order position of the pitch class within the form.
+    pub order_position: usize,
+}
+
+/// An ordered twelve-tone row.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ToneRow {
+    pitch_classes: [u8; 12],
+}
+
+impl ToneRow {
+    /// Builds a row from twelve distinct pitch classes.
+    pub fn new(pitch_classes: impl IntoIterator<Item = u8>) -> Result<Self> {
+        let pcs = pitch_classes.into_iter().collect::<Vec<_>>();
+        let pitch_classes: [u8; 12] = pcs.as_slice().try_into().map_err(|_| {
+            Error::Serial(format!(
+                "a twelve-tone row needs 12 pitch classes, got {}",
+                pcs.len()
+            ))
+        })?;
+        if PitchClassSet::new(pitch_classes) != PitchClassSet::AGGREGATE
+            || pitch_classes.iter().any(|pc| *pc > 11)
+        {
+            return Err(Error::Serial(format!(
+                "{pcs:?} does not contain each pitch class 0 through 11 once"
+            )));
+        }
+        Ok(Self { pitch_classes })
+    }
+
+    /// Looks up one of the historical rows in [`named_tone_rows`] by its
+    /// music21 class name, such as `"RowWebernOp21"`.
+    pub fn named(name: &str) -> Result<Self> {
+        named_tone_rows()
+            .iter()
+            .find(|row| row.name.eq_ignore_ascii_case(name))
+            .map(NamedToneRow::row)
+            .ok_or_else(|| Error::Serial(format!("unknown named row {name:?}")))
+    }
+
+    /// Returns the row's pitch classes in order.
+    pub fn pitch_classes(&self) -> [u8; 12] {
+        self.pitch_classes
+    }
+
+    /// Returns the pitch classes of a row form.
+    pub fn form(&self, form: RowForm) -> [u8; 12] {
+        let first = self.pitch_classes[0];
+        let n = form.transposition;
+        let mut pcs = self.pitch_classes.map(|pc| match form.kind {
+            RowFormKind::Prime | RowFormKind::Retrograde => (pc + 12 - first + n) % 12,
+            RowFormKind::Inversion | RowFormKind::RetrogradeInversion => (first + 12 - pc + n) % 12,
+        });
+        if matches!(
+            form.kind,
+            RowFormKind::Retrograde | RowFormKind::RetrogradeInversion
+        ) {
+            pcs.reverse();
+        }
+        pcs
+    }
+
+    /// Returns the prime form beginning on `n`.
+    pub fn prime(&self, n: u8) -> [u8; 12] {
+        self.form(RowForm::new(RowFormKind::Prime, n))
+    }
+
+    /// Returns the inversion beginning on `n`.
+    pub fn inversion(&self, n: u8) -> [u8; 12] {
+        self.form(RowForm::new(RowFormKind::Inversion, n))
+    }
+
+    /// Returns the retrograde of the prime form beginning on `n`.
+    pub fn retrograde(&self, n: u8) -> [u8; 12] {
+        self.form(RowForm::new(RowFormKind::Retrograde, n))
+    }
+
+    /// Returns the retrograde of the inversion beginning on `n`.
+    pub fn retrograde_inversion(&self, n: u8) -> [u8; 12] {
+        self.form(RowForm::new(RowFormKind::RetrogradeInversion, n))
+    }
+
+    /// Returns every form with the row form that produces it, in `P`, `I`,
+    /// `R`, `RI` order.
+    pub fn all_forms(&self) -> Vec<(RowForm, [u8; 12])> {
+        RowForm::all().map(|form| (form, self.form(form))).collect()
+    }
+
+    /// Returns the forms whose pitch classes are exactly `pitch_classes`;
+    /// symmetrical rows can have several.
+    pub fn find_forms(&self, pitch_classes: &[u8]) -> Vec<RowForm> {
+        RowForm::all()
+            .filter(|form| self.form(*form).as_slice() == pitch_classes)
+            .collect()
+    }
+
+    /// Returns the 12×12 matrix: row `i` is the prime form beginning on the
+    /// `i`th pitch class of the inversion, so rows read as `P` forms left to
+    /// right and columns as `I` forms top to bottom.
+    pub fn matrix(&self) -> [[u8; 12]; 12] {
+        self.inversion(self.pitch_classes[0])
+            .map(|start| self.prime(start))
+    }
+
+    /// Returns the matrix as text with `T` and `E` for 10 and 11, labeled with
+    /// `P`/`R` forms on the sides and `I`/`RI` forms on the top and bottom.
+    pub fn matrix_string(&self) -> String {
+        let matrix = self.matrix();
+        let label = |prefix: &str, pc: u8| format!("{prefix}{pc}");
+        let mut out = format!(
+            "{:>4} {}\n",
+            "",
+            matrix[0]
+                .iter()
+                .map(|pc| format!("{:>4}", label("I", *pc)))
+                .collect::<String>()
+        );
+        for row in &matrix {
+            out.push_str(&format!("{:>4} ", label("P", row[0])));
+            for pc in row {
+                out.push_str(&format!("{:>4}", pc_char(*pc)));
+            }
+            out.push_str(&format!(" {}\n", label("R", row[0])));
+        }
+        out.push_str(&format!(
+            "{:>4} {}\n",
+            "",
+            matrix[0]
+                .iter()
+                .map(|pc| format!("{:>4}", label("RI", *pc)))
+                .collect::<String>()
+        ));
+        out
+    }
+
+    /// Returns the unordered pitch classes at a range of order positions.
+    pub fn segment(&self, positions: std::ops::Range<usize>) -> PitchClassSet {
+        PitchClassSet::new(self.pitch_classes[positions].iter().copied())
+    }
+
+    /// Returns the two hexachords as pitch-class sets.
+    pub fn hexachords(&self) -> (PitchClassSet, PitchClassSet) {
+        (self.segment(0..6), self.segment(6..12))
+    }
+
+    /// Returns the Forte classes of the row's discrete segments of `size`,
+    /// such as the four trichords for `3`; `size` must divide 12.
+    pub fn segment_forte_classes(&self, size: usize) -> Result<Vec<String>> {
+        if size == 0 || 12 % size != 0 {
+            return Err(Error::Serial(format!(
+                "segment size {size} does not divide the row"
+            )));
+        }
+        (0..12)
+            .step_by(size)
+            .map(|start| {
+                self.segment(start..start + size)
+                    .forte_class_tni()
+                    .ok_or_else(|| Error::Serial("segment has no Forte class".to_string()))
+            })
+            .collect()
+    }
+
+    /// Returns the hexachordal combinatoriality of the row.
+    pub fn combinatoriality(&self) -> Combinatoriality {
+        let (first, second) = self.hexachords();
+        let forms = RowForm::all()
+            .filter(|form| {
+                let pcs = self.form(*form);
+                PitchClassSet::new(pcs[..6].iter().copied()) == second
+                    && PitchClassSet::new(pcs[6..].iter().copied()) == first
+            })
+            .collect();
+        Combinatoriality { forms }
+    }
+
+    /// Returns the letter of Babbitt's all-combinatorial source hexachord
+    /// (`'A'` through `'F'`) that the row's first hexachord belongs to.
+    pub fn all_combinatorial_source(&self) -> Option<char> {
+        let (first, _) = self.hexachords();
+        ALL_COMBINATORIAL_SOURCES
+            .iter()
+            .find(|(_, prime)| first.is_tni_equivalent(PitchClassSet::new(prime.iter().copied())))
+            .map(|(letter, _)| *letter)
+    }
+
+    /// Returns every row built from forms of `generator`, an ordered segment
+    /// of 2, 3, 4, or 6 pitch classes, that begins with the generator itself,
+    /// as in Webern's derived rows.
+    pub fn derived_rows(generator: &[u8]) -> Result<Vec<Self>> {
+        let size = generator.len();
+        let set = PitchClassSet::new(generator.iter().copied());
+        if !matches!(size, 2 | 3 | 4 | 6)
+            || set.len() != size
+            || generator.iter().any(|pc| *pc > 11)
+        {
+            return Err(Error::Serial(format!(
+                "{generator:?} is not 2, 3, 4, or 6 distinct pitch classes"
+            )));
+        }
+
+        let mut transforms = Vec::new();
+        for n in 0..12 {
+            let prime = generator.iter().map(|pc| (pc + n) % 12).collect::<Vec<_>>();
+            let inversion = generator
+                .iter()
+                .map(|pc| (generator[0] + 24 - pc + n) % 12)
+                .collect::<Vec<_>>();
+            for segment in [prime, inversion] {
+                let mut retrograde = segment.clone();
+                retrograde.reverse();
+                transforms.push(segment);
+                transforms.push(retrograde);
+            }
+        }
+        transforms.sort();
+        transforms.dedup();
+
+        let mut rows = Vec::new();
+        let mut current = generator.to_vec();
+        extend_derived(&transforms, &mut current, &mut rows);
+        rows.into_iter().map(Self::new).collect()
+    }
+
+    /// Labels a melody's notes with the row form and order position each
+    /// belongs to.
+    ///
+    /// Chords contribute their pitch classes from lowest to highest and rests
+    /// are skipped. Immediately repeated pitch classes keep their order
+    /// position. A run of notes is labeled once it completes a form or
+    /// narrows to a single form; notes outside any statement are left out.
+    pub fn label_stream(&self, stream: &Stream) -> Vec<RowLabel> {
+        let notes = stream
+            .events()
+            .iter()
+            .flat_map(|event| {
+                let offset = event.offset();
+                event.element().pitches().into_iter().map(move |pitch| {
+                    let pc = pitch.pitch_class_number();
+                    (offset, pc)
+                })
+            })
+            .collect::<Vec<_>>();
+        let forms = self.all_forms();
+
+        let mut labels = Vec::new();
+        let mut start = 0;
+        while start < notes.len() {
+            let mut candidates = forms.iter().collect::<Vec<_>>();
+            let mut positions = Vec::new();
+            let mut position = 0;
+            let mut index = start;
+            while index < notes.len() {
+                let pc = notes[index].1;
+                if index > start && notes[index - 1].1 == pc {
+                    positions.push(position - 1);
+                    index += 1;
+                    continue;
+                }
+                if position == 12 {
+                    break;
+                }
+                let narrowed = candidates
+                    .iter()
+                    .copied()
+                    .filter(|(_, pcs)| pcs[position] == pc)
+                    .collect::<Vec<_>>();
+                if narrowed.is_empty() {
+                    break;
+                }
+                candidates = narrowed;
+                positions.push(position);
+                position += 1;
+                index += 1;
+            }
+
+            if position == 12 || (position > 1 && candidates.len() == 1) {
+                let form = candidates[0].0;
+                labels.extend(notes[start..index].iter().zip(positions).map(
+                    |((offset, pitch_class), order_position)| RowLabel {
+                        offset: *offset,
+                        pitch_class: *pitch_class,
+                        form,
+                        order_position,
+                    },
+                ));
+                start = index;
+            } else {
+                start += 1;
+            }
+        }
+        labels
+    }
+}
+
+impl fmt::Display for ToneRow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let pcs = self.pitch_classes.map(pc_char);
+        f.write_str(&pcs.iter().collect::<String>())
+    }
+}
+
+impl FromStr for ToneRow {
+    type Err = Error;
+
+    /// Parses `"0 1 2 ..."`, `"[0, 1, ...]"`, or compact `"0123456789TE"`.
+    fn from_str(value: &str) -> Result<Self> {
+        Self::new(parse_pitch_class_list(value)?)
+    }
+}
+
+impl TryFrom<&str> for ToneRow {
+    type Error = Error;
+
+    fn try_from(value: &str) -> Result<Self> {
+        value.parse()
+    }
+}
+
+impl TryFrom<String> for ToneRow {
+    type Error = Error;
+
+    fn try_from(value: String) -> Result<Self> {
+        value.parse()
+    }
+}
+
+/// A historical row from music21's `serial` module.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct NamedToneRow {
+    /// music21's class name, such as `"RowSchoenbergOp25"`.
+    pub name: &'static str,
+    /// The composer's surname.
+    pub composer: &'static str,
+    /// The opus number, such as `"Op. 25"`.
+    pub opus: &'static str,
+    /// The work's title.
+    pub title: &'static str,
+    /// The row's pitch classes.
+    pub pitch_classes: [u8; 12],
+}
+
+impl NamedToneRow {
+    /// Returns the row.
+    pub fn row(&self) -> ToneRow {
+        ToneRow {
+            pitch_classes: self.pitch_classes,
+        }
+    }
+}
+
+/// Returns the historical rows: seventeen rows by Schoenberg, Webern and
+/// Berg, named after the matching classes in music21's `serial.py`.
+pub fn named_tone_rows() -> &'static [NamedToneRow] {
+    NAMED_TONE_ROWS
+}
+
+const fn named(
+    name: &'static str,
+    composer: &'static str,
+    opus: &'static str,
+    title: &'static str,
+    pitch_classes: [u8; 12],
+) -> NamedToneRow {
+    NamedToneRow {
+        name,
+        composer,
+        opus,
+        title,
+        pitch_classes,
+    }
+}
+
+static NAMED_TONE_ROWS: &[NamedToneRow] = &[
+    named(
+        "RowSchoenbergOp23No5",
+        "Schoenberg",
+        "Op. 23, No. 5",
+        "Walzer",
+        [1, 9, 11, 7, 8, 6, 10, 2, 4, 3, 0, 5],
+    ),
+    named(
+        "RowSchoenbergOp25",
+        "Schoenberg",
+        "Op. 25",
+        "Suite for Piano",
+        [4, 5, 7, 1, 6, 3, 8, 2, 11, 0, 9, 10],
+    ),
+    named(
+        "RowSchoenbergOp26",
+        "Schoenberg",
+        "Op. 26",
+        "Wind Quintet",
+        [3, 7, 9, 11, 1, 0, 10, 2, 4, 6, 8, 5],
+    ),
+    named(
+        "RowSchoenbergOp29",
+        "Schoenberg",
+        "Op. 29",
+        "Suite",
+        [3, 7, 11, 2, 10, 6, 9, 5, 1, 8, 4, 0],
+    ),
+    named(
+        "RowSchoenbergOp30",
+        "Schoenberg",
+        "Op. 30",
+        "Third String Quartet",
+        [7, 4, 3, 9, 0, 5, 6, 11, 10, 1, 8, 2],
+    ),
+    named(
+        "RowSchoenbergOp31",
+        "Schoenberg",
+        "Op. 31",
+        "Variations for Orchestra",
+        [10, 4, 6, 3, 5, 9, 2, 1, 7, 8, 11, 0],
+    ),
+    named(
+        "RowSchoenbergOp33A",
+        "Schoenberg",
+        "Op. 33A",
+        "Two Piano Pieces, No. 1",
+        [10, 5, 0, 11, 9, 6, 1, 3, 7, 8, 2, 4],
+    ),
+    named(
+        "RowSchoenbergOp36",
+        "Schoenberg",
+        "Op. 36",
+        "Concerto for Violin and Orchestra",
+        [9, 10, 3, 11, 4, 6, 0, 1, 7, 8, 2, 5],
+    ),
+    named(
+        "RowSchoenbergOp37",
+        "Schoenberg",
+        "Op. 37",
+        "Fourth String Quartet",
+        [2, 1, 9, 10, 5, 3, 4, 0, 8, 7, 6, 11],
+    ),
+    named(
+        "RowSchoenbergOp41",
+        "Schoenberg",
+        "Op. 41",
+        "Ode to Napoleon",
+        [1, 0, 4, 5, 9, 8, 3, 2, 6, 7, 11, 10],
+    ),
+    named(
+        "RowSchoenbergOp42",
+        "Schoenberg",
+        "Op. 42",
+        "Concerto for Piano and Orchestra",
+        [3, 10, 2, 5, 4, 0, 6, 8, 1, 9, 11, 7],
+    ),
+    named(
+        "RowWebernOp21",
+        "Webern",
+        "Op. 21",
+        "Symphony",
+        [9, 6, 7, 8, 4, 5, 11, 10, 2, 1, 0, 3],
+    ),
+    named(
+        "RowWebernOp24",
+        "Webern",
+        "Op. 24",
+        "Concerto",
+        [11, 10, 2, 3, 7, 6, 8, 4, 5, 0, 1, 9],
+    ),
+    named(
+        "RowWebernOp27",
+        "Webern",
+        "Op. 27",
+        "Variations for Piano",
+        [3, 11, 10, 2, 1, 0, 6, 4, 7, 5, 9, 8],
+    ),
+    named(
+        "RowWebernOp28",
+        "Webern",
+        "Op. 28",
+        "String Quartet",
+        [10, 9, 0, 11, 3, 4, 1, 2, 6, 5, 8, 7],
+    ),
+    named(
+        "RowBergLyricSuite",
+        "Berg",
+        "",
+        "Lyric Suite",
+        [5, 4, 0, 9, 7, 2, 8, 1, 3, 6, 10, 11],
+    ),
+    named(
+        "RowBergViolinConcerto",
+        "Berg",
+        "",
+        "Violin Concerto",
+        [7, 10, 2, 6, 9, 0, 4, 8, 11, 1, 3, 5],
+    ),
+];
+
+/// Babbitt's six all-combinatorial source hexachords, as prime forms.
+const ALL_COMBINATORIAL_SOURCES: [(char, [u8; 6]); 6] = [
+    ('A', [0, 1, 2, 3, 4, 5]),
+    ('B', [0, 2, 3, 4, 5, 7]),
+    ('C', [0, 2, 4, 5, 7, 9]),
+    ('D', [0, 1, 2, 6, 7, 8]),
+    ('E', [0, 1, 4, 5, 8, 9]),
+    ('F', [0, 2, 4, 6, 8, 10]),
+];
+
+fn extend_derived(transforms: &[Vec<u8>], current: &mut Vec<u8>, rows: &mut Vec<Vec<u8>>) {
+    if current.len() == 12 {
+        rows.push(current.clone());
+        return;
+    }
+    let used = PitchClassSet::new(current.iter().copied());
+    for segment in transforms {
+        if segment.iter().all(|pc| !used.contains(*pc)) {
+            current.extend_from_slice(segment);
+            extend_derived(transforms, current, rows);
+            current.truncate(current.len() - segment.len());
+        }
+    }
+}
+
+fn pc_char(pc: u8) -> char {
+    match pc {
+        10 => 'T',
+        11 => 'E',
+        pc => char::from(b'0' + pc),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{note::Note, rest::Rest};
+
+    fn form(label: &str) -> RowForm {
+        label.parse().unwrap()
+    }
+
+    #[test]
+    fn builds_row_forms_and_matrix() {
+        let row = ToneRow::named("RowWebernOp21").unwrap();
+        assert_eq!(row.to_string(), "967845ET2103");
+        assert_eq!(row.prime(0), [0, 9, 10, 11, 7, 8, 2, 1, 5, 4, 3, 6]);
+        assert_eq!(row.inversion(9), [9, 0, 11, 10, 2, 1, 7, 8, 4, 5, 6, 3]);
+        assert_eq!(row.retrograde(9), [3, 0, 1, 2, 10, 11, 5, 4, 8, 7, 6, 9]);
+        assert_eq!(
+            row.retrograde_inversion(9),
+            [3, 6, 5, 4, 8, 7, 1, 2, 10, 11, 0, 9]
+        );
+        // Op. 21's row is its own retrograde at the tritone.
+        assert_eq!(
+            row.find_forms(&row.pitch_classes()),
+            vec![form("P9"), form("R3")]
+        );
+
+        let matrix = row.matrix();
+        assert_eq!(matrix[0], row.pitch_classes());
+        assert_eq!(matrix.map(|line| line[0]), row.inversion(9));
+        assert!(row.matrix_string().starts_with("       I9  I6  I7"));
+        assert_eq!(form("RI11").to_string(), "RI11");
+        assert!("X3".parse::<RowForm>().is_err());
+        assert!("0 1 2".parse::<ToneRow>().is_err());
+        assert!("001234567890".parse::<ToneRow>().is_err());
+    }
+
+    #[test]
+    fn detects_combinatoriality_and_segment_classes() {
+        let piano_piece = ToneRow::named("RowSchoenbergOp33A").unwrap();
+        let combinatoriality = piano_piece.combinatoriality();
+        assert!(combinatoriality.is_inversion_combinatorial());
+        assert!(!combinatoriality.is_all_combinatorial());
+
+        let chromatic = "0123456789TE".parse::<ToneRow>().unwrap();
+        assert!(chromatic.combinatoriality().is_all_combinatorial());
+        assert_eq!(chromatic.all_combinatorial_source(), Some('A'));
+
+        let variations = ToneRow::named("RowSchoenbergOp31").unwrap();
+        assert!(variations.combinatoriality().is_inversion_combinatorial());
+        assert_eq!(
+            ToneRow::named("RowBergViolinConcerto")
+                .unwrap()
+                .segment_forte_classes(3)
+                .unwrap(),
+            vec!["3-11", "3-10", "3-11", "3-6"]
+        );
+        assert!(piano_piece.segment_forte_classes(5).is_err());
+    }
+
+    #[test]
+    fn derives_rows_from_a_trichord() {
+        let op24 = ToneRow::named("RowWebernOp24").unwrap();
+        let derived = ToneRow::derived_rows(&[11, 10, 2]).unwrap();
+        assert!(derived.contains(&op24));
+        assert!(
+            derived
+                .iter()
+                .all(|row| row.pitch_classes()[..3] == [11, 10, 2])
+        );
+        assert!(ToneRow::derived_rows(&[0, 1, 2, 3, 4]).is_err());
+    }
+
+    #[test]
+    fn named_rows_are_valid_and_missing_ones_are_errors() {
+        assert_eq!(named_tone_rows().len(), 17);
+        for named in named_tone_rows() {
+            assert!(ToneRow::new(named.pitch_classes).is_ok(), "{}", named.name);
+        }
+        assert!(ToneRow::named("rowwebernop21").is_ok());
+        assert!(matches!(
+            ToneRow::named("RowBergWozzeckPassacaglia"),
+            Err(Error::Serial(_))
+        ));
+    }
+
+    #[test]
+    fn labels_row_statements_in_a_melody() {
+        let row = ToneRow::named("RowWebernOp24").unwrap();
+        let mut melody = Stream::new();
+        melody.push(Rest::from_quarter_length(1.0).unwrap());
+        let names = [
+            "C", "C#", "D", "E-", "E", "F", "F#", "G", "A-", "A", "B-", "B",
+        ];
+        for (index, pc) in row.inversion(0).iter().enumerate() {
+            melody.push(Note::from_name(format!("{}4", names[usize::from(*pc)])).unwrap());
+            if index == 4 {
+                melody.push(Note::from_name(format!("{}5", names[usize::from(*pc)])).unwrap());
+            }
+        }
+
+        let labels = row.label_stream(&melody);
+        assert_eq!(labels.len(), 13);
+        assert!(labels.iter().all(|label| label.form == form("I0")));
+        assert_eq!(labels[0].offset, 1.0);
+        assert_eq!(
+            labels
+                .iter()
+                .map(|label| label.order_position)
+                .collect::<Vec<_>>(),
+            vec![0, 1, 2, 3, 4, 4, 5, 6, 7, 8, 9, 10, 11]
+        );
+    }
+}
@@ -0,0 +1,296 @@
+//! Similarity and distance measures between set classes.
+//!
+//! Each measure compares two [`PitchClassSet`]s as set classes, so any
+//! transposition or inversion of either set gives the same value.
+//! [`closest_set_classes`] ranks the entries of [`Chord::known_chord_types`]
+//! by one of these measures.
+
+use std::collections::HashMap;
+
+use crate::{
+    chord::{Chord, KnownChordType},
+    defaults::FloatType,
+    pitchclassset::{PackingConvention, PitchClassSet},
+};
+
+/// A set-class comparison used to rank related chords.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SimilarityMeasure {
+    /// Morris's SIM, see [`morris_sim`].
+    MorrisSim,
+    /// Morris's ASIM, see [`morris_asim`].
+    MorrisAsim,
+    /// Lewin's REL, see [`lewin_rel`].
+    LewinRel,
+    /// Rahn's ATMEMB, see [`rahn_atmemb`].
+    RahnAtmemb,
+    /// Isaacson's IcVSIM, see [`isaacson_icvsim`].
+    #[default]
+    IsaacsonIcvsim,
+    /// Tymoczko's voice-leading distance, see [`voice_leading_distance`].
+    VoiceLeading,
+}
+
+impl SimilarityMeasure {
+    /// Returns the measure as a distance, where `0.0` means most alike.
+    ///
+    /// The similarity scores REL and ATMEMB are reported as `1 - score`. Sets
+    /// a measure cannot compare, such as an empty set under voice leading, are
+    /// infinitely far apart.
+    pub fn distance(self, a: PitchClassSet, b: PitchClassSet) -> FloatType {
+        match self {
+            Self::MorrisSim => FloatType::from(morris_sim(a, b)),
+            Self::MorrisAsim => morris_asim(a, b),
+            Self::LewinRel => 1.0 - lewin_rel(a, b),
+            Self::RahnAtmemb => 1.0 - rahn_atmemb(a, b),
+            Self::IsaacsonIcvsim => isaacson_icvsim(a, b),
+            Self::VoiceLeading => voice_leading_distance(a, b)
+                .map(FloatType::from)
+                .unwrap_or(FloatType::INFINITY),
+        }
+    }
+}
+
+/// Robert Morris's SIM: the summed absolute differences between the two
+/// interval-class vectors.
+pub fn morris_sim(a: PitchClassSet, b: PitchClassSet) -> u32 {
+    a.interval_class_vector()
+        .iter()
+        .zip(b.interval_class_vector())
+        .map(|(x, y)| u32::from(x.abs_diff(y)))
+        .sum()
+}
+
+/// Morris's ASIM: [`morris_sim`] divided by the number of intervals in both
+/// sets, from `0.0` (same vector) to `1.0` (no interval class in common).
+pub fn morris_asim(a: PitchClassSet, b: PitchClassSet) -> FloatType {
+    let total = pair_count(a) + pair_count(b);
+    if total == 0 {
+        return 0.0;
+    }
+    FloatType::from(morris_sim(a, b)) / FloatType::from(total)
+}
+
+/// David Lewin's REL: how alike the two sets' subset-class embeddings are,
+/// from `0.0` to `1.0` for sets of the same set class.
+///
+/// Every subset of two or more pitch classes is counted.
+pub fn lewin_rel(a: PitchClassSet, b: PitchClassSet) -> FloatType {
+    let (embeddings_a, embeddings_b) = (embeddings(a), embeddings(b));
+    let total = FloatType::from(subset_count(a) * subset_count(b)).sqrt();
+    if total == 0.0 {
+        return 0.0;
+    }
+    let shared = embeddings_a
+        .iter()
+        .filter_map(|(class, x)| {
+            embeddings_b
+                .get(class)
+                .map(|y| FloatType::from(x * y).sqrt())
+        })
+        .fold(0.0, |sum, term| sum + term);
+    shared / total
+}
+
+/// John Rahn's ATMEMB: the share of both sets' subsets, two or more pitch
+/// classes large, whose set class is embedded in both, from `0.0` to `1.0`.
+pub fn rahn_atmemb(a: PitchClassSet, b: PitchClassSet) -> FloatType {
+    let (embeddings_a, embeddings_b) = (embeddings(a), embeddings(b));
+    let total = subset_count(a) + subset_count(b);
+    if total == 0 {
+        return 0.0;
+    }
+    let mutual = embeddings_a
+        .iter()
+        .filter_map(|(class, x)| embeddings_b.get(class).map(|y| x + y))
+        .sum::<u32>();
+    FloatType::from(mutual) / FloatType::from(total)
+}
+
+/// Eric Isaacson's IcVSIM: the standard deviation of the differences between
+/// the two interval-class vectors, `0.0` for identical vectors.
+pub fn isaacson_icvsim(a: PitchClassSet, b: PitchClassSet) -> FloatType {
+    let differences = a
+        .interval_class_vector()
+        .iter()
+        .zip(b.interval_class_vector())
+        .map(|(x, y)| FloatType::from(y) - FloatType::from(*x))
+        .collect::<Vec<_>>();
+    let mean = differences.iter().sum::<FloatType>() / 6.0;
+    (differences
+        .iter()
+        .map(|difference| (difference - mean).powi(2))
+        .sum::<FloatType>()
+        / 6.0)
+        .sqrt()
+}
+
+/// Dmitri Tymoczko's voice-leading distance between set classes: the fewest
+/// total semitones of motion, over every transposition and inversion of `b`,
+/// that move `a` onto `b`.
+///
+/// When the sizes differ, voices may double so that every pitch class of both
+/// sets is sounded. Returns `None` when either set is empty.
+pub fn voice_leading_distance(a: PitchClassSet, b: PitchClassSet) -> Option<u32> {
+    if a.is_empty() || b.is_empty() {
+        return None;
+    }
+    let (small, large) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+    (0..12)
+        .flat_map(|n| [large.transpose(n), large.invert(n)])
+        .map(|image| surjective_voice_leading(small, image))
+        .min()
+}
+
+/// Ranks the entries of [`Chord::known_chord_types`] by their distance from
+/// `target` and returns the `count` nearest, skipping transpositions of the
+/// target itself.
+pub fn closest_set_classes(
+    target: PitchClassSet,
+    measure: SimilarityMeasure,
+    count: usize,
+) -> Vec<(KnownChordType, FloatType)> {
+    let mut ranked = Chord::known_chord_types()
+        .into_iter()
+        .filter_map(|entry| {
+            let set = PitchClassSet::new(entry.normal_form.iter().copied());
+            (!set.is_tn_equivalent(target)).then(|| {
+                let distance = measure.distance(target, set);
+                (entry, distance)
+            })
+        })
+        .collect::<Vec<_>>();
+    ranked.sort_by(|(_, a), (_, b)| a.total_cmp(b));
+    ranked.truncate(count);
+    ranked
+}
+
+fn pair_count(set: PitchClassSet) -> u32 {
+    let n = set.len() as u32;
+    n * n.saturating_sub(1) / 2
+}
+
+/// Subsets of two or more pitch classes.
+fn subset_count(set: PitchClassSet) -> u32 {
+    let n = set.len() as u32;
+    (1 << n) - n - 1
+}
+
+/// How many subsets of `set`, two or more pitch classes large, belong to each
+/// set class, keyed by the class's prime form.
+fn embeddings(set: PitchClassSet) -> HashMap<u16, u32> {
+    let pcs = set.pitch_classes();
+    let mut counts = HashMap::new();
+    for mask in 0u32..(1 << pcs.len()) {
+        if mask.count_ones() < 2 {
+            continue;
+        }
+        let subset = PitchClassSet::new(
+            pcs.iter()
+                .enumerate()
+                .filter(|(index, _)| mask & (1 << index) != 0)
+                .map(|(_, pc)| *pc),
+        );
+        let prime = PitchClassSet::new(subset.prime_form(PackingConvention::Forte));
+        *counts.entry(prime.bits()).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// The smallest total motion from `small` to `large` in which each note of
+/// `large` comes from some note of `small` and every note of `small` moves.
+fn surjective_voice_leading(small: PitchClassSet, large: PitchClassSet) -> u32 {
+    let sources = small.pitch_classes();
+    let full = (1usize << sources.len()) - 1;
+    let mut best = vec![u32::MAX; full + 1];
+    best[0] = 0;
+    for target in large.pitch_classes() {
+        let mut next = vec![u32::MAX; full + 1];
+        for (covered, cost) in best.iter().enumerate() {
+            if *cost == u32::MAX {
+                continue;
+            }
+            for (index, source) in sources.iter().enumerate() {
+                let step = u32::from(source.abs_diff(target).min(12 - source.abs_diff(target)));
+                let mask = covered | 1 << index;
+                next[mask] = next[mask].min(cost + step);
+            }
+        }
+        best = next;
+    }
+    best[full]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(pcs: &str) -> PitchClassSet {
+        pcs.parse().unwrap()
+    }
+
+    #[test]
+    fn interval_vector_measures() {
+        let major = set("0 4 7");
+        let minor = set("0 3 7");
+        let augmented = set("0 4 8");
+        assert_eq!(morris_sim(major, minor), 0);
+        assert_eq!(morris_sim(major, augmented), 4);
+        assert!((morris_asim(major, augmented) - 4.0 / 6.0).abs() < 1e-12);
+        assert_eq!(isaacson_icvsim(major, minor), 0.0);
+        // Z-related sets share a vector but not their subsets.
+        let (z1, z2) = (set("0 1 4 6"), set("0 1 3 7"));
+        assert_eq!(morris_sim(z1, z2), 0);
+        assert!(lewin_rel(z1, z2) < 1.0);
+        assert!((isaacson_icvsim(set("0 1 2"), set("0 4 8")) - 1.5275252317).abs() < 1e-9);
+    }
+
+    #[test]
+    fn embedding_measures() {
+        let major = set("0 4 7");
+        assert!((lewin_rel(major, set("2 5 9")) - 1.0).abs() < 1e-12);
+        assert!((rahn_atmemb(major, set("0 3 7")) - 1.0).abs() < 1e-12);
+        let chromatic = set("0 1 2");
+        assert_eq!(lewin_rel(major, chromatic), 0.0);
+        assert!(lewin_rel(major, set("0 2 4 7")) > 0.5);
+        assert_eq!(rahn_atmemb(set("0 1"), set("0 2")), 0.0);
+    }
+
+    #[test]
+    fn voice_leading_distances() {
+        assert_eq!(voice_leading_distance(set("0 4 7"), set("0 3 7")), Some(0));
+        assert_eq!(voice_leading_distance(set("0 4 7"), set("0 4 8")), Some(1));
+        assert_eq!(voice_leading_distance(set("0 4 7"), set("0 3 6")), Some(1));
+        assert_eq!(
+            voice_leading_distance(set("0 4 7"), set("0 4 7 10")),
+            Some(2)
+        );
+        assert_eq!(
+            voice_leading_distance(set("0 4 7"), PitchClassSet::empty()),
+            None
+        );
+    }
+
+    #[test]
+    fn ranks_known_chord_types() {
+        let closest = closest_set_classes(set("0 4 7"), SimilarityMeasure::VoiceLeading, 5);
+        assert_eq!(closest.len(), 5);
+        assert!(closest.iter().all(|(_, distance)| *distance <= 1.0));
+        assert!(closest.iter().any(|(entry, _)| entry.forte_class == "3-10"));
+        assert!(
+            closest
+                .iter()
+                .all(|(entry, _)| entry.forte_class != "3-11B")
+        );
+        assert_eq!(closest[0].0.forte_class, "3-11A");
+
+        let by_vector = closest_set_classes(set("0 4 7"), SimilarityMeasure::MorrisSim, 1);
+        assert_eq!(by_vector[0].0.forte_class, "3-11A");
+        assert!(
+            closest_set_classes(set("0 4 7"), SimilarityMeasure::LewinRel, 3)
+                .windows(2)
+                .all(|pair| pair[0].1 <= pair[1].1)
+        );
+    }
+}
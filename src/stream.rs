@@ -1,6 +1,13 @@
 use crate::{
-    chord::Chord, defaults::FloatType, duration::Duration, error::Result, interval::Interval,
-    note::Note, pitch::Pitch, rest::Rest,
+    chord::Chord,
+    defaults::{FloatType, FractionType},
+    duration::{Duration, fraction_from_float},
+    error::Result,
+    interval::Interval,
+    note::Note,
+    pitch::Pitch,
+    rest::Rest,
+    tie::Tie,
 };
 
 /// A musical object that can live on a timeline.
@@ -32,6 +39,61 @@ impl StreamElement {
             .unwrap_or_else(|| Duration::default().quarter_length())
     }
 
+    /// Returns the exact duration in quarter lengths, defaulting to `1`.
+    pub fn quarter_length_fraction(&self) -> FractionType {
+        self.duration()
+            .map(Duration::quarter_length_fraction)
+            .unwrap_or_else(|| Duration::default().quarter_length_fraction())
+    }
+
+    /// Splits a complex duration into tied notatable parts.
+    ///
+    /// Each part takes one of [`Duration::components`]. Notes, and every
+    /// note of a chord, are tied from part to part, keeping any tie they
+    /// already had at either end; rests are simply split. A notatable element
+    /// is returned unchanged.
+    pub fn split_at_durations(&self) -> Vec<Self> {
+        let duration = self.duration().cloned().unwrap_or_default();
+        let components = duration.components();
+        if components.len() < 2 {
+            return vec![self.clone()];
+        }
+        self.split_into(&components)
+    }
+
+    /// Splits the element into one part per duration, tied as
+    /// [`StreamElement::split_at_durations`] ties them.
+    pub(crate) fn split_into(&self, components: &[Duration]) -> Vec<Self> {
+        let last = components.len().saturating_sub(1);
+        let tied = |note: &Note, index: usize, component: &Duration| {
+            let tie = note.tie();
+            let mut note = note.clone().with_duration(component.clone());
+            note.set_tie(Tie::from_holds(
+                index > 0 || tie.is_some_and(Tie::holds_from_previous),
+                index < last || tie.is_some_and(Tie::holds_into_next),
+            ));
+            note
+        };
+        components
+            .iter()
+            .enumerate()
+            .map(|(index, component)| match self {
+                Self::Note(note) => Self::Note(tied(note, index, component)),
+                Self::Chord(chord) => {
+                    let mut out = chord.clone();
+                    out._notes = chord
+                        .notes()
+                        .iter()
+                        .map(|note| tied(note, index, component))
+                        .collect();
+                    out.set_duration(component.clone());
+                    Self::Chord(out)
+                }
+                Self::Rest(_) => Self::Rest(Rest::new(component.clone())),
+            })
+            .collect()
+    }
+
     /// Returns all pitches contained by this element.
     pub fn pitches(&self) -> Vec<Pitch> {
         match self {
@@ -106,6 +168,12 @@ impl StreamEvent {
         self.offset
     }
 
+    /// Returns the offset as the exact ratio nearest it, as
+    /// [`Duration::new`] reads quarter lengths.
+    pub fn offset_fraction(&self) -> FractionType {
+        fraction_from_float(self.offset)
+    }
+
     /// Returns the stream element.
     pub fn element(&self) -> &StreamElement {
         &self.element
@@ -141,9 +209,12 @@ impl Stream {
     }
 
     /// Appends an element after the current end of the stream.
+    ///
+    /// The end is summed exactly, so three pushed triplet eighths leave the
+    /// next element at exactly `1.0`.
     pub fn push(&mut self, element: impl Into<StreamElement>) {
         let element = element.into();
-        let offset = self.end_offset();
+        let offset = fraction_to_float(self.end_offset_fraction());
         self.events.push(StreamEvent::new(offset, element));
     }
 
@@ -164,10 +235,17 @@ impl Stream {
 
     /// Returns the maximum event end offset.
     pub fn end_offset(&self) -> FloatType {
+        fraction_to_float(self.end_offset_fraction())
+    }
+
+    /// Returns the maximum event end offset as an exact ratio.
+    pub fn end_offset_fraction(&self) -> FractionType {
         self.events
             .iter()
-            .map(|event| event.offset + event.element.quarter_length())
-            .fold(0.0, FloatType::max)
+            .map(|event| event.offset_fraction() + event.element.quarter_length_fraction())
+            .fold(FractionType::from(0), |latest, end| {
+                if end > latest { end } else { latest }
+            })
     }
 
     /// Returns all pitches in timeline order.
@@ -202,6 +280,39 @@ impl Stream {
     }
 }
 
+pub(crate) fn fraction_to_float(value: FractionType) -> FloatType {
+    match (value.numer(), value.denom()) {
+        (Some(numerator), Some(denominator)) => {
+            let magnitude = FloatType::from(*numerator) / FloatType::from(*denominator);
+            if value.is_sign_negative() {
+                -magnitude
+            } else {
+                magnitude
+            }
+        }
+        _ => FloatType::NAN,
+    }
+}
+
+/// Builds a stream from `(name, quarter length)` pairs for tests, reading the
+/// name `"r"` as a rest.
+#[cfg(test)]
+pub(crate) fn test_stream(events: &[(&str, FloatType)]) -> Stream {
+    let mut stream = Stream::new();
+    for (name, quarter_length) in events {
+        if *name == "r" {
+            stream.push(Rest::from_quarter_length(*quarter_length).unwrap());
+        } else {
+            stream.push(
+                Note::from_name(*name)
+                    .unwrap()
+                    .with_duration(Duration::new(*quarter_length).unwrap()),
+            );
+        }
+    }
+    stream
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -235,4 +346,57 @@ mod tests {
             .collect::<Vec<_>>();
         assert_eq!(names, vec!["D4", "F#4", "A4"]);
     }
+
+    #[test]
+    fn pushed_triplets_land_exactly_on_the_beat() {
+        let triplet = Duration::eighth().with_tuplet(crate::duration::Tuplet::triplet());
+        let mut stream = Stream::new();
+        for _ in 0..6 {
+            stream.push(
+                Note::from_name("C4")
+                    .unwrap()
+                    .with_duration(triplet.clone()),
+            );
+        }
+        stream.push(Rest::from_quarter_length(1.0).unwrap());
+        assert_eq!(stream.events()[3].offset(), 1.0);
+        assert_eq!(stream.events()[6].offset(), 2.0);
+        assert_eq!(stream.end_offset_fraction(), FractionType::from(3));
+    }
+
+    #[test]
+    fn complex_elements_split_into_tied_parts() {
+        let chord = Chord::new("C4 E4")
+            .unwrap()
+            .with_duration(Duration::new(2.5).unwrap());
+        let parts = StreamElement::from(chord).split_at_durations();
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[0].quarter_length(), 2.0);
+        assert_eq!(parts[1].quarter_length(), 0.5);
+        let ties = |element: &StreamElement| match element {
+            StreamElement::Chord(chord) => chord.notes().iter().map(Note::tie).collect::<Vec<_>>(),
+            _ => Vec::new(),
+        };
+        assert_eq!(ties(&parts[0]), vec![Some(Tie::Start); 2]);
+        assert_eq!(ties(&parts[1]), vec![Some(Tie::Stop); 2]);
+
+        let held = Note::from_name("G4")
+            .unwrap()
+            .with_duration(Duration::new(5.0).unwrap())
+            .with_tie(Tie::Stop);
+        let parts = StreamElement::from(held).split_at_durations();
+        let ties = parts
+            .iter()
+            .map(|part| match part {
+                StreamElement::Note(note) => note.tie(),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(ties, vec![Some(Tie::Continue), Some(Tie::Stop)]);
+
+        let rest = StreamElement::from(Rest::from_quarter_length(1.25).unwrap());
+        assert_eq!(rest.split_at_durations().len(), 2);
+        let quarter = StreamElement::from(Note::from_name("C4").unwrap());
+        assert_eq!(quarter.split_at_durations().len(), 1);
+    }
 }
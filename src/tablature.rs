@@ -0,0 +1,996 @@
+//! Guitar tablature import and export.
+//!
+//! [`write_tab`] lays a [`Stream`] out as ASCII tablature, choosing strings
+//! and frets that keep the hand in as few positions as possible.
+//! [`read_tab`] reads the same kind of text back, and [`read_alphatex`]
+//! reads the note subset of alphaTex, the plain-text exchange format used by
+//! alphaTab and Guitar Pro–style tools.
+//!
+//! Time is written proportionally: with the default four columns per quarter,
+//! each dash is a sixteenth, so a quarter note on the fifth fret is `5---`,
+//! and a column with `r` on every string starts a rest. Where notes fall
+//! between columns, as triplets do, or are too close together for their frets
+//! to fit, the writer uses the first finer grid that places them exactly and
+//! says so with a line such as `Columns per quarter: 12`, which [`read_tab`]
+//! honours.
+
+use std::collections::BTreeMap;
+
+use crate::{
+    chord::{
+        Chord, FingeringOptions, GuitarFingering, GuitarTuning, GuitarTuningString,
+        guitar::{capo_tuning, cheapest_fingering_path, fingering_from_frets},
+    },
+    defaults::{FloatType, IntegerType},
+    duration::Duration,
+    error::{Error, Result},
+    note::Note,
+    pitch::Pitch,
+    rest::Rest,
+    stream::{Stream, StreamElement},
+};
+
+/// Most exact string assignments kept for one slice.
+const MAX_ASSIGNMENTS: usize = 64;
+
+/// The finest grid the writer refines to before giving up on an onset.
+const MAX_COLUMNS_PER_QUARTER: usize = 960;
+
+/// Layout settings for reading and writing tablature.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TabOptions {
+    columns_per_quarter: usize,
+    measure_columns: Option<usize>,
+    fingering: FingeringOptions,
+}
+
+impl Default for TabOptions {
+    fn default() -> Self {
+        Self {
+            columns_per_quarter: 4,
+            measure_columns: None,
+            fingering: FingeringOptions::default(),
+        }
+    }
+}
+
+impl TabOptions {
+    /// Returns the options with `columns` text columns per quarter note. The
+    /// default is 4.
+    pub fn with_columns_per_quarter(mut self, columns: usize) -> Self {
+        self.columns_per_quarter = columns.max(1);
+        self
+    }
+
+    /// Returns the options drawing a bar line every `quarters` quarter notes
+    /// when writing.
+    ///
+    /// Only whole numbers of columns are supported; a measure length is
+    /// rounded to the nearest column.
+    pub fn with_measure_length(mut self, quarters: FloatType) -> Self {
+        let columns = (quarters * self.columns_per_quarter as FloatType).round();
+        self.measure_columns = (columns >= 1.0).then_some(columns as usize);
+        self
+    }
+
+    /// Returns the options with different fret, capo and stretch limits.
+    ///
+    /// The capo also applies when reading, unless the text names its own
+    /// with a `Capo 3` line.
+    pub fn with_fingering_options(mut self, fingering: FingeringOptions) -> Self {
+        self.fingering = fingering;
+        self
+    }
+
+    /// Returns the number of columns per quarter note.
+    pub fn columns_per_quarter(&self) -> usize {
+        self.columns_per_quarter
+    }
+
+    /// Returns the fret, capo and stretch limits.
+    pub fn fingering_options(&self) -> &FingeringOptions {
+        &self.fingering
+    }
+}
+
+/// A fret, or `None` for an unplayed string, for each string bass side first.
+type Frets = Vec<Option<u8>>;
+
+/// Pitches sounding together, and the frets chosen for them, or the start of
+/// a rest when `rest` is set.
+#[derive(Clone, Debug)]
+struct Slice {
+    offset: FloatType,
+    frets: Frets,
+    rest: bool,
+}
+
+/// Writes a stream as ASCII tablature for `tuning`.
+///
+/// Notes and chords starting together share a column. Each slice's pitches
+/// are placed on distinct strings at their exact octave where possible;
+/// chords that cannot be played that way are revoiced with
+/// [`Chord::guitar_fingerings`]. The placements are scored and joined up as
+/// [`optimize_fingering_sequence`](crate::chord::optimize_fingering_sequence)
+/// does for chords, so the easiest sequence with the least hand movement is
+/// written.
+///
+/// Silence before the next onset, whether a rest or a note released early,
+/// starts with an `r` on every string. The grid is refined until every
+/// onset and release falls on a column; an error is returned if none up to
+/// 960 columns per quarter does.
+pub fn write_tab(stream: &Stream, tuning: &GuitarTuning, options: &TabOptions) -> Result<String> {
+    let mut groups: BTreeMap<IntegerType, (FloatType, Vec<IntegerType>, FloatType)> =
+        BTreeMap::new();
+    let mut end = 0.0 as FloatType;
+    for event in stream.events() {
+        let quarter_length = event.element().quarter_length();
+        end = end.max(event.offset() + quarter_length);
+        if matches!(event.element(), StreamElement::Rest(_)) {
+            continue;
+        }
+        let key = (event.offset() * 1e6).round() as IntegerType;
+        let group = groups
+            .entry(key)
+            .or_insert((event.offset(), Vec::new(), event.offset()));
+        for pitch in event.element().pitches() {
+            group.1.push(pitch_space(&pitch)?);
+        }
+        group.2 = group.2.max(event.offset() + quarter_length);
+    }
+
+    let (onsets, candidates): (Vec<_>, Vec<_>) = groups
+        .into_values()
+        .map(|(offset, mut pitch_spaces, release)| {
+            pitch_spaces.sort_unstable();
+            pitch_spaces.dedup();
+            Ok((
+                (offset, release),
+                placements(&pitch_spaces, tuning, &options.fingering)?,
+            ))
+        })
+        .collect::<Result<Vec<_>>>()?
+        .into_iter()
+        .unzip();
+    let mut slices = Vec::with_capacity(onsets.len());
+    let mut released = 0.0 as FloatType;
+    for (index, ((offset, release), fingering)) in onsets
+        .iter()
+        .zip(cheapest_fingering_path(candidates))
+        .enumerate()
+    {
+        slices.push(Slice {
+            offset: *offset,
+            frets: fingering.strings.iter().map(|string| string.fret).collect(),
+            rest: false,
+        });
+        released = released.max(*release);
+        let next = onsets.get(index + 1).map_or(end, |(next, _)| *next);
+        if released < next - 1e-9 {
+            slices.push(Slice {
+                offset: released,
+                frets: Vec::new(),
+                rest: true,
+            });
+        }
+    }
+
+    let labels = string_labels(
+        &tuning
+            .strings()
+            .iter()
+            .map(|string| (string.name.clone(), string.pitch_space))
+            .collect::<Vec<_>>(),
+    );
+    render(&labels, &slices, end, options)
+}
+
+/// Writes a sequence of fingerings as tablature, one quarter note each.
+///
+/// The strings are labelled from the first fingering.
+pub fn write_fingering_tab(fingerings: &[GuitarFingering], options: &TabOptions) -> String {
+    let Some(first) = fingerings.first() else {
+        return String::new();
+    };
+    let labels = string_labels(
+        &first
+            .strings
+            .iter()
+            .map(|string| (string.string_name.clone(), string.open_pitch_space))
+            .collect::<Vec<_>>(),
+    );
+    let slices = fingerings
+        .iter()
+        .enumerate()
+        .map(|(index, fingering)| Slice {
+            offset: index as FloatType,
+            frets: fingering.strings.iter().map(|string| string.fret).collect(),
+            rest: false,
+        })
+        .collect::<Vec<_>>();
+    render(&labels, &slices, fingerings.len() as FloatType, options)
+        .expect("whole quarters fall on every grid")
+}
+
+/// Reads plain-text tablature into a stream of notes and chords.
+///
+/// Tab lines look like `e|--3---|`: an optional string label, a bar, then
+/// frets, dashes and bar lines. Consecutive tab lines form a system, which
+/// must have one line per string of `tuning`, highest string on top.
+/// Systems follow one another in time. Numbers starting in the same column
+/// sound together, and each lasts until the next column with a number or
+/// with an `r` on every string, which starts a rest. Silence before the first
+/// number is a rest too.
+///
+/// Techniques such as `h`, `p`, `/`, `b` and `~` are read as filler, dead
+/// notes (`x`) are skipped, a line such as `Capo 2` sets the capo, and one
+/// such as `Columns per quarter: 12` overrides the options' grid.
+/// Other text between systems is ignored.
+pub fn read_tab(text: &str, tuning: &GuitarTuning, options: &TabOptions) -> Result<Stream> {
+    let mut capo = options.fingering.capo();
+    let mut columns_per_quarter = options.columns_per_quarter;
+    let mut systems: Vec<Vec<Vec<char>>> = Vec::new();
+    let mut current: Vec<Vec<char>> = Vec::new();
+    for line in text.lines() {
+        if let Some(content) = tab_line_content(line) {
+            current.push(content.chars().collect());
+            continue;
+        }
+        if !current.is_empty() {
+            systems.push(std::mem::take(&mut current));
+        }
+        let lowered = line.trim().to_ascii_lowercase();
+        if let Some(fret) = lowered.strip_prefix("capo") {
+            capo = fret
+                .trim_matches(|c: char| !c.is_ascii_digit())
+                .parse()
+                .map_err(|_| Error::Tablature(format!("cannot read capo line {line:?}")))?;
+        } else if let Some(columns) = lowered.strip_prefix("columns per quarter") {
+            columns_per_quarter = columns
+                .trim_matches(|c: char| !c.is_ascii_digit())
+                .parse::<usize>()
+                .ok()
+                .filter(|columns| *columns > 0)
+                .ok_or_else(|| Error::Tablature(format!("cannot read grid line {line:?}")))?;
+        }
+    }
+    if !current.is_empty() {
+        systems.push(current);
+    }
+
+    let tuning = capo_tuning(tuning, capo)
+        .ok_or_else(|| Error::Tablature(format!("cannot place a capo at fret {capo}")))?;
+    let string_count = tuning.strings().len();
+    let columns_per_quarter = columns_per_quarter as FloatType;
+    let mut stream = Stream::new();
+    let mut system_offset = 0.0;
+    for rows in systems {
+        if rows.len() != string_count {
+            return Err(Error::Tablature(format!(
+                "a tab system has {} lines but the tuning has {string_count} strings",
+                rows.len()
+            )));
+        }
+        let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+        let bars = (0..width)
+            .map(|column| rows.iter().any(|row| row.get(column) == Some(&'|')))
+            .collect::<Vec<_>>();
+        // The time of each column, skipping bar lines.
+        let mut times = Vec::with_capacity(width + 1);
+        let mut elapsed = 0usize;
+        for bar in &bars {
+            times.push(system_offset + elapsed as FloatType / columns_per_quarter);
+            elapsed += usize::from(!bar);
+        }
+        let system_end = system_offset + elapsed as FloatType / columns_per_quarter;
+
+        // `None` starts a rest.
+        let mut onsets: BTreeMap<usize, Option<Vec<IntegerType>>> = (0..width)
+            .filter(|column| rows.iter().all(|row| row.get(*column) == Some(&'r')))
+            .map(|column| (column, None))
+            .collect();
+        for (row_index, row) in rows.iter().enumerate() {
+            let string = &tuning.strings()[string_count - 1 - row_index];
+            let mut column = 0;
+            while column < row.len() {
+                if !row[column].is_ascii_digit() {
+                    column += 1;
+                    continue;
+                }
+                let start = column;
+                while column < row.len() && row[column].is_ascii_digit() {
+                    column += 1;
+                }
+                let fret: u8 = row[start..column]
+                    .iter()
+                    .collect::<String>()
+                    .parse()
+                    .map_err(|_| Error::Tablature("fret number is too large".to_string()))?;
+                let pitch_space = string.fret_pitch_space(fret).ok_or_else(|| {
+                    Error::Tablature(format!("string {} has no fret {fret}", string.name))
+                })?;
+                onsets
+                    .entry(start)
+                    .or_default()
+                    .get_or_insert_with(Vec::new)
+                    .push(pitch_space);
+            }
+        }
+
+        if stream.events().is_empty()
+            && let Some(first) = onsets.keys().next().map(|column| times[*column])
+            && first > 0.0
+        {
+            stream.insert(0.0, Rest::from_quarter_length(first)?);
+        }
+        let columns = onsets.keys().copied().collect::<Vec<_>>();
+        for (index, (column, pitch_spaces)) in onsets.into_iter().enumerate() {
+            let offset = times[column];
+            let next = columns
+                .get(index + 1)
+                .map_or(system_end, |next| times[*next]);
+            match pitch_spaces {
+                Some(pitch_spaces) => stream.insert(offset, element(pitch_spaces, next - offset)?),
+                None if next > offset => {
+                    stream.insert(offset, Rest::from_quarter_length(next - offset)?)
+                }
+                None => {}
+            }
+        }
+        system_offset = system_end;
+    }
+    Ok(stream)
+}
+
+/// Reads the note subset of alphaTex into a stream.
+///
+/// Metadata tags before a lone `.` are skipped, except `\tuning`, whose
+/// pitches (highest string first) replace `tuning`. In the body, `fret.string`
+/// is a note, with string 1 the highest; `(f.s f.s)` is a chord; `r` is a
+/// rest; a trailing `.4` sets one beat's duration and `:8` the duration of
+/// the beats that follow. Bar lines and `{...}` effects are ignored, and dead
+/// notes (`x.s`) are read as rests.
+pub fn read_alphatex(text: &str, tuning: &GuitarTuning) -> Result<Stream> {
+    let text = text
+        .lines()
+        .map(|line| line.split("//").next().unwrap_or_default())
+        .collect::<Vec<_>>()
+        .join("\n");
+    let tokens = text.split_whitespace().collect::<Vec<_>>();
+    let (metadata, body) = match tokens.iter().position(|token| *token == ".") {
+        Some(index) => (&tokens[..index], &tokens[index + 1..]),
+        None if tokens.first().is_some_and(|token| token.starts_with('\\')) => {
+            return Err(Error::Tablature(
+                "alphaTex metadata must end with a lone '.'".to_string(),
+            ));
+        }
+        None => (&tokens[..0], &tokens[..]),
+    };
+
+    let mut tuning = tuning.clone();
+    let mut tag = "";
+    let mut tuning_names = Vec::new();
+    for token in metadata {
+        if let Some(name) = token.strip_prefix('\\') {
+            tag = name;
+        } else if tag.eq_ignore_ascii_case("tuning") {
+            tuning_names.push(*token);
+        }
+    }
+    if !tuning_names.is_empty() {
+        tuning = GuitarTuning::new(tuning_names.iter().rev())?;
+    }
+
+    let body = body.join(" ");
+    let mut chars = body.chars().peekable();
+    let mut stream = Stream::new();
+    let mut offset = 0.0;
+    let mut default_length = 1.0;
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() || c == '|' => {
+                chars.next();
+            }
+            '{' => {
+                for c in chars.by_ref() {
+                    if c == '}' {
+                        break;
+                    }
+                }
+            }
+            ':' => {
+                chars.next();
+                let value = take_while(&mut chars, |c| c.is_ascii_digit());
+                default_length = alphatex_length(&value)?;
+            }
+            _ => {
+                let notes = if c == '(' {
+                    chars.next();
+                    let inner = take_while(&mut chars, |c| c != ')');
+                    if chars.next() != Some(')') {
+                        return Err(Error::Tablature("unclosed alphaTex chord".to_string()));
+                    }
+                    inner.split_whitespace().map(str::to_string).collect()
+                } else {
+                    vec![take_while(&mut chars, |c| {
+                        !c.is_whitespace() && !matches!(c, '|' | '{' | '(')
+                    })]
+                };
+                // A chord's duration follows its closing parenthesis.
+                let mut length = default_length;
+                if c == '(' && chars.peek() == Some(&'.') {
+                    chars.next();
+                    length = alphatex_length(&take_while(&mut chars, |c| c.is_ascii_digit()))?;
+                }
+
+                let mut pitch_spaces = Vec::new();
+                for note in &notes {
+                    let parts = note.split('.').collect::<Vec<_>>();
+                    if parts.len() == 3 || (parts.len() == 2 && parts[0] == "r") {
+                        length = alphatex_length(parts[parts.len() - 1])?;
+                    }
+                    match parts.as_slice() {
+                        ["r"] | ["r", _] => {}
+                        [fret, _] | [fret, _, _] if fret.eq_ignore_ascii_case("x") => {}
+                        [fret, string] | [fret, string, _] => {
+                            pitch_spaces.push(alphatex_pitch_space(fret, string, &tuning)?)
+                        }
+                        _ => {
+                            return Err(Error::Tablature(format!(
+                                "cannot read alphaTex beat {note:?}"
+                            )));
+                        }
+                    }
+                }
+                if pitch_spaces.is_empty() {
+                    stream.insert(offset, Rest::from_quarter_length(length)?);
+                } else {
+                    stream.insert(offset, element(pitch_spaces, length)?);
+                }
+                offset += length;
+            }
+        }
+    }
+    Ok(stream)
+}
+
+/// Every way to play a slice's pitches, scored as chord shapes are.
+fn placements(
+    pitch_spaces: &[IntegerType],
+    tuning: &GuitarTuning,
+    options: &FingeringOptions,
+) -> Result<Vec<GuitarFingering>> {
+    let stopped = capo_tuning(tuning, options.capo()).ok_or_else(|| {
+        Error::Tablature(format!("cannot place a capo at fret {}", options.capo()))
+    })?;
+    let highest = options.fret_count().saturating_sub(options.capo());
+
+    let mut exact = Vec::new();
+    let mut frets = vec![None; stopped.strings().len()];
+    assign(
+        pitch_spaces,
+        stopped.strings(),
+        highest,
+        options.max_stretch(),
+        &mut frets,
+        &mut exact,
+    );
+    let mut found = exact
+        .iter()
+        .filter_map(|frets| fingering_from_frets(frets, tuning, options))
+        .collect::<Vec<_>>();
+    if found.is_empty() && pitch_spaces.len() > 1 {
+        let pitches = pitch_spaces
+            .iter()
+            .map(|pitch_space| Pitch::from_midi(*pitch_space))
+            .collect::<Result<Vec<_>>>()?;
+        found = Chord::new(pitches.as_slice())?.guitar_fingerings(tuning, options);
+    }
+    if found.is_empty() {
+        return Err(Error::Tablature(format!(
+            "no placement of pitches {pitch_spaces:?} fits the instrument"
+        )));
+    }
+    Ok(found)
+}
+
+fn assign(
+    pitch_spaces: &[IntegerType],
+    strings: &[GuitarTuningString],
+    highest: u8,
+    max_stretch: u8,
+    frets: &mut Frets,
+    found: &mut Vec<Frets>,
+) {
+    if found.len() >= MAX_ASSIGNMENTS {
+        return;
+    }
+    let Some((pitch_space, rest)) = pitch_spaces.split_first() else {
+        found.push(frets.clone());
+        return;
+    };
+    for (string, tuning_string) in strings.iter().enumerate() {
+        let Some(fret) = tuning_string
+            .fret_for_pitch_space(*pitch_space)
+            .filter(|fret| *fret <= highest)
+        else {
+            continue;
+        };
+        if frets[string].is_some() {
+            continue;
+        }
+        frets[string] = Some(fret);
+        let fretted = frets.iter().flatten().filter(|fret| **fret > 0);
+        let stretch = fretted.clone().max().zip(fretted.min());
+        if stretch.is_none_or(|(high, low)| high - low <= max_stretch) {
+            assign(rest, strings, highest, max_stretch, frets, found);
+        }
+        frets[string] = None;
+    }
+}
+
+/// Labels for each string, bass side first: the pitch name, lowercased for a
+/// string sharing its name with a lower one, as in `E A D G B e`.
+fn string_labels(strings: &[(String, IntegerType)]) -> Vec<String> {
+    let names = strings
+        .iter()
+        .map(|(name, _)| {
+            name.trim_end_matches(|c: char| c.is_ascii_digit())
+                .replace('-', "b")
+        })
+        .collect::<Vec<_>>();
+    let labels = names
+        .iter()
+        .zip(strings)
+        .map(|(name, (_, pitch_space))| {
+            let doubled = names
+                .iter()
+                .zip(strings)
+                .any(|(other, (_, other_space))| other == name && other_space < pitch_space);
+            if doubled {
+                name.to_lowercase()
+            } else {
+                name.clone()
+            }
+        })
+        .collect::<Vec<_>>();
+    let width = labels.iter().map(String::len).max().unwrap_or(0);
+    labels
+        .into_iter()
+        .map(|label| format!("{label:<width$}"))
+        .collect()
+}
+
+/// The number of text columns a slice's frets take.
+fn slice_width(slice: &Slice) -> usize {
+    slice
+        .frets
+        .iter()
+        .flatten()
+        .map(|fret| fret.to_string().len())
+        .max()
+        .unwrap_or(1)
+}
+
+fn render(
+    labels: &[String],
+    slices: &[Slice],
+    end: FloatType,
+    options: &TabOptions,
+) -> Result<String> {
+    let requested = options.columns_per_quarter;
+    let column_at = |offset: FloatType, per_quarter: usize| {
+        (offset * per_quarter as FloatType).round().max(0.0) as usize
+    };
+    let on_grid = |offset: FloatType, per_quarter: usize| {
+        let column = offset * per_quarter as FloatType;
+        (column - column.round()).abs() < 1e-6
+    };
+    // Every onset and the end must land exactly on a column, and each fret
+    // needs its own width and a dash before the next onset, so refine the
+    // grid until both hold.
+    let factor = (1..=MAX_COLUMNS_PER_QUARTER / requested)
+        .find(|factor| {
+            let per_quarter = requested * factor;
+            on_grid(end, per_quarter)
+                && slices.iter().enumerate().all(|(index, slice)| {
+                    let next = slices
+                        .get(index + 1)
+                        .map_or(column_at(end, per_quarter) + 1, |next| {
+                            column_at(next.offset, per_quarter)
+                        });
+                    on_grid(slice.offset, per_quarter)
+                        && next > column_at(slice.offset, per_quarter) + slice_width(slice)
+                })
+        })
+        .ok_or_else(|| {
+            Error::Tablature(format!(
+                "the onsets do not fall on any grid of up to {MAX_COLUMNS_PER_QUARTER} columns per quarter"
+            ))
+        })?;
+    let per_quarter = requested * factor;
+    let ideal = |offset: FloatType| column_at(offset, per_quarter);
+    let total = ideal(end);
+
+    let mut rows = vec![String::new(); labels.len()];
+    let mut column = 0;
+    let mut bars = 0;
+    let pad = |rows: &mut Vec<String>, column: &mut usize, target: usize| {
+        while *column < target {
+            rows.iter_mut().for_each(|row| row.push('-'));
+            *column += 1;
+        }
+    };
+    let mut bar_lines = options
+        .measure_columns
+        .map(|measure| measure * factor)
+        .map(|measure| {
+            (1..)
+                .map(move |index| index * measure)
+                .take_while(move |bar| *bar < total)
+        })
+        .into_iter()
+        .flatten()
+        .peekable();
+    // A fret is followed by at least one dash before the next one.
+    let mut earliest = 0;
+    for slice in slices {
+        let start = ideal(slice.offset);
+        while let Some(bar) = bar_lines.next_if(|bar| *bar <= start) {
+            pad(&mut rows, &mut column, bar + bars);
+            rows.iter_mut().for_each(|row| row.push('|'));
+            column += 1;
+            bars += 1;
+            earliest = earliest.max(column);
+        }
+        pad(&mut rows, &mut column, (start + bars).max(earliest));
+        let texts = if slice.rest {
+            vec![Some("r".to_string()); rows.len()]
+        } else {
+            slice
+                .frets
+                .iter()
+                .map(|fret| fret.map(|fret| fret.to_string()))
+                .collect::<Vec<_>>()
+        };
+        let width = slice_width(slice);
+        for (row, text) in rows.iter_mut().zip(&texts) {
+            let text = text.as_deref().unwrap_or("");
+            row.push_str(text);
+            row.push_str(&"-".repeat(width - text.len()));
+        }
+        column += width;
+        earliest = column + 1;
+    }
+    for bar in bar_lines {
+        pad(&mut rows, &mut column, (bar + bars).max(earliest));
+        rows.iter_mut().for_each(|row| row.push('|'));
+        column += 1;
+        bars += 1;
+        earliest = column;
+    }
+    pad(&mut rows, &mut column, (total + bars).max(earliest));
+
+    let grid = if factor > 1 {
+        format!("Columns per quarter: {per_quarter}\n\n")
+    } else {
+        String::new()
+    };
+    Ok(grid
+        + &labels
+            .iter()
+            .zip(&rows)
+            .rev()
+            .map(|(label, row)| format!("{label}|{row}|\n"))
+            .collect::<String>())
+}
+
+/// The frets, dashes and bars after a tab line's label, if it is one.
+fn tab_line_content(line: &str) -> Option<&str> {
+    let line = line.trim();
+    let (label, content) = line.split_once('|')?;
+    let label_ok = label.len() <= 3 && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '#');
+    let content_ok = content.contains('-')
+        && content
+            .chars()
+            .all(|c| c.is_ascii_digit() || "-|hpbrxX/\\~()<>^.*=sv".contains(c));
+    (label_ok && content_ok).then_some(content)
+}
+
+fn element(mut pitch_spaces: Vec<IntegerType>, quarter_length: FloatType) -> Result<StreamElement> {
+    pitch_spaces.sort_unstable();
+    pitch_spaces.dedup();
+    let duration = Duration::new(quarter_length)?;
+    let pitches = pitch_spaces
+        .into_iter()
+        .map(Pitch::from_midi)
+        .collect::<Result<Vec<_>>>()?;
+    Ok(match pitches.as_slice() {
+        [pitch] => Note::from_pitch(pitch.clone())?
+            .with_duration(duration)
+            .into(),
+        _ => Chord::new(pitches.as_slice())?
+            .with_duration(duration)
+            .into(),
+    })
+}
+
+fn take_while(
+    chars: &mut std::iter::Peekable<std::str::Chars<'_>>,
+    keep: impl Fn(char) -> bool,
+) -> String {
+    let mut taken = String::new();
+    while let Some(c) = chars.next_if(|c| keep(*c)) {
+        taken.push(c);
+    }
+    taken
+}
+
+fn alphatex_length(value: &str) -> Result<FloatType> {
+    match value.parse::<u32>() {
+        Ok(denominator @ (1 | 2 | 4 | 8 | 16 | 32 | 64)) => Ok(4.0 / FloatType::from(denominator)),
+        _ => Err(Error::Tablature(format!(
+            "unknown alphaTex duration {value:?}"
+        ))),
+    }
+}
+
+fn alphatex_pitch_space(fret: &str, string: &str, tuning: &GuitarTuning) -> Result<IntegerType> {
+    let fret: u8 = fret
+        .parse()
+        .map_err(|_| Error::Tablature(format!("cannot read alphaTex fret {fret:?}")))?;
+    let strings = tuning.strings();
+    let string = string
+        .parse::<usize>()
+        .ok()
+        .filter(|string| (1..=strings.len()).contains(string))
+        .map(|string| &strings[strings.len() - string])
+        .ok_or_else(|| Error::Tablature(format!("no string {string:?} on this instrument")))?;
+    string
+        .fret_pitch_space(fret)
+        .ok_or_else(|| Error::Tablature(format!("string {} has no fret {fret}", string.name)))
+}
+
+fn pitch_space(pitch: &Pitch) -> Result<IntegerType> {
+    let ps = pitch.ps();
+    if (ps - ps.round()).abs() > FloatType::EPSILON {
+        return Err(Error::Tablature(format!(
+            "cannot write microtonal pitch {} as tablature",
+            pitch.name_with_octave()
+        )));
+    }
+    Ok(ps.round() as IntegerType)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn described(stream: &Stream) -> Vec<(FloatType, String, FloatType)> {
+        stream
+            .events()
+            .iter()
+            .map(|event| {
+                let names = event
+                    .element()
+                    .pitches()
+                    .iter()
+                    .map(Pitch::name_with_octave)
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                (event.offset(), names, event.element().quarter_length())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn writes_and_reads_back_a_melody_and_chord() {
+        let tuning = GuitarTuning::standard();
+        let mut stream = Stream::new();
+        for (index, name) in ["E4", "G4", "A4"].into_iter().enumerate() {
+            stream.insert(index as FloatType, Note::from_name(name).unwrap());
+        }
+        stream.insert(3.0, crate::Rest::from_quarter_length(1.0).unwrap());
+        stream.insert(
+            4.0,
+            Chord::new("C3 E3 G3 C4 E4")
+                .unwrap()
+                .with_duration(Duration::new(4.0).unwrap()),
+        );
+        let options = TabOptions::default().with_measure_length(4.0);
+
+        let tab = write_tab(&stream, &tuning, &options).unwrap();
+        assert_eq!(
+            tab,
+            "e|0---3---5---r---|0---------------|\n\
+             B|------------r---|1---------------|\n\
+             G|------------r---|0---------------|\n\
+             D|------------r---|2---------------|\n\
+             A|------------r---|3---------------|\n\
+             E|------------r---|----------------|\n"
+        );
+
+        let read = read_tab(&tab, &tuning, &options).unwrap();
+        assert_eq!(
+            described(&read),
+            vec![
+                (0.0, "E4".to_string(), 1.0),
+                (1.0, "G4".to_string(), 1.0),
+                (2.0, "A4".to_string(), 1.0),
+                (3.0, String::new(), 1.0),
+                (4.0, "C3 E3 G3 C4 E4".to_string(), 4.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn refines_the_grid_for_close_high_frets() {
+        let tuning = GuitarTuning::standard();
+        let mut stream = Stream::new();
+        for (index, name) in ["E5", "F#5", "G5", "A5", "B5", "C6"]
+            .into_iter()
+            .enumerate()
+        {
+            stream.insert(
+                index as FloatType * 0.25,
+                Note::from_name(name)
+                    .unwrap()
+                    .with_duration(Duration::new(0.25).unwrap()),
+            );
+        }
+        let options = TabOptions::default()
+            .with_fingering_options(FingeringOptions::default().with_fret_count(22));
+
+        let tab = write_tab(&stream, &tuning, &options).unwrap();
+        assert!(tab.starts_with("Columns per quarter: 12\n\ne|12-14-15-17-19-20-|\n"));
+        let read = read_tab(&tab, &tuning, &options).unwrap();
+        assert_eq!(described(&read), described(&stream));
+
+        // Frets that fit keep the requested grid.
+        let mut slow = Stream::new();
+        slow.insert(0.0, Note::from_name("E5").unwrap());
+        slow.insert(1.0, Note::from_name("F#5").unwrap());
+        let tab = write_tab(&slow, &tuning, &options).unwrap();
+        assert!(tab.starts_with("e|12--14--|\n"));
+        assert_eq!(
+            described(&read_tab(&tab, &tuning, &options).unwrap()),
+            described(&slow)
+        );
+    }
+
+    #[test]
+    fn places_tuplets_and_trailing_rests_exactly() {
+        let tuning = GuitarTuning::standard();
+        let third = 1.0 / 3.0;
+        let mut stream = Stream::new();
+        for (index, name) in ["E4", "F4", "G4"].into_iter().enumerate() {
+            stream.insert(
+                index as FloatType * third,
+                Note::from_name(name)
+                    .unwrap()
+                    .with_duration(Duration::new(third).unwrap()),
+            );
+        }
+        stream.insert(1.0, Note::from_name("E5").unwrap());
+        stream.insert(2.0, crate::Rest::from_quarter_length(1.0).unwrap());
+        let options = TabOptions::default().with_columns_per_quarter(8);
+
+        let tab = write_tab(&stream, &tuning, &options).unwrap();
+        assert!(tab.starts_with("Columns per quarter: 24\n\n"));
+        let read = read_tab(&tab, &tuning, &options).unwrap();
+        let described = described(&read);
+        assert_eq!(described.len(), 5);
+        for ((offset, names, length), (expected_offset, expected_names, expected_length)) in
+            described.iter().zip([
+                (0.0, "E4", third),
+                (third, "F4", third),
+                (2.0 * third, "G4", third),
+                (1.0, "E5", 1.0),
+                (2.0, "", 1.0),
+            ])
+        {
+            assert!((offset - expected_offset).abs() < 1e-9);
+            assert_eq!(names, expected_names);
+            assert!(
+                (length - expected_length).abs() < 1e-9,
+                "{names} lasts {length}"
+            );
+        }
+
+        let mut irregular = Stream::new();
+        irregular.insert(0.0, Note::from_name("E4").unwrap());
+        irregular.insert(1.0 / 997.0, Note::from_name("F4").unwrap());
+        assert!(matches!(
+            write_tab(&irregular, &tuning, &options),
+            Err(Error::Tablature(_))
+        ));
+    }
+
+    #[test]
+    fn writes_fingering_sequences() {
+        let tuning = GuitarTuning::standard();
+        let options = FingeringOptions::default();
+        let shapes = ["C3 E3 G3 C4 E4", "G2 B2 D3 G3 B3 G4"]
+            .into_iter()
+            .map(|pitches| {
+                Chord::new(pitches)
+                    .unwrap()
+                    .guitar_fingerings(&tuning, &options)[0]
+                    .clone()
+            })
+            .collect::<Vec<_>>();
+        let tab = write_fingering_tab(&shapes, &TabOptions::default().with_columns_per_quarter(2));
+        assert_eq!(
+            tab,
+            "e|0-3-|\nB|1-0-|\nG|0-0-|\nD|2-0-|\nA|3-2-|\nE|--3-|\n"
+        );
+    }
+
+    #[test]
+    fn reads_systems_in_sequence_with_capo_and_techniques() {
+        let text = "Capo 2\n\
+                    \n\
+                    e|-------|\n\
+                    B|-------|\n\
+                    G|--2h4--|\n\
+                    D|-------|\n\
+                    A|0------|\n\
+                    E|-------|\n\
+                    \n\
+                    e|12--|\n\
+                    B|x---|\n\
+                    G|----|\n\
+                    D|----|\n\
+                    A|----|\n\
+                    E|----|\n";
+        let read = read_tab(text, &GuitarTuning::standard(), &TabOptions::default()).unwrap();
+        assert_eq!(
+            described(&read),
+            vec![
+                (0.0, "B2".to_string(), 0.5),
+                (0.5, "B3".to_string(), 0.5),
+                (1.0, "C#4".to_string(), 0.75),
+                (1.75, "F#5".to_string(), 1.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_systems_with_the_wrong_string_count() {
+        let text = "G|--0--|\nC|--0--|\nE|--0--|\nA|--3--|\n";
+        assert!(read_tab(text, &GuitarTuning::ukulele(), &TabOptions::default()).is_ok());
+        assert!(matches!(
+            read_tab(text, &GuitarTuning::standard(), &TabOptions::default()),
+            Err(Error::Tablature(_))
+        ));
+    }
+
+    #[test]
+    fn reads_alphatex_notes_chords_and_rests() {
+        let text = "\\title \"Riff\"\n\
+                    \\tuning E4 B3 G3 D3 A2 D2\n\
+                    .\n\
+                    :8 0.6 3.6 {h} 5.6 | (0.1 1.2 0.3).2 r x.4 12.1.16 // tag\n";
+        let read = read_alphatex(text, &GuitarTuning::standard()).unwrap();
+        assert_eq!(
+            described(&read),
+            vec![
+                (0.0, "D2".to_string(), 0.5),
+                (0.5, "F2".to_string(), 0.5),
+                (1.0, "G2".to_string(), 0.5),
+                (1.5, "G3 C4 E4".to_string(), 2.0),
+                (3.5, String::new(), 0.5),
+                (4.0, String::new(), 0.5),
+                (4.5, "E5".to_string(), 0.25),
+            ]
+        );
+        assert_eq!(
+            described(&read_alphatex("r.4 0.1.4", &GuitarTuning::standard()).unwrap()),
+            vec![(0.0, String::new(), 1.0), (1.0, "E4".to_string(), 1.0)]
+        );
+
+        assert!(read_alphatex("0.7", &GuitarTuning::standard()).is_err());
+        assert!(read_alphatex("0.1.3", &GuitarTuning::standard()).is_err());
+    }
+}
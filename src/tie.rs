@@ -0,0 +1,84 @@
+use std::{fmt, str::FromStr};
+
+use crate::error::Error;
+
+/// Role of a note in a chain of tied notes.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Tie {
+    /// The first note of a tie, held into the next one.
+    Start,
+    /// A middle note, both held from the previous note and into the next.
+    Continue,
+    /// The last note of a tie, held from the previous one.
+    Stop,
+}
+
+impl Tie {
+    /// Returns the tie for a note held from the previous note, into the next
+    /// one, or both; `None` when it is neither.
+    pub fn from_holds(from_previous: bool, into_next: bool) -> Option<Self> {
+        match (from_previous, into_next) {
+            (false, false) => None,
+            (false, true) => Some(Self::Start),
+            (true, true) => Some(Self::Continue),
+            (true, false) => Some(Self::Stop),
+        }
+    }
+
+    /// Returns whether the note is held from the previous note.
+    pub fn holds_from_previous(self) -> bool {
+        matches!(self, Self::Continue | Self::Stop)
+    }
+
+    /// Returns whether the note is held into the next note.
+    pub fn holds_into_next(self) -> bool {
+        matches!(self, Self::Start | Self::Continue)
+    }
+
+    /// Returns the music21 tie type, such as `"start"`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Start => "start",
+            Self::Continue => "continue",
+            Self::Stop => "stop",
+        }
+    }
+}
+
+impl fmt::Display for Tie {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Tie {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "start" => Ok(Self::Start),
+            "continue" => Ok(Self::Continue),
+            "stop" => Ok(Self::Stop),
+            _ => Err(Error::Music21Object(format!("unknown tie type {value:?}"))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn holds_round_trip() {
+        for tie in [Tie::Start, Tie::Continue, Tie::Stop] {
+            assert_eq!(
+                Tie::from_holds(tie.holds_from_previous(), tie.holds_into_next()),
+                Some(tie)
+            );
+            assert_eq!(tie.to_string().parse::<Tie>().unwrap(), tie);
+        }
+        assert_eq!(Tie::from_holds(false, false), None);
+        assert!("let-ring".parse::<Tie>().is_err());
+    }
+}
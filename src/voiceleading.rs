@@ -0,0 +1,464 @@
+//! Minimal voice leadings between chords.
+//!
+//! [`minimal_voice_leading`] searches every assignment of one chord's notes
+//! to another's and keeps the one that moves the voices least under a chosen
+//! [`VoiceLeadingNorm`]. Chords of different sizes are connected by doubling
+//! notes of the smaller chord, so every note of both chords is sounded.
+
+use crate::{
+    chord::Chord,
+    defaults::{FloatType, IntegerType},
+    error::{Error, Result},
+    interval::Interval,
+    pitch::Pitch,
+};
+
+/// How the motions of the individual voices add up to a voice leading's
+/// size.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum VoiceLeadingNorm {
+    /// The total number of semitones moved (L1).
+    #[default]
+    Taxicab,
+    /// The square root of the summed squared motions (L2).
+    Euclidean,
+    /// The largest motion of any single voice (L∞).
+    Chebyshev,
+}
+
+impl VoiceLeadingNorm {
+    fn size(self, motions: impl IntoIterator<Item = FloatType>) -> FloatType {
+        let motions = motions.into_iter().map(FloatType::abs);
+        match self {
+            Self::Taxicab => motions.sum(),
+            Self::Euclidean => motions
+                .map(|motion| motion * motion)
+                .sum::<FloatType>()
+                .sqrt(),
+            Self::Chebyshev => motions.fold(0.0, FloatType::max),
+        }
+    }
+}
+
+/// Whether voices move between the chords' actual pitches or between pitch
+/// classes.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum VoiceLeadingSpace {
+    /// Voices move to the target chord's pitches in their written octaves.
+    Pitch,
+    /// Voices move to the nearest instance of each target pitch class, at
+    /// most a tritone away.
+    #[default]
+    PitchClass,
+}
+
+/// Settings for [`minimal_voice_leading`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VoiceLeadingOptions {
+    norm: VoiceLeadingNorm,
+    space: VoiceLeadingSpace,
+    allow_crossing: bool,
+}
+
+impl Default for VoiceLeadingOptions {
+    fn default() -> Self {
+        Self {
+            norm: VoiceLeadingNorm::default(),
+            space: VoiceLeadingSpace::default(),
+            allow_crossing: true,
+        }
+    }
+}
+
+impl VoiceLeadingOptions {
+    /// Returns the options with a different norm.
+    pub fn with_norm(mut self, norm: VoiceLeadingNorm) -> Self {
+        self.norm = norm;
+        self
+    }
+
+    /// Returns the options with a different space.
+    pub fn with_space(mut self, space: VoiceLeadingSpace) -> Self {
+        self.space = space;
+        self
+    }
+
+    /// Returns the options with voice crossings allowed or forbidden.
+    pub fn with_crossing(mut self, allow_crossing: bool) -> Self {
+        self.allow_crossing = allow_crossing;
+        self
+    }
+
+    /// Returns the norm.
+    pub fn norm(&self) -> VoiceLeadingNorm {
+        self.norm
+    }
+
+    /// Returns the space.
+    pub fn space(&self) -> VoiceLeadingSpace {
+        self.space
+    }
+
+    /// Returns `true` when voices may cross.
+    pub fn allows_crossing(&self) -> bool {
+        self.allow_crossing
+    }
+}
+
+/// One voice's motion in a voice leading.
+#[derive(Clone, Debug)]
+pub struct VoiceMotion {
+    from: Pitch,
+    to: Pitch,
+    interval: Interval,
+}
+
+impl VoiceMotion {
+    /// Returns the starting pitch.
+    pub fn from(&self) -> &Pitch {
+        &self.from
+    }
+
+    /// Returns the destination pitch, placed in the octave the voice reaches.
+    pub fn to(&self) -> &Pitch {
+        &self.to
+    }
+
+    /// Returns the directed interval the voice moves by.
+    pub fn interval(&self) -> &Interval {
+        &self.interval
+    }
+
+    /// Returns the directed motion in semitones.
+    pub fn semitones(&self) -> FloatType {
+        self.to.ps() - self.from.ps()
+    }
+}
+
+/// The voice leading [`minimal_voice_leading`] found.
+#[derive(Clone, Debug)]
+pub struct VoiceLeading {
+    motions: Vec<VoiceMotion>,
+    size: FloatType,
+}
+
+impl VoiceLeading {
+    /// Returns the voices from lowest to highest starting pitch.
+    pub fn motions(&self) -> &[VoiceMotion] {
+        &self.motions
+    }
+
+    /// Returns each voice's interval, from lowest to highest starting pitch.
+    pub fn intervals(&self) -> Vec<Interval> {
+        self.motions
+            .iter()
+            .map(|motion| motion.interval.clone())
+            .collect()
+    }
+
+    /// Returns the size under the norm it was optimized for.
+    pub fn size(&self) -> FloatType {
+        self.size
+    }
+}
+
+/// Finds the smallest voice leading from `from` to `to`.
+///
+/// With equal sizes every note moves to a different target. Otherwise the
+/// smaller chord's notes are doubled or merged so that each note of both
+/// chords belongs to at least one voice. Ties under the chosen norm go to the
+/// voice leading with less total motion.
+pub fn minimal_voice_leading(
+    from: &Chord,
+    to: &Chord,
+    options: VoiceLeadingOptions,
+) -> Result<VoiceLeading> {
+    let mut sources = from.pitches();
+    let mut targets = to.pitches();
+    if sources.is_empty() || targets.is_empty() {
+        return Err(Error::Chord(
+            "cannot voice-lead to or from an empty chord".to_string(),
+        ));
+    }
+    sources.sort_by(|a, b| a.ps().total_cmp(&b.ps()));
+    targets.sort_by(|a, b| a.ps().total_cmp(&b.ps()));
+
+    let motions = sources
+        .iter()
+        .map(|source| {
+            targets
+                .iter()
+                .map(|target| motion(source, target, options.space))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let mut search = Search {
+        motions: &motions,
+        sources: &sources,
+        options,
+        best: None,
+        current: Vec::new(),
+    };
+    let voices = sources.len().max(targets.len());
+    search.extend(voices, 0, 0);
+
+    let (_, _, pairs) = search.best.ok_or_else(|| {
+        Error::Chord("no voice leading satisfies the crossing constraint".to_string())
+    })?;
+    let mut voice_motions = pairs
+        .into_iter()
+        .map(|(source, target)| {
+            let from = sources[source].clone();
+            let to = place(&targets[target], from.ps() + motions[source][target])?;
+            let interval = Interval::between_pitches(&from, &to)?;
+            Ok(VoiceMotion { from, to, interval })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    voice_motions.sort_by(|a, b| {
+        a.from
+            .ps()
+            .total_cmp(&b.from.ps())
+            .then(a.to.ps().total_cmp(&b.to.ps()))
+    });
+    let size = options
+        .norm
+        .size(voice_motions.iter().map(VoiceMotion::semitones));
+
+    Ok(VoiceLeading {
+        motions: voice_motions,
+        size,
+    })
+}
+
+impl Chord {
+    /// Returns the smallest voice leading from this chord to `other`; see
+    /// [`minimal_voice_leading`].
+    pub fn voice_leading_to(
+        &self,
+        other: &Chord,
+        options: VoiceLeadingOptions,
+    ) -> Result<VoiceLeading> {
+        minimal_voice_leading(self, other, options)
+    }
+}
+
+/// A voice leading's size, its taxicab size, and its `(source, target)`
+/// pairs.
+type Candidate = (FloatType, FloatType, Vec<(usize, usize)>);
+
+/// Exhaustive search over assignments, pruned by the best size so far.
+struct Search<'a> {
+    motions: &'a [Vec<FloatType>],
+    sources: &'a [Pitch],
+    options: VoiceLeadingOptions,
+    best: Option<Candidate>,
+    current: Vec<(usize, usize)>,
+}
+
+impl Search<'_> {
+    /// Assigns voice `current.len()`, tracking which sources and targets are
+    /// already used in bit masks.
+    fn extend(&mut self, voices: usize, used_sources: u32, used_targets: u32) {
+        let source_count = self.motions.len();
+        let target_count = self.motions[0].len();
+        let remaining = voices - self.current.len();
+        let missing = (source_count - used_sources.count_ones() as usize)
+            .max(target_count - used_targets.count_ones() as usize);
+        if missing > remaining {
+            return;
+        }
+
+        let size = self
+            .options
+            .norm
+            .size(self.current.iter().map(|(s, t)| self.motions[*s][*t]));
+        if let Some((best, _, _)) = &self.best
+            && size > *best + 1e-9
+        {
+            return;
+        }
+        if remaining == 0 {
+            let total = VoiceLeadingNorm::Taxicab
+                .size(self.current.iter().map(|(s, t)| self.motions[*s][*t]));
+            let better = self.best.as_ref().is_none_or(|(best, best_total, _)| {
+                size < best - 1e-9 || (size <= best + 1e-9 && total < best_total - 1e-9)
+            });
+            if better {
+                self.best = Some((size, total, self.current.clone()));
+            }
+            return;
+        }
+
+        // The larger chord's notes each take exactly one voice, in order; the
+        // smaller chord's notes may be shared.
+        let sources_lead = source_count >= target_count;
+        let index = self.current.len();
+        for other in 0..if sources_lead {
+            target_count
+        } else {
+            source_count
+        } {
+            let pair = if sources_lead {
+                (index, other)
+            } else {
+                (other, index)
+            };
+            if !self.options.allow_crossing && self.crosses(pair) {
+                continue;
+            }
+            self.current.push(pair);
+            self.extend(
+                voices,
+                used_sources | 1 << pair.0,
+                used_targets | 1 << pair.1,
+            );
+            self.current.pop();
+        }
+    }
+
+    fn crosses(&self, (source, target): (usize, usize)) -> bool {
+        let start = self.sources[source].ps();
+        let end = start + self.motions[source][target];
+        self.current.iter().any(|(other_source, other_target)| {
+            let other_start = self.sources[*other_source].ps();
+            let other_end = other_start + self.motions[*other_source][*other_target];
+            (start - other_start) * (end - other_end) < -1e-9
+        })
+    }
+}
+
+fn motion(source: &Pitch, target: &Pitch, space: VoiceLeadingSpace) -> FloatType {
+    let difference = target.ps() - source.ps();
+    match space {
+        VoiceLeadingSpace::Pitch => difference,
+        VoiceLeadingSpace::PitchClass => {
+            let class = difference.rem_euclid(12.0);
+            if class > 6.0 { class - 12.0 } else { class }
+        }
+    }
+}
+
+/// `target`'s spelling in the octave that sounds at `ps`.
+fn place(target: &Pitch, ps: FloatType) -> Result<Pitch> {
+    let shift = ((ps - target.ps()) / 12.0).round() as IntegerType;
+    let octave = target.octave().unwrap_or(4) + shift;
+    Pitch::from_name_and_octave(target.name(), octave)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chord(pitches: &str) -> Chord {
+        Chord::new(pitches).unwrap()
+    }
+
+    fn names(leading: &VoiceLeading) -> Vec<String> {
+        leading
+            .motions()
+            .iter()
+            .map(|motion| {
+                format!(
+                    "{}>{}",
+                    motion.from().name_with_octave(),
+                    motion.to().name_with_octave()
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn finds_smooth_pitch_class_voice_leadings() {
+        let leading = minimal_voice_leading(
+            &chord("C4 E4 G4"),
+            &chord("F3 A3 C4"),
+            VoiceLeadingOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(names(&leading), vec!["C4>C4", "E4>F4", "G4>A4"]);
+        assert_eq!(leading.size(), 3.0);
+        assert_eq!(
+            leading
+                .intervals()
+                .iter()
+                .map(Interval::semitones)
+                .collect::<Vec<_>>(),
+            vec![0, 1, 2]
+        );
+    }
+
+    #[test]
+    fn respects_norms_space_and_crossing() {
+        let from = chord("C4 G4");
+        let to = chord("D4 F#4");
+        let pitch = VoiceLeadingOptions::default().with_space(VoiceLeadingSpace::Pitch);
+        let crossing = minimal_voice_leading(&from, &to, pitch).unwrap();
+        assert_eq!(crossing.size(), 3.0);
+
+        let leading = minimal_voice_leading(
+            &chord("C4 E4"),
+            &chord("E4 C4"),
+            pitch.with_norm(VoiceLeadingNorm::Chebyshev),
+        )
+        .unwrap();
+        assert_eq!(leading.size(), 0.0);
+
+        let crossed = minimal_voice_leading(&chord("C4 D4"), &chord("C#4 C#4"), pitch).unwrap();
+        assert_eq!(crossed.size(), 2.0);
+
+        let wide = chord("C4 E4");
+        let swapped = chord("D4 D5");
+        let free = minimal_voice_leading(
+            &wide,
+            &swapped,
+            pitch.with_norm(VoiceLeadingNorm::Euclidean),
+        )
+        .unwrap();
+        let strict = minimal_voice_leading(
+            &wide,
+            &swapped,
+            pitch
+                .with_norm(VoiceLeadingNorm::Euclidean)
+                .with_crossing(false),
+        )
+        .unwrap();
+        // Uncrossing never lengthens a voice leading under these norms.
+        assert_eq!(strict.size(), free.size());
+        assert_eq!(names(&strict), vec!["C4>D4", "E4>D5"]);
+    }
+
+    #[test]
+    fn changes_cardinality_by_doubling() {
+        let leading = minimal_voice_leading(
+            &chord("G3 B3 D4 F4"),
+            &chord("C4 E4 G4"),
+            VoiceLeadingOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(leading.motions().len(), 4);
+        // D4 is a whole step from both C4 and E4.
+        assert_eq!(leading.size(), 4.0);
+        let names = names(&leading);
+        assert_eq!(names[0], "G3>G3");
+        assert_eq!(names[1], "B3>C4");
+        assert_eq!(names[3], "F4>E4");
+
+        let split = chord("C4 E4 G4")
+            .voice_leading_to(&chord("C4 E4 G4 B-4"), VoiceLeadingOptions::default())
+            .unwrap();
+        assert_eq!(split.motions().len(), 4);
+        assert_eq!(split.size(), 2.0);
+        assert!(
+            minimal_voice_leading(
+                &chord("C4"),
+                &Chord::empty().unwrap(),
+                VoiceLeadingOptions::default()
+            )
+            .is_err()
+        );
+    }
+}
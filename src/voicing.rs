@@ -0,0 +1,656 @@
+//! Concrete piano voicings for chord symbols.
+//!
+//! [`voice_chord_symbol`] spells a [`ChordSymbol`] as close, spread, drop,
+//! rootless and quartal voicings within a range, then ranks them: voicings
+//! on the preferred bass first, then the smoothest from a previous voicing,
+//! then the narrowest.
+
+use std::fmt;
+
+use crate::{
+    chord::Chord,
+    chordsymbol::{ChordQuality, ChordSymbol},
+    defaults::{FloatType, IntegerType},
+    error::{Error, Result},
+    interval::Interval,
+    pitch::Pitch,
+    voiceleading::{VoiceLeadingOptions, VoiceLeadingSpace, minimal_voice_leading},
+};
+
+/// A way of arranging a chord's tones.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum VoicingStyle {
+    /// Every tone within an octave, in any inversion.
+    Close,
+    /// The root alone in the bass, with the other tones in close position
+    /// more than an octave above it.
+    Spread,
+    /// A close voicing with its second voice from the top dropped an octave.
+    Drop2,
+    /// A close voicing with its third voice from the top dropped an octave.
+    Drop3,
+    /// A close voicing with its second and fourth voices from the top dropped
+    /// an octave.
+    Drop24,
+    /// A rootless 3–5–7–9 voicing, with the thirteenth for the fifth on
+    /// dominant chords.
+    RootlessA,
+    /// A rootless 7–9–3–5 voicing, with the thirteenth for the fifth on
+    /// dominant chords.
+    RootlessB,
+    /// Three or four tones stacked in perfect fourths, optionally topped by a
+    /// major third as in the "So What" voicing.
+    Quartal,
+}
+
+impl VoicingStyle {
+    /// Every style, in declaration order.
+    pub const ALL: [Self; 8] = [
+        Self::Close,
+        Self::Spread,
+        Self::Drop2,
+        Self::Drop3,
+        Self::Drop24,
+        Self::RootlessA,
+        Self::RootlessB,
+        Self::Quartal,
+    ];
+
+    /// Returns a readable name, such as `"drop-2"`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Close => "close",
+            Self::Spread => "spread",
+            Self::Drop2 => "drop-2",
+            Self::Drop3 => "drop-3",
+            Self::Drop24 => "drop-2&4",
+            Self::RootlessA => "rootless A",
+            Self::RootlessB => "rootless B",
+            Self::Quartal => "quartal",
+        }
+    }
+}
+
+impl fmt::Display for VoicingStyle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Settings for [`voice_chord_symbol`].
+#[derive(Clone, Debug)]
+pub struct VoicingOptions {
+    styles: Vec<VoicingStyle>,
+    low: Pitch,
+    high: Pitch,
+    top_note: Option<Pitch>,
+    bass: Option<Pitch>,
+    previous: Option<Chord>,
+}
+
+impl Default for VoicingOptions {
+    fn default() -> Self {
+        Self {
+            styles: VoicingStyle::ALL.to_vec(),
+            low: Pitch::from_name_and_octave("C", 3).expect("C3 is a valid pitch"),
+            high: Pitch::from_name_and_octave("C", 6).expect("C6 is a valid pitch"),
+            top_note: None,
+            bass: None,
+            previous: None,
+        }
+    }
+}
+
+impl VoicingOptions {
+    /// Returns the options generating only `styles`.
+    pub fn with_styles(mut self, styles: impl IntoIterator<Item = VoicingStyle>) -> Self {
+        self.styles = styles.into_iter().collect();
+        self
+    }
+
+    /// Returns the options with every note kept between `low` and `high`,
+    /// inclusive. The default is C3 to C6.
+    pub fn with_range(mut self, low: Pitch, high: Pitch) -> Self {
+        self.low = low;
+        self.high = high;
+        self
+    }
+
+    /// Returns the options keeping only voicings whose top note is `top`.
+    ///
+    /// A pitch without an octave matches its pitch class in any octave.
+    pub fn with_top_note(mut self, top: Pitch) -> Self {
+        self.top_note = Some(top);
+        self
+    }
+
+    /// Returns the options ranking voicings whose lowest note has the pitch
+    /// class of `bass` first. A chord symbol's slash bass is preferred when
+    /// none is set.
+    pub fn with_bass(mut self, bass: Pitch) -> Self {
+        self.bass = Some(bass);
+        self
+    }
+
+    /// Returns the options ranking voicings by their voice leading from
+    /// `previous`.
+    pub fn with_previous(mut self, previous: Chord) -> Self {
+        self.previous = Some(previous);
+        self
+    }
+
+    /// Returns the styles generated.
+    pub fn styles(&self) -> &[VoicingStyle] {
+        &self.styles
+    }
+
+    /// Returns the lowest and highest allowed pitches.
+    pub fn range(&self) -> (&Pitch, &Pitch) {
+        (&self.low, &self.high)
+    }
+
+    /// Returns the required top note.
+    pub fn top_note(&self) -> Option<&Pitch> {
+        self.top_note.as_ref()
+    }
+
+    /// Returns the preferred bass.
+    pub fn bass(&self) -> Option<&Pitch> {
+        self.bass.as_ref()
+    }
+
+    /// Returns the voicing that rankings lead from.
+    pub fn previous(&self) -> Option<&Chord> {
+        self.previous.as_ref()
+    }
+}
+
+/// One realized voicing.
+#[derive(Clone, Debug)]
+pub struct Voicing {
+    style: VoicingStyle,
+    pitches: Vec<Pitch>,
+    voice_leading: Option<FloatType>,
+}
+
+impl Voicing {
+    /// Returns the style that produced the voicing.
+    pub fn style(&self) -> VoicingStyle {
+        self.style
+    }
+
+    /// Returns the pitches from lowest to highest.
+    pub fn pitches(&self) -> &[Pitch] {
+        &self.pitches
+    }
+
+    /// Returns the lowest pitch.
+    pub fn bass(&self) -> &Pitch {
+        &self.pitches[0]
+    }
+
+    /// Returns the highest pitch.
+    pub fn top(&self) -> &Pitch {
+        &self.pitches[self.pitches.len() - 1]
+    }
+
+    /// Returns the distance from the lowest to the highest pitch in
+    /// semitones.
+    pub fn spread(&self) -> FloatType {
+        self.top().ps() - self.bass().ps()
+    }
+
+    /// Returns the taxicab voice-leading size from the previous voicing, when
+    /// one was given.
+    pub fn voice_leading(&self) -> Option<FloatType> {
+        self.voice_leading
+    }
+
+    /// Returns the voicing as a [`Chord`].
+    pub fn to_chord(&self) -> Result<Chord> {
+        Chord::new(self.pitches.as_slice())
+    }
+}
+
+impl fmt::Display for Voicing {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names = self
+            .pitches
+            .iter()
+            .map(Pitch::name_with_octave)
+            .collect::<Vec<_>>();
+        write!(f, "{}: {}", self.style, names.join(" "))
+    }
+}
+
+/// Realizes a chord symbol as ranked voicings.
+///
+/// Styles that do not apply to the chord are skipped: drop-3 and drop-2&4
+/// need four tones, rootless voicings a third and a seventh or sixth, and
+/// quartal voicings a stack of fourths that includes the third or seventh.
+pub fn voice_chord_symbol(symbol: &ChordSymbol, options: &VoicingOptions) -> Result<Vec<Voicing>> {
+    let tones = symbol.chord_degrees()?;
+    let preferred_bass = options.bass.as_ref().or(symbol.bass());
+
+    let mut voicings = Vec::new();
+    for style in &options.styles {
+        for shape in shapes(symbol, &tones, *style)? {
+            for pitches in placements(&shape, *style, options)? {
+                if !voicings
+                    .iter()
+                    .any(|(other, existing): &(VoicingStyle, Vec<Pitch>)| {
+                        other == style && same_pitches(existing, &pitches)
+                    })
+                {
+                    voicings.push((*style, pitches));
+                }
+            }
+        }
+    }
+
+    let mut ranked = voicings
+        .into_iter()
+        .map(|(style, pitches)| {
+            let voice_leading = options
+                .previous
+                .as_ref()
+                .map(|previous| {
+                    minimal_voice_leading(
+                        previous,
+                        &Chord::new(pitches.as_slice())?,
+                        VoiceLeadingOptions::default().with_space(VoiceLeadingSpace::Pitch),
+                    )
+                    .map(|leading| leading.size())
+                })
+                .transpose()?;
+            Ok(Voicing {
+                style,
+                pitches,
+                voice_leading,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    ranked.sort_by(|a, b| {
+        let off_bass = |voicing: &Voicing| {
+            preferred_bass.is_some_and(|bass| {
+                bass.pitch_class_number() != voicing.bass().pitch_class_number()
+            })
+        };
+        off_bass(a)
+            .cmp(&off_bass(b))
+            .then(
+                a.voice_leading
+                    .unwrap_or(0.0)
+                    .total_cmp(&b.voice_leading.unwrap_or(0.0)),
+            )
+            .then(a.spread().total_cmp(&b.spread()))
+            .then(a.bass().ps().total_cmp(&b.bass().ps()))
+            .then(a.style.cmp(&b.style))
+    });
+    Ok(ranked)
+}
+
+/// Realizes a chord as ranked voicings by way of its chord symbol; see
+/// [`voice_chord_symbol`].
+pub fn voice_chord(chord: &Chord, options: &VoicingOptions) -> Result<Vec<Voicing>> {
+    let figure = chord
+        .chord_symbol()
+        .ok_or_else(|| Error::Chord(format!("{} has no chord symbol", chord.common_name())))?;
+    voice_chord_symbol(&ChordSymbol::parse(figure)?, options)
+}
+
+impl ChordSymbol {
+    /// Returns ranked voicings of the symbol; see [`voice_chord_symbol`].
+    pub fn voicings(&self, options: &VoicingOptions) -> Result<Vec<Voicing>> {
+        voice_chord_symbol(self, options)
+    }
+}
+
+impl Chord {
+    /// Returns ranked voicings of the chord; see [`voice_chord`].
+    pub fn voicings(&self, options: &VoicingOptions) -> Result<Vec<Voicing>> {
+        voice_chord(self, options)
+    }
+}
+
+/// The spelled tones of each voicing of a style, bottom to top, before
+/// octave placement. Every tone sits above the one below it, within an
+/// octave, except where the style places it otherwise.
+fn shapes(
+    symbol: &ChordSymbol,
+    tones: &[(u8, Pitch)],
+    style: VoicingStyle,
+) -> Result<Vec<Vec<Pitch>>> {
+    let pitches = tones
+        .iter()
+        .map(|(_, pitch)| pitch.clone())
+        .collect::<Vec<_>>();
+    let rotations = || {
+        (0..pitches.len()).map(|start| {
+            pitches[start..]
+                .iter()
+                .chain(&pitches[..start])
+                .cloned()
+                .collect::<Vec<_>>()
+        })
+    };
+    let degree = |wanted: u8| {
+        tones
+            .iter()
+            .find(|(degree, _)| *degree == wanted)
+            .map(|(_, pitch)| pitch.clone())
+    };
+    let above_root =
+        |name: &str| -> Result<Pitch> { Interval::from_name(name)?.transpose_pitch(symbol.root()) };
+
+    Ok(match style {
+        VoicingStyle::Close | VoicingStyle::Drop2 => rotations().collect(),
+        VoicingStyle::Drop3 | VoicingStyle::Drop24 if pitches.len() >= 4 => rotations().collect(),
+        VoicingStyle::Drop3 | VoicingStyle::Drop24 => Vec::new(),
+        VoicingStyle::Spread if pitches.len() >= 3 => vec![pitches.clone()],
+        VoicingStyle::Spread => Vec::new(),
+        VoicingStyle::RootlessA | VoicingStyle::RootlessB => {
+            let (Some(third), Some(seventh)) = (degree(3), degree(7).or_else(|| degree(6))) else {
+                return Ok(Vec::new());
+            };
+            let ninth = match degree(9) {
+                Some(ninth) => ninth,
+                None => above_root("M2")?,
+            };
+            let fifth = match (symbol.quality(), degree(13), degree(5)) {
+                (_, Some(thirteenth), _) => thirteenth,
+                (ChordQuality::Dominant, None, _) => above_root("M6")?,
+                (_, None, Some(fifth)) => fifth,
+                (_, None, None) => return Ok(Vec::new()),
+            };
+            if style == VoicingStyle::RootlessA {
+                vec![vec![third, fifth, seventh, ninth]]
+            } else {
+                vec![vec![seventh, ninth, third, fifth]]
+            }
+        }
+        VoicingStyle::Quartal => quartal_shapes(symbol, tones)?,
+    })
+}
+
+/// Stacks of fourths drawn from the chord's tones and its natural ninth,
+/// eleventh (minor and suspended chords) and thirteenth (minor and dominant
+/// chords), each containing the chord's third or seventh when it has one.
+fn quartal_shapes(symbol: &ChordSymbol, tones: &[(u8, Pitch)]) -> Result<Vec<Vec<Pitch>>> {
+    let mut members = tones
+        .iter()
+        .map(|(_, pitch)| pitch.clone())
+        .collect::<Vec<_>>();
+    let has_degree = |wanted: u8| tones.iter().any(|(degree, _)| *degree == wanted);
+    let mut tensions = Vec::new();
+    if !has_degree(9) {
+        tensions.push("M2");
+    }
+    if matches!(
+        symbol.quality(),
+        ChordQuality::Minor | ChordQuality::Suspended4 | ChordQuality::Suspended2
+    ) && !has_degree(11)
+    {
+        tensions.push("P4");
+    }
+    if matches!(
+        symbol.quality(),
+        ChordQuality::Minor | ChordQuality::Dominant
+    ) && !has_degree(6)
+        && !has_degree(13)
+    {
+        tensions.push("M6");
+    }
+    for name in tensions {
+        members.push(Interval::from_name(name)?.transpose_pitch(symbol.root())?);
+    }
+    let guide_tones = tones
+        .iter()
+        .filter(|(degree, _)| matches!(degree, 3 | 7))
+        .map(|(_, pitch)| pitch.pitch_class_number())
+        .collect::<Vec<_>>();
+
+    let member = |pc: u8| {
+        members
+            .iter()
+            .find(|pitch| pitch.pitch_class_number() == pc)
+            .cloned()
+    };
+    let mut shapes = Vec::new();
+    for bottom in &members {
+        let mut stack = vec![bottom.clone()];
+        while stack.len() < 4 {
+            let top = stack[stack.len() - 1].pitch_class_number();
+            match member((top + 5) % 12) {
+                Some(next)
+                    if !stack
+                        .iter()
+                        .any(|pitch| pitch.pitch_class_number() == (top + 5) % 12) =>
+                {
+                    stack.push(next)
+                }
+                _ => break,
+            }
+        }
+        if stack.len() < 3 {
+            continue;
+        }
+        let has_guide_tone = |stack: &[Pitch]| {
+            guide_tones.is_empty()
+                || stack
+                    .iter()
+                    .any(|pitch| guide_tones.contains(&pitch.pitch_class_number()))
+        };
+        if stack.len() == 4 {
+            let top = stack[3].pitch_class_number();
+            if let Some(so_what) = member((top + 4) % 12)
+                && !stack
+                    .iter()
+                    .any(|pitch| pitch.pitch_class_number() == so_what.pitch_class_number())
+            {
+                let mut five = stack.clone();
+                five.push(so_what);
+                if has_guide_tone(&five) {
+                    shapes.push(five);
+                }
+            }
+        }
+        if has_guide_tone(&stack) {
+            shapes.push(stack);
+        }
+    }
+    Ok(shapes)
+}
+
+/// Every octave placement of a shape that fits the options' range and top
+/// note.
+fn placements(
+    shape: &[Pitch],
+    style: VoicingStyle,
+    options: &VoicingOptions,
+) -> Result<Vec<Vec<Pitch>>> {
+    let (low, high) = (options.low.ps(), options.high.ps());
+    let mut placed = Vec::new();
+    for octave in -1..=9 {
+        let mut ps: Vec<FloatType> = Vec::with_capacity(shape.len());
+        for (index, pitch) in shape.iter().enumerate() {
+            let pc = FloatType::from(pitch.pitch_class_number());
+            let next = match ps.last() {
+                None => pc + 12.0 * FloatType::from(octave + 1),
+                // A spread voicing's upper tones start over an octave above
+                // the bass.
+                Some(previous) if style == VoicingStyle::Spread && index == 1 => {
+                    let step = (pc - previous - 12.0).rem_euclid(12.0);
+                    previous + 12.0 + if step == 0.0 { 12.0 } else { step }
+                }
+                Some(previous) => {
+                    let step = (pc - previous).rem_euclid(12.0);
+                    previous + if step == 0.0 { 12.0 } else { step }
+                }
+            };
+            ps.push(next);
+        }
+        let drops: &[usize] = match style {
+            VoicingStyle::Drop2 => &[2],
+            VoicingStyle::Drop3 => &[3],
+            VoicingStyle::Drop24 => &[2, 4],
+            _ => &[],
+        };
+        for from_top in drops {
+            let index = ps.len() - from_top;
+            ps[index] -= 12.0;
+        }
+        let mut voiced = shape.iter().zip(ps).collect::<Vec<_>>();
+        voiced.sort_by(|(_, a), (_, b)| a.total_cmp(b));
+        if voiced.first().is_some_and(|(_, ps)| *ps < low)
+            || voiced.last().is_some_and(|(_, ps)| *ps > high)
+        {
+            continue;
+        }
+        let pitches = voiced
+            .into_iter()
+            .map(|(pitch, ps)| pitch_at(pitch, ps))
+            .collect::<Result<Vec<_>>>()?;
+        if let Some(top) = &options.top_note {
+            let actual = &pitches[pitches.len() - 1];
+            let matches = match top.octave() {
+                Some(_) => (top.ps() - actual.ps()).abs() < 1e-9,
+                None => top.pitch_class_number() == actual.pitch_class_number(),
+            };
+            if !matches {
+                continue;
+            }
+        }
+        placed.push(pitches);
+    }
+    Ok(placed)
+}
+
+/// `pitch`'s spelling in the octave that sounds at `ps`.
+fn pitch_at(pitch: &Pitch, ps: FloatType) -> Result<Pitch> {
+    let octave = ((ps - pitch.alter()) / 12.0).floor() as IntegerType - 1;
+    Pitch::from_name_and_octave(pitch.name(), octave)
+}
+
+fn same_pitches(a: &[Pitch], b: &[Pitch]) -> bool {
+    a.len() == b.len()
+        && a.iter()
+            .zip(b)
+            .all(|(a, b)| a.name_with_octave() == b.name_with_octave())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(voicing: &Voicing) -> Vec<String> {
+        voicing
+            .pitches()
+            .iter()
+            .map(Pitch::name_with_octave)
+            .collect()
+    }
+
+    fn first(voicings: &[Voicing], style: VoicingStyle) -> Vec<String> {
+        names(
+            voicings
+                .iter()
+                .find(|voicing| voicing.style() == style)
+                .unwrap(),
+        )
+    }
+
+    #[test]
+    fn realizes_each_style() {
+        let symbol = ChordSymbol::parse("G7").unwrap();
+        let voicings = symbol.voicings(&VoicingOptions::default()).unwrap();
+        assert_eq!(
+            first(&voicings, VoicingStyle::Close),
+            ["B3", "D4", "F4", "G4"]
+        );
+        assert_eq!(
+            first(&voicings, VoicingStyle::Spread),
+            ["G3", "B4", "D5", "F5"]
+        );
+        assert_eq!(
+            first(&voicings, VoicingStyle::Drop2),
+            ["F3", "B3", "D4", "G4"]
+        );
+        assert_eq!(
+            first(&voicings, VoicingStyle::Drop3),
+            ["D3", "B3", "F4", "G4"]
+        );
+        assert_eq!(
+            first(&voicings, VoicingStyle::Drop24),
+            ["B3", "F4", "D5", "G5"]
+        );
+        assert_eq!(
+            first(&voicings, VoicingStyle::RootlessA),
+            ["B3", "E4", "F4", "A4"]
+        );
+        assert_eq!(
+            first(&voicings, VoicingStyle::RootlessB),
+            ["F3", "A3", "B3", "E4"]
+        );
+        assert_eq!(
+            first(&voicings, VoicingStyle::Quartal),
+            ["B3", "E4", "A4", "D5"]
+        );
+        assert!(
+            voicings
+                .windows(2)
+                .all(|pair| pair[0].spread() <= pair[1].spread())
+        );
+
+        let triad = voice_chord(&Chord::new("C E G").unwrap(), &VoicingOptions::default()).unwrap();
+        assert!(triad.iter().all(|voicing| !matches!(
+            voicing.style(),
+            VoicingStyle::Drop3 | VoicingStyle::RootlessA | VoicingStyle::Quartal
+        )));
+    }
+
+    #[test]
+    fn applies_range_top_note_and_bass() {
+        let symbol = ChordSymbol::parse("Cmaj7").unwrap();
+        let options = VoicingOptions::default()
+            .with_styles([VoicingStyle::Drop2])
+            .with_range(
+                Pitch::from_name_and_octave("G", 3).unwrap(),
+                Pitch::from_name_and_octave("G", 5).unwrap(),
+            )
+            .with_top_note(Pitch::from_name("E").unwrap());
+        let voicings = symbol.voicings(&options).unwrap();
+        assert!(!voicings.is_empty());
+        assert!(voicings.iter().all(|voicing| {
+            voicing.top().name() == "E" && voicing.bass().ps() >= 55.0 && voicing.top().ps() <= 79.0
+        }));
+
+        let slash = ChordSymbol::parse("C/E").unwrap();
+        let voicings = slash.voicings(&VoicingOptions::default()).unwrap();
+        assert_eq!(voicings[0].bass().name(), "E");
+        let on_g = symbol
+            .voicings(&VoicingOptions::default().with_bass(Pitch::from_name("G").unwrap()))
+            .unwrap();
+        assert_eq!(on_g[0].bass().name(), "G");
+    }
+
+    #[test]
+    fn ranks_by_voice_leading() {
+        let previous = Chord::new("F3 A3 C4 E4").unwrap();
+        let options = VoicingOptions::default()
+            .with_styles([VoicingStyle::Close, VoicingStyle::Drop2])
+            .with_previous(previous);
+        let voicings = ChordSymbol::parse("G7")
+            .unwrap()
+            .voicings(&options)
+            .unwrap();
+        let sizes = voicings
+            .iter()
+            .map(|voicing| voicing.voice_leading().unwrap())
+            .collect::<Vec<_>>();
+        assert!(sizes.windows(2).all(|pair| pair[0] <= pair[1]));
+        assert_eq!(names(&voicings[0]), ["F3", "G3", "B3", "D4"]);
+        assert_eq!(voicings[0].to_string(), "close: F3 G3 B3 D4");
+    }
+}